@@ -0,0 +1,337 @@
+use std::ffi::{c_uint, CStr};
+
+use llvm_sys::core::*;
+use llvm_sys::prelude::*;
+use llvm_sys::target::{LLVMABISizeOfType, LLVMGetModuleDataLayout, LLVMOffsetOfElement, LLVMTargetDataRef};
+use llvm_sys::LLVMTypeKind::{LLVMArrayTypeKind, LLVMDoubleTypeKind, LLVMFloatTypeKind, LLVMStructTypeKind};
+
+/// How a value is passed to (or returned from) a function at the machine level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PassMode {
+    /// As a value of its own LLVM type
+    Direct,
+    /// As a value of another LLVM type with the same bytes, like a `{ i32, i32 }` struct passed as
+    /// an `i64` (so that it ends up in the registers C expects)
+    Coerced(LLVMTypeRef),
+    /// Through a pointer to a copy of the value, which is marked `byval` if the copy belongs to the
+    /// callee's stack frame; for return values, the caller passes that pointer as a hidden first
+    /// parameter (marked `sret`)
+    Indirect { byval: bool },
+    /// As a pointer to its first byte, like a C `char *`; this is how a `str` is passed to (or
+    /// returned from) an `extern` function, whose length is found with `strlen` when it's returned
+    CString,
+}
+
+/// The LLVM type of a parameter or return value, and how it's passed.
+#[derive(Debug, Clone, Copy)]
+pub struct AbiValue {
+    pub llvm_type: LLVMTypeRef,
+    pub mode: PassMode,
+}
+
+/// How the parameters and return value of a function are passed, following the C calling
+/// convention of the target; since every function uses it, Flick functions interoperate with
+/// `extern` C functions.
+#[derive(Debug, Clone)]
+pub struct FuncAbi {
+    pub ret: AbiValue,
+    pub params: Vec<AbiValue>,
+}
+
+impl FuncAbi {
+    /// Returns whether the return value is written through a hidden first parameter.
+    pub fn has_sret(&self) -> bool {
+        matches!(self.ret.mode, PassMode::Indirect { .. })
+    }
+
+    /// Returns the index of the LLVM parameter that the `i`th parameter is passed as.
+    pub fn param_index(&self, i: usize) -> c_uint {
+        (i + self.has_sret() as usize) as c_uint
+    }
+
+    /// Returns the LLVM function type of the lowered function.
+    pub unsafe fn llvm_type(&self, context: LLVMContextRef) -> LLVMTypeRef {
+        let mut param_types = Vec::with_capacity(self.params.len() + 1);
+        if self.has_sret() {
+            param_types.push(LLVMPointerType(self.ret.llvm_type, 0));
+        }
+        param_types.extend(self.params.iter().map(|param| match param.mode {
+            PassMode::Direct => param.llvm_type,
+            PassMode::Coerced(coerced_type) => coerced_type,
+            PassMode::Indirect { .. } => LLVMPointerType(param.llvm_type, 0),
+            PassMode::CString => LLVMPointerType(LLVMInt8TypeInContext(context), 0),
+        }));
+        let return_type = match self.ret.mode {
+            PassMode::Direct => self.ret.llvm_type,
+            PassMode::Coerced(coerced_type) => coerced_type,
+            PassMode::Indirect { .. } => LLVMVoidTypeInContext(context),
+            PassMode::CString => LLVMPointerType(LLVMInt8TypeInContext(context), 0),
+        };
+
+        let num_params = param_types.len() as c_uint;
+        LLVMFunctionType(return_type, param_types.as_mut_ptr(), num_params, 0)
+    }
+
+    /// Returns the attributes (with their LLVM attribute indices) that the function and its call
+    /// sites need, like `sret(%T)` on a hidden return pointer.
+    pub unsafe fn attributes(&self, context: LLVMContextRef) -> Vec<(c_uint, LLVMAttributeRef)> {
+        let mut attributes = Vec::new();
+        // Attribute index 0 is the return value, so parameters start at 1
+        if self.has_sret() {
+            attributes.push((1, type_attribute(context, "sret", self.ret.llvm_type)));
+        }
+        for (i, param) in self.params.iter().enumerate() {
+            if let PassMode::Indirect { byval: true } = param.mode {
+                let index = self.param_index(i) + 1;
+                attributes.push((index, type_attribute(context, "byval", param.llvm_type)));
+                attributes.push((index, int_attribute(context, "align", 8)));
+            }
+        }
+        attributes
+    }
+}
+
+/// The architectures whose C calling conventions are supported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Arch {
+    X86_64,
+    AArch64,
+    /// Structs are passed directly, which might not match C
+    Other,
+}
+
+/// Decides how values are passed between functions of a module, based on its target triple and
+/// data layout.
+pub struct TargetAbi {
+    context: LLVMContextRef,
+    target_data: LLVMTargetDataRef,
+    arch: Arch,
+}
+
+impl TargetAbi {
+    /// Creates the ABI for `module`, whose target triple and data layout must already be set.
+    pub unsafe fn new(context: LLVMContextRef, module: LLVMModuleRef) -> Self {
+        let triple = CStr::from_ptr(LLVMGetTarget(module)).to_string_lossy();
+        let arch = match triple.split('-').next() {
+            Some("x86_64") => Arch::X86_64,
+            Some("aarch64" | "arm64") => Arch::AArch64,
+            _ => Arch::Other,
+        };
+        Self {
+            context,
+            target_data: LLVMGetModuleDataLayout(module),
+            arch,
+        }
+    }
+
+    /// Returns how a function with the given LLVM return and parameter types is lowered.
+    pub unsafe fn func_abi(&self, return_type: LLVMTypeRef, param_types: &[LLVMTypeRef]) -> FuncAbi {
+        let classify = |llvm_type| AbiValue {
+            llvm_type,
+            mode: self.classify(llvm_type),
+        };
+        FuncAbi {
+            ret: classify(return_type),
+            params: param_types.iter().map(|&t| classify(t)).collect(),
+        }
+    }
+
+    /// Returns how a value of `llvm_type` is passed; only structs are passed differently from
+    /// their own type.
+    ///
+    /// Flick structs only contain integers (including bools), floats, strings (a pointer and an
+    /// integer) and arrays of those, so the rules are simple:
+    /// - x86_64 (System V): structs of up to 16 bytes are split into eightbytes, which are passed
+    ///   in floating-point registers if they only contain floats, and in integer registers
+    ///   otherwise; bigger structs are copied onto the stack (`byval`).
+    /// - AArch64: structs of one to four floats of the same type are passed in floating-point
+    ///   registers, other structs of up to 16 bytes are passed in one or two integer registers,
+    ///   and bigger ones are passed as a pointer to a copy made by the caller.
+    ///
+    /// On both, structs returned in registers follow the same rules, and bigger ones are written
+    /// through a pointer given by the caller (`sret`).
+    unsafe fn classify(&self, llvm_type: LLVMTypeRef) -> PassMode {
+        if LLVMGetTypeKind(llvm_type) != LLVMStructTypeKind {
+            return PassMode::Direct;
+        }
+        let size = LLVMABISizeOfType(self.target_data, llvm_type) as c_uint;
+        let int_type = |bytes: c_uint| LLVMIntTypeInContext(self.context, bytes * 8);
+
+        let mut fields = Vec::new();
+        self.flatten(llvm_type, 0, &mut fields);
+        // On AArch64, these "homogeneous floating-point aggregates" use one register per float
+        // even if they're bigger than 16 bytes
+        if self.arch == Arch::AArch64 && fields.len() <= 4 {
+            if let Some(float_type) = Self::homogeneous_float_type(fields.iter().map(|&(_, field_type)| field_type)) {
+                // (LLVMArrayType2 is missing from older versions of LLVM)
+                #[allow(deprecated)]
+                return PassMode::Coerced(LLVMArrayType(float_type, fields.len() as c_uint));
+            }
+        }
+
+        match (self.arch, size) {
+            (Arch::Other, _) | (_, 0) => PassMode::Direct,
+            (Arch::X86_64, 1..=8) => PassMode::Coerced(self.eightbyte_type(&fields, 0, size)),
+            (Arch::X86_64, 9..=16) => {
+                let mut element_types = [self.eightbyte_type(&fields, 0, 8), self.eightbyte_type(&fields, 8, size - 8)];
+                PassMode::Coerced(LLVMStructTypeInContext(self.context, element_types.as_mut_ptr(), 2, 0))
+            }
+            (Arch::X86_64, _) => PassMode::Indirect { byval: true },
+            (Arch::AArch64, 1..=8) => PassMode::Coerced(int_type(8)),
+            // (LLVMArrayType2 is missing from older versions of LLVM)
+            #[allow(deprecated)]
+            (Arch::AArch64, 9..=16) => PassMode::Coerced(LLVMArrayType(int_type(8), 2)),
+            (Arch::AArch64, _) => PassMode::Indirect { byval: false },
+        }
+    }
+
+    /// Appends the scalar (non-struct, non-array) fields of `llvm_type`, which starts `offset`
+    /// bytes into the value being classified, to `fields` along with their offsets.
+    unsafe fn flatten(&self, llvm_type: LLVMTypeRef, offset: u64, fields: &mut Vec<(u64, LLVMTypeRef)>) {
+        match LLVMGetTypeKind(llvm_type) {
+            LLVMStructTypeKind => {
+                let mut element_types = vec![std::ptr::null_mut(); LLVMCountStructElementTypes(llvm_type) as usize];
+                LLVMGetStructElementTypes(llvm_type, element_types.as_mut_ptr());
+                for (i, element_type) in element_types.into_iter().enumerate() {
+                    let element_offset = LLVMOffsetOfElement(self.target_data, llvm_type, i as c_uint);
+                    self.flatten(element_type, offset + element_offset, fields);
+                }
+            }
+            LLVMArrayTypeKind => {
+                let element_type = LLVMGetElementType(llvm_type);
+                let element_size = LLVMABISizeOfType(self.target_data, element_type);
+                // (LLVMGetArrayLength2 is missing from older versions of LLVM)
+                #[allow(deprecated)]
+                let length = LLVMGetArrayLength(llvm_type) as u64;
+                for i in 0..length {
+                    self.flatten(element_type, offset + i * element_size, fields);
+                }
+            }
+            _ => fields.push((offset, llvm_type)),
+        }
+    }
+
+    /// Returns the type that the x86_64 eightbyte starting `offset` bytes into a struct (of which
+    /// `size` bytes are left) is passed as: a `double`, a `float` or two `float`s if it only
+    /// contains floats, so that it's passed in a floating-point register, or an integer otherwise.
+    unsafe fn eightbyte_type(&self, fields: &[(u64, LLVMTypeRef)], offset: u64, size: c_uint) -> LLVMTypeRef {
+        let eightbyte_fields: Vec<_> = fields
+            .iter()
+            .filter(|(field_offset, _)| (offset..offset + 8).contains(field_offset))
+            .map(|&(_, field_type)| field_type)
+            .collect();
+        match Self::homogeneous_float_type(eightbyte_fields.iter().copied()) {
+            Some(float_type) if eightbyte_fields.len() == 2 => LLVMVectorType(float_type, 2),
+            Some(float_type) => float_type,
+            None => LLVMIntTypeInContext(self.context, size * 8),
+        }
+    }
+
+    /// Returns the floating-point type of `field_types` if there are some and they all have it.
+    unsafe fn homogeneous_float_type(mut field_types: impl Iterator<Item = LLVMTypeRef>) -> Option<LLVMTypeRef> {
+        let first_type = field_types.next()?;
+        let is_float = matches!(LLVMGetTypeKind(first_type), LLVMFloatTypeKind | LLVMDoubleTypeKind);
+        (is_float && field_types.all(|field_type| field_type == first_type)).then_some(first_type)
+    }
+}
+
+/// Creates an attribute like `sret(%T)`, which takes a type.
+unsafe fn type_attribute(context: LLVMContextRef, name: &str, llvm_type: LLVMTypeRef) -> LLVMAttributeRef {
+    let kind = LLVMGetEnumAttributeKindForName(name.as_ptr() as *const _, name.len());
+    LLVMCreateTypeAttribute(context, kind, llvm_type)
+}
+
+/// Creates an attribute like `align 8`, which takes an integer.
+unsafe fn int_attribute(context: LLVMContextRef, name: &str, value: u64) -> LLVMAttributeRef {
+    let kind = LLVMGetEnumAttributeKindForName(name.as_ptr() as *const _, name.len());
+    LLVMCreateEnumAttribute(context, kind, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llvm_sys::target::{LLVMCreateTargetData, LLVMDisposeTargetData, LLVMSetModuleDataLayout};
+    use std::ffi::CString;
+
+    /// Classifies structs for `triple` (whose data layout is `data_layout`), and returns how each
+    /// is passed, as the printed LLVM type it's coerced to if it is. Each struct is given as its
+    /// field types separated by spaces, like `"f32 i32"`, where `3xf32` is an array of 3 `f32`s.
+    fn classify_structs(triple: &str, data_layout: &str, structs: &[&str]) -> Vec<String> {
+        unsafe {
+            let context = LLVMContextCreate();
+            let module = LLVMModuleCreateWithNameInContext(c"test".as_ptr(), context);
+            let triple = CString::new(triple).unwrap();
+            LLVMSetTarget(module, triple.as_ptr());
+            let data_layout = CString::new(data_layout).unwrap();
+            let target_data = LLVMCreateTargetData(data_layout.as_ptr());
+            LLVMSetModuleDataLayout(module, target_data);
+
+            let abi = TargetAbi::new(context, module);
+            let field_type = |field: &str| match field {
+                "f32" => LLVMFloatTypeInContext(context),
+                "f64" => LLVMDoubleTypeInContext(context),
+                "i32" => LLVMInt32TypeInContext(context),
+                "i64" => LLVMInt64TypeInContext(context),
+                #[allow(deprecated)]
+                "3xf32" => LLVMArrayType(LLVMFloatTypeInContext(context), 3),
+                _ => panic!("Unknown field type {}", field),
+            };
+            let modes = structs
+                .iter()
+                .map(|fields| {
+                    let mut field_types: Vec<_> = fields.split(' ').map(field_type).collect();
+                    let num_fields = field_types.len() as c_uint;
+                    let struct_type = LLVMStructTypeInContext(context, field_types.as_mut_ptr(), num_fields, 0);
+                    match abi.classify(struct_type) {
+                        PassMode::Coerced(coerced_type) => {
+                            let printed = LLVMPrintTypeToString(coerced_type);
+                            let coerced = CStr::from_ptr(printed).to_string_lossy().into_owned();
+                            LLVMDisposeMessage(printed);
+                            coerced
+                        }
+                        mode => format!("{:?}", mode),
+                    }
+                })
+                .collect();
+
+            LLVMDisposeModule(module);
+            LLVMDisposeTargetData(target_data);
+            LLVMContextDispose(context);
+            modes
+        }
+    }
+
+    #[test]
+    fn x86_64_passes_eightbytes_of_floats_in_float_registers() {
+        let modes = classify_structs(
+            "x86_64-pc-linux-gnu",
+            "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128",
+            &["f32", "f32 f32", "3xf32", "f64 f32", "f32 i32", "f64 i64", "f64 f64 f64"],
+        );
+        assert_eq!(
+            modes,
+            [
+                "float",
+                "<2 x float>",
+                "{ <2 x float>, float }",
+                "{ double, float }",
+                "i64",
+                "{ double, i64 }",
+                "Indirect { byval: true }",
+            ]
+        );
+    }
+
+    #[test]
+    fn aarch64_passes_homogeneous_float_structs_in_float_registers() {
+        let modes = classify_structs(
+            "aarch64-unknown-linux-gnu",
+            "e-m:e-i8:8:32-i16:16:32-i64:64-i128:128-n32:64-S128",
+            &["f32 f32", "3xf32", "f64 f64 f64 f64", "f64 f32", "f32 i32", "f32 f32 3xf32"],
+        );
+        assert_eq!(
+            modes,
+            ["[2 x float]", "[3 x float]", "[4 x double]", "[2 x i64]", "i64", "Indirect { byval: false }"]
+        );
+    }
+}
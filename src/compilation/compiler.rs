@@ -1,34 +1,43 @@
 use llvm_sys::analysis::LLVMVerifierFailureAction::LLVMPrintMessageAction;
 use llvm_sys::analysis::LLVMVerifyFunction;
+use std::collections::HashMap;
+use std::cmp::Ordering;
 use std::ffi::{c_char, c_uint, CStr, CString};
 use std::mem::MaybeUninit;
 use std::path::Path;
 
 use llvm_sys::core::*;
-use llvm_sys::LLVMIntPredicate;
+use llvm_sys::{LLVMIntPredicate, LLVMRealPredicate};
 use llvm_sys::error::LLVMGetErrorMessage;
 use llvm_sys::prelude::*;
 use llvm_sys::target::{
+    LLVMABIAlignmentOfType, LLVMABISizeOfType, LLVMGetModuleDataLayout,
     LLVMSetModuleDataLayout, LLVM_InitializeNativeAsmParser, LLVM_InitializeNativeAsmPrinter,
     LLVM_InitializeNativeTarget,
 };
 use llvm_sys::target_machine::LLVMCodeGenFileType::LLVMObjectFile;
 use llvm_sys::target_machine::LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault;
 use llvm_sys::target_machine::LLVMCodeModel::LLVMCodeModelDefault;
-use llvm_sys::target_machine::LLVMRelocMode::LLVMRelocDefault;
+use llvm_sys::target_machine::LLVMRelocMode::LLVMRelocPIC;
 use llvm_sys::target_machine::{
     LLVMCreateTargetDataLayout, LLVMCreateTargetMachine, LLVMDisposeTargetMachine,
     LLVMGetDefaultTargetTriple, LLVMGetTargetFromTriple, LLVMTarget, LLVMTargetMachineEmitToFile,
     LLVMTargetMachineRef,
 };
 use llvm_sys::transforms::pass_builder::*;
-use llvm_sys::LLVMLinkage::{LLVMExternalLinkage, LLVMInternalLinkage};
+use llvm_sys::LLVMLinkage::{LLVMExternalLinkage, LLVMInternalLinkage, LLVMPrivateLinkage};
+use llvm_sys::LLVMUnnamedAddr;
 
 use crate::ast::*;
+use crate::error::{ErrorKind, FlickError};
+use crate::span::{FileId, Span};
 use crate::typed_ast::*;
-use crate::types::{Type, IntType};
+use crate::types::{Type, FloatType, IntType};
 use crate::ScopeManager;
 
+use super::abi::{AbiValue, FuncAbi, PassMode, TargetAbi};
+use super::error::CompilationError;
+
 /// Converts a `&str`, like `"hi"`, into a pointer to a null-terminated C-style str.
 macro_rules! cstr {
     ($str_literal:expr) => {
@@ -36,6 +45,31 @@ macro_rules! cstr {
     };
 }
 
+/// Settings that change the code generated by a [Compiler].
+#[derive(Debug, Clone)]
+pub struct CompilerOptions {
+    /// Whether to check that array indices are in bounds at runtime, aborting the program with a
+    /// message if they aren't (indexing out of bounds is undefined behavior otherwise)
+    pub bounds_checks: bool,
+    /// Whether to check that integer arithmetic doesn't overflow its type at runtime, aborting the
+    /// program with a message if it does (it wraps around otherwise). Dividing by zero aborts the
+    /// program either way
+    pub overflow_checks: bool,
+    /// The names of the source files, which runtime error messages refer to (unnamed files are
+    /// called `<unknown>`)
+    pub file_names: HashMap<FileId, String>,
+}
+
+impl Default for CompilerOptions {
+    fn default() -> Self {
+        Self {
+            bounds_checks: true,
+            overflow_checks: true,
+            file_names: HashMap::new(),
+        }
+    }
+}
+
 /// A struct that takes an [abstract syntax tree][a] and converts it into LLVM code.
 ///
 /// # Example usage
@@ -48,8 +82,8 @@ macro_rules! cstr {
 ///     # global_statements: vec![]
 /// };
 /// compiler.compile(&syntax_tree);
-/// compiler.optimize();
-/// compiler.print_ir();  // or compiler.to_file("out")
+/// compiler.optimize().unwrap();
+/// compiler.print_ir();  // or compiler.to_file("out").unwrap()
 /// ```
 ///
 /// [a]: crate::parsing::ast
@@ -60,11 +94,37 @@ pub struct Compiler {
     target_machine: LLVMTargetMachineRef,
     scope_manager: ScopeManager<LLVMValueRef>,
     pass_builder: LLVMPassBuilderOptionsRef,
+    /// The loops enclosing the statement being compiled, from outermost to innermost
+    loops: Vec<LoopBlocks>,
+    /// The LLVM named struct types of the program's structs and enums
+    named_types: HashMap<String, LLVMTypeRef>,
+    /// The LLVM struct types of the payloads of each enum's variants, in order
+    enum_payloads: HashMap<String, Vec<LLVMTypeRef>>,
+    /// How functions pass their parameters and return values
+    abi: TargetAbi,
+    /// How the function being compiled returns its value
+    ret_mode: PassMode,
+    options: CompilerOptions,
+}
+
+/// The basic blocks that `continue` and `break` jump to inside of a loop.
+struct LoopBlocks {
+    label: Option<String>,
+    /// The block that starts the next iteration (like the condition of a while loop)
+    continue_block: LLVMBasicBlockRef,
+    /// The block after the loop
+    exit_block: LLVMBasicBlockRef,
 }
 
 impl Compiler {
-    /// Creates a new instance, setting up relevant llvm-sys boilerplate.
+    /// Creates a new instance with the default [CompilerOptions], setting up relevant llvm-sys
+    /// boilerplate.
     pub fn new() -> Self {
+        Self::with_options(CompilerOptions::default())
+    }
+
+    /// Creates a new instance that generates code according to `options`.
+    pub fn with_options(options: CompilerOptions) -> Self {
         unsafe {
             let context = LLVMContextCreate();
             let module = LLVMModuleCreateWithNameInContext(cstr!("module"), context);
@@ -95,7 +155,9 @@ impl Compiler {
                 cpu,
                 features,
                 LLVMCodeGenLevelDefault,
-                LLVMRelocDefault,
+                // Position-independent, since linkers like gcc produce position-independent executables
+                // by default (which matters once the program has global data, like error messages)
+                LLVMRelocPIC,
                 LLVMCodeModelDefault,
             );
 
@@ -105,6 +167,8 @@ impl Compiler {
             // Configure pass manager
             let pass_builder = LLVMCreatePassBuilderOptions();
 
+            let abi = TargetAbi::new(context, module);
+
             Self {
                 context,
                 module,
@@ -112,6 +176,12 @@ impl Compiler {
                 target_machine,
                 scope_manager,
                 pass_builder,
+                loops: Vec::new(),
+                named_types: HashMap::new(),
+                enum_payloads: HashMap::new(),
+                abi,
+                ret_mode: PassMode::Direct,
+                options,
             }
         }
     }
@@ -139,21 +209,22 @@ impl Compiler {
     /// This function optimizes the LLVM IR generated so far (via methods like [compile][a]).
     ///
     /// [a]: Compiler::compile
-    pub fn optimize(&mut self) {
+    pub fn optimize(&mut self) -> crate::Result<()> {
         unsafe {
             let passes = cstr!("default<O1>");
             let res = LLVMRunPasses(self.module, passes, self.target_machine, self.pass_builder);
             if !res.is_null() {
-                let error_string = CStr::from_ptr(LLVMGetErrorMessage(res));
-                panic!("Error running optimizations: {:?}", error_string);
+                let message = CStr::from_ptr(LLVMGetErrorMessage(res)).to_string_lossy().into_owned();
+                return Err(Self::err(CompilationError::Optimization(message)));
             }
         }
+        Ok(())
     }
 
     /// This function dumps to a file the LLVM IR generated so far (via methods like [compile][a]).
     ///
     /// [a]: Compiler::compile
-    pub fn to_file(&self, path: &impl AsRef<Path>) {
+    pub fn to_file(&self, path: &impl AsRef<Path>) -> crate::Result<()> {
         unsafe {
             let mut path_cchars: Vec<_> = path
                 .as_ref()
@@ -173,10 +244,22 @@ impl Compiler {
             );
 
             if result == 1 {
-                // TODO: Actually print error
-                panic!("Error emitting object file ({:?})", err_str.assume_init());
+                let err_str = err_str.assume_init();
+                let message = CStr::from_ptr(err_str).to_string_lossy().into_owned();
+                LLVMDisposeMessage(err_str);
+                return Err(Self::err(CompilationError::ObjectEmission(message)));
             }
         }
+        Ok(())
+    }
+
+    /// Wraps a [CompilationError] as a [FlickError]; since these errors are about the whole
+    /// program rather than any particular code, they don't point anywhere.
+    fn err(kind: CompilationError) -> FlickError {
+        FlickError {
+            span: Span::default(),
+            kind: ErrorKind::CompilationError(kind),
+        }
     }
 
     /// This function compiles the provided program; once compiled, its LLVM IR can be [optimized][a],
@@ -191,17 +274,20 @@ impl Compiler {
     pub fn compile(&mut self, program: &TypedProgram) {
         unsafe {
             self.scope_manager.enter_scope();
+            self.compile_named_types(program);
             for global_statement in program.global_statements.iter() {
                 // TODO: In the future when we have additional global statements, maybe move this into a new function called 'preprocess_global_statement' or something like that
                 match global_statement {
                     TypedGlobalStatement::Extern(p) => self.compile_func_proto(p),
-                    TypedGlobalStatement::FuncDef(f) => self.compile_func_proto(&f.proto)
+                    TypedGlobalStatement::FuncDef(f) => self.compile_func_proto(&f.proto),
+                    TypedGlobalStatement::GlobalVar(global_var) => self.compile_global_var(global_var),
+                    TypedGlobalStatement::Struct(_) | TypedGlobalStatement::Enum(_) => {}
                 }
             }
             for global_statement in program.global_statements.iter() {
                 self.compile_global_statement(global_statement);
             }
-            self.scope_manager.exit_scope()
+            self.scope_manager.exit_scope();
         }
     }
 
@@ -212,14 +298,147 @@ impl Compiler {
         }
     }
 
+    /// Compiles a `static mut` into an LLVM global variable, which is only visible outside of the
+    /// module if it's public. Constants aren't compiled, since the typer replaced their uses with
+    /// their values.
+    unsafe fn compile_global_var(&mut self, global_var: &TypedGlobalVar) {
+        let GlobalVarKind::StaticMut { is_public } = global_var.kind else {
+            return;
+        };
+        let var_type = self.to_llvm_type(&global_var.var_type);
+        let var_name = CString::new(global_var.var_name.as_str()).unwrap();
+        let global = LLVMAddGlobal(self.module, var_type, var_name.as_ptr());
+        LLVMSetInitializer(global, self.compile_const(&global_var.var_value));
+        match is_public {
+            true => LLVMSetLinkage(global, LLVMExternalLinkage),
+            false => LLVMSetLinkage(global, LLVMInternalLinkage),
+        }
+        self.scope_manager.set(&global_var.var_name, global);
+    }
+
+    /// Compiles a constant expression (as evaluated by the typer, so it only consists of
+    /// literals) into an LLVM constant, which doesn't need a builder.
+    // (LLVMConstArray2 is missing from older versions of LLVM)
+    #[allow(deprecated)]
+    unsafe fn compile_const(&mut self, expr: &TypedExpr) -> LLVMValueRef {
+        match &expr.kind {
+            TypedExprKind::StructLiteral(literal) => {
+                let mut fields: Vec<_> = literal.fields.iter().collect();
+                fields.sort_by_key(|(field_index, _)| *field_index);
+                let mut values: Vec<_> = fields.into_iter().map(|(_, value)| self.compile_const(value)).collect();
+                let struct_type = self.to_llvm_type(&Type::Named(literal.struct_name.clone()));
+                LLVMConstNamedStruct(struct_type, values.as_mut_ptr(), values.len() as c_uint)
+            }
+            TypedExprKind::ArrayLiteral(literal) => {
+                let mut elements: Vec<_> = literal.elements.iter().map(|element| self.compile_const(element)).collect();
+                let element_type = self.to_llvm_type(&literal.element_type);
+                LLVMConstArray(element_type, elements.as_mut_ptr(), elements.len() as c_uint)
+            }
+            TypedExprKind::ArrayRepeat(repeat) => {
+                let value = self.compile_const(&repeat.value);
+                let mut elements = vec![value; repeat.length as usize];
+                LLVMConstArray(LLVMTypeOf(value), elements.as_mut_ptr(), elements.len() as c_uint)
+            }
+            TypedExprKind::Identifier(_)
+            | TypedExprKind::Binary(_)
+            | TypedExprKind::Comparison(_)
+            | TypedExprKind::Logical(_)
+            | TypedExprKind::Call(_)
+            | TypedExprKind::ArithmeticCall(_)
+            | TypedExprKind::Unary(_)
+            | TypedExprKind::FieldAccess(_)
+            | TypedExprKind::EnumLiteral(_)
+            | TypedExprKind::Match(_)
+            | TypedExprKind::Index(_) => unreachable!("the typer only allows literals in constant expressions"),
+            // The remaining literals are constants already
+            _ => self.compile_expr(expr),
+        }
+    }
+
+    /// Creates an LLVM named struct type for each struct and enum of the program.
+    ///
+    /// All of the types are created before any of their bodies are set, since a type's fields (or
+    /// payloads) can use types defined after it.
+    unsafe fn compile_named_types(&mut self, program: &TypedProgram) {
+        let mut struct_defs = Vec::new();
+        let mut enum_defs = Vec::new();
+        for global_statement in program.global_statements.iter() {
+            let name = match global_statement {
+                TypedGlobalStatement::Struct(struct_def) => {
+                    struct_defs.push(struct_def);
+                    &struct_def.name
+                }
+                TypedGlobalStatement::Enum(enum_def) => {
+                    enum_defs.push(enum_def);
+                    &enum_def.name
+                }
+                _ => continue,
+            };
+            let type_name = CString::new(name.as_str()).unwrap();
+            let named_type = LLVMStructCreateNamed(self.context, type_name.as_ptr());
+            self.named_types.insert(name.clone(), named_type);
+        }
+
+        for struct_def in struct_defs.iter() {
+            let mut field_types: Vec<_> = struct_def
+                .fields
+                .iter()
+                .map(|field| self.to_llvm_type(&field.field_type))
+                .collect();
+            let struct_type = self.named_types[&struct_def.name];
+            LLVMStructSetBody(struct_type, field_types.as_mut_ptr(), field_types.len() as c_uint, 0);
+        }
+
+        // An enum's size depends on the sizes of its payloads, which might contain other enums, so
+        // each enum's body is set once all of its payloads have a known size (the typer already
+        // rejected enums that contain themselves)
+        let mut remaining_enums = enum_defs;
+        while !remaining_enums.is_empty() {
+            remaining_enums.retain(|enum_def| !self.compile_enum_type(enum_def));
+        }
+    }
+
+    /// Sets the body of an enum's LLVM type, returning `false` (and leaving it unset) if one of its
+    /// payloads doesn't have a known size yet.
+    ///
+    /// An enum is a tagged union, `{ i32, [N x iA] }`: the tag is the index of the variant, and
+    /// the array is big enough (and aligned enough) to hold the payload of any variant. A
+    /// variant's payload is stored as a struct of its payload types, at the array's address.
+    unsafe fn compile_enum_type(&mut self, enum_def: &EnumDef) -> bool {
+        let payload_types: Vec<_> = enum_def
+            .variants
+            .iter()
+            .map(|variant| {
+                let mut element_types: Vec<_> = variant.payload_types.iter().map(|t| self.to_llvm_type(t)).collect();
+                LLVMStructTypeInContext(self.context, element_types.as_mut_ptr(), element_types.len() as c_uint, 0)
+            })
+            .collect();
+        if payload_types.iter().any(|&payload_type| LLVMTypeIsSized(payload_type) == 0) {
+            return false;
+        }
+
+        let target_data = LLVMGetModuleDataLayout(self.module);
+        let size = payload_types.iter().map(|&t| LLVMABISizeOfType(target_data, t)).max().unwrap_or(0);
+        let alignment = payload_types.iter().map(|&t| LLVMABIAlignmentOfType(target_data, t)).max().unwrap_or(1);
+        let element_type = LLVMIntTypeInContext(self.context, alignment * 8);
+        // (LLVMArrayType2 is missing from older versions of LLVM)
+        #[allow(deprecated)]
+        let payload_storage = LLVMArrayType(element_type, size.div_ceil(alignment as u64) as c_uint);
+
+        let mut field_types = [LLVMInt32TypeInContext(self.context), payload_storage];
+        LLVMStructSetBody(self.named_types[&enum_def.name], field_types.as_mut_ptr(), 2, 0);
+        self.enum_payloads.insert(enum_def.name.clone(), payload_types);
+        true
+    }
+
     /// Registers a function prototype, panicking if the function has already been defined.
     unsafe fn compile_func_proto(&mut self, func_proto: &FuncProto) {
         if self.scope_manager.get(&func_proto.name).is_some() {
             panic!("Cannot redefine '{}'", func_proto.name);
         }
 
-        let func_type = Type::Func(func_proto.clone());
-        let func_llvm_type = self.to_llvm_type(&func_type);
+        let func_abi = self.func_abi(func_proto);
+        let func_llvm_type = func_abi.llvm_type(self.context);
         let func_name = CString::new(func_proto.name.as_str()).unwrap();
         let func = LLVMAddFunction(self.module, func_name.as_ptr(), func_llvm_type);
 
@@ -227,8 +446,11 @@ impl Compiler {
             panic!("Error defining function '{}'", func_proto.name);
         }
 
+        for (index, attribute) in func_abi.attributes(self.context) {
+            LLVMAddAttributeAtIndex(func, index, attribute);
+        }
         for (i, param) in func_proto.params.iter().enumerate() {
-            let param_value_ref = LLVMGetParam(func, i as c_uint);
+            let param_value_ref = LLVMGetParam(func, func_abi.param_index(i));
             let param_name = CString::new(param.param_name.as_str()).unwrap();
             let param_name_len = param.param_name.len();
             LLVMSetValueName2(param_value_ref, param_name.as_ptr(), param_name_len);
@@ -263,13 +485,16 @@ impl Compiler {
 
         self.scope_manager.enter_scope();
 
+        let func_abi = self.func_abi(&func_def.proto);
+        self.ret_mode = func_abi.ret.mode;
         for (i, param) in func_def.proto.params.iter().enumerate() {
             let param_name = param.param_name.as_str();
             let param_type = &param.param_type;
-            let param_value_ref = LLVMGetParam(func, i as c_uint);
+            let param_value_ref = LLVMGetParam(func, func_abi.param_index(i));
+            let param_value = self.lift_abi_value(param_value_ref, func_abi.params[i]);
             let alloca = self.create_alloca(param_name, param_type);
 
-            LLVMBuildStore(self.builder, param_value_ref, alloca);
+            LLVMBuildStore(self.builder, param_value, alloca);
             self.scope_manager.set(param_name, alloca);
         }
 
@@ -285,14 +510,25 @@ impl Compiler {
 
     /// Compiles a statement, assuming the LLVM builder is building inside a function body.
     unsafe fn compile_statement(&mut self, statement: &TypedStatement) {
-        match statement {
-            TypedStatement::VarDeclaration(v) => self.compile_var_declaration(v),
-            TypedStatement::WhileLoop(w) => self.compile_while_loop(w),
-            TypedStatement::Assignment(a) => self.compile_assignment_statement(a),
-            TypedStatement::Return(r) => self.compile_ret_statement(r),
-            TypedStatement::Call(c) => _ = self.compile_call(c),
-            TypedStatement::If(i) => self.compile_if_statement(i),
-            TypedStatement::Unreachable => self.compile_unreachable(),
+        match &statement.kind {
+            TypedStatementKind::VarDeclaration(v) => self.compile_var_declaration(v),
+            TypedStatementKind::WhileLoop(w) => self.compile_while_loop(w),
+            TypedStatementKind::Loop(l) => self.compile_loop(l),
+            TypedStatementKind::ForLoop(f) => self.compile_for_loop(f),
+            TypedStatementKind::Break(label) => {
+                let exit_block = self.get_loop(label.as_deref()).exit_block;
+                LLVMBuildBr(self.builder, exit_block);
+            }
+            TypedStatementKind::Continue(label) => {
+                let continue_block = self.get_loop(label.as_deref()).continue_block;
+                LLVMBuildBr(self.builder, continue_block);
+            }
+            TypedStatementKind::Assignment(a) => self.compile_assignment_statement(a),
+            TypedStatementKind::Return(r) => self.compile_ret_statement(r),
+            TypedStatementKind::Call(c) => _ = self.compile_call(c),
+            TypedStatementKind::If(i) => self.compile_if_statement(i),
+            TypedStatementKind::Match(m) => self.compile_match(m, |compiler, body| compiler.compile_body(body)),
+            TypedStatementKind::Unreachable => self.compile_unreachable(),
         }
     }
 
@@ -333,15 +569,163 @@ impl Compiler {
         LLVMAppendExistingBasicBlock(cur_func, loop_block);
         LLVMPositionBuilderAtEnd(self.builder, loop_block);
         
+        self.loops.push(LoopBlocks {
+            label: while_loop.label.clone(),
+            continue_block: cond_block,
+            exit_block: after_block,
+        });
         if !self.compile_body(&while_loop.body) {
             LLVMBuildBr(self.builder, cond_block);
         }
+        self.loops.pop();
         
         LLVMAppendExistingBasicBlock(cur_func, after_block);
         LLVMPositionBuilderAtEnd(self.builder, after_block);
     }
 
-    /// Compiles an assignment expression like `foo = 28` (and panics if `foo`'s type can't store 28).
+    /// Compiles an infinite loop, assuming the LLVM builder is building inside a function body.
+    ///
+    /// If nothing breaks out of the loop, the block after it has no predecessors; anything built
+    /// there is unreachable (but the block still gets terminated by whatever follows the loop).
+    unsafe fn compile_loop(&mut self, loop_statement: &TypedLoop) {
+        let cur_func = match self.get_cur_function() {
+            Some(func) => func,
+            None => panic!("Cannot compile loop outside of a function"),
+        };
+        let loop_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("loop"));
+        let after_block = LLVMCreateBasicBlockInContext(self.context, cstr!("after"));
+
+        LLVMBuildBr(self.builder, loop_block);
+        LLVMPositionBuilderAtEnd(self.builder, loop_block);
+
+        self.loops.push(LoopBlocks {
+            label: loop_statement.label.clone(),
+            continue_block: loop_block,
+            exit_block: after_block,
+        });
+        if !self.compile_body(&loop_statement.body) {
+            LLVMBuildBr(self.builder, loop_block);
+        }
+        self.loops.pop();
+
+        LLVMAppendExistingBasicBlock(cur_func, after_block);
+        LLVMPositionBuilderAtEnd(self.builder, after_block);
+    }
+
+    /// Compiles a for loop, assuming the LLVM builder is building inside a function body.
+    ///
+    /// The range and step are evaluated once, before the loop. A hidden counter holds the value
+    /// of the current iteration, and is copied into the loop variable at the start of the body (so
+    /// assigning to the loop variable doesn't change how many iterations there are). Instead of
+    /// adding the step and then comparing with the end (which could overflow, like the last
+    /// iteration of `0..=255` for a `u8`), the step block compares the step with the distance that's
+    /// left until the end:
+    ///
+    /// ```text
+    /// <current>: %counter = start; br (start < end), label %loop, label %after
+    /// loop:      %i = %counter; <body>; br label %step
+    /// step:      %remaining = end - %counter
+    ///            %counter = %counter + step
+    ///            br (%remaining <= step), label %after, label %loop
+    /// after:
+    /// ```
+    ///
+    /// (For inclusive ranges, `<` and `<=` are swapped with `<=` and `<`.)
+    unsafe fn compile_for_loop(&mut self, for_loop: &TypedForLoop) {
+        use LLVMIntPredicate::*;
+
+        let cur_func = match self.get_cur_function() {
+            Some(func) => func,
+            None => panic!("Cannot compile for loop outside of a function"),
+        };
+        let var_type = Type::Int(for_loop.var_type);
+        let llvm_type = self.to_llvm_type(&var_type);
+
+        let counter = self.create_alloca("counter", &var_type);
+        let var_alloca = self.create_alloca(&for_loop.var_name, &var_type);
+        let start = self.compile_expr(&for_loop.start);
+        let end = self.compile_expr(&for_loop.end);
+        let step = match &for_loop.step {
+            Some(step) => self.compile_expr(step),
+            None => LLVMConstInt(llvm_type, 1, 0),
+        };
+        // The typer rejects literal steps that aren't positive, but other steps are only known now
+        if let Some(step_expr) = for_loop.step.as_ref().filter(|step| !matches!(step.kind, TypedExprKind::IntLiteral(_))) {
+            let zero = LLVMConstInt(llvm_type, 0, 0);
+            let predicate = match for_loop.var_type.signed {
+                true => LLVMIntSLE,
+                false => LLVMIntEQ,
+            };
+            let non_positive = LLVMBuildICmp(self.builder, predicate, step, zero, cstr!("non_positive"));
+            self.compile_runtime_check(non_positive, step_expr.span, "for loop step must be positive");
+        }
+        LLVMBuildStore(self.builder, start, counter);
+
+        let loop_block = LLVMCreateBasicBlockInContext(self.context, cstr!("loop"));
+        let step_block = LLVMCreateBasicBlockInContext(self.context, cstr!("step"));
+        let after_block = LLVMCreateBasicBlockInContext(self.context, cstr!("after"));
+
+        let start_operator = match for_loop.inclusive {
+            true => ComparisonOperator::LessOrEqualTo,
+            false => ComparisonOperator::LessThan,
+        };
+        let predicate = self.comparison_int_op(start_operator, for_loop.var_type);
+        let in_range = LLVMBuildICmp(self.builder, predicate, start, end, cstr!("in_range"));
+        LLVMBuildCondBr(self.builder, in_range, loop_block, after_block);
+
+        // ------------------------ LOOP BLOCK ------------------------------
+        LLVMAppendExistingBasicBlock(cur_func, loop_block);
+        LLVMPositionBuilderAtEnd(self.builder, loop_block);
+        let value = LLVMBuildLoad2(self.builder, llvm_type, counter, cstr!(""));
+        LLVMBuildStore(self.builder, value, var_alloca);
+
+        self.scope_manager.enter_scope();
+        self.scope_manager.set(&for_loop.var_name, var_alloca);
+        self.loops.push(LoopBlocks {
+            label: for_loop.label.clone(),
+            continue_block: step_block,
+            exit_block: after_block,
+        });
+        if !self.compile_body(&for_loop.body) {
+            LLVMBuildBr(self.builder, step_block);
+        }
+        self.loops.pop();
+        self.scope_manager.exit_scope();
+
+        // ------------------------ STEP BLOCK ------------------------------
+        LLVMAppendExistingBasicBlock(cur_func, step_block);
+        LLVMPositionBuilderAtEnd(self.builder, step_block);
+        let value = LLVMBuildLoad2(self.builder, llvm_type, counter, cstr!(""));
+        // The counter never passes the end, so this is the exact distance (even for signed types,
+        // once it's treated as unsigned), and it's compared to the step as unsigned too, since the
+        // step is always positive
+        let remaining = LLVMBuildSub(self.builder, end, value, cstr!("remaining"));
+        let done_predicate = match for_loop.inclusive {
+            true => LLVMIntULT,
+            false => LLVMIntULE,
+        };
+        let done = LLVMBuildICmp(self.builder, done_predicate, remaining, step, cstr!("done"));
+        let next = LLVMBuildAdd(self.builder, value, step, cstr!("next"));
+        LLVMBuildStore(self.builder, next, counter);
+        LLVMBuildCondBr(self.builder, done, after_block, loop_block);
+
+        // ------------------------ AFTER BLOCK ------------------------------
+        LLVMAppendExistingBasicBlock(cur_func, after_block);
+        LLVMPositionBuilderAtEnd(self.builder, after_block);
+    }
+
+    /// Returns the blocks of the loop labeled `label`, or of the innermost loop if `label` is
+    /// `None`, panicking if there's no such loop (the typer already checks for this).
+    fn get_loop(&self, label: Option<&str>) -> &LoopBlocks {
+        let found = match label {
+            Some(label) => self.loops.iter().rev().find(|l| l.label.as_deref() == Some(label)),
+            None => self.loops.last(),
+        };
+        found.expect("'break' and 'continue' should only target enclosing loops")
+    }
+
+    /// Compiles an assignment expression like `foo = 28`, `foo.bar = 28`, `foo[i] = 28` or
+    /// `*foo = 28` (and panics if the target's type can't store 28).
     // TODO: should we remove the panics from here since they're already in Typer
     unsafe fn compile_assignment_statement(&mut self, assign: &TypedAssignment) {
         let alloca = match self.scope_manager.get(&assign.name) {
@@ -354,8 +738,33 @@ impl Compiler {
             panic!("Cannot assign a value to function '{}'", assign.name);
         }
 
+        // Walk down to the assigned field or element (if any)
+        let mut target = alloca;
+        let mut target_type = match LLVMIsAGlobalVariable(alloca).is_null() {
+            true => LLVMGetAllocatedType(alloca),
+            // Statics are global variables rather than allocas
+            false => LLVMGlobalGetValueType(alloca),
+        };
+        for accessor in assign.accessors.iter() {
+            match accessor {
+                TypedAccessor::Field(field_index) => {
+                    let field_index = *field_index as c_uint;
+                    target = LLVMBuildStructGEP2(self.builder, target_type, target, field_index, cstr!("field"));
+                    target_type = LLVMStructGetTypeAtIndex(target_type, field_index);
+                }
+                TypedAccessor::Index(index) => {
+                    target = self.compile_element_ptr(target_type, target, index);
+                    target_type = LLVMGetElementType(target_type);
+                }
+                TypedAccessor::Deref(pointee_type) => {
+                    target = LLVMBuildLoad2(self.builder, target_type, target, cstr!("ptr"));
+                    target_type = self.to_llvm_type(pointee_type);
+                }
+            }
+        }
+
         let value = self.compile_expr(&assign.value);
-        LLVMBuildStore(self.builder, value, alloca);
+        LLVMBuildStore(self.builder, value, target);
     }
 
     /// Compiles a return statement, panicking if the builder isn't inside a function.
@@ -364,22 +773,37 @@ impl Compiler {
             panic!("Cannot compile ret statement outside of a function");
         }
 
-        match ret_value {
-            Some(expr) => LLVMBuildRet(self.builder, self.compile_expr(expr)),
-            None => LLVMBuildRetVoid(self.builder),
+        let value = match ret_value {
+            Some(expr) => self.compile_expr(expr),
+            None => {
+                LLVMBuildRetVoid(self.builder);
+                return;
+            }
+        };
+        match self.ret_mode {
+            PassMode::Direct => LLVMBuildRet(self.builder, value),
+            PassMode::Coerced(coerced_type) => LLVMBuildRet(self.builder, self.coerce(value, coerced_type)),
+            PassMode::Indirect { .. } => {
+                let func = self.get_cur_function().unwrap();
+                LLVMBuildStore(self.builder, value, LLVMGetParam(func, 0));
+                LLVMBuildRetVoid(self.builder)
+            }
+            PassMode::CString => unreachable!("only extern functions return C strings, and they have no body"),
         };
     }
 
     /// Compiles a function body, assuming the LLVM builder is building inside a function body.
     /// 
-    /// Returns `true` if the body returns (contains a return statement), `false` otherwise.
+    /// Returns `true` if the body is terminated (contains a return, break or continue statement),
+    /// `false` otherwise.
     unsafe fn compile_body(&mut self, body: &[TypedStatement]) -> bool {
         let mut body_returns = false;
         self.scope_manager.enter_scope();
         for statement in body {
             self.compile_statement(statement);
             // If we have a termination instruction, stop compiling statements
-            if let TypedStatement::Return(_) = statement { 
+            use TypedStatementKind::{Break, Continue, Return};
+            if let Return(_) | Break(_) | Continue(_) = statement.kind {
                 body_returns = true;
                 break;
              }
@@ -447,15 +871,453 @@ impl Compiler {
     /// Note: if `expected_type` is `None`, then no type-checking is performed (because the caller
     /// doesn't actually know what the type must be).
     unsafe fn compile_expr(&mut self, expr: &TypedExpr) -> LLVMValueRef {
-        match expr {
-            TypedExpr::Identifier(id) => self.compile_identifier(id),
-            TypedExpr::IntLiteral(int_literal) => self.compile_int_literal(int_literal),
-            TypedExpr::BoolLiteral(bool_literal) => self.compile_bool_literal(*bool_literal),
-            TypedExpr::Binary(bin_expr) => self.compile_bin_expr(bin_expr),
-            TypedExpr::Comparison(comparison) => self.compile_comparison_expr(comparison),
-            TypedExpr::Call(call) => self.compile_call(call),
-            TypedExpr::Unary(unary) => self.compile_unary(unary),
+        match &expr.kind {
+            TypedExprKind::Identifier(id) => self.compile_identifier(id),
+            TypedExprKind::IntLiteral(int_literal) => self.compile_int_literal(int_literal),
+            TypedExprKind::FloatLiteral(float_literal) => self.compile_float_literal(float_literal),
+            TypedExprKind::BoolLiteral(bool_literal) => self.compile_bool_literal(*bool_literal),
+            TypedExprKind::StrLiteral(str_literal) => self.compile_str_literal(str_literal),
+            TypedExprKind::CharLiteral(c) => LLVMConstInt(self.to_llvm_type(&Type::Char), *c as u64, 0),
+            TypedExprKind::Null(pointer_type) => LLVMConstNull(self.to_llvm_type(pointer_type)),
+            TypedExprKind::Binary(bin_expr) => self.compile_bin_expr(bin_expr, expr.span),
+            TypedExprKind::Comparison(comparison) => self.compile_comparison_expr(comparison),
+            TypedExprKind::Logical(logical) => self.compile_logical_expr(logical),
+            TypedExprKind::Call(call) => self.compile_call(call),
+            TypedExprKind::ArithmeticCall(call) => self.compile_arithmetic_call(call),
+            TypedExprKind::Unary(unary) => self.compile_unary(unary, expr.span),
+            TypedExprKind::StructLiteral(literal) => self.compile_struct_literal(literal),
+            TypedExprKind::FieldAccess(access) => {
+                let value = self.compile_expr(&access.expr);
+                LLVMBuildExtractValue(self.builder, value, access.field_index as c_uint, cstr!("field"))
+            }
+            TypedExprKind::EnumLiteral(literal) => self.compile_enum_literal(literal),
+            TypedExprKind::Match(m) => self.compile_match_expr(m),
+            TypedExprKind::ArrayLiteral(literal) => self.compile_array_literal(literal),
+            TypedExprKind::ArrayRepeat(repeat) => self.compile_array_repeat(repeat),
+            TypedExprKind::Index(index) => self.compile_index(index),
+        }
+    }
+
+    /// Compiles an array literal like `[1, 2, 3]`, by inserting each element into the array.
+    unsafe fn compile_array_literal(&mut self, literal: &TypedArrayLiteral) -> LLVMValueRef {
+        let element_type = Box::new(literal.element_type.clone());
+        let array_type = self.to_llvm_type(&Type::Array(element_type, literal.elements.len() as u64));
+        let mut array = LLVMGetUndef(array_type);
+        for (i, element) in literal.elements.iter().enumerate() {
+            let element = self.compile_expr(element);
+            array = LLVMBuildInsertValue(self.builder, array, element, i as c_uint, cstr!(""));
+        }
+        array
+    }
+
+    /// Compiles a repeated array literal like `[0; 16]`, by storing the value into each element
+    /// of the array with a loop (unless the value is zero, which is just a constant).
+    unsafe fn compile_array_repeat(&mut self, repeat: &TypedArrayRepeat) -> LLVMValueRef {
+        let element_type = Box::new(repeat.value.get_result_type());
+        let array_type = self.to_llvm_type(&Type::Array(element_type, repeat.length));
+        let value = self.compile_expr(&repeat.value);
+        if LLVMIsNull(value) == 1 {
+            return LLVMConstNull(array_type);
+        }
+        if repeat.length == 0 {
+            return LLVMGetUndef(array_type);
+        }
+
+        let cur_func = self.get_cur_function().unwrap();
+        let i64_type = LLVMInt64TypeInContext(self.context);
+        let memory = self.create_entry_alloca(array_type);
+        let counter = self.create_entry_alloca(i64_type);
+        LLVMBuildStore(self.builder, LLVMConstInt(i64_type, 0, 0), counter);
+
+        let loop_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("repeat"));
+        let after_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("after_repeat"));
+        LLVMBuildBr(self.builder, loop_block);
+
+        LLVMPositionBuilderAtEnd(self.builder, loop_block);
+        let i = LLVMBuildLoad2(self.builder, i64_type, counter, cstr!("i"));
+        let mut indices = [LLVMConstInt(i64_type, 0, 0), i];
+        let element_ptr = LLVMBuildGEP2(self.builder, array_type, memory, indices.as_mut_ptr(), 2, cstr!("element"));
+        LLVMBuildStore(self.builder, value, element_ptr);
+        let next = LLVMBuildAdd(self.builder, i, LLVMConstInt(i64_type, 1, 0), cstr!("next"));
+        LLVMBuildStore(self.builder, next, counter);
+        let length = LLVMConstInt(i64_type, repeat.length, 0);
+        let done = LLVMBuildICmp(self.builder, LLVMIntPredicate::LLVMIntEQ, next, length, cstr!("done"));
+        LLVMBuildCondBr(self.builder, done, after_block, loop_block);
+
+        LLVMPositionBuilderAtEnd(self.builder, after_block);
+        LLVMBuildLoad2(self.builder, array_type, memory, cstr!("array"))
+    }
+
+    /// Compiles an indexing expression like `buf[i]`, by loading the element from the array's
+    /// memory (which is either the memory of a variable, or a copy of a temporary array).
+    unsafe fn compile_index(&mut self, index: &TypedIndex) -> LLVMValueRef {
+        let array_type = self.to_llvm_type(&index.expr.get_result_type());
+        let array_ptr = match self.compile_place(&index.expr) {
+            Some(array_ptr) => array_ptr,
+            None => {
+                let array = self.compile_expr(&index.expr);
+                let memory = self.create_entry_alloca(array_type);
+                LLVMBuildStore(self.builder, array, memory);
+                memory
+            }
+        };
+        let element_ptr = self.compile_element_ptr(array_type, array_ptr, &index.index);
+        LLVMBuildLoad2(self.builder, self.to_llvm_type(&index.element_type), element_ptr, cstr!("element"))
+    }
+
+    /// Returns a pointer to the memory that `expr` reads from, if it's a variable (or a field or
+    /// element of one) or a dereferenced pointer, so that its elements can be read without copying
+    /// the whole array (and so that its address can be taken).
+    unsafe fn compile_place(&mut self, expr: &TypedExpr) -> Option<LLVMValueRef> {
+        match &expr.kind {
+            TypedExprKind::Identifier(id) if !matches!(id.id_type, Type::Func(_)) => {
+                self.scope_manager.get(&id.name).copied()
+            }
+            TypedExprKind::FieldAccess(access) => {
+                let struct_ptr = self.compile_place(&access.expr)?;
+                let struct_type = self.to_llvm_type(&access.expr.get_result_type());
+                let field_index = access.field_index as c_uint;
+                Some(LLVMBuildStructGEP2(self.builder, struct_type, struct_ptr, field_index, cstr!("field")))
+            }
+            TypedExprKind::Index(index) => {
+                let array_ptr = self.compile_place(&index.expr)?;
+                let array_type = self.to_llvm_type(&index.expr.get_result_type());
+                Some(self.compile_element_ptr(array_type, array_ptr, &index.index))
+            }
+            TypedExprKind::Unary(unary) if unary.operator == UnaryOperator::Deref => {
+                Some(self.compile_expr(&unary.operand))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a pointer to the element at `index` of the array (of type `array_type`) stored at
+    /// `array_ptr`, checking that the index is in bounds first (unless bounds checks are off).
+    unsafe fn compile_element_ptr(
+        &mut self,
+        array_type: LLVMTypeRef,
+        array_ptr: LLVMValueRef,
+        index: &TypedExpr,
+    ) -> LLVMValueRef {
+        let Type::Int(index_type) = index.get_result_type() else {
+            unreachable!("the typer only allows integer indices");
+        };
+        let index_value = self.compile_expr(index);
+        if self.options.bounds_checks {
+            // (LLVMGetArrayLength2 is missing from older versions of LLVM)
+            #[allow(deprecated)]
+            let length = LLVMGetArrayLength(array_type) as u64;
+            self.compile_bounds_check(index_value, index_type, length, index.span);
+        }
+
+        let i64_type = LLVMInt64TypeInContext(self.context);
+        let index_value = LLVMBuildIntCast2(self.builder, index_value, i64_type, index_type.signed as LLVMBool, cstr!("index"));
+        let mut indices = [LLVMConstInt(i64_type, 0, 0), index_value];
+        LLVMBuildGEP2(self.builder, array_type, array_ptr, indices.as_mut_ptr(), 2, cstr!("element"))
+    }
+
+    /// Checks that `index` (of type `index_type`) is less than `length`, and aborts the program
+    /// with a message pointing at `span` if it isn't.
+    ///
+    /// Negative indices are sign-extended before being compared as unsigned integers, so they're
+    /// also out of bounds.
+    unsafe fn compile_bounds_check(&mut self, index: LLVMValueRef, index_type: IntType, length: u64, span: Span) {
+        let cur_func = self.get_cur_function().unwrap();
+        let signed = index_type.signed as LLVMBool;
+        let check_type = LLVMIntTypeInContext(self.context, index_type.width.max(64));
+        let index = LLVMBuildIntCast2(self.builder, index, check_type, signed, cstr!(""));
+        let length_value = LLVMConstInt(check_type, length, 0);
+        let in_bounds = LLVMBuildICmp(self.builder, LLVMIntPredicate::LLVMIntULT, index, length_value, cstr!("in_bounds"));
+
+        let out_of_bounds_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("out_of_bounds"));
+        let in_bounds_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("in_bounds"));
+        LLVMBuildCondBr(self.builder, in_bounds, in_bounds_block, out_of_bounds_block);
+
+        LLVMPositionBuilderAtEnd(self.builder, out_of_bounds_block);
+        let index = LLVMBuildIntCast2(self.builder, index, LLVMInt64TypeInContext(self.context), signed, cstr!(""));
+        let index_format = if index_type.signed { "%lld" } else { "%llu" };
+        let message = format!("index out of bounds: the length is {} but the index is {}", length, index_format);
+        self.compile_runtime_error(span, &message, &[index]);
+
+        LLVMPositionBuilderAtEnd(self.builder, in_bounds_block);
+    }
+
+    /// Aborts the program with `message` (see [Compiler::compile_runtime_error]) if `failed`, an
+    /// `i1`, is true.
+    unsafe fn compile_runtime_check(&mut self, failed: LLVMValueRef, span: Span, message: &str) {
+        let cur_func = self.get_cur_function().unwrap();
+        let failed_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("check_failed"));
+        let passed_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("check_passed"));
+        LLVMBuildCondBr(self.builder, failed, failed_block, passed_block);
+
+        LLVMPositionBuilderAtEnd(self.builder, failed_block);
+        self.compile_runtime_error(span, message, &[]);
+
+        LLVMPositionBuilderAtEnd(self.builder, passed_block);
+    }
+
+    /// Prints `message` (a `printf` format string, whose arguments are `args`) to stderr, after
+    /// the file, line and column of `span`, and then aborts the program. This terminates the
+    /// current block.
+    unsafe fn compile_runtime_error(&mut self, span: Span, message: &str, args: &[LLVMValueRef]) {
+        let file_name = match self.options.file_names.get(&span.file_id) {
+            Some(file_name) => file_name.replace('%', "%%"),
+            None => "<unknown>".to_string(),
+        };
+        let format = format!("{}:{}:{}: {}\n", file_name, span.start.line, span.start.column, message);
+        let format = CString::new(format).unwrap();
+        let format = LLVMBuildGlobalStringPtr(self.builder, format.as_ptr(), cstr!("runtime_error"));
+
+        let i32_type = LLVMInt32TypeInContext(self.context);
+        let mut dprintf_params = [i32_type, LLVMPointerType(LLVMInt8TypeInContext(self.context), 0)];
+        let dprintf_type = LLVMFunctionType(i32_type, dprintf_params.as_mut_ptr(), 2, 1);
+        let dprintf = self.get_runtime_function("dprintf", dprintf_type);
+        let stderr = LLVMConstInt(i32_type, 2, 0);
+        let mut dprintf_args: Vec<_> = [stderr, format].into_iter().chain(args.iter().copied()).collect();
+        LLVMBuildCall2(self.builder, dprintf_type, dprintf, dprintf_args.as_mut_ptr(), dprintf_args.len() as c_uint, cstr!(""));
+
+        let abort_type = LLVMFunctionType(LLVMVoidTypeInContext(self.context), std::ptr::null_mut(), 0, 0);
+        let abort = self.get_runtime_function("abort", abort_type);
+        LLVMBuildCall2(self.builder, abort_type, abort, std::ptr::null_mut(), 0, cstr!(""));
+        LLVMBuildUnreachable(self.builder);
+    }
+
+    /// Returns the C library function named `name` (of type `func_type`), declaring it if the
+    /// program hasn't already.
+    unsafe fn get_runtime_function(&self, name: &str, func_type: LLVMTypeRef) -> LLVMValueRef {
+        let name = CString::new(name).unwrap();
+        let func = LLVMGetNamedFunction(self.module, name.as_ptr());
+        if !func.is_null() {
+            return func;
         }
+        LLVMAddFunction(self.module, name.as_ptr(), func_type)
+    }
+
+    /// Compiles an enum literal, by storing its tag and payload into memory and loading the
+    /// whole enum back (see [Compiler::compile_enum_type] for the layout).
+    unsafe fn compile_enum_literal(&mut self, literal: &TypedEnumLiteral) -> LLVMValueRef {
+        let enum_type = self.named_types[&literal.enum_name];
+        let memory = self.create_entry_alloca(enum_type);
+
+        let tag = LLVMConstInt(LLVMInt32TypeInContext(self.context), literal.variant_index as u64, 0);
+        let tag_ptr = LLVMBuildStructGEP2(self.builder, enum_type, memory, 0, cstr!("tag"));
+        LLVMBuildStore(self.builder, tag, tag_ptr);
+
+        if !literal.payload.is_empty() {
+            let payload_type = self.enum_payloads[&literal.enum_name][literal.variant_index];
+            let mut payload = LLVMGetUndef(payload_type);
+            for (i, value) in literal.payload.iter().enumerate() {
+                let value = self.compile_expr(value);
+                payload = LLVMBuildInsertValue(self.builder, payload, value, i as c_uint, cstr!(""));
+            }
+            let payload_ptr = self.enum_payload_ptr(enum_type, memory, payload_type);
+            LLVMBuildStore(self.builder, payload, payload_ptr);
+        }
+
+        LLVMBuildLoad2(self.builder, enum_type, memory, cstr!(""))
+    }
+
+    /// Returns a pointer to the payload of the enum stored at `enum_ptr`, as a pointer to a
+    /// `payload_type` (the payload struct of one of the enum's variants).
+    unsafe fn enum_payload_ptr(
+        &self,
+        enum_type: LLVMTypeRef,
+        enum_ptr: LLVMValueRef,
+        payload_type: LLVMTypeRef,
+    ) -> LLVMValueRef {
+        let storage_ptr = LLVMBuildStructGEP2(self.builder, enum_type, enum_ptr, 1, cstr!("payload"));
+        LLVMBuildPointerCast(self.builder, storage_ptr, LLVMPointerType(payload_type, 0), cstr!(""))
+    }
+
+    /// Compiles a match expression, whose arms each store their value into memory that's loaded
+    /// once the match is done.
+    unsafe fn compile_match_expr(&mut self, m: &TypedMatch<TypedExpr>) -> LLVMValueRef {
+        let result_type = self.to_llvm_type(&m.result_type);
+        let result = self.create_entry_alloca(result_type);
+        self.compile_match(m, |compiler, arm_expr| {
+            let value = compiler.compile_expr(arm_expr);
+            LLVMBuildStore(compiler.builder, value, result);
+            false
+        });
+        LLVMBuildLoad2(self.builder, result_type, result, cstr!("match"))
+    }
+
+    /// Compiles a match, using `compile_arm` to compile the body of each arm (which returns
+    /// whether it terminated the block it was compiled in, like [Compiler::compile_body]).
+    ///
+    /// Enums are dispatched with a `switch` on their tag, which jumps to the first arm matching
+    /// each variant. Integers and bools are tested against each arm's pattern in order. Since the
+    /// typer checked that the match is exhaustive, falling through every arm is unreachable.
+    unsafe fn compile_match<Body>(
+        &mut self,
+        m: &TypedMatch<Body>,
+        mut compile_arm: impl FnMut(&mut Self, &Body) -> bool,
+    ) {
+        let cur_func = match self.get_cur_function() {
+            Some(func) => func,
+            None => panic!("Cannot compile match outside of a function"),
+        };
+        let scrutinee_type = m.scrutinee.get_result_type();
+        let scrutinee = self.compile_expr(&m.scrutinee);
+
+        let arm_blocks: Vec<_> = m
+            .arms
+            .iter()
+            .map(|_| LLVMCreateBasicBlockInContext(self.context, cstr!("arm")))
+            .collect();
+        let merge_block = LLVMCreateBasicBlockInContext(self.context, cstr!("merge"));
+
+        // Enums are kept in memory, so that the payloads of their variants can be read from it
+        let enum_ptr = match &scrutinee_type {
+            Type::Named(enum_name) => {
+                let enum_ptr = self.create_entry_alloca(self.named_types[enum_name]);
+                LLVMBuildStore(self.builder, scrutinee, enum_ptr);
+                self.compile_enum_switch(enum_name, enum_ptr, &m.arms, &arm_blocks);
+                Some((enum_name, enum_ptr))
+            }
+            _ => {
+                self.compile_pattern_tests(scrutinee, &scrutinee_type, &m.arms, &arm_blocks);
+                None
+            }
+        };
+
+        for (arm, &arm_block) in m.arms.iter().zip(arm_blocks.iter()) {
+            LLVMAppendExistingBasicBlock(cur_func, arm_block);
+            LLVMPositionBuilderAtEnd(self.builder, arm_block);
+
+            self.scope_manager.enter_scope();
+            if let (TypedPattern::EnumVariant { variant_index, bindings }, Some((enum_name, enum_ptr))) =
+                (&arm.pattern, enum_ptr)
+            {
+                let enum_type = self.named_types[enum_name];
+                let payload_type = self.enum_payloads[enum_name][*variant_index];
+                let payload_ptr = self.enum_payload_ptr(enum_type, enum_ptr, payload_type);
+                let payload = LLVMBuildLoad2(self.builder, payload_type, payload_ptr, cstr!(""));
+                for binding in bindings.iter() {
+                    let payload_index = binding.payload_index as c_uint;
+                    let value = LLVMBuildExtractValue(self.builder, payload, payload_index, cstr!(""));
+                    let alloca = self.create_alloca(&binding.name, &binding.binding_type);
+                    LLVMBuildStore(self.builder, value, alloca);
+                    self.scope_manager.set(&binding.name, alloca);
+                }
+            }
+            if !compile_arm(self, &arm.body) {
+                LLVMBuildBr(self.builder, merge_block);
+            }
+            self.scope_manager.exit_scope();
+        }
+
+        LLVMAppendExistingBasicBlock(cur_func, merge_block);
+        LLVMPositionBuilderAtEnd(self.builder, merge_block);
+    }
+
+    /// Builds a `switch` on the tag of the enum stored at `enum_ptr`, which jumps to the block of
+    /// the first arm matching each variant.
+    unsafe fn compile_enum_switch<Body>(
+        &mut self,
+        enum_name: &str,
+        enum_ptr: LLVMValueRef,
+        arms: &[TypedMatchArm<Body>],
+        arm_blocks: &[LLVMBasicBlockRef],
+    ) {
+        let enum_type = self.named_types[enum_name];
+        let tag_type = LLVMInt32TypeInContext(self.context);
+        let tag_ptr = LLVMBuildStructGEP2(self.builder, enum_type, enum_ptr, 0, cstr!(""));
+        let tag = LLVMBuildLoad2(self.builder, tag_type, tag_ptr, cstr!("tag"));
+
+        let unreachable_block = self.append_unreachable_block();
+        let num_variants = self.enum_payloads[enum_name].len();
+        let switch = LLVMBuildSwitch(self.builder, tag, unreachable_block, num_variants as c_uint);
+        for variant in 0..num_variants {
+            let first_arm = arms.iter().position(|arm| match arm.pattern {
+                TypedPattern::EnumVariant { variant_index, .. } => variant_index == variant,
+                _ => true,
+            });
+            if let Some(arm) = first_arm {
+                LLVMAddCase(switch, LLVMConstInt(tag_type, variant as u64, 0), arm_blocks[arm]);
+            }
+        }
+    }
+
+    /// Builds a chain of blocks that test `scrutinee` (an integer or a bool) against each arm's
+    /// pattern in order, jumping to the block of the first arm that matches.
+    unsafe fn compile_pattern_tests<Body>(
+        &mut self,
+        scrutinee: LLVMValueRef,
+        scrutinee_type: &Type,
+        arms: &[TypedMatchArm<Body>],
+        arm_blocks: &[LLVMBasicBlockRef],
+    ) {
+        use LLVMIntPredicate::*;
+
+        let cur_func = self.get_cur_function().unwrap();
+        let llvm_type = self.to_llvm_type(scrutinee_type);
+        let (greater_or_equal, less_or_equal) = match scrutinee_type {
+            Type::Int(IntType { signed: true, .. }) => (LLVMIntSGE, LLVMIntSLE),
+            _ => (LLVMIntUGE, LLVMIntULE),
+        };
+        let constant = |value: i128| {
+            let value = CString::new(value.to_string()).unwrap();
+            LLVMConstIntOfString(llvm_type, value.as_ptr(), 10)
+        };
+
+        for (arm, &arm_block) in arms.iter().zip(arm_blocks.iter()) {
+            let matches = match arm.pattern {
+                TypedPattern::Wildcard => {
+                    // No arm after this one can be reached
+                    LLVMBuildBr(self.builder, arm_block);
+                    return;
+                }
+                TypedPattern::Bool(b) => {
+                    let value = LLVMConstInt(llvm_type, b as u64, 0);
+                    LLVMBuildICmp(self.builder, LLVMIntEQ, scrutinee, value, cstr!("matches"))
+                }
+                TypedPattern::IntRange { start, end } if start == end => {
+                    LLVMBuildICmp(self.builder, LLVMIntEQ, scrutinee, constant(start), cstr!("matches"))
+                }
+                TypedPattern::IntRange { start, end } => {
+                    let after_start = LLVMBuildICmp(self.builder, greater_or_equal, scrutinee, constant(start), cstr!(""));
+                    let before_end = LLVMBuildICmp(self.builder, less_or_equal, scrutinee, constant(end), cstr!(""));
+                    LLVMBuildAnd(self.builder, after_start, before_end, cstr!("matches"))
+                }
+                TypedPattern::EnumVariant { .. } => unreachable!("the typer only allows enum patterns on enums"),
+            };
+            let next_test_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("test"));
+            LLVMBuildCondBr(self.builder, matches, arm_block, next_test_block);
+            LLVMPositionBuilderAtEnd(self.builder, next_test_block);
+        }
+        // The typer checked that some arm always matches
+        LLVMBuildUnreachable(self.builder);
+    }
+
+    /// Appends a block that only contains an `unreachable` instruction to the current function,
+    /// without moving the builder.
+    unsafe fn append_unreachable_block(&self) -> LLVMBasicBlockRef {
+        let cur_func = self.get_cur_function().unwrap();
+        let block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("unreachable"));
+        let builder = LLVMCreateBuilderInContext(self.context);
+        LLVMPositionBuilderAtEnd(builder, block);
+        LLVMBuildUnreachable(builder);
+        LLVMDisposeBuilder(builder);
+        block
+    }
+
+    /// Compiles a struct literal, inserting its fields (in the order they're written) into an
+    /// undefined struct value.
+    unsafe fn compile_struct_literal(&mut self, literal: &TypedStructLiteral) -> LLVMValueRef {
+        let struct_type = self.to_llvm_type(&Type::Named(literal.struct_name.clone()));
+        let mut struct_value = LLVMGetUndef(struct_type);
+        for (field_index, field_value) in literal.fields.iter() {
+            let value = self.compile_expr(field_value);
+            struct_value = LLVMBuildInsertValue(
+                self.builder,
+                struct_value,
+                value,
+                *field_index as c_uint,
+                cstr!(""),
+            );
+        }
+        struct_value
     }
 
     /// Compiles an identifier expression (variable value) with an expected type.
@@ -481,14 +1343,38 @@ impl Compiler {
         LLVMConstIntOfString(int_type, value_cstr.as_ptr(), 10)
     }
 
+    /// Compiles a float literal expression, which LLVM rounds to the nearest value of its type.
+    unsafe fn compile_float_literal(&self, float_literal: &TypedFloatLiteral) -> LLVMValueRef {
+        let float_type = self.to_llvm_type(&Type::Float(float_literal.float_type));
+        let value_cstr = CString::new(float_literal.float_value.as_str()).unwrap();
+        LLVMConstRealOfString(float_type, value_cstr.as_ptr())
+    }
+
     /// Compiles an bool literal expression (true/false, also known as 1/0).
     unsafe fn compile_bool_literal(&self, bool_literal: bool) -> LLVMValueRef {
         let bool_type = self.to_llvm_type(&Type::Bool);
         LLVMConstInt(bool_type, bool_literal as u64, 0)
     }
 
-    /// Compiles a binary expression (recursively compiling left- and right-hand sides).
-    unsafe fn compile_bin_expr(&mut self, bin_expr: &TypedBinary) -> LLVMValueRef {
+    /// Compiles a string literal, whose bytes (followed by a NUL byte, for C functions) are
+    /// stored in a private constant global.
+    unsafe fn compile_str_literal(&self, str_literal: &str) -> LLVMValueRef {
+        let i64_type = LLVMInt64TypeInContext(self.context);
+        let bytes = LLVMConstStringInContext(self.context, str_literal.as_ptr() as *const c_char, str_literal.len() as c_uint, 0);
+        let global = LLVMAddGlobal(self.module, LLVMTypeOf(bytes), cstr!("str"));
+        LLVMSetInitializer(global, bytes);
+        LLVMSetGlobalConstant(global, 1);
+        LLVMSetLinkage(global, LLVMPrivateLinkage);
+        LLVMSetUnnamedAddress(global, LLVMUnnamedAddr::LLVMGlobalUnnamedAddr);
+
+        let ptr = LLVMConstPointerCast(global, LLVMPointerType(LLVMInt8TypeInContext(self.context), 0));
+        let mut fields = [ptr, LLVMConstInt(i64_type, str_literal.len() as u64, 0)];
+        LLVMConstStructInContext(self.context, fields.as_mut_ptr(), 2, 0)
+    }
+
+    /// Compiles a binary expression (recursively compiling left- and right-hand sides), whose
+    /// runtime errors (like overflow) point at `span`.
+    unsafe fn compile_bin_expr(&mut self, bin_expr: &TypedBinary, span: Span) -> LLVMValueRef {
         use BinaryOperator::*;
 
         let lhs = self.compile_expr(&bin_expr.left);
@@ -500,39 +1386,349 @@ impl Compiler {
 
         let int_type = match bin_expr.result_type {
             Type::Int(int_type) => int_type,
-            _ => panic!("Unsupported lhs and rhs types for binary expr; can only handle integers"),
+            Type::Float(_) => return self.compile_float_bin_expr(bin_expr.operator, lhs, rhs),
+            _ => panic!("Unsupported lhs and rhs types for binary expr; can only handle integers and floats"),
         };
 
         match bin_expr.operator {
-            Add => LLVMBuildAdd(self.builder, lhs, rhs, cstr!("add")),
-            Subtract => LLVMBuildSub(self.builder, lhs, rhs, cstr!("sub")),
-            Multiply => LLVMBuildMul(self.builder, lhs, rhs, cstr!("mul")),
-            // TODO: signed-ints: Signed vs unsigned division
-            Divide => match int_type {
-                IntType { signed: true, .. } => LLVMBuildSDiv(self.builder, lhs, rhs, cstr!("sdiv")),
-                IntType { signed: false, .. } => LLVMBuildUDiv(self.builder, lhs, rhs, cstr!("udiv")),
-            },
-            Remainder => match int_type {
-                IntType { signed: true, .. } => LLVMBuildSRem(self.builder, lhs, rhs, cstr!("srem")),
-                IntType { signed: false, .. } => LLVMBuildURem(self.builder, lhs, rhs, cstr!("urem")),
+            Add | Subtract | Multiply if self.options.overflow_checks => {
+                self.compile_checked_arithmetic(bin_expr.operator, int_type, lhs, rhs, span)
+            }
+            Add | Subtract | Multiply => self.compile_wrapping_arithmetic(bin_expr.operator, lhs, rhs),
+            Divide | Remainder => self.compile_division(bin_expr.operator, int_type, lhs, rhs, span),
+            BitwiseAnd => LLVMBuildAnd(self.builder, lhs, rhs, cstr!("and")),
+            BitwiseOr => LLVMBuildOr(self.builder, lhs, rhs, cstr!("or")),
+            BitwiseXor => LLVMBuildXor(self.builder, lhs, rhs, cstr!("xor")),
+            ShiftLeft | ShiftRight => self.compile_shift(bin_expr.operator, int_type, lhs, rhs, span),
+        }
+    }
+
+    /// Compiles `lhs << rhs` or `lhs >> rhs` (depending on `operator`) on integers.
+    ///
+    /// Shifting by the type's width or more (or by a negative amount) would be poison in LLVM, so
+    /// it aborts the program with a message pointing at `span` if overflow checks are on.
+    /// Otherwise, the amount wraps around the width, like `x << 9` shifting a `u8` by 1.
+    unsafe fn compile_shift(
+        &mut self,
+        operator: BinaryOperator,
+        int_type: IntType,
+        lhs: LLVMValueRef,
+        rhs: LLVMValueRef,
+        span: Span,
+    ) -> LLVMValueRef {
+        let llvm_type = LLVMTypeOf(lhs);
+        let width = LLVMConstInt(llvm_type, int_type.width as u64, 0);
+        let amount = if self.options.overflow_checks {
+            // Negative amounts are huge when treated as unsigned, so they're caught too
+            let too_big = LLVMBuildICmp(self.builder, LLVMIntPredicate::LLVMIntUGE, rhs, width, cstr!("too_big"));
+            let message = match operator {
+                BinaryOperator::ShiftLeft => "attempt to shift left with overflow",
+                _ => "attempt to shift right with overflow",
+            };
+            self.compile_runtime_check(too_big, span, message);
+            rhs
+        } else if int_type.width.is_power_of_two() {
+            let mask = LLVMConstInt(llvm_type, int_type.width as u64 - 1, 0);
+            LLVMBuildAnd(self.builder, rhs, mask, cstr!("amount"))
+        } else {
+            LLVMBuildURem(self.builder, rhs, width, cstr!("amount"))
+        };
+
+        match (operator, int_type.signed) {
+            (BinaryOperator::ShiftLeft, _) => LLVMBuildShl(self.builder, lhs, amount, cstr!("shl")),
+            // Signed integers keep their sign when shifted right
+            (_, true) => LLVMBuildAShr(self.builder, lhs, amount, cstr!("ashr")),
+            (_, false) => LLVMBuildLShr(self.builder, lhs, amount, cstr!("lshr")),
+        }
+    }
+
+    /// Compiles `lhs + rhs`, `lhs - rhs` or `lhs * rhs` (depending on `operator`) on integers,
+    /// wrapping around if the result overflows.
+    unsafe fn compile_wrapping_arithmetic(&mut self, operator: BinaryOperator, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+        match operator {
+            BinaryOperator::Add => LLVMBuildAdd(self.builder, lhs, rhs, cstr!("add")),
+            BinaryOperator::Subtract => LLVMBuildSub(self.builder, lhs, rhs, cstr!("sub")),
+            BinaryOperator::Multiply => LLVMBuildMul(self.builder, lhs, rhs, cstr!("mul")),
+            _ => unreachable!("only +, - and * can overflow"),
+        }
+    }
+
+    /// Like [Compiler::compile_wrapping_arithmetic], but also returns an `i1` that's true if the
+    /// result overflowed `int_type` (using LLVM's `llvm.*.with.overflow` intrinsics).
+    unsafe fn compile_overflowing_arithmetic(
+        &mut self,
+        operator: BinaryOperator,
+        int_type: IntType,
+        lhs: LLVMValueRef,
+        rhs: LLVMValueRef,
+    ) -> (LLVMValueRef, LLVMValueRef) {
+        let operation = match operator {
+            BinaryOperator::Add => "add",
+            BinaryOperator::Subtract => "sub",
+            BinaryOperator::Multiply => "mul",
+            _ => unreachable!("only +, - and * can overflow"),
+        };
+        let sign = if int_type.signed { "s" } else { "u" };
+        let intrinsic_name = format!("llvm.{}{}.with.overflow", sign, operation);
+        let (intrinsic_type, intrinsic) = self.get_intrinsic(&intrinsic_name, &[LLVMTypeOf(lhs)]);
+        let mut args = [lhs, rhs];
+        let result = LLVMBuildCall2(self.builder, intrinsic_type, intrinsic, args.as_mut_ptr(), 2, cstr!(""));
+        let value = LLVMBuildExtractValue(self.builder, result, 0, cstr!("result"));
+        let overflowed = LLVMBuildExtractValue(self.builder, result, 1, cstr!("overflowed"));
+        (value, overflowed)
+    }
+
+    /// Compiles `lhs + rhs`, `lhs - rhs` or `lhs * rhs` (depending on `operator`) on integers,
+    /// aborting the program with a message pointing at `span` if the result overflows `int_type`.
+    unsafe fn compile_checked_arithmetic(
+        &mut self,
+        operator: BinaryOperator,
+        int_type: IntType,
+        lhs: LLVMValueRef,
+        rhs: LLVMValueRef,
+        span: Span,
+    ) -> LLVMValueRef {
+        let (result, overflowed) = self.compile_overflowing_arithmetic(operator, int_type, lhs, rhs);
+        let operation = match operator {
+            BinaryOperator::Add => "add",
+            BinaryOperator::Subtract => "subtract",
+            _ => "multiply",
+        };
+        self.compile_runtime_check(overflowed, span, &format!("attempt to {} with overflow", operation));
+        result
+    }
+
+    /// Compiles `lhs + rhs`, `lhs - rhs` or `lhs * rhs` (depending on `operator`) on integers,
+    /// clamping the result to the smallest or largest value of `int_type` if it overflows.
+    unsafe fn compile_saturating_arithmetic(
+        &mut self,
+        operator: BinaryOperator,
+        int_type: IntType,
+        lhs: LLVMValueRef,
+        rhs: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let sign = if int_type.signed { "s" } else { "u" };
+        let llvm_type = LLVMTypeOf(lhs);
+        let intrinsic_name = match operator {
+            BinaryOperator::Add => format!("llvm.{}add.sat", sign),
+            BinaryOperator::Subtract => format!("llvm.{}sub.sat", sign),
+            // LLVM has no saturating multiplication for integers that aren't fixed-point numbers
+            _ => {
+                let (product, overflowed) = self.compile_overflowing_arithmetic(operator, int_type, lhs, rhs);
+                let max = LLVMConstAllOnes(llvm_type);
+                let bound = if int_type.signed {
+                    let max = LLVMBuildLShr(self.builder, max, LLVMConstInt(llvm_type, 1, 0), cstr!("max"));
+                    let min = LLVMBuildNot(self.builder, max, cstr!("min"));
+                    // The exact product is negative if exactly one of the operands is
+                    let signs = LLVMBuildXor(self.builder, lhs, rhs, cstr!("signs"));
+                    let zero = LLVMConstNull(llvm_type);
+                    let is_negative = LLVMBuildICmp(self.builder, LLVMIntPredicate::LLVMIntSLT, signs, zero, cstr!("is_negative"));
+                    LLVMBuildSelect(self.builder, is_negative, min, max, cstr!("bound"))
+                } else {
+                    max
+                };
+                return LLVMBuildSelect(self.builder, overflowed, bound, product, cstr!("saturated"));
+            }
+        };
+        let (intrinsic_type, intrinsic) = self.get_intrinsic(&intrinsic_name, &[llvm_type]);
+        let mut args = [lhs, rhs];
+        LLVMBuildCall2(self.builder, intrinsic_type, intrinsic, args.as_mut_ptr(), 2, cstr!("saturated"))
+    }
+
+    /// Compiles `lhs / rhs` or `lhs % rhs` (depending on `operator`) on integers, aborting the
+    /// program with a message pointing at `span` if `rhs` is zero.
+    ///
+    /// Dividing the smallest value of a signed type by -1 overflows, which aborts the program too
+    /// if overflow checks are on. Otherwise, it wraps around like negation does (to the smallest
+    /// value again, or 0 for the remainder).
+    unsafe fn compile_division(
+        &mut self,
+        operator: BinaryOperator,
+        int_type: IntType,
+        lhs: LLVMValueRef,
+        rhs: LLVMValueRef,
+        span: Span,
+    ) -> LLVMValueRef {
+        let is_divide = operator == BinaryOperator::Divide;
+        let llvm_type = LLVMTypeOf(lhs);
+        let zero = LLVMConstNull(llvm_type);
+        let is_zero = LLVMBuildICmp(self.builder, LLVMIntPredicate::LLVMIntEQ, rhs, zero, cstr!("is_zero"));
+        let message = match is_divide {
+            true => "attempt to divide by zero",
+            false => "attempt to calculate the remainder with a divisor of zero",
+        };
+        self.compile_runtime_check(is_zero, span, message);
+
+        if !int_type.signed {
+            return match is_divide {
+                true => LLVMBuildUDiv(self.builder, lhs, rhs, cstr!("udiv")),
+                false => LLVMBuildURem(self.builder, lhs, rhs, cstr!("urem")),
+            };
+        }
+
+        let minus_one = LLVMConstAllOnes(llvm_type);
+        let is_minus_one = LLVMBuildICmp(self.builder, LLVMIntPredicate::LLVMIntEQ, rhs, minus_one, cstr!("is_minus_one"));
+        let rhs = if self.options.overflow_checks {
+            let one = LLVMConstInt(llvm_type, 1, 0);
+            let min = LLVMBuildShl(self.builder, one, LLVMConstInt(llvm_type, int_type.width as u64 - 1, 0), cstr!("min"));
+            let is_min = LLVMBuildICmp(self.builder, LLVMIntPredicate::LLVMIntEQ, lhs, min, cstr!("is_min"));
+            let overflowed = LLVMBuildAnd(self.builder, is_min, is_minus_one, cstr!("overflowed"));
+            let message = match is_divide {
+                true => "attempt to divide with overflow",
+                false => "attempt to calculate the remainder with overflow",
+            };
+            self.compile_runtime_check(overflowed, span, message);
+            rhs
+        } else {
+            // Overflowing `sdiv` and `srem` are undefined behavior, so dividing by -1 is done by
+            // dividing by 1 (which never overflows) and negating the quotient
+            let one = LLVMConstInt(llvm_type, 1, 0);
+            LLVMBuildSelect(self.builder, is_minus_one, one, rhs, cstr!("divisor"))
+        };
+
+        if !is_divide {
+            // The remainder is 0 when dividing by 1 and -1 alike
+            return LLVMBuildSRem(self.builder, lhs, rhs, cstr!("srem"));
+        }
+        let quotient = LLVMBuildSDiv(self.builder, lhs, rhs, cstr!("sdiv"));
+        if self.options.overflow_checks {
+            return quotient;
+        }
+        let negated = LLVMBuildNeg(self.builder, quotient, cstr!("neg"));
+        LLVMBuildSelect(self.builder, is_minus_one, negated, quotient, cstr!("quotient"))
+    }
+
+    /// Compiles a call to a built-in arithmetic function like `wrapping_add`, which handles
+    /// overflow according to its [Overflow] behavior (regardless of the overflow checks option).
+    unsafe fn compile_arithmetic_call(&mut self, call: &TypedArithmeticCall) -> LLVMValueRef {
+        let Type::Int(int_type) = call.binary.result_type else {
+            unreachable!("the typer only allows integer operands");
+        };
+        let operator = call.binary.operator;
+        let lhs = self.compile_expr(&call.binary.left);
+        let rhs = self.compile_expr(&call.binary.right);
+        match call.overflow {
+            Overflow::Wrapping => self.compile_wrapping_arithmetic(operator, lhs, rhs),
+            Overflow::Saturating => self.compile_saturating_arithmetic(operator, int_type, lhs, rhs),
+            Overflow::Checked => {
+                let result_ptr = match &call.result_ptr {
+                    Some(result_ptr) => self.compile_expr(result_ptr),
+                    None => unreachable!("the typer requires a pointer for checked arithmetic"),
+                };
+                let (result, overflowed) = self.compile_overflowing_arithmetic(operator, int_type, lhs, rhs);
+
+                // The result is only stored if it's correct
+                let cur_func = self.get_cur_function().unwrap();
+                let store_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("store_result"));
+                let after_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("after_store"));
+                LLVMBuildCondBr(self.builder, overflowed, after_block, store_block);
+                LLVMPositionBuilderAtEnd(self.builder, store_block);
+                LLVMBuildStore(self.builder, result, result_ptr);
+                LLVMBuildBr(self.builder, after_block);
+
+                LLVMPositionBuilderAtEnd(self.builder, after_block);
+                LLVMBuildNot(self.builder, overflowed, cstr!("succeeded"))
             }
         }
     }
 
-    /// Compiles a unary expression.
-    unsafe fn compile_unary(&mut self, unary: &TypedUnary) -> LLVMValueRef {
+    /// Returns the type and declaration of the LLVM intrinsic function named `name` (like
+    /// `llvm.sadd.sat`), overloaded for `overloaded_types` (like `[i32]` for `llvm.sadd.sat.i32`).
+    unsafe fn get_intrinsic(&self, name: &str, overloaded_types: &[LLVMTypeRef]) -> (LLVMTypeRef, LLVMValueRef) {
+        let id = LLVMLookupIntrinsicID(name.as_ptr() as *const c_char, name.len());
+        let mut param_types = overloaded_types.to_vec();
+        let num_types = param_types.len();
+        let intrinsic = LLVMGetIntrinsicDeclaration(self.module, id, param_types.as_mut_ptr(), num_types);
+        let intrinsic_type = LLVMIntrinsicGetType(self.context, id, param_types.as_mut_ptr(), num_types);
+        (intrinsic_type, intrinsic)
+    }
+
+    /// Compiles the arithmetic of a binary expression on floats, whose operands are already
+    /// compiled.
+    unsafe fn compile_float_bin_expr(&mut self, operator: BinaryOperator, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+        use BinaryOperator::*;
+
+        match operator {
+            Add => LLVMBuildFAdd(self.builder, lhs, rhs, cstr!("fadd")),
+            Subtract => LLVMBuildFSub(self.builder, lhs, rhs, cstr!("fsub")),
+            Multiply => LLVMBuildFMul(self.builder, lhs, rhs, cstr!("fmul")),
+            Divide => LLVMBuildFDiv(self.builder, lhs, rhs, cstr!("fdiv")),
+            Remainder => LLVMBuildFRem(self.builder, lhs, rhs, cstr!("frem")),
+            _ => panic!("Bitwise operators on floats should've been handled by the typer"),
+        }
+    }
+
+    /// Compiles a unary expression, whose runtime errors (like overflow) point at `span`.
+    unsafe fn compile_unary(&mut self, unary: &TypedUnary, span: Span) -> LLVMValueRef {
+        // The operand of `&` is a place, whose value isn't read
+        if unary.operator == UnaryOperator::AddressOf {
+            return match self.compile_place(&unary.operand) {
+                Some(ptr) => ptr,
+                None => panic!("Taking the address of a temporary value should've been handled by the typer"),
+            };
+        }
+
         let operand = self.compile_expr(&unary.operand);
         let source_type = &unary.operand.get_result_type();
         match &unary.operator {
             UnaryOperator::Cast(cast_type) => self.compile_cast(operand, cast_type, source_type),
-            UnaryOperator::Negate => self.compile_negation(operand, source_type),
+            UnaryOperator::Negate => match (source_type, &unary.operand.kind) {
+                // A negated literal like `-128` is in range even if the literal alone isn't
+                (_, TypedExprKind::IntLiteral(_)) => self.compile_negation(operand, source_type),
+                (Type::Int(int_type), _) if self.options.overflow_checks => {
+                    let zero = LLVMConstNull(LLVMTypeOf(operand));
+                    let (result, overflowed) = self.compile_overflowing_arithmetic(BinaryOperator::Subtract, *int_type, zero, operand);
+                    self.compile_runtime_check(overflowed, span, "attempt to negate with overflow");
+                    result
+                }
+                _ => self.compile_negation(operand, source_type),
+            },
+            UnaryOperator::Not | UnaryOperator::BitwiseNot => LLVMBuildNot(self.builder, operand, cstr!("not")),
+            UnaryOperator::Deref => {
+                LLVMBuildLoad2(self.builder, self.to_llvm_type(&unary.result_type), operand, cstr!("deref"))
+            }
+            UnaryOperator::AddressOf => unreachable!("the address of the operand was taken above"),
         }
     }
 
     /// Compiles a cast expression.
     unsafe fn compile_cast(&mut self, operand: LLVMValueRef, cast_type: &Type, source_type: &Type) -> LLVMValueRef {
+        let llvm_cast_type = self.to_llvm_type(cast_type);
+        // Chars are cast like the unsigned 32-bit integers they're stored as
+        let char_int_type = Type::Int(IntType { signed: false, width: 32 });
+        let cast_type = if *cast_type == Type::Char { &char_int_type } else { cast_type };
+        let source_type = if *source_type == Type::Char { &char_int_type } else { source_type };
+
         let (cast_int_type, source_int_type) = match (cast_type, source_type) {
             (Type::Int(cast), Type::Int(source)) => (cast, source),
+            (Type::Float(_), Type::Int(IntType { signed: true, .. })) => {
+                return LLVMBuildSIToFP(self.builder, operand, llvm_cast_type, cstr!("sitofp"));
+            }
+            (Type::Float(_), Type::Int(IntType { signed: false, .. })) => {
+                return LLVMBuildUIToFP(self.builder, operand, llvm_cast_type, cstr!("uitofp"));
+            }
+            // Plain `fptosi` and `fptoui` give poison for NaN and out of range values, so floats
+            // saturate instead (like Rust's `as`): NaN becomes 0, and other values are clamped to
+            // the integer type's range
+            (Type::Int(IntType { signed, .. }), Type::Float(_)) => {
+                let intrinsic_name = match signed {
+                    true => "llvm.fptosi.sat",
+                    false => "llvm.fptoui.sat",
+                };
+                let (intrinsic_type, intrinsic) = self.get_intrinsic(intrinsic_name, &[llvm_cast_type, LLVMTypeOf(operand)]);
+                let mut args = [operand];
+                return LLVMBuildCall2(self.builder, intrinsic_type, intrinsic, args.as_mut_ptr(), 1, cstr!("saturated"));
+            }
+            (Type::Float(cast), Type::Float(source)) => {
+                return match cast.width.cmp(&source.width) {
+                    Ordering::Less => LLVMBuildFPTrunc(self.builder, operand, llvm_cast_type, cstr!("fptrunc")),
+                    Ordering::Greater => LLVMBuildFPExt(self.builder, operand, llvm_cast_type, cstr!("fpext")),
+                    Ordering::Equal => operand,
+                };
+            }
+            (Type::Pointer(_), Type::Pointer(_)) => {
+                return LLVMBuildPointerCast(self.builder, operand, self.to_llvm_type(cast_type), cstr!("ptr"));
+            }
             (cast, source) => {
                 panic!("Unsupported cast from {} to {} should've been handled by the typer", cast, source)
             }
@@ -541,14 +1737,16 @@ impl Compiler {
         if cast_int_type.width < source_int_type.width {
             LLVMBuildTrunc(self.builder, operand, self.to_llvm_type(cast_type), cstr!("trunc"))
         } else if cast_int_type.width > source_int_type.width {
-            if cast_int_type.signed {
+            // The typer implicitly widens unsigned integers to wider signed ones, whose new bits
+            // must be zeros
+            if source_int_type.signed {
                 LLVMBuildSExt(self.builder, operand, self.to_llvm_type(cast_type), cstr!("sext"))
             } else {
                 LLVMBuildZExt(self.builder, operand, self.to_llvm_type(cast_type), cstr!("zext"))
             }
         } else {
             // Width is the same. No need to cast.
-            // (Note: Typer ensures source_int_type.signed == cast_int_type.signed)
+            // (Note: Typer ensures source_int_type.signed == cast_int_type.signed at equal widths)
             operand
         }
     }
@@ -557,7 +1755,8 @@ impl Compiler {
     unsafe fn compile_negation(&mut self, operand: LLVMValueRef, source_type: &Type) -> LLVMValueRef {
         match source_type {
             Type::Int(IntType { signed: true, .. }) => LLVMBuildNeg(self.builder, operand, cstr!("neg")),
-            _ => panic!("Unsupported type for negation, can only handle integers; this should have been handled by typer"),
+            Type::Float(_) => LLVMBuildFNeg(self.builder, operand, cstr!("fneg")),
+            _ => panic!("Unsupported type for negation, can only handle integers and floats; this should have been handled by typer"),
         }
     }
 
@@ -572,10 +1771,67 @@ impl Compiler {
 
         match comparison.operand_type {
             Type::Int(int_type) => LLVMBuildICmp(self.builder, self.comparison_int_op(comparison.operator, int_type), lhs, rhs, cstr!("")),
-            _ => panic!("Unsupported lhs and rhs types for comparison; can only handle integers"),
+            // Addresses and chars (code points) are compared as unsigned integers
+            Type::Pointer(_) | Type::Char => {
+                let address_type = IntType { signed: false, width: 64 };
+                LLVMBuildICmp(self.builder, self.comparison_int_op(comparison.operator, address_type), lhs, rhs, cstr!(""))
+            }
+            Type::Float(_) => LLVMBuildFCmp(self.builder, Self::comparison_float_op(comparison.operator), lhs, rhs, cstr!("")),
+            _ => panic!("Unsupported lhs and rhs types for comparison; can only handle integers, floats and pointers"),
         }
     }
 
+    /// Compiles a logical expression (`and`/`or`), only evaluating the right-hand side if the
+    /// left-hand side doesn't already decide the result.
+    ///
+    /// For `a and b`, this produces the following blocks (`or` swaps the branch targets):
+    ///
+    /// ```text
+    /// <current>: %a = ...; br %a, label %rhs, label %merge
+    /// rhs:       %b = ...; br label %merge
+    /// merge:     %result = phi i1 [false, %<current>], [%b, %rhs]
+    /// ```
+    unsafe fn compile_logical_expr(&mut self, logical: &TypedLogical) -> LLVMValueRef {
+        let cur_func = match self.get_cur_function() {
+            Some(func) => func,
+            None => panic!("Cannot compile logical expression outside of a function"),
+        };
+
+        let lhs = self.compile_expr(&logical.left);
+        // The left-hand side might have created blocks of its own (if it's a logical expression)
+        let lhs_block = LLVMGetInsertBlock(self.builder);
+
+        // rhs_block and merge_block are appended once the blocks before them are built
+        let rhs_block = LLVMCreateBasicBlockInContext(self.context, cstr!("rhs"));
+        let merge_block = LLVMCreateBasicBlockInContext(self.context, cstr!("merge"));
+
+        // short_circuit_value is the result whenever the right-hand side is skipped
+        let short_circuit_value = match logical.operator {
+            LogicalOperator::And => {
+                LLVMBuildCondBr(self.builder, lhs, rhs_block, merge_block);
+                self.compile_bool_literal(false)
+            }
+            LogicalOperator::Or => {
+                LLVMBuildCondBr(self.builder, lhs, merge_block, rhs_block);
+                self.compile_bool_literal(true)
+            }
+        };
+
+        LLVMAppendExistingBasicBlock(cur_func, rhs_block);
+        LLVMPositionBuilderAtEnd(self.builder, rhs_block);
+        let rhs = self.compile_expr(&logical.right);
+        let rhs_end_block = LLVMGetInsertBlock(self.builder);
+        LLVMBuildBr(self.builder, merge_block);
+
+        LLVMAppendExistingBasicBlock(cur_func, merge_block);
+        LLVMPositionBuilderAtEnd(self.builder, merge_block);
+        let phi = LLVMBuildPhi(self.builder, self.to_llvm_type(&Type::Bool), cstr!("logical"));
+        let mut incoming_values = [short_circuit_value, rhs];
+        let mut incoming_blocks = [lhs_block, rhs_end_block];
+        LLVMAddIncoming(phi, incoming_values.as_mut_ptr(), incoming_blocks.as_mut_ptr(), 2);
+        phi
+    }
+
     unsafe fn comparison_int_op(&mut self, operator: ComparisonOperator, result_type: IntType) -> LLVMIntPredicate {
         use LLVMIntPredicate::*;
         
@@ -601,6 +1857,21 @@ impl Compiler {
         }
     }
 
+    /// Returns the predicate for comparing floats with `operator`. The predicates are ordered,
+    /// so every comparison involving NaN is false (even `!=`, unlike in C).
+    fn comparison_float_op(operator: ComparisonOperator) -> LLVMRealPredicate {
+        use LLVMRealPredicate::*;
+
+        match operator {
+            ComparisonOperator::NotEqualTo => LLVMRealONE,
+            ComparisonOperator::EqualTo => LLVMRealOEQ,
+            ComparisonOperator::LessThan => LLVMRealOLT,
+            ComparisonOperator::GreaterThan => LLVMRealOGT,
+            ComparisonOperator::LessOrEqualTo => LLVMRealOLE,
+            ComparisonOperator::GreaterOrEqualTo => LLVMRealOGE,
+        }
+    }
+
     /// Compiles a typed function call
     unsafe fn compile_call(&mut self, call: &TypedCall) -> LLVMValueRef {
         let func = match self.scope_manager.get(&call.function_name) {
@@ -619,40 +1890,185 @@ impl Compiler {
             panic!("Number of arguments should be handled by typer");
         }
 
-        let mut arg_values = Vec::with_capacity(call.args.len());
+        let func_abi = self.func_abi(&call.function_proto);
+        let mut arg_values = Vec::with_capacity(call.args.len() + 1);
+        // The callee writes big return values into memory given by the caller
+        let sret = match func_abi.ret.mode {
+            PassMode::Indirect { .. } => Some(self.create_entry_alloca(func_abi.ret.llvm_type)),
+            _ => None,
+        };
+        arg_values.extend(sret);
         for i in 0..num_params {
             let arg = call.args.get(i).unwrap();
             let value = self.compile_expr(arg);
-            arg_values.push(value);
+            arg_values.push(self.lower_abi_value(value, func_abi.params[i]));
         }
 
-        let func_type = self.to_llvm_type(&Type::Func(call.function_proto.clone()));
-        LLVMBuildCall2(
+        let func_type = func_abi.llvm_type(self.context);
+        let call_value = LLVMBuildCall2(
             self.builder,
             func_type,
             func,
             arg_values.as_mut_ptr(),
             arg_values.len() as c_uint,
             cstr!(""),
-        )
+        );
+        for (index, attribute) in func_abi.attributes(self.context) {
+            LLVMAddCallSiteAttribute(call_value, index, attribute);
+        }
+
+        match sret {
+            Some(memory) => LLVMBuildLoad2(self.builder, func_abi.ret.llvm_type, memory, cstr!("ret")),
+            None => self.lift_abi_value(call_value, func_abi.ret),
+        }
+    }
+
+    /// Returns how the parameters and return value of a function are passed.
+    unsafe fn func_abi(&self, func_proto: &FuncProto) -> FuncAbi {
+        let return_type = self.to_llvm_type(func_proto.return_type.as_ref());
+        let param_types: Vec<_> = func_proto
+            .params
+            .iter()
+            .map(|p| self.to_llvm_type(&p.param_type))
+            .collect();
+        let mut func_abi = self.abi.func_abi(return_type, &param_types);
+
+        // C functions take and return strings as a pointer to their first character
+        if func_proto.func_visibility == FuncVisibility::Extern {
+            let flick_types = std::iter::once(func_proto.return_type.as_ref())
+                .chain(func_proto.params.iter().map(|p| &p.param_type));
+            let abi_values = std::iter::once(&mut func_abi.ret).chain(func_abi.params.iter_mut());
+            for (flick_type, abi_value) in flick_types.zip(abi_values) {
+                if *flick_type == Type::Str {
+                    abi_value.mode = PassMode::CString;
+                }
+            }
+        }
+        func_abi
+    }
+
+    /// Converts `value` into the form it's passed in (as an argument), following `abi_value`.
+    unsafe fn lower_abi_value(&self, value: LLVMValueRef, abi_value: AbiValue) -> LLVMValueRef {
+        match abi_value.mode {
+            PassMode::Direct => value,
+            PassMode::Coerced(coerced_type) => self.coerce(value, coerced_type),
+            PassMode::Indirect { .. } => {
+                let copy = self.create_entry_alloca(abi_value.llvm_type);
+                LLVMBuildStore(self.builder, value, copy);
+                copy
+            }
+            PassMode::CString => LLVMBuildExtractValue(self.builder, value, 0, cstr!("")),
+        }
+    }
+
+    /// Converts `value`, in the form it's passed in (as a parameter or return value), back into a
+    /// value of its own type, following `abi_value`.
+    unsafe fn lift_abi_value(&self, value: LLVMValueRef, abi_value: AbiValue) -> LLVMValueRef {
+        match abi_value.mode {
+            PassMode::Direct => value,
+            PassMode::Coerced(_) => self.coerce(value, abi_value.llvm_type),
+            PassMode::Indirect { .. } => LLVMBuildLoad2(self.builder, abi_value.llvm_type, value, cstr!("")),
+            PassMode::CString => self.lift_c_string(value),
+        }
+    }
+
+    /// Converts `ptr`, a pointer to a NUL-terminated string returned by a C function, into a
+    /// `str` (whose length is found with `strlen`, unless `ptr` is null).
+    unsafe fn lift_c_string(&self, ptr: LLVMValueRef) -> LLVMValueRef {
+        let cur_func = self.get_cur_function().unwrap();
+        let i64_type = LLVMInt64TypeInContext(self.context);
+        let mut strlen_params = [LLVMTypeOf(ptr)];
+        let strlen_type = LLVMFunctionType(i64_type, strlen_params.as_mut_ptr(), 1, 0);
+        let strlen = self.get_runtime_function("strlen", strlen_type);
+
+        let null_block = LLVMGetInsertBlock(self.builder);
+        let strlen_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("strlen"));
+        let merge_block = LLVMAppendBasicBlockInContext(self.context, cur_func, cstr!("c_string"));
+        let is_null = LLVMBuildIsNull(self.builder, ptr, cstr!(""));
+        LLVMBuildCondBr(self.builder, is_null, merge_block, strlen_block);
+
+        LLVMPositionBuilderAtEnd(self.builder, strlen_block);
+        let mut strlen_args = [ptr];
+        let length = LLVMBuildCall2(self.builder, strlen_type, strlen, strlen_args.as_mut_ptr(), 1, cstr!(""));
+        LLVMBuildBr(self.builder, merge_block);
+
+        LLVMPositionBuilderAtEnd(self.builder, merge_block);
+        let phi = LLVMBuildPhi(self.builder, i64_type, cstr!("length"));
+        let mut incoming_values = [LLVMConstInt(i64_type, 0, 0), length];
+        let mut incoming_blocks = [null_block, strlen_block];
+        LLVMAddIncoming(phi, incoming_values.as_mut_ptr(), incoming_blocks.as_mut_ptr(), 2);
+
+        let string = LLVMGetUndef(self.to_llvm_type(&Type::Str));
+        let string = LLVMBuildInsertValue(self.builder, string, ptr, 0, cstr!(""));
+        LLVMBuildInsertValue(self.builder, string, phi, 1, cstr!(""))
+    }
+
+    /// Reinterprets the bytes of `value` as a value of `to_type`, by storing it into memory and
+    /// loading it back.
+    unsafe fn coerce(&self, value: LLVMValueRef, to_type: LLVMTypeRef) -> LLVMValueRef {
+        let from_type = LLVMTypeOf(value);
+        let target_data = LLVMGetModuleDataLayout(self.module);
+        // The memory must be big (and aligned) enough for both types
+        let memory_type = match LLVMABISizeOfType(target_data, from_type) >= LLVMABISizeOfType(target_data, to_type) {
+            true => from_type,
+            false => to_type,
+        };
+        let alignment = LLVMABIAlignmentOfType(target_data, from_type).max(LLVMABIAlignmentOfType(target_data, to_type));
+
+        let memory = self.create_entry_alloca(memory_type);
+        LLVMSetAlignment(memory, alignment);
+        let from_ptr = LLVMBuildPointerCast(self.builder, memory, LLVMPointerType(from_type, 0), cstr!(""));
+        LLVMBuildStore(self.builder, value, from_ptr);
+        let to_ptr = LLVMBuildPointerCast(self.builder, memory, LLVMPointerType(to_type, 0), cstr!(""));
+        LLVMBuildLoad2(self.builder, to_type, to_ptr, cstr!("coerced"))
+    }
+
+    /// Creates an unnamed LLVM 'alloca' at the start of the current function, so that it's only
+    /// allocated once even if it's used inside of a loop.
+    unsafe fn create_entry_alloca(&self, llvm_type: LLVMTypeRef) -> LLVMValueRef {
+        let entry_block = LLVMGetEntryBasicBlock(self.get_cur_function().unwrap());
+        let builder = LLVMCreateBuilderInContext(self.context);
+        match LLVMGetFirstInstruction(entry_block) {
+            first if first.is_null() => LLVMPositionBuilderAtEnd(builder, entry_block),
+            first => LLVMPositionBuilderBefore(builder, first),
+        }
+        let alloca = LLVMBuildAlloca(builder, llvm_type, cstr!(""));
+        LLVMDisposeBuilder(builder);
+        alloca
     }
 
     /// Converts Flick's [Type] enum to llvm-sys's [LLVMTypeRef].
     unsafe fn to_llvm_type(&self, t: &Type) -> LLVMTypeRef {
         match t {
             Type::Int(int_type) => LLVMIntTypeInContext(self.context, int_type.width),
+            Type::Float(FloatType { width: 32 }) => LLVMFloatTypeInContext(self.context),
+            Type::Float(_) => LLVMDoubleTypeInContext(self.context),
+            Type::Char => LLVMInt32TypeInContext(self.context),
             Type::Bool => LLVMInt1TypeInContext(self.context),
             Type::Void => LLVMVoidTypeInContext(self.context),
-            Type::Func(func_proto) => {
-                let return_type = self.to_llvm_type(func_proto.return_type.as_ref());
-                let num_params = func_proto.params.len() as c_uint;
-                let mut param_types: Vec<_> = func_proto
-                    .params
-                    .iter()
-                    .map(|p| self.to_llvm_type(&p.param_type))
-                    .collect();
-
-                LLVMFunctionType(return_type, param_types.as_mut_ptr(), num_params, 0)
+            Type::Func(func_proto) => self.func_abi(func_proto).llvm_type(self.context),
+            Type::Named(name) => match self.named_types.get(name) {
+                Some(struct_type) => *struct_type,
+                None => panic!("Undefined structs and enums should be handled by typer"),
+            },
+            // (LLVMArrayType2 is missing from older versions of LLVM, and the parser only
+            // allows lengths that fit in a u32)
+            #[allow(deprecated)]
+            Type::Array(element_type, length) => LLVMArrayType(self.to_llvm_type(element_type), *length as c_uint),
+            // LLVM has no pointers to void, so they're pointers to bytes like in C
+            Type::Pointer(pointee_type) if **pointee_type == Type::Void => {
+                LLVMPointerType(LLVMInt8TypeInContext(self.context), 0)
+            }
+            Type::Pointer(pointee_type) => LLVMPointerType(self.to_llvm_type(pointee_type), 0),
+            Type::Str => {
+                let mut field_types = [
+                    LLVMPointerType(LLVMInt8TypeInContext(self.context), 0),
+                    LLVMInt64TypeInContext(self.context),
+                ];
+                LLVMStructTypeInContext(self.context, field_types.as_mut_ptr(), 2, 0)
+            }
+            Type::Error | Type::InferredInt(_) => {
+                unreachable!("the typer never produces a program containing type errors or uninferred types")
             }
         }
     }
@@ -700,3 +2116,198 @@ impl Default for Compiler {
         Compiler::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Lexer, Parser, Typer};
+
+    /// Compiles `source_code` with `options` and returns the unoptimized LLVM IR.
+    fn compile_to_ir(source_code: &str, options: CompilerOptions) -> String {
+        let chars: Vec<_> = source_code.chars().collect();
+        let (tokens, errors) = Lexer::lex(&chars, 0);
+        assert!(errors.is_empty(), "Lexing errors: {:?}", errors);
+        let (program, errors) = Parser::parse_program(&tokens);
+        assert!(errors.is_empty(), "Parsing errors: {:?}", errors);
+        let typed_program = Typer::new().type_program(&program).unwrap();
+
+        let mut compiler = Compiler::with_options(options);
+        compiler.compile(&typed_program);
+        unsafe {
+            let ir = LLVMPrintModuleToString(compiler.module);
+            let ir_string = CStr::from_ptr(ir).to_string_lossy().into_owned();
+            LLVMDisposeMessage(ir);
+            ir_string
+        }
+    }
+
+    /// Returns the definition of the function `name` in `ir`, from `define` to the closing `}`.
+    fn function_ir<'a>(ir: &'a str, name: &str) -> &'a str {
+        let signature = format!("@{}(", name);
+        let start = ir
+            .match_indices("define ")
+            .map(|(i, _)| i)
+            .find(|&i| ir[i..].lines().next().unwrap().contains(&signature))
+            .unwrap_or_else(|| panic!("Function '{}' isn't defined in:\n{}", name, ir));
+        let end = start + ir[start..].find("\n}\n").unwrap();
+        &ir[start..end]
+    }
+
+    /// Compiles `functions` along with an empty `main`, with overflow checks turned on or off.
+    fn compile_functions(functions: &str, overflow_checks: bool) -> String {
+        let source_code = format!("{}\n\npub fn main() u8 {{\n    ret 0\n}}", functions);
+        compile_to_ir(&source_code, CompilerOptions { overflow_checks, ..CompilerOptions::default() })
+    }
+
+    #[test]
+    fn casts_extend_by_the_source_signedness() {
+        // An unsigned integer cast to a wider signed type must be zero-extended, so that 255 stays
+        // 255 instead of becoming -1
+        let ir = compile_to_ir(
+            "fn widen(u8 byte, i8 small) i16 {\n    ret (i16) byte + (i16) small\n}\n\npub fn main() u8 {\n    ret 0\n}",
+            CompilerOptions::default(),
+        );
+        assert!(ir.contains("= zext i8 %byte"), "{}", ir);
+        assert!(!ir.contains("= sext i8 %byte"), "{}", ir);
+        assert!(ir.contains("= sext i8 %small"), "{}", ir);
+    }
+
+    #[test]
+    fn for_loop_steps_are_checked_at_runtime_unless_literal() {
+        let ir = compile_to_ir(
+            "fn count(i32 by) i32 {\n    i32 total = 0\n    for i32 _i in 0..10 step by {\n        total += 1\n    }\n    ret total\n}\n\npub fn main() u8 {\n    for u8 _j in 0..10 step 2 {\n    }\n    ret 0\n}",
+            CompilerOptions::default(),
+        );
+        // Only `count`'s step is checked, and a negative step is caught along with zero
+        let count = &ir[ir.find("@count(").unwrap()..ir.find("@main(").unwrap()];
+        assert!(count.contains("icmp sle i32 %by"), "{}", ir);
+        assert!(ir.contains(":3:30: for loop step must be positive"), "{}", ir);
+        let main = &ir[ir.find("@main(").unwrap()..];
+        assert!(!main.contains("non_positive"), "{}", ir);
+    }
+
+    #[test]
+    fn arithmetic_builtins_pick_intrinsics_by_signedness() {
+        let functions = [
+            "fn wrapping(i32 a, i32 b, u8 c, u8 d) u8 {",
+            "    i32 _x = wrapping_add(a, b)",
+            "    ret wrapping_mul(c, d)",
+            "}",
+            "fn saturating(i32 a, i32 b, u8 c, u8 d) u8 {",
+            "    i32 _x = saturating_sub(a, b)",
+            "    ret saturating_add(c, d)",
+            "}",
+            "fn checked(i32 a, i32 b, u8 c, u8 d) bool {",
+            "    i32 x = 0",
+            "    u8 y = 0",
+            "    ret checked_mul(a, b, &x) and checked_sub(c, d, &y)",
+            "}",
+        ]
+        .join("\n");
+
+        // The built-ins behave the same whether or not overflow checks are on
+        for overflow_checks in [true, false] {
+            let ir = compile_functions(&functions, overflow_checks);
+
+            let wrapping = function_ir(&ir, "wrapping");
+            assert!(wrapping.contains("= add i32"), "{}", wrapping);
+            assert!(wrapping.contains("= mul i8"), "{}", wrapping);
+            assert!(!wrapping.contains("with.overflow") && !wrapping.contains("check_failed"), "{}", wrapping);
+
+            let saturating = function_ir(&ir, "saturating");
+            assert!(saturating.contains("call i32 @llvm.ssub.sat.i32("), "{}", saturating);
+            assert!(saturating.contains("call i8 @llvm.uadd.sat.i8("), "{}", saturating);
+
+            // The result is only stored if the operation didn't overflow, and the call's value is
+            // whether it succeeded
+            let checked = function_ir(&ir, "checked");
+            assert!(checked.contains("call { i32, i1 } @llvm.smul.with.overflow.i32("), "{}", checked);
+            assert!(checked.contains("call { i8, i1 } @llvm.usub.with.overflow.i8("), "{}", checked);
+            assert!(checked.contains("br i1 %overflowed, label %after_store, label %store_result"), "{}", checked);
+            assert!(checked.contains("%succeeded = xor i1 %overflowed, true"), "{}", checked);
+            assert!(!checked.contains("check_failed"), "{}", checked);
+        }
+    }
+
+    #[test]
+    fn arithmetic_operators_check_overflow_unless_disabled() {
+        let functions = "fn add(i32 a, i32 b, u8 c, u8 d) u8 {\n    i32 _x = a + b\n    ret c * d\n}";
+
+        let ir = compile_functions(functions, true);
+        let add = function_ir(&ir, "add");
+        assert!(add.contains("call { i32, i1 } @llvm.sadd.with.overflow.i32("), "{}", add);
+        assert!(add.contains("call { i8, i1 } @llvm.umul.with.overflow.i8("), "{}", add);
+        assert_eq!(add.matches("call void @abort()").count(), 2, "{}", add);
+        assert!(ir.contains(": attempt to add with overflow\\0A"), "{}", ir);
+        assert!(ir.contains(": attempt to multiply with overflow\\0A"), "{}", ir);
+
+        let ir = compile_functions(functions, false);
+        let add = function_ir(&ir, "add");
+        assert!(add.contains("= add i32") && add.contains("= mul i8"), "{}", add);
+        assert!(!add.contains("with.overflow") && !add.contains("@abort"), "{}", add);
+    }
+
+    #[test]
+    fn division_by_zero_always_traps() {
+        let functions = "fn divide(i32 a, i32 b, u8 c, u8 d) u8 {\n    i32 _x = a / b\n    ret c % d\n}";
+
+        for overflow_checks in [true, false] {
+            let ir = compile_functions(functions, overflow_checks);
+            let divide = function_ir(&ir, "divide");
+            assert!(divide.contains("%is_zero = icmp eq i32 %b"), "{}", divide);
+            assert!(divide.contains("icmp eq i8 %d"), "{}", divide);
+            assert!(divide.contains("= urem i8"), "{}", divide);
+            assert!(ir.contains(": attempt to divide by zero\\0A"), "{}", ir);
+            assert!(ir.contains(": attempt to calculate the remainder with a divisor of zero\\0A"), "{}", ir);
+        }
+    }
+
+    #[test]
+    fn min_divided_by_minus_one_traps_or_wraps() {
+        let functions = "fn divide(i8 a, i8 b) i8 {\n    ret a / b\n}";
+
+        // With overflow checks, -128 / -1 aborts the program
+        let ir = compile_functions(functions, true);
+        let divide = function_ir(&ir, "divide");
+        assert!(divide.contains("%is_min = icmp eq i8 %a"), "{}", divide);
+        assert!(divide.contains("%overflowed = and i1 %is_min, %is_minus_one"), "{}", divide);
+        assert!(ir.contains(": attempt to divide with overflow\\0A"), "{}", ir);
+
+        // Without them, it wraps around to -128 by dividing by 1 and negating, since an
+        // overflowing `sdiv` would be undefined behavior
+        let ir = compile_functions(functions, false);
+        let divide = function_ir(&ir, "divide");
+        assert!(divide.contains("%divisor = select i1 %is_minus_one, i8 1, i8 %b"), "{}", divide);
+        assert!(divide.contains("%sdiv = sdiv i8 %a"), "{}", divide);
+        assert!(divide.contains("%quotient = select i1 %is_minus_one, i8 %neg, i8 %sdiv"), "{}", divide);
+        assert!(!ir.contains("attempt to divide with overflow"), "{}", ir);
+        assert!(ir.contains(": attempt to divide by zero\\0A"), "{}", ir);
+    }
+
+    #[test]
+    fn float_to_int_casts_saturate() {
+        let functions = "fn to_u8(f64 x) u8 {\n    ret (u8) x\n}\nfn to_i16(f32 x) i16 {\n    ret (i16) x\n}";
+        let ir = compile_functions(functions, true);
+        assert!(function_ir(&ir, "to_u8").contains("call i8 @llvm.fptoui.sat.i8.f64(double %x"), "{}", ir);
+        assert!(function_ir(&ir, "to_i16").contains("call i16 @llvm.fptosi.sat.i16.f32(float %x"), "{}", ir);
+        assert!(!ir.contains("= fptoui") && !ir.contains("= fptosi"), "{}", ir);
+    }
+
+    #[test]
+    fn shift_amounts_trap_or_wrap() {
+        let functions = "fn shl(u8 a, u8 b) u8 {\n    ret a << b\n}\nfn sar(i24 a, i24 b) i24 {\n    ret a >> b\n}";
+
+        // With overflow checks, shifting by the width or more aborts the program
+        let ir = compile_functions(functions, true);
+        assert!(function_ir(&ir, "shl").contains("%too_big = icmp uge i8 %b4, 8"), "{}", ir);
+        assert!(function_ir(&ir, "sar").contains("%too_big = icmp uge i24 %b4, 24"), "{}", ir);
+        assert!(ir.contains(": attempt to shift left with overflow\\0A"), "{}", ir);
+        assert!(ir.contains(": attempt to shift right with overflow\\0A"), "{}", ir);
+
+        // Without them, the amount wraps around the width
+        let ir = compile_functions(functions, false);
+        assert!(function_ir(&ir, "shl").contains("%amount = and i8 %b4, 7"), "{}", ir);
+        assert!(function_ir(&ir, "sar").contains("%amount = urem i24 %b4, 24"), "{}", ir);
+        assert!(!ir.contains("attempt to shift"), "{}", ir);
+    }
+}
@@ -0,0 +1,28 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CompilationError {
+    /// LLVM failed to optimize the generated code (the message comes from LLVM)
+    Optimization(String),
+    /// LLVM failed to write the object file (the message comes from LLVM)
+    ObjectEmission(String),
+}
+
+impl CompilationError {
+    /// Returns the error's stable code (see [ErrorKind::code](crate::error::ErrorKind::code)).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Optimization(_) => "F0030",
+            Self::ObjectEmission(_) => "F0031",
+        }
+    }
+}
+
+impl fmt::Display for CompilationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Optimization(message) => write!(f, "failed to optimize the program: {}", message),
+            Self::ObjectEmission(message) => write!(f, "failed to write the object file: {}", message),
+        }
+    }
+}
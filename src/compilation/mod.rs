@@ -3,3 +3,10 @@
 ///
 /// [a]: crate::parser::ast;
 pub mod compiler;
+
+/// Module that lowers function signatures to the C calling convention of the target, so that
+/// structs can be passed to and returned from `extern` functions.
+pub mod abi;
+
+/// Module that defines the compilation errors.
+pub mod error;
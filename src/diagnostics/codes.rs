@@ -0,0 +1,1692 @@
+/// The long-form explanation of every diagnostic code, as `(code, explanation)` pairs sorted by
+/// code.
+///
+/// Codes are never reused or renumbered (even if the diagnostic's wording changes), so that links
+/// to them stay valid.
+static EXPLANATIONS: &[(&str, &str)] = &[
+    ("F0001", r#"A character that isn't part of Flick's syntax was found.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 x = 5 @ 2
+        ret x
+    }
+
+Remove the character, or replace it with an operator that Flick supports:
+
+    pub fn main() u8 {
+        u8 x = 5 * 2
+        ret x
+    }
+"#),
+    ("F0002", r#"A token was found somewhere it can't appear.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        else {
+            ret 1
+        }
+        ret 0
+    }
+
+Here, `else` doesn't follow an `if` statement. Make sure each token is used where the grammar
+allows it:
+
+    pub fn main() u8 {
+        if true {
+            ret 1
+        } else {
+            ret 0
+        }
+    }
+"#),
+    ("F0003", r#"The parser needed a particular token (or kind of token) but found something else.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 x 5
+        ret x
+    }
+
+Here, the variable declaration is missing its `=`. Add the token that the error asks for:
+
+    pub fn main() u8 {
+        u8 x = 5
+        ret x
+    }
+"#),
+    ("F0004", r#"The file ended while the parser still needed something.
+
+Erroneous code example (where the file ends right after `fn`):
+
+    pub fn main() u8 {
+        ret 0
+    }
+
+    fn
+
+Finish whatever was left incomplete (here, the second function's definition), or remove it:
+
+    pub fn main() u8 {
+        ret 0
+    }
+
+    fn one() u8 {
+        ret 1
+    }
+"#),
+    ("F0005", r#"Comparison operators were chained together.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u64 x = 5
+        if 1 < x < 10 {
+            ret 1
+        }
+        ret 0
+    }
+
+A chain like `1 < x < 10` is ambiguous, so Flick doesn't allow it. Split it into separate
+comparisons, for example with nested `if` statements:
+
+    pub fn main() u8 {
+        u64 x = 5
+        if 1 < x {
+            if x < 10 {
+                ret 1
+            }
+        }
+        ret 0
+    }
+"#),
+    ("F0006", r#"A body's opening `{` never got a matching `}`.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 x = 0
+        u8 limit = 10
+        while x < limit {
+            x += 1
+        ret x
+    }
+
+Add the missing `}` at the end of the body:
+
+    pub fn main() u8 {
+        u8 x = 0
+        u8 limit = 10
+        while x < limit {
+            x += 1
+        }
+        ret x
+    }
+"#),
+    ("F0007", r#"A function was defined inside of another function.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        fn helper() u8 {
+            ret 1
+        }
+        ret helper()
+    }
+
+Flick only allows functions at the top level of a file. Move the inner function out:
+
+    fn helper() u8 {
+        ret 1
+    }
+
+    pub fn main() u8 {
+        ret helper()
+    }
+"#),
+    ("F0008", r#"The program doesn't define a `main` function.
+
+Erroneous code example:
+
+    pub fn start() u8 {
+        ret 0
+    }
+
+Every program starts running at `main`, which must be defined as `pub fn main() u8`:
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0009", r#"Something named `main` exists, but it isn't a function.
+
+Flick reports this when the name `main` is used for something other than the program's entry
+point. Rename that item, and define `main` as `pub fn main() u8`:
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0010", r#"The `main` function isn't public.
+
+Erroneous code example:
+
+    fn main() u8 {
+        ret 0
+    }
+
+`main` is called from outside of the program, so it must be declared with `pub fn`:
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0011", r#"The `main` function takes parameters.
+
+Erroneous code example:
+
+    pub fn main(u8 argc) u8 {
+        ret argc
+    }
+
+`main` can't accept any parameters:
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0012", r#"The `main` function returns something other than `u8`.
+
+Erroneous code example:
+
+    pub fn main() i32 {
+        ret 0
+    }
+
+The value returned by `main` becomes the program's exit status, which is always a `u8`:
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0013", r#"A function was defined more than once.
+
+Erroneous code example:
+
+    fn double(u8 x) u8 {
+        ret x * 2
+    }
+
+    fn double(u8 x) u8 {
+        ret x + x
+    }
+
+Each function needs a unique name. Remove one of the definitions, or rename it:
+
+    fn double(u8 x) u8 {
+        ret x * 2
+    }
+
+    fn add_to_itself(u8 x) u8 {
+        ret x + x
+    }
+"#),
+    ("F0014", r#"A function that returns a value has a path that never reaches `ret`.
+
+Erroneous code example:
+
+    fn max(u8 a, u8 b) u8 {
+        if a > b {
+            ret a
+        }
+    }
+
+Here, `max` doesn't return anything when `a` isn't greater than `b`. Make sure every path ends
+with a `ret` statement:
+
+    fn max(u8 a, u8 b) u8 {
+        if a > b {
+            ret a
+        }
+        ret b
+    }
+"#),
+    ("F0015", r#"A function without a return type returned a value.
+
+Erroneous code example:
+
+    extern fn putchar(i8 c) i8
+
+    fn log(i8 c) {
+        ret putchar(c)
+    }
+
+Either remove the value from the `ret` statement, or give the function a return type:
+
+    extern fn putchar(i8 c) i8
+
+    fn log(i8 c) i8 {
+        ret putchar(c)
+    }
+"#),
+    ("F0016", r#"A function with a return type has a `ret` statement without a value.
+
+Erroneous code example:
+
+    fn answer() u8 {
+        ret
+    }
+
+Return a value of the function's return type:
+
+    fn answer() u8 {
+        ret 42
+    }
+"#),
+    ("F0017", r#"An identifier was used before it was declared.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        x = 5
+        ret x
+    }
+
+Declare the variable (with its type) before using it:
+
+    pub fn main() u8 {
+        u8 x = 5
+        ret x
+    }
+"#),
+    ("F0018", r#"A function was called, but it was never defined or declared.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        putchar(72)
+        ret 0
+    }
+
+Define the function, or declare it with `extern fn` if it comes from another library (like C's
+standard library):
+
+    extern fn putchar(i8 c) i8
+
+    pub fn main() u8 {
+        putchar(72)
+        ret 0
+    }
+"#),
+    ("F0019", r#"Something that isn't a function was called.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 x = 5
+        ret x()
+    }
+
+Only functions can be called. Remove the parentheses, or call a function instead:
+
+    pub fn main() u8 {
+        u8 x = 5
+        ret x
+    }
+"#),
+    ("F0020", r#"A function was called with the wrong number of arguments.
+
+Erroneous code example:
+
+    fn add(u8 a, u8 b) u8 {
+        ret a + b
+    }
+
+    pub fn main() u8 {
+        ret add(1)
+    }
+
+Pass exactly one argument for each of the function's parameters:
+
+    pub fn main() u8 {
+        ret add(1, 2)
+    }
+"#),
+    ("F0021", r#"An expression has a different type than the one its context requires.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 x = true
+        ret x
+    }
+
+Make sure the value's type matches the type that's needed, for example by declaring the variable
+with the value's type:
+
+    pub fn main() u8 {
+        bool x = true
+        ret 0
+    }
+"#),
+    ("F0022", r#"A variable was used where a value of a different type is required.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        i64 big = 5
+        u8 small = big
+        ret small
+    }
+
+Declare the variable with the right type, or cast it (casts can change an integer's width, but
+only change its signedness when widening an unsigned integer to a wider signed one):
+
+    pub fn main() u8 {
+        u64 big = 5
+        u8 small = (u8) big
+        ret small
+    }
+"#),
+    ("F0023", r#"A function call was used where a value of a different type is required.
+
+Erroneous code example:
+
+    fn five() i64 {
+        ret 5
+    }
+
+    pub fn main() u8 {
+        ret five()
+    }
+
+Change the function's return type, or cast the value it returns:
+
+    fn five() u8 {
+        ret 5
+    }
+
+    pub fn main() u8 {
+        ret five()
+    }
+"#),
+    ("F0024", r#"An integer literal was used where a non-integer value is required.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        bool flag = 1
+        ret 0
+    }
+
+Use a value of the required type instead, like `true` or `false` for a `bool`:
+
+    pub fn main() u8 {
+        bool flag = true
+        ret 0
+    }
+"#),
+    ("F0025", r#"The two sides of a binary operator like `+` have different types.
+
+Erroneous code example:
+
+    fn fits(i16 a, u16 b, i32 limit) bool {
+        ret a + b < limit
+    }
+
+Flick only converts an integer to another type automatically when the other type is wider and can
+hold every value of the integer's type, like `u8` to `u16` or `i32`. Neither `i16` nor `u16` can
+hold every value of the other, so cast one side to a type that can hold both, which the other side
+is then converted to:
+
+    fn fits(i16 a, u16 b, i32 limit) bool {
+        ret (i32) a + b < limit
+    }
+"#),
+    ("F0026", r#"A binary operator like `+` was applied to a type that doesn't support it.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        bool b = true + false
+        ret 0
+    }
+
+Arithmetic operators only work on integers:
+
+    pub fn main() u8 {
+        u8 n = 1 + 0
+        ret n
+    }
+"#),
+    ("F0027", r#"The two sides of a comparison like `<` have different types.
+
+Erroneous code example:
+
+    fn smaller(i8 a, u8 b) bool {
+        ret a < b
+    }
+
+Neither `i8` nor `u8` can hold every value of the other, so neither side is converted
+automatically. Cast one side to a type that can hold both, which the other side is then converted
+to:
+
+    fn smaller(i8 a, u8 b) bool {
+        ret (i16) a < b
+    }
+"#),
+    ("F0028", r#"A cast that isn't allowed was used.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        i64 x = 5
+        ret (u8) x
+    }
+
+Casts can change an integer's width, but only change whether it's signed when an unsigned
+integer is widened to a wider signed type, like `(i16)` of a `u8`, since no value can change
+then. Declare the value with the right signedness in the first place:
+
+    pub fn main() u8 {
+        u64 x = 5
+        ret (u8) x
+    }
+
+Besides that, casts can convert between integers and floats, between floats of different
+widths, between pointer types, from a `char` to any integer (its code point), and from a `u8`
+to a `char`. Other integers can't be cast to a `char`, since they might not be valid code
+points. An integer literal cast to a `char`, like `(char) 65`, is a `u8`.
+"#),
+    ("F0029", r#"Something that can't be negative was negated.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 x = 5
+        u8 y = -x
+        ret 0
+    }
+
+Only signed integers can be negated:
+
+    pub fn main() u8 {
+        i8 x = 5
+        i8 y = -x
+        ret 0
+    }
+"#),
+    ("F0030", r#"LLVM failed to optimize the program.
+
+This usually means that the compiler generated invalid code, which is a bug in Flick rather than
+in your program. Please report it, along with the program that caused it.
+"#),
+    ("F0031", r#"LLVM failed to write the object file.
+
+This usually happens when the object file's path can't be written to, like when its directory
+doesn't exist or isn't writable. Pass a different path with `--object-output-path`, or fix the
+directory's permissions.
+"#),
+    ("F0032", r#"A variable was declared but never read (reported by the `unused_variables` lint).
+
+Example:
+
+    pub fn main() u8 {
+        u8 x = 5
+        ret 0
+    }
+
+Remove the variable, or use it. If it's unused on purpose, start its name with an underscore:
+
+    pub fn main() u8 {
+        u8 _x = 5
+        ret 0
+    }
+
+This is a warning by default. Pass `-A unused_variables` to ignore it, or `-D unused_variables`
+to make it an error.
+"#),
+    ("F0033", r#"Some code comes after a `ret`, `break` or `continue` statement, so it can never run
+(reported by the `unreachable_code` lint).
+
+Example:
+
+    extern fn putchar(i8 c) i8
+
+    pub fn main() u8 {
+        ret 0
+        putchar(72)
+    }
+
+Remove the unreachable code, or move it before the `ret` statement:
+
+    extern fn putchar(i8 c) i8
+
+    pub fn main() u8 {
+        putchar(72)
+        ret 0
+    }
+
+This is a warning by default. Pass `-A unreachable_code` to ignore it, or `-D unreachable_code`
+to make it an error.
+"#),
+    ("F0034", r#"The bitwise negation operator `~` was applied to something that isn't an integer.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        bool flag = ~true
+        ret 0
+    }
+
+`~` flips every bit of an integer. To negate a `bool`, use `not` instead:
+
+    pub fn main() u8 {
+        bool flag = not true
+        ret 0
+    }
+"#),
+    ("F0035", r#"A `break` or `continue` statement was used outside of a loop.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u64 x = 5
+        if x > 3 {
+            break
+        }
+        ret 0
+    }
+
+`break` exits a loop and `continue` starts its next iteration, so both only make sense inside
+of a `while` loop or a `loop`. To leave a function early, use `ret` instead:
+
+    pub fn main() u8 {
+        u64 x = 5
+        if x > 3 {
+            ret 1
+        }
+        ret 0
+    }
+"#),
+    ("F0036", r#"A `break` or `continue` statement refers to a label that none of the loops around it
+have.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        'outer: loop {
+            loop {
+                break 'outr
+            }
+        }
+        ret 0
+    }
+
+Labels are declared before a loop, like `'outer: loop`, and can only be used inside of that
+loop. Make sure the label is spelled correctly:
+
+    pub fn main() u8 {
+        'outer: loop {
+            loop {
+                break 'outer
+            }
+        }
+        ret 0
+    }
+"#),
+    ("F0037", r#"A `for` loop's variable has a type that isn't an integer.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        for bool b in false..true {
+        }
+        ret 0
+    }
+
+`for` loops count from the start of a range to its end, so they only work with integer types
+like `u8` or `i64`. To loop while a condition holds, use a `while` loop instead:
+
+    pub fn main() u8 {
+        bool b = false
+        while not b {
+            b = true
+        }
+        ret 0
+    }
+"#),
+    ("F0038", r#"A type was used that isn't a built-in type, or a struct or enum defined in the program.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        Pointt p = Point { x: 1, y: 2 }
+        ret 0
+    }
+
+    struct Point { i64 x, i64 y }
+
+Struct and enum types can be used anywhere in the program (even before they're defined), but
+they must be defined with `struct` or `enum`. Make sure the type is spelled correctly:
+
+    pub fn main() u8 {
+        Point _p = Point { x: 1, y: 2 }
+        ret 0
+    }
+
+    struct Point { i64 x, i64 y }
+"#),
+    ("F0039", r#"Two types (structs or enums) were defined with the same name.
+
+Erroneous code example:
+
+    struct Point { i64 x, i64 y }
+    struct Point { i64 x, i64 y, i64 z }
+
+    pub fn main() u8 {
+        ret 0
+    }
+
+Each struct and enum needs a unique name, so that it's clear which one a type refers to. Rename
+one of the types:
+
+    struct Point { i64 x, i64 y }
+    struct Point3D { i64 x, i64 y, i64 z }
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0040", r#"A struct field was declared (or given a value) more than once.
+
+Erroneous code example:
+
+    struct Point { i64 x, i64 x }
+
+    pub fn main() u8 {
+        ret 0
+    }
+
+Each field of a struct needs a unique name, and a struct literal gives each field exactly one
+value. Remove the repeated field (or rename it):
+
+    struct Point { i64 x, i64 y }
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0041", r#"A struct or enum contains itself, either directly or through other types' fields or
+payloads.
+
+Erroneous code example:
+
+    struct Node { i64 value, Node next }
+
+    pub fn main() u8 {
+        ret 0
+    }
+
+Fields (and payloads) are stored inside of the value that has them, so a type that contains
+itself would be infinitely large. Remove the field that leads back to the struct:
+
+    struct Node { i64 value, i64 next_index }
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0042", r#"A struct field was declared with a type that can't hold a value, like `void`.
+
+Erroneous code example:
+
+    struct Empty { void nothing }
+
+    pub fn main() u8 {
+        ret 0
+    }
+
+Every field stores a value, so `void` fields aren't allowed. Remove the field, or give it a type
+like `bool`:
+
+    struct Empty { bool nothing }
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0043", r#"A field was used that the struct doesn't have.
+
+Erroneous code example:
+
+    struct Point { i64 x, i64 y }
+
+    pub fn main() u8 {
+        Point p = Point { x: 1, y: 2 }
+        i64 _z = p.z
+        ret 0
+    }
+
+Only the fields in a struct's definition can be read, assigned or given values in a struct
+literal. Make sure the field is spelled correctly (or add it to the struct):
+
+    struct Point { i64 x, i64 y }
+
+    pub fn main() u8 {
+        Point p = Point { x: 1, y: 2 }
+        i64 _y = p.y
+        ret 0
+    }
+"#),
+    ("F0044", r#"A struct literal doesn't give a value to every field of the struct.
+
+Erroneous code example:
+
+    struct Point { i64 x, i64 y }
+
+    pub fn main() u8 {
+        Point _p = Point { x: 1 }
+        ret 0
+    }
+
+Fields don't have default values, so a struct literal must give a value to each of them (in any
+order):
+
+    struct Point { i64 x, i64 y }
+
+    pub fn main() u8 {
+        Point _p = Point { y: 0, x: 1 }
+        ret 0
+    }
+"#),
+    ("F0045", r#"A field was accessed on a value that isn't a struct.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        i64 x = 5
+        i64 _y = x.value
+        ret 0
+    }
+
+Only structs have fields. Use the value itself instead:
+
+    pub fn main() u8 {
+        i64 x = 5
+        i64 _y = x
+        ret 0
+    }
+"#),
+    ("F0046", r#"A comparison operator was used on values that can't be compared, like structs.
+
+Erroneous code example:
+
+    struct Point { i64 x, i64 y }
+
+    pub fn main() u8 {
+        Point a = Point { x: 1, y: 2 }
+        Point b = Point { x: 1, y: 2 }
+        if a == b {
+            ret 1
+        }
+        ret 0
+    }
+
+Structs can't be compared as a whole. Compare their fields instead:
+
+    struct Point { i64 x, i64 y }
+
+    pub fn main() u8 {
+        Point a = Point { x: 1, y: 2 }
+        Point b = Point { x: 1, y: 2 }
+        if a.x == b.x and a.y == b.y {
+            ret 1
+        }
+        ret 0
+    }
+"#),
+    ("F0047", r#"A variant was declared more than once in an enum.
+
+Erroneous code example:
+
+    enum Shape { Circle(u32), Circle(u32, u32) }
+
+    pub fn main() u8 {
+        ret 0
+    }
+
+Each variant of an enum needs a unique name, so that it's clear which one a value or pattern
+refers to. Remove the repeated variant (or rename it):
+
+    enum Shape { Circle(u32), Ellipse(u32, u32) }
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0048", r#"An enum variant's payload has a type that values can't have, like `void`.
+
+Erroneous code example:
+
+    enum Message { Quit(void) }
+
+    pub fn main() u8 {
+        ret 0
+    }
+
+A payload holds values, so each of its types must be a type that values can have. Variants that
+don't carry any data are declared without a payload:
+
+    enum Message { Quit }
+
+    pub fn main() u8 {
+        ret 0
+    }
+"#),
+    ("F0049", r#"An enum variant was used that the enum doesn't have (or the enum doesn't exist).
+
+Erroneous code example:
+
+    enum Shape { Circle(u32), Empty }
+
+    pub fn main() u8 {
+        Shape _s = Shape::Square(2)
+        ret 0
+    }
+
+Enum literals and patterns must name one of the variants declared in the enum's definition.
+Make sure the variant is spelled correctly (or add it to the enum):
+
+    enum Shape { Circle(u32), Square(u32), Empty }
+
+    pub fn main() u8 {
+        Shape _s = Shape::Square(2)
+        ret 0
+    }
+"#),
+    ("F0050", r#"An enum literal or pattern has the wrong number of payload values for its variant.
+
+Erroneous code example:
+
+    enum Shape { Rect(u32, u32), Empty }
+
+    pub fn main() u8 {
+        Shape _s = Shape::Rect(2)
+        ret 0
+    }
+
+Enum literals must give a value to each part of the variant's payload, and patterns must bind
+each part (use `_` for the parts that aren't needed):
+
+    enum Shape { Rect(u32, u32), Empty }
+
+    pub fn main() u8 {
+        Shape _s = Shape::Rect(2, 3)
+        ret 0
+    }
+"#),
+    ("F0051", r#"A pattern can't match values of the type being matched on.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 n = 3
+        match n {
+            true => {
+                ret 1
+            }
+            _ => {
+                ret 0
+            }
+        }
+    }
+
+Patterns must have the same type as the value being matched, and integer patterns must be in the
+range of that type (so `300` can't match a `u8`). Use a pattern of the right type:
+
+    pub fn main() u8 {
+        u8 n = 3
+        match n {
+            3 => {
+                ret 1
+            }
+            _ => {
+                ret 0
+            }
+        }
+    }
+"#),
+    ("F0052", r#"A range pattern doesn't contain any values.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 n = 3
+        match n {
+            5..1 => {
+                ret 1
+            }
+            _ => {
+                ret 0
+            }
+        }
+    }
+
+A range pattern matches the integers from its start up to its end (excluding the end with `..`,
+and including it with `..=`), so its start must come before its end:
+
+    pub fn main() u8 {
+        u8 n = 3
+        match n {
+            1..5 => {
+                ret 1
+            }
+            _ => {
+                ret 0
+            }
+        }
+    }
+"#),
+    ("F0053", r#"A `match` doesn't handle every possible value of what it matches on.
+
+Erroneous code example:
+
+    enum Shape { Circle(u32), Rect(u32, u32), Empty }
+
+    pub fn main() u8 {
+        Shape s = Shape::Empty
+        match s {
+            Shape::Circle(_) => {
+                ret 1
+            }
+            Shape::Empty => {
+                ret 0
+            }
+        }
+    }
+
+Every value must be matched by one of the arms, so that a `match` always runs an arm. Add arms
+for the missing patterns, or a wildcard arm (`_`) that matches everything else:
+
+    enum Shape { Circle(u32), Rect(u32, u32), Empty }
+
+    pub fn main() u8 {
+        Shape s = Shape::Empty
+        match s {
+            Shape::Circle(_) => {
+                ret 1
+            }
+            Shape::Rect(_, _) => {
+                ret 2
+            }
+            Shape::Empty => {
+                ret 0
+            }
+        }
+    }
+"#),
+    ("F0054", r#"A `match` was used on a value whose type can't be matched on, like a struct.
+
+Erroneous code example:
+
+    struct Point { i64 x, i64 y }
+
+    pub fn main() u8 {
+        Point p = Point { x: 1, y: 2 }
+        match p {
+            _ => {
+                ret 0
+            }
+        }
+    }
+
+Only integers, bools and enums can be matched on. Match on a field of the struct instead:
+
+    struct Point { i64 x, i64 y }
+
+    pub fn main() u8 {
+        Point p = Point { x: 1, y: 2 }
+        match p.x {
+            _ => {
+                ret 0
+            }
+        }
+    }
+"#),
+    ("F0055", r#"Something that isn't an array was indexed.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 x = 5
+        ret x[0]
+    }
+
+Only arrays can be indexed. Use the value directly, or put it in an array:
+
+    pub fn main() u8 {
+        [u8; 1] x = [5]
+        ret x[0]
+    }
+"#),
+    ("F0056", r#"An array was indexed by something that isn't an integer.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        [u8; 2] pair = [1, 2]
+        ret pair[true]
+    }
+
+Indices can have any integer type, signed or unsigned:
+
+    pub fn main() u8 {
+        [u8; 2] pair = [1, 2]
+        u32 i = 1
+        ret pair[i]
+    }
+"#),
+    ("F0057", r#"An array literal has a different number of elements than the array type it's used as.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        [u8; 3] rgb = [255, 0]
+        ret rgb[0]
+    }
+
+An array's length is part of its type, so the literal must have exactly that many elements:
+
+    pub fn main() u8 {
+        [u8; 3] rgb = [255, 0, 0]
+        ret rgb[0]
+    }
+"#),
+    ("F0058", r#"The element type of an empty array literal couldn't be inferred.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        ret [][0]
+    }
+
+An empty array literal can only be used where an array type is expected, like in a variable
+declaration:
+
+    pub fn main() u8 {
+        [u8; 0] _empty = []
+        ret 0
+    }
+"#),
+    ("F0059", r#"An array was indexed by an integer literal that's past its end.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        [u8; 4] buf = [0; 4]
+        ret buf[4]
+    }
+
+Indices start at 0, so the last element of an array of length `n` is at index `n - 1`:
+
+    pub fn main() u8 {
+        [u8; 4] buf = [0; 4]
+        ret buf[3]
+    }
+
+Indices that aren't literals are checked when the program runs instead, which aborts the program
+if they're out of bounds (unless it's compiled with `--no-bounds-checks`).
+"#),
+    ("F0060", r#"An array type has elements of a type that values can't have, like `void`.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        [void; 2] _nothing = []
+        ret 0
+    }
+
+Use an element type that has values, or remove the array:
+
+    pub fn main() u8 {
+        [u8; 2] _bytes = [0; 2]
+        ret 0
+    }
+"#),
+    ("F0061", r#"A string literal isn't closed with a `"` before the end of its line.
+
+Erroneous code example:
+
+    extern fn puts(str s) i32
+
+    pub fn main() u8 {
+        puts("Hello)
+        ret 0
+    }
+
+Close the string literal (use `\n` for a newline inside of it):
+
+    extern fn puts(str s) i32
+
+    pub fn main() u8 {
+        puts("Hello")
+        ret 0
+    }
+"#),
+    ("F0062", r#"A string literal contains an escape sequence that doesn't stand for a character.
+
+Erroneous code example:
+
+    extern fn puts(str s) i32
+
+    pub fn main() u8 {
+        puts("C:\Users")
+        ret 0
+    }
+
+The valid escape sequences are `\n`, `\t`, `\r`, `\0`, `\"` and `\\`, `\xNN` for an ASCII
+character (up to `\x7F`) and `\u{NNNN}` for any Unicode character. Escape the backslash if
+it's meant literally:
+
+    extern fn puts(str s) i32
+
+    pub fn main() u8 {
+        puts("C:\\Users")
+        ret 0
+    }
+"#),
+    ("F0063", r#"Something that isn't a pointer (or is a `*void`) is dereferenced with `*`.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 x = 3
+        ret *x
+    }
+
+Only dereference pointers to known types (cast a `*void` to another pointer type first):
+
+    pub fn main() u8 {
+        u8 x = 3
+        *u8 p = &x
+        ret *p
+    }
+"#),
+    ("F0064", r#"The address of a value that isn't stored in a variable is taken with `&`.
+
+Erroneous code example:
+
+    fn three() i64 {
+        ret 3
+    }
+
+    pub fn main() u8 {
+        *i64 _p = &three()
+        ret 0
+    }
+
+Only variables (and their fields and elements) and dereferenced pointers have an address.
+Store the value in a variable first:
+
+    fn three() i64 {
+        ret 3
+    }
+
+    pub fn main() u8 {
+        i64 x = three()
+        *i64 _p = &x
+        ret 0
+    }
+"#),
+    ("F0065", r#"`null` is used where its pointer type can't be inferred.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        if null == null {
+            ret 1
+        }
+        ret 0
+    }
+
+Use `null` where a specific pointer type is expected, like in a variable of that type or in a
+comparison with another pointer:
+
+    pub fn main() u8 {
+        *u8 p = null
+        if p == null {
+            ret 1
+        }
+        ret 0
+    }
+"#),
+    ("F0066", r#"`null` is used where a value that isn't a pointer is expected.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 _x = null
+        ret 0
+    }
+
+`null` can only be a pointer, so use a value of the expected type instead, or make the
+variable a pointer:
+
+    pub fn main() u8 {
+        *u8 _x = null
+        ret 0
+    }
+"#),
+    ("F0067", r#"A float literal is used where a value that isn't a float is expected.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u64 _x = 1.5
+        ret 0
+    }
+
+Float literals are never rounded to integers implicitly, so either make the variable a float,
+or cast the value explicitly:
+
+    pub fn main() u8 {
+        f64 _x = 1.5
+        u64 _y = (u64) 1.5
+        ret 0
+    }
+"#),
+    ("F0068", r#"A float literal is too large to be represented by its type.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        f32 _x = 1e39
+        ret 0
+    }
+
+The largest finite `f32` is about `3.4e38` (and the largest `f64` about `1.8e308`), so use a
+smaller value, or a wider type:
+
+    pub fn main() u8 {
+        f64 _x = 1e39
+        ret 0
+    }
+"#),
+    ("F0069", r#"A character literal with an escape sequence isn't closed right after it.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        char _c = '\n
+        ret 0
+    }
+
+A character literal is exactly one character (or escape sequence) between `'`s, so close it:
+
+    pub fn main() u8 {
+        char _c = '\n'
+        ret 0
+    }
+"#),
+    ("F0070", r#"A global variable has the same name as a function or another global variable.
+
+Erroneous code example:
+
+    const u8 answer = 42
+
+    pub fn main() u8 {
+        ret answer()
+    }
+
+    fn answer() u8 {
+        ret 42
+    }
+
+Functions and global variables share the global scope, so each needs a unique name. Rename one
+of them:
+
+    const u8 ANSWER = 42
+
+    pub fn main() u8 {
+        ret answer()
+    }
+
+    fn answer() u8 {
+        ret ANSWER
+    }
+"#),
+    ("F0071", r#"A global variable's value can't be evaluated at compile time.
+
+Erroneous code example:
+
+    static mut u64 seed = initial_seed()
+
+    fn initial_seed() u64 {
+        ret 42
+    }
+
+    pub fn main() u8 {
+        seed += 1
+        ret 0
+    }
+
+Global variables are initialized before the program runs, so their values can only use literals,
+operators, casts, struct and array literals, and the constants declared before them. Use a
+constant expression, or assign the value at runtime instead:
+
+    static mut u64 seed = 0
+
+    fn initial_seed() u64 {
+        ret 42
+    }
+
+    pub fn main() u8 {
+        seed = initial_seed()
+        ret 0
+    }
+"#),
+    ("F0072", r#"Evaluating a constant expression overflows its type.
+
+Erroneous code example:
+
+    const u8 LIMIT = 200 + 100
+
+    pub fn main() u8 {
+        ret LIMIT
+    }
+
+Constant expressions are evaluated at compile time, where an overflow is an error rather than
+wrapping around. The same goes for casting a float to an integer that can't hold it, and for
+shifting an integer by at least its width. Use a wider type, or a smaller value:
+
+    const u8 LIMIT = 200 + 50
+
+    pub fn main() u8 {
+        ret LIMIT
+    }
+"#),
+    ("F0073", r#"A constant expression divides by zero.
+
+Erroneous code example:
+
+    const u64 PER_PAGE = 0
+    const u64 PAGES = 100 / PER_PAGE
+
+    pub fn main() u8 {
+        ret (u8) PAGES
+    }
+
+Constant expressions are evaluated at compile time, so a division (or remainder) by zero is
+caught before the program runs. Make sure the divisor isn't zero:
+
+    const u64 PER_PAGE = 10
+    const u64 PAGES = 100 / PER_PAGE
+
+    pub fn main() u8 {
+        ret (u8) PAGES
+    }
+"#),
+    ("F0074", r#"A constant is assigned to.
+
+Erroneous code example:
+
+    const u64 count = 0
+
+    pub fn main() u8 {
+        count += 1
+        ret 0
+    }
+
+Constants can't change, since their values are filled in wherever they're used. Declare a global
+variable that can change with `static mut` instead:
+
+    static mut u64 count = 0
+
+    pub fn main() u8 {
+        count += 1
+        ret 0
+    }
+"#),
+    ("F0076", r#"A variable declared without `mut` is assigned to.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        let total = 1
+        total += 2
+        ret total
+    }
+
+Variables declared with `let` can't change after they're declared. Declare the variable with
+`let mut` to allow assigning to it:
+
+    pub fn main() u8 {
+        let mut total = 1
+        total += 2
+        ret total
+    }
+"#),
+    ("F0077", r#"A variable is declared with a type that values can't have.
+
+Erroneous code example:
+
+    fn log() {}
+
+    pub fn main() u8 {
+        void result = log()
+        ret 0
+    }
+
+Functions that don't return anything (like `log` above) don't produce a value that can be stored
+in a variable, so `void` can't be a variable's type. Call the function on its own instead:
+
+    fn log() {}
+
+    pub fn main() u8 {
+        log()
+        ret 0
+    }
+"#),
+    ("F0078", r#"An integer literal is out of range for its type.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        i8 _temperature = -200
+        ret 256
+    }
+
+An integer type can only hold a limited range of values: `u8` holds 0 to 255, `i8` holds -128
+to 127, and in general, an unsigned type with N bits holds 0 to 2^N - 1, while a signed type with
+N bits holds -2^(N - 1) to 2^(N - 1) - 1. Literals outside of that range would silently change
+value, so they're rejected. Use a wider type, or a value in range:
+
+    pub fn main() u8 {
+        i16 _temperature = -200
+        ret 255
+    }
+"#),
+    ("F0079", r#"A built-in integer arithmetic function was called with operands that aren't integers.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        f64 _total = wrapping_add(1.5, 2.5)
+        ret 0
+    }
+
+The functions `wrapping_add`, `checked_add`, `saturating_add` (and their `sub` and `mul`
+versions) choose what happens when integer arithmetic overflows its type. Floats don't overflow
+the same way, so use the operators on them instead:
+
+    pub fn main() u8 {
+        f64 _total = 1.5 + 2.5
+        ret 0
+    }
+"#),
+    ("F0080", r#"A `for` loop's `step` is zero or negative.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        for u8 _i in 0..10 step 0 {
+        }
+        ret 0
+    }
+
+`for` loops only count up, by adding the step to the loop variable until it reaches the end of
+the range, so a step of zero would loop forever and a negative one would never get there. Leave
+out the step to count up by one, or use a positive step:
+
+    pub fn main() u8 {
+        for u8 _i in 0..10 step 2 {
+        }
+        ret 0
+    }
+
+A step that isn't a literal is checked when the program runs instead, which aborts the program
+if the step isn't positive.
+"#),
+    ("F0081", r#"An integer type without any bits, `u0` or `i0`, was used.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u0 _nothing = 0
+        ret 0
+    }
+
+An integer type with N bits can hold 2^N different values, so a type with zero bits would only
+have a single value, and its range would make no sense. Use a type that's at least one bit wide,
+like `u1` or `u8`:
+
+    pub fn main() u8 {
+        u1 _nothing = 0
+        ret 0
+    }
+"#),
+    ("F0082", r#"A literal shift amount is negative or at least as big as the shifted integer's width.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u8 one = 1
+        ret one << 8
+    }
+
+Shifting a `u8` left by 8 would move every bit out of it. Shift amounts must be between 0 and the
+type's width minus one. Amounts that aren't literals are checked when the program runs, and
+shifting by too much aborts it unless overflow checks are disabled:
+
+    pub fn main() u8 {
+        u8 one = 1
+        ret one << 7
+    }
+"#),
+    ("F0083", r#"An integer type is wider than the maximum of 8388608 bits.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u10000000 _huge = 0
+        ret 0
+    }
+
+Integer types can have any width from 1 up to 8388608 bits (2^23), which is the most LLVM
+supports. Use a narrower type:
+
+    pub fn main() u8 {
+        u128 _huge = 0
+        ret 0
+    }
+"#),
+    ("F0084", r#"An integer type's width is too big to even be read, like `u99999999999`.
+
+Erroneous code example:
+
+    pub fn main() u8 {
+        u99999999999 _huge = 0
+        ret 0
+    }
+
+Integer types can have any width from 1 up to 8388608 bits (2^23), which is the most LLVM
+supports (see F0083). Use a narrower type:
+
+    pub fn main() u8 {
+        u128 _huge = 0
+        ret 0
+    }
+"#),
+];
+
+/// Returns the long-form explanation of a diagnostic code like `F0012`, including an example of
+/// code that causes it and how to fix that code. Returns `None` if there's no such code.
+///
+/// # Examples
+/// ```
+/// use flick::diagnostics::codes::explain;
+///
+/// assert!(explain("F0012").unwrap().contains("pub fn main() u8"));
+/// assert!(explain("F9999").is_none());
+/// ```
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .binary_search_by_key(&code, |&(code, _)| code)
+        .ok()
+        .map(|i| EXPLANATIONS[i].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::lint::ALL_LINTS;
+    use crate::error::{CompilationError, LexingError, ParsingError, TypingError};
+
+    #[test]
+    fn codes_are_sorted_and_unique() {
+        for pair in EXPLANATIONS.windows(2) {
+            assert!(pair[0].0 < pair[1].0, "{} should come before {}", pair[0].0, pair[1].0);
+        }
+    }
+
+    #[test]
+    fn codes_are_explained() {
+        let codes = [
+            LexingError::UnexpectedCharacter('@').code(),
+            LexingError::UnterminatedString.code(),
+            LexingError::InvalidEscape(String::new()).code(),
+            LexingError::UnterminatedChar.code(),
+            LexingError::IntTypeTooWide(String::new()).code(),
+            ParsingError::ChainedComparison.code(),
+            TypingError::MissingMain.code(),
+            TypingError::InvalidNegation(crate::types::Type::Bool).code(),
+            TypingError::InvalidBitwiseNot(crate::types::Type::Bool).code(),
+            TypingError::UnknownNullType.code(),
+            TypingError::FloatLiteralOutOfRange { literal: String::new(), float_type: crate::types::FloatType { width: 32 } }.code(),
+            TypingError::NonConstantExpr.code(),
+            TypingError::AssignmentToConst(String::new()).code(),
+            TypingError::AssignmentToImmutable(String::new()).code(),
+            TypingError::InvalidVarType(crate::types::Type::Void).code(),
+            TypingError::IntLiteralOutOfRange { literal: String::new(), int_type: crate::types::IntType { signed: false, width: 8 } }.code(),
+            TypingError::NonIntegerArithmetic { function_name: String::new(), found: crate::types::Type::Bool }.code(),
+            TypingError::NonPositiveStep(String::new()).code(),
+            TypingError::ZeroWidthInt(crate::types::IntType { signed: false, width: 0 }).code(),
+            TypingError::ShiftAmountOutOfRange {
+                amount: String::new(),
+                int_type: crate::types::IntType { signed: false, width: 8 },
+            }
+            .code(),
+            TypingError::TooWideInt(crate::types::IntType { signed: false, width: 0 }).code(),
+            CompilationError::ObjectEmission(String::new()).code(),
+        ];
+        let lint_codes = ALL_LINTS.iter().map(|lint| lint.code);
+
+        for code in codes.into_iter().chain(lint_codes) {
+            assert!(explain(code).is_some(), "{} has no explanation", code);
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt;
+
+use crate::ast::BinaryOperator;
+use crate::error::{ErrorKind, Expected, FlickError, ParsingError, TypingError};
+use crate::types::Type;
+use crate::span::Span;
+
+/// Everything needed to show a message about the user's source code, like a [FlickError].
+///
+/// A diagnostic has a primary [Label] (where the problem is) and optionally secondary labels
+/// (related code, like a previous definition), along with notes, help text, and [Suggestion]s.
+/// Use [Diagnostic::render] to turn it into a human-readable string, or [Diagnostic::to_json] to
+/// turn it into JSON.
+///
+/// # Examples
+/// ```
+/// use flick::diagnostics::Diagnostic;
+/// use flick::span::Span;
+///
+/// let diagnostic = Diagnostic::new("something went wrong", Span::default())
+///     .with_note("this is why")
+///     .with_help("this is how to fix it");
+/// assert_eq!(diagnostic.notes, vec!["this is why".to_string()]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// A stable identifier for the kind of diagnostic, like `F0012`
+    pub code: Option<&'static str>,
+    pub primary: Label,
+    pub secondary: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// How serious a [Diagnostic] is.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Severity {
+    /// A problem that stops the program from being compiled
+    Error,
+    /// A likely mistake that doesn't stop the program from being compiled, like an unused variable
+    Warning,
+    /// Extra information that isn't a problem on its own
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+            Self::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A span of source code, along with an optional message that's shown next to it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: Option<String>,
+}
+
+/// A change to the source code that would fix the problem, like inserting a missing `=`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Suggestion {
+    /// The code to replace (an empty span means `replacement` should be inserted)
+    pub span: Span,
+    pub replacement: String,
+    /// A description of the change, like `insert '='`
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Creates an error diagnostic whose primary label (without a message) points at `span`.
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            code: None,
+            primary: Label { span, message: None },
+            secondary: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Sets how serious the diagnostic is (diagnostics are errors by default).
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Sets the diagnostic's stable code, like `F0012`.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Sets the message shown next to the primary label.
+    pub fn with_primary_message(mut self, message: impl Into<String>) -> Self {
+        self.primary.message = Some(message.into());
+        self
+    }
+
+    /// Adds a secondary label, which points at code related to the diagnostic.
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.secondary.push(Label { span, message: Some(message.into()) });
+        self
+    }
+
+    /// Adds a note, which explains the diagnostic.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Adds help text, which suggests how to fix the problem.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    /// Adds a suggestion to replace the code at `span` with `replacement`.
+    pub fn with_suggestion(
+        mut self,
+        span: Span,
+        replacement: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            span,
+            replacement: replacement.into(),
+            message: message.into(),
+        });
+        self
+    }
+}
+
+impl From<&FlickError> for Diagnostic {
+    fn from(error: &FlickError) -> Self {
+        let diagnostic = Diagnostic::new(error.to_string(), error.span).with_code(error.kind.code());
+
+        match &error.kind {
+            ErrorKind::LexingError(_) => diagnostic,
+            ErrorKind::ParsingError(kind) => match kind {
+                ParsingError::Expected { expected: Expected::Token(token), .. } => {
+                    let insertion_point = error.span.shrink_to_start();
+                    diagnostic.with_suggestion(insertion_point, token.to_string(), format!("insert '{}'", token))
+                }
+                ParsingError::ChainedComparison => diagnostic
+                    .with_note("comparisons like 'a < b < c' are ambiguous"),
+                ParsingError::UnterminatedBody => diagnostic
+                    .with_primary_message("this body is never closed")
+                    .with_help("add a '}' at the end of the body"),
+                ParsingError::NestedFunctionDefinition => diagnostic
+                    .with_help("move this function outside of the function that contains it"),
+                _ => diagnostic,
+            },
+            ErrorKind::TypingError(kind) => match kind.as_ref() {
+                TypingError::MainNotPublic => diagnostic
+                    .with_suggestion(error.span.shrink_to_start(), "pub ", "make 'main' public"),
+                TypingError::InvalidMainReturnType(_) => diagnostic
+                    .with_help("change the return type to 'u8'"),
+                TypingError::FunctionRedefinition { name, previous_definition } => diagnostic
+                    .with_primary_message(format!("'{}' redefined here", name))
+                    .with_label(*previous_definition, format!("previous definition of '{}' here", name)),
+                TypingError::GlobalRedefinition { name, previous_definition } => diagnostic
+                    .with_primary_message(format!("'{}' redefined here", name))
+                    .with_label(*previous_definition, format!("previous definition of '{}' here", name)),
+                TypingError::TypeRedefinition { name, previous_definition } => diagnostic
+                    .with_primary_message(format!("'{}' redefined here", name))
+                    .with_label(*previous_definition, format!("previous definition of '{}' here", name)),
+                TypingError::NonConstantExpr => diagnostic
+                    .with_note("global variables can only be initialized with literals, operators, casts and other constants"),
+                TypingError::AssignmentToConst(_) => diagnostic
+                    .with_help("declare it with 'static mut' to make it mutable"),
+                TypingError::AssignmentToImmutable(_) => diagnostic
+                    .with_help("declare it with 'let mut' to make it mutable"),
+                TypingError::NonExhaustiveMatch { .. } => diagnostic
+                    .with_help("add an arm for each missing pattern, or a wildcard arm ('_ => ...')"),
+                TypingError::MissingReturn { .. } => diagnostic
+                    .with_help("add a 'ret' statement at the end of the function"),
+                TypingError::InvalidBinaryOperand { operator, operand_type: Type::Bool } => match operator {
+                    BinaryOperator::BitwiseAnd => diagnostic.with_help("use 'and' to combine bools"),
+                    BinaryOperator::BitwiseOr => diagnostic.with_help("use 'or' to combine bools"),
+                    _ => diagnostic,
+                },
+                TypingError::InvalidBitwiseNot(Type::Bool) => diagnostic
+                    .with_help("use 'not' to negate a bool"),
+                TypingError::InvalidCast { from: Type::Int(_), to: Type::Int(_) } => diagnostic
+                    .with_note("casts can only change an integer's signedness by widening an unsigned integer to a wider signed one"),
+                TypingError::InvalidCast { from: Type::Int(_), to: Type::Char } => diagnostic
+                    .with_note("only 'u8' can be cast to 'char', since wider integers might not be valid code points"),
+                _ => diagnostic,
+            },
+            ErrorKind::CompilationError(_) => diagnostic,
+        }
+    }
+}
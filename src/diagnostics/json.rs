@@ -0,0 +1,170 @@
+use std::fmt::Write;
+
+use super::diagnostic::{Diagnostic, Label};
+use super::source_map::SourceMap;
+use crate::span::Span;
+
+impl Diagnostic {
+    /// Serializes the diagnostic as a single-line JSON object, using `source_map` to look up the
+    /// names of the files that its spans point into.
+    ///
+    /// The object has the following shape (spans that don't point anywhere real, like those of
+    /// a missing `main` function, are left out of `spans`):
+    ///
+    /// ```text
+    /// {
+    ///   "severity": "error",    (or "warning" or "note")
+    ///   "code": null,
+    ///   "message": "expected '=', found integer literal '5'",
+    ///   "spans": [
+    ///     {
+    ///       "file": "main.fl", "byte_start": 26, "byte_end": 27,
+    ///       "line_start": 2, "column_start": 11, "line_end": 2, "column_end": 12,
+    ///       "is_primary": true, "label": null
+    ///     }
+    ///   ],
+    ///   "notes": [],
+    ///   "help": [],
+    ///   "suggestions": [
+    ///     { "message": "insert '='", "replacement": "=", "span": { "file": "main.fl", ... } }
+    ///   ]
+    /// }
+    /// ```
+    pub fn to_json(&self, source_map: &SourceMap) -> String {
+        let primary = std::iter::once((&self.primary, true));
+        let secondary = self.secondary.iter().map(|label| (label, false));
+        let spans: Vec<_> = primary
+            .chain(secondary)
+            .filter(|(label, _)| label.span.start.line > 0)
+            .map(|(label, is_primary)| label_to_json(label, is_primary, source_map))
+            .collect();
+
+        let suggestions: Vec<_> = self
+            .suggestions
+            .iter()
+            .map(|s| {
+                format!(
+                    "{{\"message\":{},\"replacement\":{},\"span\":{{{}}}}}",
+                    json_string(&s.message),
+                    json_string(&s.replacement),
+                    span_fields(s.span, source_map)
+                )
+            })
+            .collect();
+
+        let code = match self.code {
+            Some(code) => json_string(code),
+            None => "null".to_string(),
+        };
+
+        let mut json = String::new();
+        write!(
+            json,
+            "{{\"severity\":\"{}\",\"code\":{},\"message\":{},\"spans\":[{}],\"notes\":{},\"help\":{},\"suggestions\":[{}]}}",
+            self.severity,
+            code,
+            json_string(&self.message),
+            spans.join(","),
+            json_string_array(&self.notes),
+            json_string_array(&self.help),
+            suggestions.join(",")
+        )
+        .unwrap();
+        json
+    }
+}
+
+/// Serializes a label as a JSON object containing its span's fields (see [span_fields]).
+fn label_to_json(label: &Label, is_primary: bool, source_map: &SourceMap) -> String {
+    let message = match &label.message {
+        Some(message) => json_string(message),
+        None => "null".to_string(),
+    };
+
+    format!(
+        "{{{},\"is_primary\":{},\"label\":{}}}",
+        span_fields(label.span, source_map),
+        is_primary,
+        message
+    )
+}
+
+/// Returns the comma-separated JSON fields describing `span`, without the surrounding braces.
+fn span_fields(span: Span, source_map: &SourceMap) -> String {
+    let file = match source_map.get(span.file_id) {
+        Some(file) => json_string(file.name()),
+        None => "null".to_string(),
+    };
+
+    format!(
+        "\"file\":{},\"byte_start\":{},\"byte_end\":{},\"line_start\":{},\"column_start\":{},\"line_end\":{},\"column_end\":{}",
+        file,
+        span.start.offset,
+        span.end.offset,
+        span.start.line,
+        span.start.column,
+        span.end.line,
+        span.end.column
+    )
+}
+
+/// Serializes a slice of strings as a JSON array.
+fn json_string_array(strings: &[String]) -> String {
+    let strings: Vec<_> = strings.iter().map(|s| json_string(s)).collect();
+    format!("[{}]", strings.join(","))
+}
+
+/// Serializes `string` as a JSON string, including the surrounding quotes.
+fn json_string(string: &str) -> String {
+    let mut json = String::with_capacity(string.len() + 2);
+    json.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::span::Position;
+
+    #[test]
+    fn escaping() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("a \"quote\"\\"), "\"a \\\"quote\\\"\\\\\"");
+        assert_eq!(json_string("line\nbreak\ttab\u{1}"), "\"line\\nbreak\\ttab\\u0001\"");
+    }
+
+    #[test]
+    fn full_diagnostic() {
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add_file("main.fl", "i64 x 5");
+
+        let start = Position { offset: 6, line: 1, column: 7 };
+        let end = Position { offset: 7, line: 1, column: 8 };
+        let span = Span::new(file_id, start, end);
+
+        let diagnostic = Diagnostic::new("expected '='", span)
+            .with_label(Span::default(), "not shown")
+            .with_note("a note")
+            .with_suggestion(span.shrink_to_start(), "=", "insert '='");
+
+        let expected = concat!(
+            r#"{"severity":"error","code":null,"message":"expected '='","#,
+            r#""spans":[{"file":"main.fl","byte_start":6,"byte_end":7,"line_start":1,"column_start":7,"line_end":1,"column_end":8,"is_primary":true,"label":null}],"#,
+            r#""notes":["a note"],"help":[],"#,
+            r#""suggestions":[{"message":"insert '='","replacement":"=","span":{"file":"main.fl","byte_start":6,"byte_end":6,"line_start":1,"column_start":7,"line_end":1,"column_end":7}}]}"#,
+        );
+        assert_eq!(diagnostic.to_json(&source_map), expected);
+    }
+}
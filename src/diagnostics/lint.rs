@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// A check for code that's probably a mistake, even though it's valid Flick (like an unused
+/// variable).
+///
+/// Each lint's [LintLevel] decides whether its findings are ignored, reported as warnings, or
+/// reported as errors. See [ALL_LINTS] for the available lints.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Lint {
+    /// The name used to refer to the lint on the command line, like `unused_variables`
+    pub name: &'static str,
+    /// The stable code of the lint's diagnostics (see [codes](super::codes))
+    pub code: &'static str,
+    pub default_level: LintLevel,
+    pub description: &'static str,
+}
+
+/// How the findings of a [Lint] are reported.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LintLevel {
+    /// Findings are ignored
+    Allow,
+    /// Findings are reported as warnings
+    Warn,
+    /// Findings are reported as errors, so the program won't be compiled
+    Deny,
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Warn => write!(f, "warn"),
+            Self::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+pub static UNUSED_VARIABLES: Lint = Lint {
+    name: "unused_variables",
+    code: "F0032",
+    default_level: LintLevel::Warn,
+    description: "variables that are declared but never read (unless their name starts with '_')",
+};
+
+pub static UNREACHABLE_CODE: Lint = Lint {
+    name: "unreachable_code",
+    code: "F0033",
+    default_level: LintLevel::Warn,
+    description: "statements that come after a 'ret', 'break' or 'continue', so they can never run",
+};
+
+/// Every lint that Flick checks for.
+pub static ALL_LINTS: &[&Lint] = &[&UNUSED_VARIABLES, &UNREACHABLE_CODE];
+
+impl Lint {
+    /// Returns the lint named `name`, or `None` if there's no such lint.
+    pub fn from_name(name: &str) -> Option<&'static Lint> {
+        ALL_LINTS.iter().copied().find(|lint| lint.name == name)
+    }
+}
+
+/// The level of each lint, which starts out as the lint's [default level](Lint::default_level).
+#[derive(Debug, Default, Clone)]
+pub struct LintLevels {
+    overrides: HashMap<&'static str, LintLevel>,
+}
+
+impl LintLevels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Changes the level of `lint` to `level`.
+    pub fn set(&mut self, lint: &Lint, level: LintLevel) {
+        self.overrides.insert(lint.name, level);
+    }
+
+    /// Returns the current level of `lint`.
+    pub fn get(&self, lint: &Lint) -> LintLevel {
+        self.overrides.get(lint.name).copied().unwrap_or(lint.default_level)
+    }
+}
@@ -0,0 +1,18 @@
+/// Module that defines the [SourceMap] of files that spans point into.
+pub mod source_map;
+/// Module that defines the [Diagnostic] struct.
+pub mod diagnostic;
+/// Module that explains diagnostic codes, like `F0012` (see [codes::explain]).
+pub mod codes;
+/// Module that defines lints (checks for likely mistakes), like [lint::UNUSED_VARIABLES].
+pub mod lint;
+/// Module that defines the [DiagnosticSink] that collects non-fatal diagnostics.
+pub mod sink;
+/// Module that renders diagnostics as text (see [Diagnostic::render]).
+mod render;
+/// Module that serializes diagnostics as JSON (see [Diagnostic::to_json]).
+mod json;
+
+pub use diagnostic::{Diagnostic, Label, Severity, Suggestion};
+pub use sink::DiagnosticSink;
+pub use source_map::{SourceFile, SourceMap};
@@ -0,0 +1,250 @@
+use std::fmt::Write;
+
+use super::diagnostic::{Diagnostic, Label};
+use super::source_map::{SourceFile, SourceMap};
+
+/// How many lines of source code are shown above and below each labeled line
+const CONTEXT_LINES: usize = 1;
+
+/// How many columns a tab character takes up when source code is displayed
+const TAB_WIDTH: usize = 4;
+
+impl Diagnostic {
+    /// Renders the diagnostic as human-readable text, using `source_map` to look up the source
+    /// code that its labels point at.
+    ///
+    /// For example, a diagnostic pointing at `b` in `u8 c = b` is rendered like this:
+    ///
+    /// ```text
+    /// error[F0022]: identifier 'b' of type 'i64' cannot be used as type 'u8'
+    ///  --> main.fl:3:12
+    ///   │
+    /// 2 │     i64 b = 5
+    /// 3 │     u8 c = b
+    ///   │            ^
+    /// 4 │     ret c
+    /// ```
+    ///
+    /// Labels that don't point anywhere real (like those with a default [Span](crate::span::Span))
+    /// are left out, as are labels pointing into files that aren't in `source_map`.
+    pub fn render(&self, source_map: &SourceMap) -> String {
+        let mut output = match self.code {
+            Some(code) => format!("{}[{}]: {}\n", self.severity, code, self.message),
+            None => format!("{}: {}\n", self.severity, self.message),
+        };
+
+        let primary_span = self.primary.span;
+        let file = source_map.get(primary_span.file_id);
+        let labels: Vec<_> = std::iter::once(&self.primary)
+            .chain(self.secondary.iter())
+            .filter(|label| label.span.file_id == primary_span.file_id && label.span.start.line > 0)
+            .collect();
+
+        // Enough space for the largest line number that might be shown
+        let gutter_width = match (file, labels.iter().map(|l| l.span.start.line).max()) {
+            (Some(file), Some(max_line)) => (max_line + CONTEXT_LINES).min(file.line_count()).to_string().len(),
+            _ => 0,
+        };
+
+        if let Some(file) = file {
+            match primary_span.start.line {
+                0 => writeln!(output, "{:w$}--> {}", "", file.name(), w = gutter_width),
+                line => writeln!(
+                    output,
+                    "{:w$}--> {}:{}:{}",
+                    "",
+                    file.name(),
+                    line,
+                    primary_span.start.column,
+                    w = gutter_width
+                ),
+            }
+            .unwrap();
+
+            // Secondary labels are only shown alongside the primary one
+            if primary_span.start.line > 0 {
+                render_snippet(&mut output, file, &labels, gutter_width);
+            }
+        }
+
+        for note in self.notes.iter() {
+            writeln!(output, "{:w$} = note: {}", "", note, w = gutter_width).unwrap();
+        }
+        let suggestions = self.suggestions.iter().map(|s| &s.message);
+        for help in self.help.iter().chain(suggestions) {
+            writeln!(output, "{:w$} = help: {}", "", help, w = gutter_width).unwrap();
+        }
+
+        output
+    }
+}
+
+/// Writes the lines of `file` that the `labels` point at (plus some context) to `output`, with
+/// each label's span underlined.
+fn render_snippet(output: &mut String, file: &SourceFile, labels: &[&Label], gutter_width: usize) {
+    let last_line = file.line_count();
+    let mut lines_to_show: Vec<_> = labels
+        .iter()
+        .flat_map(|label| {
+            let line = label.span.start.line;
+            line.saturating_sub(CONTEXT_LINES).max(1)..=(line + CONTEXT_LINES).min(last_line)
+        })
+        .collect();
+    lines_to_show.sort();
+    lines_to_show.dedup();
+
+    // A file that ends with a newline has an empty last line, which isn't worth showing as context
+    let is_labeled = |line_num| labels.iter().any(|l| l.span.start.line == line_num);
+    lines_to_show.retain(|&n| n != last_line || is_labeled(n) || file.line(n) != Some(""));
+
+    writeln!(output, "{:w$} │", "", w = gutter_width).unwrap();
+
+    let mut previous_line = None;
+    for line_num in lines_to_show {
+        if previous_line.is_some_and(|previous| line_num > previous + 1) {
+            writeln!(output, "...").unwrap();
+        }
+        previous_line = Some(line_num);
+
+        let line = file.line(line_num).unwrap_or_default();
+        let row = format!("{:>w$} │ {}", line_num, expand_tabs(line), w = gutter_width);
+        writeln!(output, "{}", row.trim_end()).unwrap();
+
+        let mut line_labels: Vec<_> = labels
+            .iter()
+            .enumerate()
+            .filter(|(_, label)| label.span.start.line == line_num)
+            .collect();
+        line_labels.sort_by_key(|(_, label)| label.span.start.column);
+
+        for (index, label) in line_labels {
+            // The primary label is always the first label
+            let marker = if index == 0 { '^' } else { '-' };
+            let underline = underline(line, label, marker);
+            let message = label.message.as_deref().unwrap_or_default();
+            let row = format!("{:w$} │ {} {}", "", underline, message, w = gutter_width);
+            writeln!(output, "{}", row.trim_end()).unwrap();
+        }
+    }
+}
+
+/// Returns a string that lines up with `label`'s span in `line`, consisting of spaces followed
+/// by at least one `marker`.
+///
+/// If the span continues past the end of `line`, it's underlined until the end of `line`.
+fn underline(line: &str, label: &Label, marker: char) -> String {
+    let start = label.span.start.column.saturating_sub(1);
+    let end = match label.span.end.line == label.span.start.line {
+        true => label.span.end.column.saturating_sub(1),
+        false => line.chars().count(),
+    };
+
+    let prefix: String = line.chars().take(start).collect();
+    let underlined: String = line.chars().skip(start).take(end.saturating_sub(start)).collect();
+
+    let padding = display_width(&prefix);
+    let length = display_width(&underlined).max(1);
+    format!("{}{}", " ".repeat(padding), marker.to_string().repeat(length))
+}
+
+/// Replaces each tab in `line` with spaces, so that underlines line up with the source code.
+fn expand_tabs(line: &str) -> String {
+    line.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+/// Returns the number of columns `text` takes up once its tabs are expanded.
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c == '\t' { TAB_WIDTH } else { 1 }).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::span::{Position, Span};
+
+    /// Returns the span from (`line`, `start_column`) to (`line`, `end_column`) in file 0.
+    ///
+    /// Byte offsets are left as 0, since they aren't used for rendering.
+    fn span(line: usize, start_column: usize, end_column: usize) -> Span {
+        let start = Position { offset: 0, line, column: start_column };
+        let end = Position { offset: 0, line, column: end_column };
+        Span::new(0, start, end)
+    }
+
+    fn source_map(source: &str) -> SourceMap {
+        let mut source_map = SourceMap::new();
+        source_map.add_file("main.fl", source);
+        source_map
+    }
+
+    #[test]
+    fn primary_label_with_context() {
+        let source_map = source_map("pub fn main() u8 {\n    i64 b = 5\n    u8 c = b\n    ret c\n}\n");
+        let diagnostic = Diagnostic::new("mismatched types", span(3, 12, 13))
+            .with_primary_message("expected 'u8'")
+            .with_help("use a cast");
+
+        let expected = "\
+error: mismatched types
+ --> main.fl:3:12
+  │
+2 │     i64 b = 5
+3 │     u8 c = b
+  │            ^ expected 'u8'
+4 │     ret c
+  = help: use a cast
+";
+        assert_eq!(diagnostic.render(&source_map), expected);
+    }
+
+    #[test]
+    fn secondary_labels_and_gaps() {
+        let source_map = source_map("fn f() {}\n\n\n\n\nfn f() {}");
+        let diagnostic = Diagnostic::new("function 'f' is defined more than once", span(6, 1, 7))
+            .with_label(span(1, 1, 7), "previous definition here");
+
+        let expected = "\
+error: function 'f' is defined more than once
+ --> main.fl:6:1
+  │
+1 │ fn f() {}
+  │ ------ previous definition here
+2 │
+...
+5 │
+6 │ fn f() {}
+  │ ^^^^^^
+";
+        assert_eq!(diagnostic.render(&source_map), expected);
+    }
+
+    #[test]
+    fn tabs_and_empty_spans() {
+        let source_map = source_map("\tfoo(");
+        let diagnostic = Diagnostic::new("expected expression, found end of file", span(1, 6, 6));
+
+        let expected = "\
+error: expected expression, found end of file
+ --> main.fl:1:6
+  │
+1 │     foo(
+  │         ^
+";
+        assert_eq!(diagnostic.render(&source_map), expected);
+    }
+
+    #[test]
+    fn no_location() {
+        let source_map = source_map("fn f() {}");
+        let diagnostic = Diagnostic::new("no 'main' function defined", Span::default())
+            .with_code("F0008")
+            .with_note("every program needs one");
+
+        let expected = "\
+error[F0008]: no 'main' function defined
+--> main.fl
+ = note: every program needs one
+";
+        assert_eq!(diagnostic.render(&source_map), expected);
+    }
+}
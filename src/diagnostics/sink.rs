@@ -0,0 +1,97 @@
+use super::diagnostic::{Diagnostic, Severity};
+use super::lint::{Lint, LintLevel, LintLevels};
+
+/// A place to collect non-fatal diagnostics (like warnings) while compilation keeps going.
+///
+/// Findings of a [Lint] should be reported with [DiagnosticSink::lint], which uses the sink's
+/// [LintLevels] to decide whether to drop the finding, keep it as a warning, or turn it into an
+/// error.
+///
+/// # Examples
+/// ```
+/// use flick::diagnostics::lint::{LintLevel, LintLevels, UNUSED_VARIABLES};
+/// use flick::diagnostics::{Diagnostic, DiagnosticSink};
+/// use flick::span::Span;
+///
+/// let mut lint_levels = LintLevels::new();
+/// lint_levels.set(&UNUSED_VARIABLES, LintLevel::Deny);
+///
+/// let mut sink = DiagnosticSink::new(lint_levels);
+/// sink.lint(&UNUSED_VARIABLES, Diagnostic::new("unused variable 'x'", Span::default()));
+/// assert!(sink.has_errors());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct DiagnosticSink {
+    lint_levels: LintLevels,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticSink {
+    pub fn new(lint_levels: LintLevels) -> Self {
+        Self {
+            lint_levels,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Adds `diagnostic` to the sink as is.
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Reports a finding of `lint`, whose severity is decided by the lint's level.
+    pub fn lint(&mut self, lint: &Lint, diagnostic: Diagnostic) {
+        let level = self.lint_levels.get(lint);
+        let severity = match level {
+            LintLevel::Allow => return,
+            LintLevel::Warn => Severity::Warning,
+            LintLevel::Deny => Severity::Error,
+        };
+
+        let note = match level == lint.default_level {
+            true => format!("'{}' is set to '{}' by default", lint.name, level),
+            false => format!("'{}' is set to '{}'", lint.name, level),
+        };
+        self.push(diagnostic.with_severity(severity).with_code(lint.code).with_note(note));
+    }
+
+    /// Removes the diagnostics added after the first `len` (like the findings of an attempt that
+    /// was thrown away).
+    pub fn truncate(&mut self, len: usize) {
+        self.diagnostics.truncate(len);
+    }
+
+    /// Returns every diagnostic in the sink, in the order they were added.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Returns whether any diagnostic in the sink is an error (for example, a denied lint).
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.severity == Severity::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::lint::{UNREACHABLE_CODE, UNUSED_VARIABLES};
+    use crate::span::Span;
+
+    #[test]
+    fn lint_levels() {
+        let mut lint_levels = LintLevels::new();
+        lint_levels.set(&UNREACHABLE_CODE, LintLevel::Allow);
+
+        let mut sink = DiagnosticSink::new(lint_levels);
+        sink.lint(&UNUSED_VARIABLES, Diagnostic::new("unused", Span::default()));
+        sink.lint(&UNREACHABLE_CODE, Diagnostic::new("unreachable", Span::default()));
+
+        assert_eq!(sink.diagnostics().len(), 1);
+        let diagnostic = &sink.diagnostics()[0];
+        assert_eq!(diagnostic.message, "unused");
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(diagnostic.notes, vec!["'unused_variables' is set to 'warn' by default".to_string()]);
+        assert!(!sink.has_errors());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::span::FileId;
+
+/// A collection of source files, which lets a [Span](crate::span::Span) (which only stores a
+/// [FileId]) be traced back to the file name and source code it points into.
+///
+/// # Examples
+/// ```
+/// use flick::diagnostics::SourceMap;
+///
+/// let mut source_map = SourceMap::new();
+/// let file_id = source_map.add_file("main.fl", "pub fn main() u8 {\n    ret 0\n}");
+///
+/// let file = source_map.get(file_id).unwrap();
+/// assert_eq!(file.name(), "main.fl");
+/// assert_eq!(file.line(2), Some("    ret 0"));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file to the source map and returns the [FileId] that its spans should use.
+    pub fn add_file(&mut self, name: impl Into<String>, source: impl Into<String>) -> FileId {
+        self.files.push(SourceFile::new(name.into(), source.into()));
+        self.files.len() - 1
+    }
+
+    /// Returns the file with the given id, or `None` if no such file was added.
+    pub fn get(&self, file_id: FileId) -> Option<&SourceFile> {
+        self.files.get(file_id)
+    }
+}
+
+/// A single source file in a [SourceMap].
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    name: String,
+    source: String,
+    /// The byte offset at which each line starts
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    fn new(name: String, source: String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            name,
+            source,
+            line_starts,
+        }
+    }
+
+    /// Returns the name of the file (usually its path), as shown in diagnostics.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the file's entire source code.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the number of lines in the file.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns line number `line_num` (starting at 1), without its line ending, or `None` if the
+    /// file doesn't have that many lines.
+    pub fn line(&self, line_num: usize) -> Option<&str> {
+        let start = *self.line_starts.get(line_num.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line_num)
+            .map_or(self.source.len(), |&next_start| next_start - 1);
+
+        let line = &self.source[start..end];
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add_file("test.fl", "first\r\n\nthird\n");
+        let file = source_map.get(file_id).unwrap();
+
+        assert_eq!(file.line_count(), 4);
+        assert_eq!(file.line(0), None);
+        assert_eq!(file.line(1), Some("first"));
+        assert_eq!(file.line(2), Some(""));
+        assert_eq!(file.line(3), Some("third"));
+        assert_eq!(file.line(4), Some(""));
+        assert_eq!(file.line(5), None);
+    }
+
+    #[test]
+    fn multiple_files() {
+        let mut source_map = SourceMap::new();
+        let first = source_map.add_file("first.fl", "a");
+        let second = source_map.add_file("second.fl", "b");
+
+        assert_eq!(source_map.get(first).unwrap().name(), "first.fl");
+        assert_eq!(source_map.get(second).unwrap().source(), "b");
+        assert!(source_map.get(2).is_none());
+    }
+}
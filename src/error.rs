@@ -1,13 +1,18 @@
 use std::fmt;
 use std::error::Error;
 
-use crate::lexing::error::LexingError;
+use crate::span::Span;
+
+pub use crate::compilation::error::CompilationError;
+pub use crate::lexing::error::LexingError;
+pub use crate::parsing::error::{Expected, ParsingError};
+pub use crate::typing::error::TypingError;
 
 pub type Result<T> = std::result::Result<T, FlickError>;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct FlickError {
-    pub index: usize,
+    pub span: Span,
     pub kind: ErrorKind,
 }
 
@@ -22,15 +27,32 @@ impl Error for FlickError {}
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ErrorKind {
     LexingError(LexingError),
-    // ParsingError(ParsingError),
-    // TypingError(TypingError),
-    // CompilationError(CompilationError),
+    ParsingError(ParsingError),
+    // Boxed since typing errors carry several types, which would otherwise bloat every Result
+    TypingError(Box<TypingError>),
+    CompilationError(CompilationError),
+}
+
+impl ErrorKind {
+    /// Returns the error's stable code, like `F0012`, which can be passed to `flick explain`
+    /// (see [diagnostics::codes](crate::diagnostics::codes)).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::LexingError(err) => err.code(),
+            Self::ParsingError(err) => err.code(),
+            Self::TypingError(err) => err.code(),
+            Self::CompilationError(err) => err.code(),
+        }
+    }
 }
 
 impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::LexingError(err) => err.fmt(f),
+            Self::ParsingError(err) => err.fmt(f),
+            Self::TypingError(err) => err.fmt(f),
+            Self::CompilationError(err) => err.fmt(f),
         }
     }
 }
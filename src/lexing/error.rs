@@ -1,14 +1,44 @@
 use std::fmt;
 
+use crate::types::IntType;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum LexingError {
     UnexpectedCharacter(char),
+    /// A string literal isn't closed before the end of its line
+    UnterminatedString,
+    /// A string literal contains an escape sequence that doesn't stand for a character, like
+    /// `\q` or `\u{110000}`
+    InvalidEscape(String),
+    /// A character literal with an escape sequence isn't closed right after it, like `'\n`
+    UnterminatedChar,
+    /// An integer type's width doesn't even fit in a `u32`, like `u99999999999`
+    IntTypeTooWide(String),
+}
+
+impl LexingError {
+    /// Returns the error's stable code (see [ErrorKind::code](crate::error::ErrorKind::code)).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnexpectedCharacter(_) => "F0001",
+            Self::UnterminatedString => "F0061",
+            Self::InvalidEscape(_) => "F0062",
+            Self::UnterminatedChar => "F0069",
+            Self::IntTypeTooWide(_) => "F0084",
+        }
+    }
 }
 
 impl fmt::Display for LexingError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::UnexpectedCharacter(c) => write!(f, "unexpected character: '{}'", c),
+            Self::UnterminatedString => write!(f, "string literal is never closed"),
+            Self::InvalidEscape(escape) => write!(f, "invalid escape sequence '{}'", escape),
+            Self::UnterminatedChar => write!(f, "character literal is never closed"),
+            Self::IntTypeTooWide(int_type) => {
+                write!(f, "integer type '{}' is too wide (at most {} bits)", int_type, IntType::MAX_WIDTH)
+            }
         }
     }
 }
@@ -2,7 +2,8 @@ use crate::lexing::token::AssignmentSymbol::*;
 use crate::lexing::token::ComparatorSymbol::*;
 use crate::lexing::token::OperatorSymbol::*;
 use crate::lexing::token::Token;
-use crate::types::IntType;
+use crate::span::{FileId, Position, Span, Spanned};
+use crate::types::{FloatType, IntType};
 use crate::types::Type;
 
 use crate::error::{FlickError, ErrorKind};
@@ -16,7 +17,8 @@ use super::error::LexingError;
 /// ```
 /// use flick::Lexer;
 /// let source_code: Vec<_> = "foo(42)".to_string().chars().collect();
-/// let mut tokens = Lexer::lex(&source_code);
+/// let (tokens, errors) = Lexer::lex(&source_code, 0);
+/// assert!(errors.is_empty());
 /// ```
 pub struct Lexer<'a> {
     /// Source code slice
@@ -27,29 +29,75 @@ pub struct Lexer<'a> {
     /// In other words, the character at index `self.cursor` of `self.chars` hasn't
     /// been processed yet.
     cursor: usize,
+
+    /// The line, column, and byte offset of the character at index `self.cursor`
+    position: Position,
+
+    /// The file that `source_code` was read from; stored in every [Span] the lexer creates
+    file_id: FileId,
+
+    /// Errors in tokens that were still produced, like an invalid escape sequence inside of an
+    /// otherwise valid string literal
+    recovered_errors: Vec<FlickError>,
 }
 
 impl<'a> Lexer<'a> {
-    /// Converts the source code into a vector of tokens
-    /// 
-    /// Returns an `Err()` if lexing fails.
-    pub fn lex(source_code: &'a [char]) -> crate::Result<Vec<Token>> {
+    /// Converts the source code into a vector of tokens, each labeled with the [Span] of
+    /// source code that it came from.
+    ///
+    /// Lexing doesn't stop at the first error: characters that can't start a token are reported
+    /// and skipped, so this function returns every token it could form along with every error
+    /// it encountered (if lexing succeeded, the vector of errors is empty).
+    pub fn lex(source_code: &'a [char], file_id: FileId) -> (Vec<Spanned<Token>>, Vec<FlickError>) {
         let mut lexer = Self {
             source_code,
             cursor: 0,
+            position: Position::start_of_file(),
+            file_id,
+            recovered_errors: Vec::new(),
         };
 
         let mut tokens = Vec::new();
-        while lexer.cursor < lexer.source_code.len() {
-            tokens.push(lexer.next_token()?);
+        let mut errors = Vec::new();
+        loop {
+            lexer.skip_non_newline_whitespace();
+            if lexer.cursor >= lexer.source_code.len() {
+                break;
+            }
+
+            let start = lexer.position;
+            let token = lexer.next_token();
+            errors.append(&mut lexer.recovered_errors);
+            match token {
+                Ok(token) => tokens.push(Spanned::new(token, lexer.span_from(start))),
+                Err(err) => {
+                    // Skip the offending character (unless the lexer already moved past it, like
+                    // after an unterminated string literal) and keep lexing
+                    errors.push(err);
+                    if lexer.position == start {
+                        lexer.skip_chars(1);
+                    }
+                }
+            }
         }
-        Ok(tokens)
+        (tokens, errors)
     }
 
     /// Returns (and consumes) a reference to the next character in the source code.
     fn next_char(&mut self) -> Option<&char> {
         let char = self.source_code.get(self.cursor);
         self.cursor += 1;
+
+        if let Some(&c) = char {
+            self.position.offset += c.len_utf8();
+            if c == '\n' {
+                self.position.line += 1;
+                self.position.column = 1;
+            } else {
+                self.position.column += 1;
+            }
+        }
+
         char
     }
 
@@ -65,26 +113,54 @@ impl<'a> Lexer<'a> {
 
     /// Advances the cursor past the next `n` characters without returning anything.
     fn skip_chars(&mut self, n: usize) {
-        self.cursor += n;
+        for _ in 0..n {
+            self.next_char();
+        }
+    }
+
+    /// Returns the span from `start` up to (but not including) the next unprocessed character.
+    fn span_from(&self, start: Position) -> Span {
+        Span::new(self.file_id, start, self.position)
     }
 
     /// Consumes source code characters until a token is formed; returns the token.
     ///
-    /// Note: this function skips any initial whitespace (except for a newline, which is a
-    /// token, namely [Token::Newline]).
-    ///
     /// # Assumptions
     /// - There's at least one character of the source code left (`self.cursor < self.source_code`)
+    /// - Any whitespace before the token (except for a newline, which is a token, namely
+    ///   [Token::Newline]) has already been skipped
     fn next_token(&mut self) -> crate::Result<Token> {
-        self.skip_non_newline_whitespace();
-
         let first_token = self.peek_char(1).expect("see assumptions in docstring");
 
         // Figure out what type the next token is and call handling function
         let peeked_token = match (first_token, self.peek_char(2)) {
             ('a'..='z' | 'A'..='Z' | '_', _) => return Ok(self.read_word()),
-            ('0'..='9', _) => return Ok(self.read_int_literal()),
+            ('0'..='9', _) => return Ok(self.read_number_literal()),
+            ('"', _) => return self.read_str_literal(),
             ('/', Some('/')) => return Ok(self.read_comment()),
+            // `'a'` is a character literal, but `'a` (without a closing `'`) is a label
+            ('\'', Some('\\')) => return self.read_char_literal(),
+            ('\'', Some(c)) if *c != '\'' && *c != '\n' && self.peek_char(3) == Some(&'\'') => {
+                return self.read_char_literal();
+            }
+            ('\'', Some('a'..='z' | 'A'..='Z' | '_')) => return Ok(self.read_label()),
+
+            ('<', Some('<')) => match self.peek_char(3) {
+                Some('=') => Token::AssignmentSymbol(ShiftLeftEq),
+                _ => Token::OperatorSymbol(ShiftLeft),
+            },
+            ('>', Some('>')) => match self.peek_char(3) {
+                Some('=') => Token::AssignmentSymbol(ShiftRightEq),
+                _ => Token::OperatorSymbol(ShiftRight),
+            },
+
+            ('.', Some('.')) => match self.peek_char(3) {
+                Some('=') => Token::DotDotEq,
+                _ => Token::DotDot,
+            },
+
+            (':', Some(':')) => Token::ColonColon,
+            ('=', Some('>')) => Token::FatArrow,
 
             ('>', Some('=')) => Token::ComparatorSymbol(GreaterOrEqualTo),
             ('<', Some('=')) => Token::ComparatorSymbol(LessOrEqualTo),
@@ -95,6 +171,9 @@ impl<'a> Lexer<'a> {
             ('/', Some('=')) => Token::AssignmentSymbol(DivideEq),
             ('-', Some('=')) => Token::AssignmentSymbol(MinusEq),
             ('+', Some('=')) => Token::AssignmentSymbol(PlusEq),
+            ('&', Some('=')) => Token::AssignmentSymbol(AndEq),
+            ('|', Some('=')) => Token::AssignmentSymbol(OrEq),
+            ('^', Some('=')) => Token::AssignmentSymbol(XorEq),
             ('=', _) => Token::AssignmentSymbol(Eq),
 
             ('>', _) => Token::ComparatorSymbol(GreaterThan),
@@ -104,9 +183,18 @@ impl<'a> Lexer<'a> {
             ('/', _) => Token::OperatorSymbol(Slash),
             ('-', _) => Token::OperatorSymbol(Minus),
             ('+', _) => Token::OperatorSymbol(Plus),
+            ('&', _) => Token::OperatorSymbol(Ampersand),
+            ('|', _) => Token::OperatorSymbol(Pipe),
+            ('^', _) => Token::OperatorSymbol(Caret),
+            ('~', _) => Token::OperatorSymbol(Tilde),
             (',', _) => Token::Comma,
+            (':', _) => Token::Colon,
+            (';', _) => Token::Semicolon,
+            ('.', _) => Token::Dot,
             ('(', _) => Token::LParen,
             (')', _) => Token::RParen,
+            ('[', _) => Token::LBracket,
+            (']', _) => Token::RBracket,
             ('{', _) => Token::LSquirly,
             ('}', _) => Token::RSquirly,
             ('\n', _) => Token::Newline,
@@ -118,9 +206,25 @@ impl<'a> Lexer<'a> {
         Ok(peeked_token)
     }
 
+    /// Returns an error pointing at the next unprocessed character.
     fn err(&self, kind: LexingError) -> FlickError {
+        let mut end = self.position;
+        if let Some(c) = self.peek_char(1) {
+            end.offset += c.len_utf8();
+            end.column += 1;
+        }
+
+        FlickError {
+            span: Span::new(self.file_id, self.position, end),
+            kind: ErrorKind::LexingError(kind),
+        }
+    }
+
+    /// Returns an error spanning from `start` up to (but not including) the next unprocessed
+    /// character.
+    fn err_from(&self, kind: LexingError, start: Position) -> FlickError {
         FlickError {
-            index: self.cursor,
+            span: self.span_from(start),
             kind: ErrorKind::LexingError(kind),
         }
     }
@@ -163,15 +267,23 @@ impl<'a> Lexer<'a> {
     /// Consumes source code characters and returns the corresponding [Token], either a keyword
     /// (e.g., `while`) or an identifier (e.g., `foo`).
     ///
+    /// An integer type whose width doesn't fit in a `u32` is reported (as a recovered error), and
+    /// the widest valid type is produced instead so that it doesn't cause more errors.
+    ///
     /// # Assumptions:
     ///
     /// - The next source code character is one of `a-z`, `A-Z`, or `_`.
     fn read_word(&mut self) -> Token {
+        let start = self.position;
         let s = self.take_chars_while(|&c| c.is_ascii_alphanumeric() || c == '_');
 
         if (s.starts_with('u') || s.starts_with('i')) && s.len() > 1 && s.chars().skip(1).all(|c| c.is_ascii_digit()) {
             let num: String = s.chars().skip(1).collect();
-            let width: u32 = num.parse().unwrap();
+            let width = num.parse().unwrap_or_else(|_| {
+                let err = self.err_from(LexingError::IntTypeTooWide(s.clone()), start);
+                self.recovered_errors.push(err);
+                IntType::MAX_WIDTH
+            });
             match s.chars().next().unwrap() {
                 'u' => return Token::Type(Type::Int(IntType { width, signed: false })),
                 'i' => return Token::Type(Type::Int(IntType { width, signed: true })),
@@ -181,28 +293,213 @@ impl<'a> Lexer<'a> {
 
         match s.as_str() {
             "bool" => Token::Type(Type::Bool),
+            "f32" => Token::Type(Type::Float(FloatType { width: 32 })),
+            "f64" => Token::Type(Type::Float(FloatType { width: 64 })),
+            "str" => Token::Type(Type::Str),
+            "char" => Token::Type(Type::Char),
             "void" => Token::Type(Type::Void),
             "while" => Token::While,
+            "loop" => Token::Loop,
+            "for" => Token::For,
+            "in" => Token::In,
+            "step" => Token::Step,
+            "break" => Token::Break,
+            "continue" => Token::Continue,
             "pub" => Token::Pub,
+            "const" => Token::Const,
+            "static" => Token::Static,
+            "mut" => Token::Mut,
+            "let" => Token::Let,
             "fn" => Token::Fn,
+            "struct" => Token::Struct,
+            "enum" => Token::Enum,
+            "match" => Token::Match,
             "ret" => Token::Ret,
             "if" => Token::If,
             "extern" => Token::Extern,
             "else" => Token::Else,
             "true" => Token::True,
             "false" => Token::False,
+            "null" => Token::Null,
+            "and" => Token::And,
+            "or" => Token::Or,
+            "not" => Token::Not,
             _ => Token::Identifier(s),
         }
     }
 
-    /// Consumes source code characters and returns the corresponding [Token::IntLiteral].
+    /// Consumes source code characters and returns the corresponding [Token::Label].
     ///
     /// # Assumptions:
     ///
-    /// - The next source code character is a digit or a '-'
-    fn read_int_literal(&mut self) -> Token {
-        let number = self.take_chars_while(|&c| c.is_ascii_digit());
-        Token::IntLiteral(number)
+    /// - The next source code character is a `'`, followed by one of `a-z`, `A-Z`, or `_`.
+    fn read_label(&mut self) -> Token {
+        self.skip_chars(1);
+        Token::Label(self.take_chars_while(|&c| c.is_ascii_alphanumeric() || c == '_'))
+    }
+
+    /// Consumes source code characters and returns the corresponding [Token::IntLiteral], or a
+    /// [Token::FloatLiteral] if the digits are followed by a fractional part (like `.5`, which
+    /// needs a digit after the `.` so that `0..10` is still a range) or an exponent (like `e-3`).
+    ///
+    /// # Assumptions:
+    ///
+    /// - The next source code character is a digit
+    fn read_number_literal(&mut self) -> Token {
+        let mut number = self.take_chars_while(|&c| c.is_ascii_digit());
+        let mut is_float = false;
+
+        if self.peek_char(1) == Some(&'.') && self.peek_char(2).is_some_and(char::is_ascii_digit) {
+            self.skip_chars(1);
+            number.push('.');
+            number.push_str(&self.take_chars_while(|&c| c.is_ascii_digit()));
+            is_float = true;
+        }
+
+        let sign_len = match self.peek_char(2) {
+            Some('+' | '-') => 1,
+            _ => 0,
+        };
+        let has_exponent = matches!(self.peek_char(1), Some('e' | 'E'))
+            && self.peek_char(2 + sign_len).is_some_and(char::is_ascii_digit);
+        if has_exponent {
+            for _ in 0..=sign_len {
+                number.push(*self.next_char().unwrap());
+            }
+            number.push_str(&self.take_chars_while(|&c| c.is_ascii_digit()));
+            is_float = true;
+        }
+
+        match is_float {
+            true => Token::FloatLiteral(number),
+            false => Token::IntLiteral(number),
+        }
+    }
+
+    /// Consumes source code characters and returns the corresponding [Token::StrLiteral], whose
+    /// escape sequences (like `\n`) are replaced by the characters they stand for.
+    ///
+    /// Invalid escape sequences are reported (as recovered errors) and left out of the literal,
+    /// which is still produced so that they don't cause more errors when parsing.
+    ///
+    /// # Assumptions:
+    ///
+    /// - The next source code character is a `"`.
+    fn read_str_literal(&mut self) -> crate::Result<Token> {
+        let start = self.position;
+        self.skip_chars(1);
+
+        let mut string = String::new();
+        loop {
+            match self.peek_char(1) {
+                Some('"') => {
+                    self.skip_chars(1);
+                    break;
+                }
+                Some('\n') | None => return Err(self.err_from(LexingError::UnterminatedString, start)),
+                Some('\\') => {
+                    if let Some(c) = self.read_escape_or_recover() {
+                        string.push(c);
+                    }
+                }
+                Some(_) => string.push(*self.next_char().unwrap()),
+            }
+        }
+        Ok(Token::StrLiteral(string))
+    }
+
+    /// Consumes source code characters and returns the corresponding [Token::CharLiteral].
+    ///
+    /// An invalid escape sequence is reported (as a recovered error) and replaced by U+FFFD, so
+    /// that the literal is still produced.
+    ///
+    /// # Assumptions:
+    ///
+    /// - The next source code characters are a `'` and a `\`, or a `'`, a character other than
+    ///   `'` or a newline, and another `'`.
+    fn read_char_literal(&mut self) -> crate::Result<Token> {
+        let start = self.position;
+        self.skip_chars(1);
+
+        let c = match self.peek_char(1) {
+            Some('\\') => self.read_escape_or_recover().unwrap_or(char::REPLACEMENT_CHARACTER),
+            _ => *self.next_char().unwrap(),
+        };
+        if self.peek_char(1) != Some(&'\'') {
+            return Err(self.err_from(LexingError::UnterminatedChar, start));
+        }
+        self.skip_chars(1);
+        Ok(Token::CharLiteral(c))
+    }
+
+    /// Consumes an escape sequence like [Lexer::read_escape], but reports it (as a recovered
+    /// error) if it's invalid.
+    fn read_escape_or_recover(&mut self) -> Option<char> {
+        let (escape_start, escape_cursor) = (self.position, self.cursor);
+        let c = self.read_escape();
+        if c.is_none() {
+            let escape = self.source_code[escape_cursor..self.cursor].iter().collect();
+            let err = self.err_from(LexingError::InvalidEscape(escape), escape_start);
+            self.recovered_errors.push(err);
+        }
+        c
+    }
+
+    /// Consumes an escape sequence inside of a string or character literal, and returns the
+    /// character it stands for, or `None` if it's invalid. The valid escape sequences are:
+    /// - `\n`, `\t`, `\r` and `\0` (newline, tab, carriage return and NUL)
+    /// - `\"`, `\'` and `\\` (quotes and a backslash)
+    /// - `\xNN`, an ASCII character given by exactly 2 hex digits (up to `\x7F`)
+    /// - `\u{NNNN}`, a Unicode character given by 1 to 6 hex digits (like `\u{1F600}`)
+    ///
+    /// # Assumptions:
+    ///
+    /// - The next source code character is a `\`.
+    fn read_escape(&mut self) -> Option<char> {
+        self.skip_chars(1);
+        // A newline can't be escaped, and ends the (unterminated) string literal instead
+        let c = *self.peek_char(1).filter(|&&c| c != '\n')?;
+        self.skip_chars(1);
+
+        match c {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            '"' => Some('"'),
+            '\'' => Some('\''),
+            '\\' => Some('\\'),
+            'x' => {
+                let digits = self.take_hex_digits(2);
+                let value = u8::from_str_radix(&digits, 16).ok().filter(|_| digits.len() == 2)?;
+                value.is_ascii().then_some(value as char)
+            }
+            'u' => {
+                if self.peek_char(1) != Some(&'{') {
+                    return None;
+                }
+                self.skip_chars(1);
+                let digits = self.take_hex_digits(6);
+                if self.peek_char(1) != Some(&'}') {
+                    return None;
+                }
+                self.skip_chars(1);
+                u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns (and consumes) up to `max_digits` hexadecimal digits.
+    fn take_hex_digits(&mut self, max_digits: usize) -> String {
+        let mut digits = String::new();
+        while digits.len() < max_digits {
+            match self.peek_char(1) {
+                Some(c) if c.is_ascii_hexdigit() => digits.push(*self.next_char().unwrap()),
+                _ => break,
+            }
+        }
+        digits
     }
 
     /// Consumes source code characters and returns the corresponding [Token::Comment] or
@@ -224,6 +521,13 @@ mod tests {
     use super::*;
     // todo make macro to avoid last three lines of boilerplate
 
+    /// Lexes `source_code_chars` and strips the spans, for tests that only care about tokens.
+    fn lex_without_spans(source_code_chars: &[char]) -> Vec<Token> {
+        let (tokens, errors) = Lexer::lex(source_code_chars, 0);
+        assert_eq!(errors, vec![]);
+        tokens.into_iter().map(|t| t.node).collect()
+    }
+
     #[test]
     fn comments() {
         let source_code = "//    simple comment\n/// // / docstring";
@@ -234,7 +538,7 @@ mod tests {
         ];
 
         let source_code_chars: Vec<_> = source_code.chars().collect();
-        let received_tokens = Lexer::lex(&source_code_chars).unwrap();
+        let received_tokens = lex_without_spans(&source_code_chars);
 
         assert_eq!(received_tokens, expected_tokens);
     }
@@ -255,7 +559,54 @@ mod tests {
         ];
 
         let source_code_chars: Vec<_> = source_code.chars().collect();
-        let received_tokens = Lexer::lex(&source_code_chars).unwrap();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn global_vars() {
+        let source_code = "const u8 MAX = 5\npub static mut u8 counter = MAX";
+        let expected_tokens = vec![
+            Token::Const,
+            Token::Type(Type::Int(IntType { signed: false, width: 8 })),
+            Token::Identifier("MAX".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::IntLiteral("5".to_string()),
+            Token::Newline,
+            Token::Pub,
+            Token::Static,
+            Token::Mut,
+            Token::Type(Type::Int(IntType { signed: false, width: 8 })),
+            Token::Identifier("counter".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::Identifier("MAX".to_string()),
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn let_declarations() {
+        let source_code = "let x = 5\nlet mut letter = x";
+        let expected_tokens = vec![
+            Token::Let,
+            Token::Identifier("x".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::IntLiteral("5".to_string()),
+            Token::Newline,
+            Token::Let,
+            Token::Mut,
+            Token::Identifier("letter".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::Identifier("x".to_string()),
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
 
         assert_eq!(received_tokens, expected_tokens);
     }
@@ -273,7 +624,296 @@ mod tests {
         ];
 
         let source_code_chars: Vec<_> = source_code.chars().collect();
-        let received_tokens = Lexer::lex(&source_code_chars).unwrap();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn bitwise_operators() {
+        let source_code = "a<<=b>>c&~d|e^f &= |= ^= >>= <<";
+        let expected_tokens = vec![
+            Token::Identifier("a".to_string()),
+            Token::AssignmentSymbol(ShiftLeftEq),
+            Token::Identifier("b".to_string()),
+            Token::OperatorSymbol(ShiftRight),
+            Token::Identifier("c".to_string()),
+            Token::OperatorSymbol(Ampersand),
+            Token::OperatorSymbol(Tilde),
+            Token::Identifier("d".to_string()),
+            Token::OperatorSymbol(Pipe),
+            Token::Identifier("e".to_string()),
+            Token::OperatorSymbol(Caret),
+            Token::Identifier("f".to_string()),
+            Token::AssignmentSymbol(AndEq),
+            Token::AssignmentSymbol(OrEq),
+            Token::AssignmentSymbol(XorEq),
+            Token::AssignmentSymbol(ShiftRightEq),
+            Token::OperatorSymbol(ShiftLeft),
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn logical_keywords() {
+        let source_code = "not a and b or nothing";
+        let expected_tokens = vec![
+            Token::Not,
+            Token::Identifier("a".to_string()),
+            Token::And,
+            Token::Identifier("b".to_string()),
+            Token::Or,
+            Token::Identifier("nothing".to_string()),
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn loop_labels() {
+        let source_code = "'outer: loop { break 'outer }\ncontinue";
+        let expected_tokens = vec![
+            Token::Label("outer".to_string()),
+            Token::Colon,
+            Token::Loop,
+            Token::LSquirly,
+            Token::Break,
+            Token::Label("outer".to_string()),
+            Token::RSquirly,
+            Token::Newline,
+            Token::Continue,
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn structs() {
+        let source_code = "struct Point { i64 x }\np.x";
+        let expected_tokens = vec![
+            Token::Struct,
+            Token::Identifier("Point".to_string()),
+            Token::LSquirly,
+            Token::Type(Type::Int(IntType { signed: true, width: 64 })),
+            Token::Identifier("x".to_string()),
+            Token::RSquirly,
+            Token::Newline,
+            Token::Identifier("p".to_string()),
+            Token::Dot,
+            Token::Identifier("x".to_string()),
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn enums_and_match() {
+        let source_code = "enum E { A(u8) }\nmatch e { E::A(x) => x }";
+        let expected_tokens = vec![
+            Token::Enum,
+            Token::Identifier("E".to_string()),
+            Token::LSquirly,
+            Token::Identifier("A".to_string()),
+            Token::LParen,
+            Token::Type(Type::Int(IntType { signed: false, width: 8 })),
+            Token::RParen,
+            Token::RSquirly,
+            Token::Newline,
+            Token::Match,
+            Token::Identifier("e".to_string()),
+            Token::LSquirly,
+            Token::Identifier("E".to_string()),
+            Token::ColonColon,
+            Token::Identifier("A".to_string()),
+            Token::LParen,
+            Token::Identifier("x".to_string()),
+            Token::RParen,
+            Token::FatArrow,
+            Token::Identifier("x".to_string()),
+            Token::RSquirly,
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn arrays() {
+        let source_code = "[u8; 2] a = [0; 2]\na[1]";
+        let expected_tokens = vec![
+            Token::LBracket,
+            Token::Type(Type::Int(IntType { signed: false, width: 8 })),
+            Token::Semicolon,
+            Token::IntLiteral("2".to_string()),
+            Token::RBracket,
+            Token::Identifier("a".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::LBracket,
+            Token::IntLiteral("0".to_string()),
+            Token::Semicolon,
+            Token::IntLiteral("2".to_string()),
+            Token::RBracket,
+            Token::Newline,
+            Token::Identifier("a".to_string()),
+            Token::LBracket,
+            Token::IntLiteral("1".to_string()),
+            Token::RBracket,
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn strings() {
+        let source_code = r#"str s = "a\tb\"\\ \x41\u{1F600}" + """#;
+        let expected_tokens = vec![
+            Token::Type(Type::Str),
+            Token::Identifier("s".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::StrLiteral("a\tb\"\\ A\u{1F600}".to_string()),
+            Token::OperatorSymbol(Plus),
+            Token::StrLiteral(String::new()),
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn floats() {
+        let source_code = "f32 x = 1.5 * 2e-3 + 1E+5\n0..10 3.e";
+        let expected_tokens = vec![
+            Token::Type(Type::Float(FloatType { width: 32 })),
+            Token::Identifier("x".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::FloatLiteral("1.5".to_string()),
+            Token::OperatorSymbol(Asterisk),
+            Token::FloatLiteral("2e-3".to_string()),
+            Token::OperatorSymbol(Plus),
+            Token::FloatLiteral("1E+5".to_string()),
+            Token::Newline,
+            // Ranges and field accesses aren't fractions, and `e` without digits isn't an exponent
+            Token::IntLiteral("0".to_string()),
+            Token::DotDot,
+            Token::IntLiteral("10".to_string()),
+            Token::IntLiteral("3".to_string()),
+            Token::Dot,
+            Token::Identifier("e".to_string()),
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn chars() {
+        let source_code = r"char c = 'a' + '\n' + '\'' + '\u{1F600}' + '😀'
+'outer: 'b'";
+        let expected_tokens = vec![
+            Token::Type(Type::Char),
+            Token::Identifier("c".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::CharLiteral('a'),
+            Token::OperatorSymbol(Plus),
+            Token::CharLiteral('\n'),
+            Token::OperatorSymbol(Plus),
+            Token::CharLiteral('\''),
+            Token::OperatorSymbol(Plus),
+            Token::CharLiteral('\u{1F600}'),
+            Token::OperatorSymbol(Plus),
+            Token::CharLiteral('\u{1F600}'),
+            Token::Newline,
+            // A `'` followed by a name is only a character literal if it's closed right away
+            Token::Label("outer".to_string()),
+            Token::Colon,
+            Token::CharLiteral('b'),
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+
+        let source_code_chars: Vec<_> = r"'\q' '\n".chars().collect();
+        let (tokens, errors) = Lexer::lex(&source_code_chars, 0);
+        let tokens: Vec<_> = tokens.into_iter().map(|t| t.node).collect();
+        assert_eq!(tokens, vec![Token::CharLiteral(char::REPLACEMENT_CHARACTER)]);
+        let errors: Vec<_> = errors.into_iter().map(|e| (e.kind, e.span.start.column)).collect();
+        let expected_errors = vec![
+            (ErrorKind::LexingError(LexingError::InvalidEscape("\\q".to_string())), 2),
+            (ErrorKind::LexingError(LexingError::UnterminatedChar), 6),
+        ];
+        assert_eq!(errors, expected_errors);
+    }
+
+    #[test]
+    fn pointers() {
+        let source_code = "*u8 p = &x\n*p == null";
+        let expected_tokens = vec![
+            Token::OperatorSymbol(Asterisk),
+            Token::Type(Type::Int(IntType { signed: false, width: 8 })),
+            Token::Identifier("p".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::OperatorSymbol(Ampersand),
+            Token::Identifier("x".to_string()),
+            Token::Newline,
+            Token::OperatorSymbol(Asterisk),
+            Token::Identifier("p".to_string()),
+            Token::ComparatorSymbol(EqualTo),
+            Token::Null,
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
+
+        assert_eq!(received_tokens, expected_tokens);
+    }
+
+    #[test]
+    fn for_loop() {
+        let source_code = "for u8 i in 0..=n step 2 {}\n0..10";
+        let expected_tokens = vec![
+            Token::For,
+            Token::Type(Type::Int(IntType { signed: false, width: 8 })),
+            Token::Identifier("i".to_string()),
+            Token::In,
+            Token::IntLiteral("0".to_string()),
+            Token::DotDotEq,
+            Token::Identifier("n".to_string()),
+            Token::Step,
+            Token::IntLiteral("2".to_string()),
+            Token::LSquirly,
+            Token::RSquirly,
+            Token::Newline,
+            Token::IntLiteral("0".to_string()),
+            Token::DotDot,
+            Token::IntLiteral("10".to_string()),
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let received_tokens = lex_without_spans(&source_code_chars);
 
         assert_eq!(received_tokens, expected_tokens);
     }
@@ -303,8 +943,134 @@ mod tests {
         ];
 
         let source_code_chars: Vec<_> = source_code.chars().collect();
-        let received_tokens = Lexer::lex(&source_code_chars).unwrap();
+        let received_tokens = lex_without_spans(&source_code_chars);
 
         assert_eq!(received_tokens, expected_tokens);
     }
+
+    #[test]
+    fn spans() {
+        let source_code = "fn f()\n  ret x1";
+        let expected_spans = vec![
+            ((1, 1), (1, 3)),  // fn
+            ((1, 4), (1, 5)),  // f
+            ((1, 5), (1, 6)),  // (
+            ((1, 6), (1, 7)),  // )
+            ((1, 7), (2, 1)),  // newline
+            ((2, 3), (2, 6)),  // ret
+            ((2, 7), (2, 9)),  // x1
+        ];
+
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let (received_tokens, _) = Lexer::lex(&source_code_chars, 0);
+        let received_spans: Vec<_> = received_tokens
+            .iter()
+            .map(|t| ((t.span.start.line, t.span.start.column), (t.span.end.line, t.span.end.column)))
+            .collect();
+
+        assert_eq!(received_spans, expected_spans);
+        assert_eq!(received_tokens[6].span.start.offset, 13);
+    }
+
+    #[test]
+    fn unexpected_character_location() {
+        let source_code = "pub fn main() u8 {\n    @ret 0\n}";
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let (_, errors) = Lexer::lex(&source_code_chars, 0);
+        assert_eq!(errors.len(), 1);
+        let err = &errors[0];
+
+        assert_eq!(err.kind, ErrorKind::LexingError(LexingError::UnexpectedCharacter('@')));
+        assert_eq!((err.span.start.line, err.span.start.column), (2, 5));
+        assert_eq!((err.span.end.line, err.span.end.column), (2, 6));
+    }
+
+    #[test]
+    fn skips_unexpected_characters() {
+        let source_code = "a @ b $\nc";
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let (tokens, errors) = Lexer::lex(&source_code_chars, 0);
+
+        let tokens: Vec<_> = tokens.into_iter().map(|t| t.node).collect();
+        let expected_tokens = vec![
+            Token::Identifier("a".to_string()),
+            Token::Identifier("b".to_string()),
+            Token::Newline,
+            Token::Identifier("c".to_string()),
+        ];
+        assert_eq!(tokens, expected_tokens);
+
+        let errors: Vec<_> = errors
+            .into_iter()
+            .map(|e| (e.kind, e.span.start.column))
+            .collect();
+        let expected_errors = vec![
+            (ErrorKind::LexingError(LexingError::UnexpectedCharacter('@')), 3),
+            (ErrorKind::LexingError(LexingError::UnexpectedCharacter('$')), 7),
+        ];
+        assert_eq!(errors, expected_errors);
+    }
+
+    #[test]
+    fn int_types_too_wide_for_u32() {
+        let source_code = "u4294967295 i99999999999 x";
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let (tokens, errors) = Lexer::lex(&source_code_chars, 0);
+
+        // The type that's too wide is replaced by the widest valid one
+        let tokens: Vec<_> = tokens.into_iter().map(|t| t.node).collect();
+        let expected_tokens = vec![
+            Token::Type(Type::Int(IntType { signed: false, width: u32::MAX })),
+            Token::Type(Type::Int(IntType { signed: true, width: IntType::MAX_WIDTH })),
+            Token::Identifier("x".to_string()),
+        ];
+        assert_eq!(tokens, expected_tokens);
+
+        assert_eq!(errors.len(), 1);
+        let err = &errors[0];
+        assert_eq!(err.kind, ErrorKind::LexingError(LexingError::IntTypeTooWide("i99999999999".to_string())));
+        assert_eq!((err.span.start.column, err.span.end.column), (13, 25));
+        assert_eq!(err.kind.to_string(), "integer type 'i99999999999' is too wide (at most 8388608 bits)");
+    }
+
+    #[test]
+    fn invalid_strings() {
+        let source_code = "a \"\\q \\x80 \\u{110000}\" b\n\"open\nc";
+        let source_code_chars: Vec<_> = source_code.chars().collect();
+        let (tokens, errors) = Lexer::lex(&source_code_chars, 0);
+
+        // Invalid escape sequences are left out, and lexing continues after unterminated literals
+        let tokens: Vec<_> = tokens.into_iter().map(|t| t.node).collect();
+        let expected_tokens = vec![
+            Token::Identifier("a".to_string()),
+            Token::StrLiteral("  ".to_string()),
+            Token::Identifier("b".to_string()),
+            Token::Newline,
+            Token::Newline,
+            Token::Identifier("c".to_string()),
+        ];
+        assert_eq!(tokens, expected_tokens);
+
+        let errors: Vec<_> = errors
+            .into_iter()
+            .map(|e| (e.kind, e.span.start.column, e.span.end.column))
+            .collect();
+        let expected_errors = vec![
+            (ErrorKind::LexingError(LexingError::InvalidEscape("\\q".to_string())), 4, 6),
+            (ErrorKind::LexingError(LexingError::InvalidEscape("\\x80".to_string())), 7, 11),
+            (ErrorKind::LexingError(LexingError::InvalidEscape("\\u{110000}".to_string())), 12, 22),
+            (ErrorKind::LexingError(LexingError::UnterminatedString), 1, 6),
+        ];
+        assert_eq!(errors, expected_errors);
+
+        for escape in ["\\x80", "\\x4", "\\u{}", "\\u{D800}", "\\u{1234567}", "\\u41"] {
+            let source_code_chars: Vec<_> = format!("\"{}\"", escape).chars().collect();
+            let (_, errors) = Lexer::lex(&source_code_chars, 0);
+            assert!(
+                matches!(&errors[..], [e] if matches!(e.kind, ErrorKind::LexingError(LexingError::InvalidEscape(_)))),
+                "{} should be invalid",
+                escape,
+            );
+        }
+    }
 }
@@ -14,18 +14,45 @@ pub enum Token {
     Comment(String),
 
     IntLiteral(String),
+    /// A float literal like `1.5` or `2e-3` (which has a fractional part, an exponent, or both)
+    FloatLiteral(String),
+    /// A string literal like `"hi\n"` (stored with its escape sequences already replaced by the
+    /// characters they stand for)
+    StrLiteral(String),
+    /// A character literal like `'a'` or `'\n'` (stored with its escape sequence already
+    /// replaced by the character it stands for)
+    CharLiteral(char),
     Identifier(String),
+    /// A loop label like `'outer` (stored without the leading `'`)
+    Label(String),
 
     // Keywords
     Extern,
     Pub,
     Fn,
+    Struct,
+    Enum,
+    Const,
+    Static,
+    Mut,
+    Let,
+    Match,
     Ret,
     While,
+    Loop,
+    For,
+    In,
+    Step,
+    Break,
+    Continue,
     If,
     Else,
     True,
     False,
+    Null,
+    And,
+    Or,
+    Not,
 
     /// The built-in Flick types, like `void`
     Type(Type),
@@ -35,16 +62,31 @@ pub enum Token {
     RSquirly,
     LParen,
     RParen,
+    LBracket,
+    RBracket,
 
     // Punctuation
     Newline,
     Comma,
-
-    /// One of `+`, `-`, `*`, and `/`
+    Colon,
+    /// `;`, which separates an array type's element type from its length, like `[u8; 16]`
+    Semicolon,
+    /// `::`, as in the enum variant `Shape::Empty`
+    ColonColon,
+    /// `=>`, which separates a match arm's pattern from its body
+    FatArrow,
+    /// `.`, as in the field access `point.x`
+    Dot,
+    /// `..`, as in the exclusive range `0..10`
+    DotDot,
+    /// `..=`, as in the inclusive range `1..=10`
+    DotDotEq,
+
+    /// One of `+`, `-`, `*`, `/`, `%`, `&`, `|`, `^`, `~`, `<<`, and `>>`
     OperatorSymbol(OperatorSymbol),
     /// One of `>`, `<`, `<=`, `>=`, `==`, and `!=`
     ComparatorSymbol(ComparatorSymbol),
-    /// One of `+=`, `-=`, `*=`, `/=`, and `=`
+    /// One of `+=`, `-=`, `*=`, `/=`, `&=`, `|=`, `^=`, `<<=`, `>>=`, and `=`
     AssignmentSymbol(AssignmentSymbol),
 }
 
@@ -61,17 +103,38 @@ impl fmt::Display for Token {
             Self::Comment(comment) => write!(f, "{}", comment),
 
             Self::IntLiteral(int) => write!(f, "{}", int),
+            Self::FloatLiteral(float) => write!(f, "{}", float),
+            Self::StrLiteral(string) => write!(f, "{:?}", string),
+            Self::CharLiteral(c) => write!(f, "{:?}", c),
             Self::Identifier(id) => write!(f, "{}", id),
+            Self::Label(label) => write!(f, "'{}", label),
 
             Self::Pub => write!(f, "pub"),
             Self::Fn => write!(f, "fn"),
+            Self::Struct => write!(f, "struct"),
+            Self::Enum => write!(f, "enum"),
+            Self::Const => write!(f, "const"),
+            Self::Static => write!(f, "static"),
+            Self::Mut => write!(f, "mut"),
+            Self::Let => write!(f, "let"),
+            Self::Match => write!(f, "match"),
             Self::Extern => write!(f, "extern"),
             Self::While => write!(f, "while"),
+            Self::Loop => write!(f, "loop"),
+            Self::For => write!(f, "for"),
+            Self::In => write!(f, "in"),
+            Self::Step => write!(f, "step"),
+            Self::Break => write!(f, "break"),
+            Self::Continue => write!(f, "continue"),
             Self::Ret => write!(f, "ret"),
             Self::If => write!(f, "if"),
             Self::Else => write!(f, "else"),
             Self::True => write!(f, "true"),
             Self::False => write!(f, "false"),
+            Self::Null => write!(f, "null"),
+            Self::And => write!(f, "and"),
+            Self::Or => write!(f, "or"),
+            Self::Not => write!(f, "not"),
 
             Self::Type(var_type) => write!(f, "{}", var_type),
 
@@ -79,8 +142,17 @@ impl fmt::Display for Token {
             Self::RSquirly => write!(f, "}}"),
             Self::LParen => write!(f, "("),
             Self::RParen => write!(f, ")"),
+            Self::LBracket => write!(f, "["),
+            Self::RBracket => write!(f, "]"),
             Self::Newline => writeln!(f),
             Self::Comma => write!(f, ","),
+            Self::Colon => write!(f, ":"),
+            Self::Semicolon => write!(f, ";"),
+            Self::ColonColon => write!(f, "::"),
+            Self::FatArrow => write!(f, "=>"),
+            Self::Dot => write!(f, "."),
+            Self::DotDot => write!(f, ".."),
+            Self::DotDotEq => write!(f, "..="),
 
             Self::OperatorSymbol(operator_symbol) => write!(f, "{}", operator_symbol),
             Self::ComparatorSymbol(comparator_symbol) => write!(f, "{}", comparator_symbol),
@@ -89,7 +161,7 @@ impl fmt::Display for Token {
     }
 }
 
-/// An enum to store one of `+`, `-`, `*`, and `/`
+/// An enum to store one of `+`, `-`, `*`, `/`, `%`, `&`, `|`, `^`, `~`, `<<`, and `>>`
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum OperatorSymbol {
     Plus,
@@ -97,6 +169,12 @@ pub enum OperatorSymbol {
     Asterisk,
     Slash,
     Modulo,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
+    ShiftLeft,
+    ShiftRight,
 }
 
 impl fmt::Display for OperatorSymbol {
@@ -107,6 +185,12 @@ impl fmt::Display for OperatorSymbol {
             Self::Asterisk => write!(f, "*"),
             Self::Slash => write!(f, "/"),
             Self::Modulo => write!(f, "%"),
+            Self::Ampersand => write!(f, "&"),
+            Self::Pipe => write!(f, "|"),
+            Self::Caret => write!(f, "^"),
+            Self::Tilde => write!(f, "~"),
+            Self::ShiftLeft => write!(f, "<<"),
+            Self::ShiftRight => write!(f, ">>"),
         }
     }
 }
@@ -135,13 +219,18 @@ impl fmt::Display for ComparatorSymbol {
     }
 }
 
-/// An enum to store one of `+=`, `-=`, `*=`, `/=`, and `=`
+/// An enum to store one of `+=`, `-=`, `*=`, `/=`, `&=`, `|=`, `^=`, `<<=`, `>>=`, and `=`
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum AssignmentSymbol {
     PlusEq,
     MinusEq,
     TimesEq,
     DivideEq,
+    AndEq,
+    OrEq,
+    XorEq,
+    ShiftLeftEq,
+    ShiftRightEq,
     Eq,
 }
 
@@ -152,6 +241,11 @@ impl fmt::Display for AssignmentSymbol {
             Self::MinusEq => write!(f, "-="),
             Self::TimesEq => write!(f, "*="),
             Self::DivideEq => write!(f, "/="),
+            Self::AndEq => write!(f, "&="),
+            Self::OrEq => write!(f, "|="),
+            Self::XorEq => write!(f, "^="),
+            Self::ShiftLeftEq => write!(f, "<<="),
+            Self::ShiftRightEq => write!(f, ">>="),
             Self::Eq => write!(f, "="),
         }
     }
@@ -8,6 +8,28 @@
 ///
 /// [a]: llvm_sys::prelude::LLVMValueRef
 mod compilation;
+/// Module to turn [FlickError](crate::error::FlickError)s into human-readable messages that
+/// show the offending source code, so that every frontend (the CLI, the playground, editor
+/// integrations, ...) renders errors the same way.
+///
+/// # Examples
+/// ```
+/// use flick::diagnostics::{Diagnostic, SourceMap};
+/// use flick::{Lexer, Parser};
+///
+/// let source_code = "pub fn main() u8 {\n    ret 1 < 2 < 3\n}";
+///
+/// let mut source_map = SourceMap::new();
+/// let file_id = source_map.add_file("main.fl", source_code);
+///
+/// let chars: Vec<_> = source_code.chars().collect();
+/// let (tokens, _) = Lexer::lex(&chars, file_id);
+/// let (_, errors) = Parser::parse_program(&tokens);
+///
+/// let rendered = Diagnostic::from(&errors[0]).render(&source_map);
+/// assert!(rendered.starts_with("error[F0005]: comparison operators cannot be chained\n"));
+/// ```
+pub mod diagnostics;
 /// Module to convert source files into token streams
 mod lexing;
 /// Module to convert token streams into [abstract syntax trees](ast)
@@ -16,6 +38,8 @@ mod parsing;
 ///
 /// See [ScopeManager] for more details.
 mod scope_manager;
+/// Module to represent locations in source code (see [Span](span::Span))
+pub mod span;
 /// Module to store all the Flick types
 pub mod types;
 /// Module to add types to [abstract syntax trees](ast)
@@ -24,7 +48,7 @@ mod typing;
 pub mod error;
 
 // TODO (Max): Should we remove pub use and just make users use absolute path (I kinda like the idea of that if we somehow make the paths nicer)
-pub use compilation::compiler::Compiler;
+pub use compilation::compiler::{Compiler, CompilerOptions};
 pub use lexing::lexer::Lexer;
 pub use lexing::token;
 pub use parsing::ast;
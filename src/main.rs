@@ -1,19 +1,29 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
-use std::path::{PathBuf, Path};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use anyhow::Result;
-use clap::Parser as ClapParser;
+use anyhow::{bail, Result};
+use clap::builder::PossibleValuesParser;
+use clap::{Parser as ClapParser, Subcommand, ValueEnum};
 
-use flick::{error::FlickError, Compiler, Lexer, Parser, Typer};
+use flick::diagnostics::codes;
+use flick::diagnostics::lint::{Lint, LintLevel, LintLevels, ALL_LINTS};
+use flick::diagnostics::{Diagnostic, DiagnosticSink, SourceMap};
+use flick::{Compiler, CompilerOptions, Lexer, Parser, Typer};
 
 /// A command line interface using [clap]
 #[derive(ClapParser)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
     /// Input path for source code
-    source_path: PathBuf,
+    #[arg(required = true)]
+    source_path: Option<PathBuf>,
 
     /// Whether to print LLVM intermediate representation during compilation
     #[arg(short, long)]
@@ -34,17 +44,66 @@ struct Cli {
     /// Whether to just compile without running the linker to generate an executable
     #[arg(long)]
     no_link: bool,
+
+    /// Whether to skip checking that array indices are in bounds at runtime (for release builds)
+    #[arg(long)]
+    no_bounds_checks: bool,
+
+    /// Whether to let integer arithmetic wrap around on overflow instead of aborting the program
+    /// at runtime (for release builds)
+    #[arg(long)]
+    no_overflow_checks: bool,
+
+    /// How to print errors
+    #[arg(long, value_enum, default_value_t = ErrorFormat::Human)]
+    error_format: ErrorFormat,
+
+    /// Ignore the findings of a lint (can be repeated)
+    #[arg(short = 'A', long = "allow", value_name = "LINT", value_parser = lint_names())]
+    allowed_lints: Vec<String>,
+
+    /// Report the findings of a lint as warnings (can be repeated)
+    #[arg(short = 'W', long = "warn", value_name = "LINT", value_parser = lint_names())]
+    warned_lints: Vec<String>,
+
+    /// Report the findings of a lint as errors, which stop compilation (can be repeated)
+    #[arg(short = 'D', long = "deny", value_name = "LINT", value_parser = lint_names())]
+    denied_lints: Vec<String>,
+}
+
+/// Accepts the name of any lint in [ALL_LINTS]
+fn lint_names() -> PossibleValuesParser {
+    PossibleValuesParser::new(ALL_LINTS.iter().map(|lint| lint.name))
+}
+
+/// Commands that can be run instead of compiling a file
+#[derive(Subcommand)]
+enum Commands {
+    /// Print a detailed explanation of an error code, like F0012
+    Explain {
+        /// The error code to explain
+        code: String,
+    },
+}
+
+/// The formats that errors can be printed in; see [Cli::error_format]
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ErrorFormat {
+    /// Messages that show the offending source code (see [Diagnostic::render])
+    Human,
+    /// One JSON object per line (see [Diagnostic::to_json])
+    Json,
 }
 
 impl Cli {
     /// Retrieves the provided executable output path (returns a default if none provided)
     ///
     /// Note that the default executable output path for a file like `test.fl` is `test`.
-    fn get_executable_output_path(&self) -> PathBuf {
+    fn get_executable_output_path(&self, source_path: &Path) -> PathBuf {
         match &self.output_path {
             Some(path) => path.clone(),
             None => {
-                let mut path = self.source_path.clone();
+                let mut path = source_path.to_path_buf();
                 path.set_extension("");
                 path
             }
@@ -54,17 +113,46 @@ impl Cli {
     /// Retrieves the provided object output path (returns a default if none provided)
     ///
     /// Note that the default object output path for a file like `test.fl` is `test.o`.
-    fn get_object_output_path(&self) -> PathBuf {
+    fn get_object_output_path(&self, source_path: &Path) -> PathBuf {
         match &self.object_output_path {
             Some(path) => path.clone(),
             None => {
-                let mut path = self.source_path.clone();
+                let mut path = source_path.to_path_buf();
                 path.set_extension("o");
                 path
             }
         }
     }
 
+    /// Builds the lint levels requested with `--allow`, `--warn`, and `--deny`
+    ///
+    /// Note that if a lint is given more than one level, `--deny` wins over `--warn`, which wins
+    /// over `--allow`.
+    fn get_lint_levels(&self) -> LintLevels {
+        let mut lint_levels = LintLevels::new();
+        let requested_levels = [
+            (&self.allowed_lints, LintLevel::Allow),
+            (&self.warned_lints, LintLevel::Warn),
+            (&self.denied_lints, LintLevel::Deny),
+        ];
+        for (names, level) in requested_levels {
+            for name in names {
+                // The names were already checked by clap (see lint_names)
+                let lint = Lint::from_name(name).unwrap();
+                lint_levels.set(lint, level);
+            }
+        }
+        lint_levels
+    }
+
+    /// Prints a diagnostic in the requested [ErrorFormat]
+    fn print_diagnostic(&self, diagnostic: &Diagnostic, source_map: &SourceMap) {
+        match self.error_format {
+            ErrorFormat::Human => eprintln!("{}", diagnostic.render(source_map)),
+            ErrorFormat::Json => eprintln!("{}", diagnostic.to_json(source_map)),
+        }
+    }
+
     /// Retrieves the provided linker path (returns a default if none provided)
     ///
     /// Note that the default linker path is `gcc`.
@@ -76,29 +164,64 @@ impl Cli {
     }
 }
 
+/// Exits with a failure status after errors were printed as diagnostics, so that scripts and CI
+/// can tell that the program didn't compile.
+fn exit_with_errors() -> ! {
+    std::process::exit(1)
+}
+
 /// Runs the command line interface for the compiler; see [Cli] for details
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    let mut file = File::open(&cli.source_path)?;
+    if let Some(Commands::Explain { code }) = &cli.command {
+        return explain(code);
+    }
+
+    // Clap makes sure that a source path is given whenever there's no subcommand
+    let source_path = cli.source_path.as_deref().unwrap();
+    let mut file = File::open(source_path)?;
     let mut file_contents = String::new();
     file.read_to_string(&mut file_contents)?;
     let file_chars: Vec<_> = file_contents.chars().collect();
 
-    let tokens = match Lexer::lex(&file_chars) {
-        Ok(tokens) => tokens,
-        Err(err) => {
-            print_error(&cli.source_path, &file_chars, err);
-            return Ok(());
-        }
-    };
+    let mut source_map = SourceMap::new();
+    let file_id = source_map.add_file(source_path.display().to_string(), file_contents);
+
+    // Each stage keeps going after an error, so that every error in the file is reported at once
+    let (tokens, mut errors) = Lexer::lex(&file_chars, file_id);
+
+    let (program, parsing_errors) = Parser::parse_program(&tokens);
+    let parsing_failed = !parsing_errors.is_empty();
+    errors.extend(parsing_errors);
 
-    let program = Parser::parse_program(&tokens);
+    // Statements that failed to parse are missing from the program, but the rest of it is typed
+    let mut typer = Typer::with_sink(DiagnosticSink::new(cli.get_lint_levels()));
+    let typed_program = typer.type_program(&program).map_err(|typing_errors| errors.extend(typing_errors)).ok();
+
+    for error in errors.iter() {
+        cli.print_diagnostic(&Diagnostic::from(error), &source_map);
+    }
+    // Lints might be wrong about programs missing statements that didn't parse (like reporting
+    // a variable as unused when the statement using it was skipped), so they're only reported
+    // when parsing succeeded
+    if !parsing_failed {
+        for diagnostic in typer.sink().diagnostics() {
+            cli.print_diagnostic(diagnostic, &source_map);
+        }
+    }
 
-    let mut typer = Typer::new();
-    let typed_program = typer.type_program(&program);
+    // Warnings alone don't stop the program from being compiled, but denied lints do
+    let typed_program = match typed_program {
+        Some(typed_program) if errors.is_empty() && !typer.sink().has_errors() => typed_program,
+        _ => exit_with_errors(),
+    };
 
-    let mut compiler = Compiler::new();
+    let mut compiler = Compiler::with_options(CompilerOptions {
+        bounds_checks: !cli.no_bounds_checks,
+        overflow_checks: !cli.no_overflow_checks,
+        file_names: HashMap::from([(file_id, source_path.display().to_string())]),
+    });
     compiler.compile(&typed_program);
 
     if cli.emit_ir {
@@ -106,27 +229,36 @@ fn main() -> Result<()> {
         compiler.print_ir();
     }
 
-    compiler.optimize();
+    if let Err(error) = compiler.optimize() {
+        cli.print_diagnostic(&Diagnostic::from(&error), &source_map);
+        exit_with_errors();
+    }
 
     if cli.emit_ir {
         println!("\nIR after optimization:");
         compiler.print_ir();
     }
 
-    let object_output_path = cli.get_object_output_path();
-    compiler.to_file(&object_output_path);
+    let object_output_path = cli.get_object_output_path(source_path);
+    if let Err(error) = compiler.to_file(&object_output_path) {
+        cli.print_diagnostic(&Diagnostic::from(&error), &source_map);
+        exit_with_errors();
+    }
 
     if cli.no_link {
         return Ok(());
     }
 
-    let executable_output_path = cli.get_executable_output_path();
+    let executable_output_path = cli.get_executable_output_path(source_path);
     let linker_path = cli.get_linker_path();
-    Command::new(linker_path)
+    let linker_output = Command::new(linker_path)
         .arg(&object_output_path)
         .arg("-o")
         .arg(&executable_output_path)
         .output()?;
+    if !linker_output.status.success() {
+        bail!("linking failed:\n{}", String::from_utf8_lossy(&linker_output.stderr));
+    }
 
     if cli.object_output_path.is_none() {
         std::fs::remove_file(&object_output_path)?;
@@ -135,36 +267,13 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-
-fn print_error(source_path: impl AsRef<Path>, file_chars: &[char], error: FlickError) {
-    // TODO: Write to string then print string to stderr instead of printing to stderr directly
-
-    let line_start_index = file_chars
-        .iter()
-        .take(error.index)
-        .take_while(|c: &&char| **c != '\n')
-        .count() + 1;
-    let line_num = file_chars[..line_start_index].iter().filter(|&&c| c == '\n').count() + 1;
-    let col_num = error.index - line_start_index + 1;
-    let line = &file_chars[line_start_index..]
-        .iter()
-        .take_while(|&&c| c != '\n')
-        .collect::<String>();
-
-    eprintln!("{}: {}", "error", error);
-
-    let max_line_num_width = (line_num+1).to_string().len();
-    // if line_num > 1 {
-    //     write_source_code_line(&file_chars[line_start_index - line.len()..line_start_index], line_num - 1, max_line_num_width);
-    // }
-    write_source_code_line(line, line_num, max_line_num_width);
-    // let num_lines = file_chars.iter().filter(|&&c| c == '\n').count() + 1;
-    // if line_num < num_lines {
-    //     write_source_code_line(&file_chars[line_start_index + line.len()..], line_num + 1, max_line_num_width);
-    // }
-    eprintln!("in {}:{}:{}", source_path.as_ref().display(), line_num, col_num);
-}
-
-fn write_source_code_line(line: &str, line_num: usize, max_line_num_width: usize) {
-    eprintln!(" {:0>width$} │ {}", line_num, line, width = max_line_num_width);
+/// Prints the long-form explanation of an error code (see [codes::explain])
+fn explain(code: &str) -> Result<()> {
+    match codes::explain(&code.to_uppercase()) {
+        Some(explanation) => {
+            print!("{}", explanation);
+            Ok(())
+        }
+        None => bail!("'{}' is not a Flick error code", code),
+    }
 }
@@ -1,6 +1,7 @@
 use crate::lexing::token::ComparatorSymbol::*;
 use crate::lexing::token::OperatorSymbol::*;
 use crate::lexing::token::{ComparatorSymbol, OperatorSymbol};
+use crate::span::Span;
 use crate::types::Type;
 use std::fmt;
 
@@ -11,12 +12,70 @@ pub struct Program {
 }
 
 /// A global statement is something that can be written in the "global" scope, as opposed
-/// to inside of a function body. So, for example, function definitions and external function
-/// declarations are "global" statements.
+/// to inside of a function body. So, for example, function definitions, external function
+/// declarations, struct and enum definitions and global variables are "global" statements.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum GlobalStatement {
     Extern(FuncProto),
     FuncDef(FuncDef),
+    Struct(StructDef),
+    Enum(EnumDef),
+    GlobalVar(GlobalVar),
+}
+
+/// A global variable, like `const u32 MAX = 100` or `pub static mut u64 counter = 0`, whose
+/// value must be a constant expression.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GlobalVar {
+    pub kind: GlobalVarKind,
+    pub var_type: Type,
+    pub var_name: String,
+    pub var_value: Expr,
+    /// The span of the whole declaration
+    pub span: Span,
+}
+
+/// Whether a [GlobalVar] is a constant or a mutable static variable.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GlobalVarKind {
+    /// A `const`, which only exists at compile time (its value is substituted wherever it's used)
+    Const,
+    /// A `static mut`, which is stored in memory for the whole program; a public one is visible
+    /// to other object files, like a `pub fn`
+    StaticMut { is_public: bool },
+}
+
+/// A struct definition, like `struct Point { i64 x, i64 y }`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StructDef {
+    pub name: String,
+    pub fields: Vec<StructField>,
+    /// The span of the whole definition
+    pub span: Span,
+}
+
+/// A field of a struct (its name and its data type).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StructField {
+    pub field_type: Type,
+    pub field_name: String,
+}
+
+/// An enum definition, like `enum Shape { Circle(u32), Rect(u32, u32), Empty }`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EnumDef {
+    pub name: String,
+    pub variants: Vec<EnumVariant>,
+    /// The span of the whole definition
+    pub span: Span,
+}
+
+/// A variant of an enum (its name and the types of its payload, which is empty for variants like
+/// `Empty`).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EnumVariant {
+    pub name: String,
+    pub payload_types: Vec<Type>,
 }
 
 /// A function definition (metadata, prototype, and body).
@@ -33,6 +92,8 @@ pub struct FuncProto {
     pub name: String,
     pub params: Vec<FuncParam>,
     pub return_type: Box<Type>,
+    /// The span of the prototype, like `pub fn foo(i64 x) i64`
+    pub span: Span,
 }
 
 impl fmt::Display for FuncProto {
@@ -89,13 +150,28 @@ pub struct FuncParam {
 ///
 /// See also: [Expr].
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub enum Statement {
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+/// The different kinds of [Statement].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum StatementKind {
     VarDeclaration(VarDeclaration),
+    Let(Let),
     WhileLoop(WhileLoop),
+    Loop(Loop),
+    ForLoop(Box<ForLoop>),
+    /// A `break`, with the label of the loop to exit (the innermost loop if `None`)
+    Break(Option<String>),
+    /// A `continue`, with the label of the loop to continue (the innermost loop if `None`)
+    Continue(Option<String>),
     Assignment(Assignment),
     Return(Option<Expr>),
     Call(Call),
     If(If),
+    Match(Match<Vec<Statement>>),
 }
 
 /// A variable declaration.
@@ -109,6 +185,17 @@ pub struct VarDeclaration {
     pub var_value: Expr,
 }
 
+/// A variable declaration whose type is inferred from its initial value, like `let x = foo()`.
+///
+/// Unlike variables declared with a type, a `let` variable can only be assigned to if it's
+/// declared with `let mut`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Let {
+    pub var_name: String,
+    pub is_mutable: bool,
+    pub var_value: Expr,
+}
+
 /// An if statement.
 ///
 /// Note, `then_body` corresponds to the statements to be executed if the condition is true,
@@ -120,32 +207,207 @@ pub struct If {
     pub else_body: Option<Vec<Statement>>,
 }
 
-/// A while loop (its 'while condition' and its body).
+/// A match statement or expression, like `match shape { Shape::Circle(r) => r, _ => 0 }`.
+///
+/// `Body` is what each arm runs: a list of statements in a match statement, or an expression in a
+/// match expression. The first arm whose pattern matches the scrutinee is the one that runs.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Match<Body> {
+    pub scrutinee: Box<Expr>,
+    pub arms: Vec<MatchArm<Body>>,
+}
+
+/// An arm of a [Match], like `Shape::Circle(r) => r`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MatchArm<Body> {
+    pub pattern: Pattern,
+    pub body: Body,
+}
+
+/// A pattern in a [MatchArm], which a value either matches or doesn't.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Pattern {
+    pub kind: PatternKind,
+    pub span: Span,
+}
+
+/// The different kinds of [Pattern].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PatternKind {
+    /// `_`, which matches anything
+    Wildcard,
+    /// An integer, like `3` or `-3`
+    IntLiteral(String),
+    BoolLiteral(bool),
+    /// A range of integers, like `1..=5` (which includes `end`) or `1..5` (which doesn't)
+    Range { start: String, end: String, inclusive: bool },
+    /// A variant of an enum, like `Shape::Rect(w, _)`, with the names of the variables that its
+    /// payload is bound to (`None` for `_`)
+    EnumVariant { enum_name: String, variant_name: String, bindings: Vec<Option<String>> },
+}
+
+impl fmt::Display for PatternKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Wildcard => write!(f, "_"),
+            Self::IntLiteral(int) => write!(f, "{}", int),
+            Self::BoolLiteral(b) => write!(f, "{}", b),
+            Self::Range { start, end, inclusive: true } => write!(f, "{}..={}", start, end),
+            Self::Range { start, end, inclusive: false } => write!(f, "{}..{}", start, end),
+            Self::EnumVariant { enum_name, variant_name, bindings } => {
+                write!(f, "{}::{}", enum_name, variant_name)?;
+                if !bindings.is_empty() {
+                    let bindings: Vec<_> = bindings.iter().map(|b| b.as_deref().unwrap_or("_")).collect();
+                    write!(f, "({})", bindings.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A while loop (its optional label, its 'while condition' and its body).
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct WhileLoop {
+    pub label: Option<String>,
     pub condition: Expr,
     pub body: Vec<Statement>,
 }
 
+/// A counted loop over a range of integers, like `for u8 i in 0..10 step 2 { }`.
+///
+/// The loop variable is only declared inside of the body. The range includes `end` if `inclusive`
+/// is true (`..=`), and excludes it otherwise (`..`).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ForLoop {
+    pub label: Option<String>,
+    pub var_type: Type,
+    pub var_name: String,
+    pub start: Expr,
+    pub end: Expr,
+    pub inclusive: bool,
+    /// How much the loop variable increases by after each iteration (1 if `None`)
+    pub step: Option<Expr>,
+    pub body: Vec<Statement>,
+}
+
+/// An infinite loop (its optional label and its body), which can only be exited with `break` or
+/// `ret`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Loop {
+    pub label: Option<String>,
+    pub body: Vec<Statement>,
+}
+
 /// An expression, which is any piece of code that has a value.
 ///
 /// For example, `current_length` or `1 + 2` or `foo("bye")` are expressions.
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub enum Expr {
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+/// The different kinds of [Expr].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ExprKind {
     Identifier(String),
     IntLiteral(String),
+    /// A float literal, like `1.5` or `2e-3`
+    FloatLiteral(String),
     BoolLiteral(bool),
+    /// A string literal, with its escape sequences already replaced
+    StrLiteral(String),
+    /// A character literal, like `'a'` or `'\n'`
+    CharLiteral(char),
+    /// `null`, a pointer that doesn't point to anything
+    Null,
     Binary(Binary),
     Comparison(Comparison),
+    Logical(Logical),
     Call(Call),
     Unary(Unary),
+    StructLiteral(StructLiteral),
+    FieldAccess(FieldAccess),
+    EnumLiteral(EnumLiteral),
+    Match(Match<Expr>),
+    /// An array literal listing each element, like `[1, 2, 3]`
+    ArrayLiteral(Vec<Expr>),
+    ArrayRepeat(ArrayRepeat),
+    Index(Index),
 }
 
-/// An assignment statement (the variable name and the new value).
+/// An assignment statement (the variable name, the fields and elements being assigned to, and the
+/// new value).
+///
+/// For example, `p.points[i].x = 3` breaks down into:
+/// - name: `p`
+/// - accessors: `.points`, `[i]`, `.x`
+/// - value: `3`
+///
+/// Dereferences are accessors too, so `(*p).x = 3` has the accessors `*` and `.x`.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Assignment {
     pub name: String,
+    /// The path to the part of the variable being assigned to (empty when assigning to the
+    /// variable itself)
+    pub accessors: Vec<Accessor>,
+    pub value: Box<Expr>,
+}
+
+/// One step of the path to the part of a variable being assigned to.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Accessor {
+    /// A struct field, like `.x`
+    Field(String),
+    /// An array element, like `[i]`
+    Index(Expr),
+    /// The value that a pointer points to, like `*p`
+    Deref,
+}
+
+/// A struct literal, like `Point { x: 1, y: 2 }`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StructLiteral {
+    pub struct_name: String,
+    /// The value of each field, in the order they were written
+    pub fields: Vec<FieldValue>,
+}
+
+/// The value given to one field in a [StructLiteral], like `x: 1`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FieldValue {
+    pub field_name: String,
+    pub value: Expr,
+}
+
+/// A value of an enum, like `Shape::Rect(2, 3)` or `Shape::Empty`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EnumLiteral {
+    pub enum_name: String,
+    pub variant_name: String,
+    pub payload: Vec<Expr>,
+}
+
+/// A field access expression, like `p.x`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FieldAccess {
+    pub expr: Box<Expr>,
+    pub field_name: String,
+}
+
+/// An array literal that repeats one value, like `[0; 16]`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ArrayRepeat {
     pub value: Box<Expr>,
+    pub length: u64,
+}
+
+/// An indexing expression, like `buf[i]`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Index {
+    pub expr: Box<Expr>,
+    pub index: Box<Expr>,
 }
 
 /// A binary expression (the operator and the left/right-hand sides).
@@ -169,6 +431,12 @@ pub enum BinaryOperator {
     Multiply,
     Divide,
     Remainder,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    /// Arithmetic for signed integers (copying the sign bit), logical for unsigned integers
+    ShiftRight,
 }
 
 impl fmt::Display for BinaryOperator {
@@ -179,6 +447,11 @@ impl fmt::Display for BinaryOperator {
             Self::Multiply => write!(f, "*"),
             Self::Divide => write!(f, "/"),
             Self::Remainder => write!(f, "%"),
+            Self::BitwiseAnd => write!(f, "&"),
+            Self::BitwiseOr => write!(f, "|"),
+            Self::BitwiseXor => write!(f, "^"),
+            Self::ShiftLeft => write!(f, "<<"),
+            Self::ShiftRight => write!(f, ">>"),
         }
     }
 }
@@ -191,6 +464,12 @@ impl From<OperatorSymbol> for BinaryOperator {
             Asterisk => Self::Multiply,
             Slash => Self::Divide,
             Modulo => Self::Remainder,
+            Ampersand => Self::BitwiseAnd,
+            Pipe => Self::BitwiseOr,
+            Caret => Self::BitwiseXor,
+            ShiftLeft => Self::ShiftLeft,
+            ShiftRight => Self::ShiftRight,
+            Tilde => unreachable!("'~' is only a unary operator, so it's never parsed as a binary one"),
         }
     }
 }
@@ -245,6 +524,38 @@ impl From<ComparatorSymbol> for ComparisonOperator {
     }
 }
 
+/// A logical expression (the operator and the left/right-hand sides).
+///
+/// For example, `a and foo(1)` breaks down into:
+/// - left: `a`
+/// - operator: `and`
+/// - right: `foo(1)`
+///
+/// The right-hand side is only evaluated if the left-hand side doesn't already decide the result
+/// (that is, if it's `true` for `and`, or `false` for `or`).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Logical {
+    pub left: Box<Expr>,
+    pub operator: LogicalOperator,
+    pub right: Box<Expr>,
+}
+
+/// An operator for the [Logical] expression.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum LogicalOperator {
+    And,
+    Or,
+}
+
+impl fmt::Display for LogicalOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::And => write!(f, "and"),
+            Self::Or => write!(f, "or"),
+        }
+    }
+}
+
 /// A call expression (the name of the function to call and the arguments to pass).
 ///
 /// For example, `foo(a, 12 - b, "test")` is a call expression with 3 args.
@@ -266,6 +577,14 @@ pub struct Unary {
 pub enum UnaryOperator {
     /// A cast converts its operand into the specified destination [Type].
     Cast(Type),
-    /// Logical negation.
-    Negate
+    /// Arithmetic negation, like `-x`.
+    Negate,
+    /// Logical negation, like `not x`.
+    Not,
+    /// Bitwise negation (flipping every bit), like `~x`.
+    BitwiseNot,
+    /// Reading the value that a pointer points to, like `*p`.
+    Deref,
+    /// Taking the address of a variable (or a field or element of one), like `&x`.
+    AddressOf,
 }
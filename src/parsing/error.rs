@@ -0,0 +1,109 @@
+use std::fmt;
+
+use crate::lexing::token::Token;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParsingError {
+    /// A token that can't appear where it was found, like `else` at the start of a statement
+    UnexpectedToken(Token),
+    /// A token other than the one the parser needed, like `i64 x 5` (missing `=`)
+    Expected { expected: Expected, found: Token },
+    /// The file ended while the parser still needed something, like a closing `)`
+    UnexpectedEndOfFile { expected: Expected },
+    /// Comparisons like `a < b < c`, which are ambiguous
+    ChainedComparison,
+    /// A body whose opening `{` never got a matching `}`
+    UnterminatedBody,
+    /// A function definition inside of another function's body
+    NestedFunctionDefinition,
+}
+
+impl ParsingError {
+    /// Returns the error's stable code (see [ErrorKind::code](crate::error::ErrorKind::code)).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnexpectedToken(_) => "F0002",
+            Self::Expected { .. } => "F0003",
+            Self::UnexpectedEndOfFile { .. } => "F0004",
+            Self::ChainedComparison => "F0005",
+            Self::UnterminatedBody => "F0006",
+            Self::NestedFunctionDefinition => "F0007",
+        }
+    }
+}
+
+impl fmt::Display for ParsingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedToken(token) => write!(f, "unexpected {}", describe_token(token)),
+            Self::Expected { expected, found } => {
+                write!(f, "expected {}, found {}", expected, describe_token(found))
+            }
+            Self::UnexpectedEndOfFile { expected } => {
+                write!(f, "expected {}, found end of file", expected)
+            }
+            Self::ChainedComparison => write!(f, "comparison operators cannot be chained"),
+            Self::UnterminatedBody => write!(f, "this '{{' is never closed"),
+            Self::NestedFunctionDefinition => {
+                write!(f, "functions cannot be defined inside of other functions")
+            }
+        }
+    }
+}
+
+/// Whatever the parser was looking for when it encountered a [ParsingError].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Expected {
+    /// One specific token, like `=` in a variable declaration
+    Token(Token),
+    /// Any one of several tokens, like `,` or `)` after a function argument
+    OneOf(Vec<Token>),
+    Identifier,
+    Type,
+    /// The length of an array type, which must be an integer literal that fits in a `u32`, like
+    /// `16` in `[u8; 16]`
+    ArrayLength,
+    /// A function's return type (or the start of its body)
+    ReturnType,
+    Expression,
+    /// The pattern of a match arm, like `_` or `Shape::Circle(r)`
+    Pattern,
+    Statement,
+    /// The newline (or end of file) that must follow each statement
+    EndOfStatement,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Token(token) => write!(f, "{}", describe_token(token)),
+            Self::OneOf(tokens) => {
+                let tokens: Vec<_> = tokens.iter().map(describe_token).collect();
+                write!(f, "one of {}", tokens.join(", "))
+            }
+            Self::Identifier => write!(f, "identifier"),
+            Self::Type => write!(f, "type"),
+            Self::ArrayLength => write!(f, "array length"),
+            Self::ReturnType => write!(f, "return type"),
+            Self::Expression => write!(f, "expression"),
+            Self::Pattern => write!(f, "pattern"),
+            Self::Statement => write!(f, "statement"),
+            Self::EndOfStatement => write!(f, "newline or end of file"),
+        }
+    }
+}
+
+/// Describes a token for error messages, like `'while'` or `newline`.
+fn describe_token(token: &Token) -> String {
+    match token {
+        Token::Newline => "newline".to_string(),
+        Token::Comment(_) => "comment".to_string(),
+        Token::Docstring(_) => "docstring".to_string(),
+        Token::Identifier(id) => format!("identifier '{}'", id),
+        Token::IntLiteral(int) => format!("integer literal '{}'", int),
+        Token::FloatLiteral(float) => format!("float literal '{}'", float),
+        Token::StrLiteral(string) => format!("string literal {:?}", string),
+        Token::CharLiteral(c) => format!("character literal {:?}", c),
+        t => format!("'{}'", t),
+    }
+}
@@ -9,21 +9,36 @@
 ///
 /// ```
 /// # use flick::ast::*;
+/// # use flick::span::Span;
 /// # let _ =
-/// Expr::Binary(
-///     Binary {
-///         left: Box::new(Expr::IntLiteral("9".to_string())),
+/// Expr {
+///     kind: ExprKind::Binary(Binary {
+///         left: Box::new(Expr {
+///             kind: ExprKind::IntLiteral("9".to_string()),
+///             span: Span::default(),
+///         }),
 ///         operator: BinaryOperator::Multiply,
-///         right: Box::new(Expr::Binary(
-///             Binary {
-///                 left: Box::new(Expr::IntLiteral("2".to_string())),
+///         right: Box::new(Expr {
+///             kind: ExprKind::Binary(Binary {
+///                 left: Box::new(Expr {
+///                     kind: ExprKind::IntLiteral("2".to_string()),
+///                     span: Span::default(),
+///                 }),
 ///                 operator: BinaryOperator::Add,
-///                 right: Box::new(Expr::IntLiteral("3".to_string())),
-///             }
-///         )),
-///     }
-/// );
+///                 right: Box::new(Expr {
+///                     kind: ExprKind::IntLiteral("3".to_string()),
+///                     span: Span::default(),
+///                 }),
+///             }),
+///             span: Span::default(),
+///         }),
+///     }),
+///     // In practice, the parser fills in each span with the source code location of the expression
+///     span: Span::default(),
+/// };
 /// ```
 pub mod ast;
 /// Module that defines the [Parser] struct for converting tokens to an abstract syntax tree.
 pub mod parser;
+/// Module that defines the parser errors.
+pub mod error;
@@ -1,44 +1,118 @@
+use crate::error::{ErrorKind, FlickError};
 use crate::lexing::token::AssignmentSymbol::*;
 use crate::lexing::token::OperatorSymbol::*;
 use crate::lexing::token::Token;
 use crate::parsing::ast::*;
+use crate::span::{Span, Spanned};
 use crate::types::Type;
 
+use super::error::{Expected, ParsingError};
+
 /// A struct that takes tokens and parses them into a [abstract syntax tree](crate::parsing::ast)
 pub struct Parser<'a> {
     /// The slice of tokens to parse
-    tokens: &'a [Token],
+    tokens: &'a [Spanned<Token>],
     /// The index of the next unparsed token
     cursor: usize,
+    /// Errors from statements that the parser has already recovered from
+    errors: Vec<FlickError>,
+    /// Whether an identifier followed by `{` starts a struct literal, like `Point { x: 1 }`.
+    ///
+    /// This is `false` while parsing conditions, so that `if done {` is parsed as a condition
+    /// followed by a body (see [Parser::with_struct_literals]).
+    struct_literals_allowed: bool,
 }
 
 impl<'a> Parser<'a> {
-    /// Parses as many global statements as possible and returns a [Program] containing them all.
-    pub fn parse_program(tokens: &'a [Token]) -> Program {
-        let mut parser = Self { 
-            tokens, 
-            cursor: 0 
-        };
-
+    /// Parses as many global statements as possible and returns a [Program] containing them all,
+    /// along with every error it encountered (if parsing succeeded, the vector of errors is
+    /// empty).
+    ///
+    /// When a statement fails to parse, the parser records the error, skips to the start of the
+    /// next statement, and keeps going. The statement is left out of the program, so that the
+    /// rest of it can still be typed.
+    pub fn parse_program(tokens: &'a [Spanned<Token>]) -> (Program, Vec<FlickError>) {
+        let mut parser = Self::new(tokens);
         let mut global_statements = Vec::new();
 
         loop {
             parser.skip_newlines_comments_and_docstrings();
 
+            let statement_start = parser.cursor;
             match parser.parse_global_statement() {
-                Some(s) => global_statements.push(s),
-                None => break,
+                Ok(Some(s)) => global_statements.push(s),
+                Ok(None) => break,
+                Err(err) => {
+                    parser.recover(err, statement_start);
+                    // Unlike inside a body, a '}' can't end anything here, so skip past it
+                    if let Some(Token::RSquirly) = parser.peek_token(1) {
+                        parser.skip_token();
+                    }
+                }
             }
         }
 
-        Program { global_statements }
+        (Program { global_statements }, parser.errors)
+    }
+
+    fn new(tokens: &'a [Spanned<Token>]) -> Self {
+        Self {
+            tokens,
+            cursor: 0,
+            errors: Vec::new(),
+            struct_literals_allowed: true,
+        }
+    }
+
+    /// Records `err`, which was raised by the statement starting at the token index
+    /// `statement_start`, and then moves the cursor to where the next statement should start.
+    fn recover(&mut self, err: FlickError, statement_start: usize) {
+        // If the error was caused by a newline or squirly bracket that was just consumed, put it
+        // back: it either ended the broken statement or opened a body that should be skipped
+        let caused_by_delimiter = matches!(
+            self.cursor.checked_sub(1).and_then(|i| self.tokens.get(i)),
+            Some(token) if token.span == err.span
+                && matches!(token.node, Token::Newline | Token::LSquirly | Token::RSquirly)
+        );
+        if caused_by_delimiter {
+            self.cursor -= 1;
+        }
+
+        self.errors.push(err);
+        self.synchronize(statement_start);
+    }
+
+    /// Skips tokens until the end of the statement starting at the token index `statement_start`,
+    /// namely, a newline or a '}' that isn't nested inside of the statement's own braces (like the
+    /// body of a broken while loop, or the arms of a match that broke halfway through).
+    ///
+    /// The newline or '}' itself isn't consumed.
+    fn synchronize(&mut self, statement_start: usize) {
+        // The braces that the statement already opened before the error must be closed too
+        let mut depth = self.tokens[statement_start..self.cursor.min(self.tokens.len())]
+            .iter()
+            .fold(0, |depth, token| match token.node {
+                Token::LSquirly => depth + 1,
+                Token::RSquirly => depth - 1,
+                _ => depth,
+            })
+            .max(0);
+        while let Some(token) = self.peek_token(1) {
+            match token {
+                Token::Newline | Token::RSquirly if depth == 0 => break,
+                Token::LSquirly => depth += 1,
+                Token::RSquirly => depth -= 1,
+                _ => {}
+            }
+            self.skip_token();
+        }
     }
 
     /// Returns a reference to the next token and advances the cursor past it.
-    fn next_token(&mut self) -> Option<&Token> {
+    fn next_token(&mut self) -> Option<&'a Token> {
         let token = self.tokens.get(self.cursor);
         self.cursor += 1;
-        token
+        token.map(|t| &t.node)
     }
 
     /// Returns a reference to the `n`-th token out of the remaining tokens.
@@ -47,8 +121,15 @@ impl<'a> Parser<'a> {
     ///
     /// This function doesn't affect the internal state of the lexer (i.e., it doesn't consume
     /// any characters / it doesn't advance the internal cursor)
-    fn peek_token(&self, n: usize) -> Option<&Token> {
-        self.tokens.get(self.cursor + (n - 1)) // n-1 to fix indexing
+    fn peek_token(&self, n: usize) -> Option<&'a Token> {
+        self.tokens.get(self.cursor + (n - 1)).map(|t| &t.node) // n-1 to fix indexing
+    }
+
+    /// Returns the number of consecutive `*` tokens starting at the `n`-th remaining token.
+    fn count_asterisks(&self, n: usize) -> usize {
+        (n..)
+            .take_while(|&i| self.peek_token(i) == Some(&Token::OperatorSymbol(Asterisk)))
+            .count()
     }
 
     /// Advances the cursor past the next `n` tokens without returning anything.
@@ -56,16 +137,71 @@ impl<'a> Parser<'a> {
         self.cursor += 1;
     }
 
-    /// Parses a global statement, like an external function declaration or a function definition.
-    fn parse_global_statement(&mut self) -> Option<GlobalStatement> {
-        match self.peek_token(1) {
-            Some(Token::Extern) => Some(GlobalStatement::Extern(self.parse_func_proto())),
-            Some(Token::Fn | Token::Pub) => Some(GlobalStatement::FuncDef(self.parse_func_def())),
-            Some(t) => panic!("Unknown global statement starting with token '{}'", t),
-            None => None,
+    /// Returns the span of the next token.
+    ///
+    /// If no tokens remain, this function returns an empty span right after the last token.
+    fn peek_span(&self) -> Span {
+        match self.tokens.get(self.cursor) {
+            Some(token) => token.span,
+            None => self.prev_span().shrink_to_end(),
+        }
+    }
+
+    /// Returns the span of the most recently consumed token (or an empty span if no tokens
+    /// have been consumed yet).
+    fn prev_span(&self) -> Span {
+        match self.cursor.checked_sub(1).and_then(|i| self.tokens.get(i)) {
+            Some(token) => token.span,
+            None => self.tokens.last().map(|t| t.span).unwrap_or_default(),
+        }
+    }
+
+    /// Returns the span from `start` up to the end of the most recently consumed token.
+    ///
+    /// This is useful for computing the span of a syntax tree node: remember the span of its
+    /// first token with [Parser::peek_span], parse the node, and then call this function.
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.prev_span())
+    }
+
+    /// Returns a [ParsingError] located at `span`.
+    fn err(&self, kind: ParsingError, span: Span) -> FlickError {
+        FlickError {
+            span,
+            kind: ErrorKind::ParsingError(kind),
+        }
+    }
+
+    /// Returns an error explaining that the parser expected `expected` but found `found`.
+    ///
+    /// Note: this function assumes that `found` was just consumed (or, if `found` is `None`,
+    /// that the token stream ended).
+    fn expected_err(&self, expected: Expected, found: Option<&Token>) -> FlickError {
+        match found {
+            Some(found) => self.err(ParsingError::Expected { expected, found: found.clone() }, self.prev_span()),
+            None => self.err(ParsingError::UnexpectedEndOfFile { expected }, self.peek_span()),
         }
     }
 
+    /// Parses a global statement, like an external function declaration or a function definition.
+    ///
+    /// Returns `Ok(None)` if there are no tokens left.
+    fn parse_global_statement(&mut self) -> crate::Result<Option<GlobalStatement>> {
+        let global_statement = match self.peek_token(1) {
+            Some(Token::Extern) => GlobalStatement::Extern(self.parse_func_proto()?),
+            Some(Token::Const | Token::Static) => GlobalStatement::GlobalVar(self.parse_global_var()?),
+            Some(Token::Pub) if self.peek_token(2) == Some(&Token::Static) => {
+                GlobalStatement::GlobalVar(self.parse_global_var()?)
+            }
+            Some(Token::Fn | Token::Pub) => GlobalStatement::FuncDef(self.parse_func_def()?),
+            Some(Token::Struct) => GlobalStatement::Struct(self.parse_struct_def()?),
+            Some(Token::Enum) => GlobalStatement::Enum(self.parse_enum_def()?),
+            Some(t) => return Err(self.err(ParsingError::UnexpectedToken(t.clone()), self.peek_span())),
+            None => return Ok(None),
+        };
+        Ok(Some(global_statement))
+    }
+
     /// Advances the cursor past all newline, comment, and docstring tokens.
     fn skip_newlines_comments_and_docstrings(&mut self) {
         // todo take into account the fact that docstring CAN appear in parse tree
@@ -76,47 +212,167 @@ impl<'a> Parser<'a> {
 
     /// Parses the `fn foo(i64 x) i64` part of a function definition or an external function
     /// declaration.
-    fn parse_func_proto(&mut self) -> FuncProto {
-        let func_visibility = match (self.peek_token(1), self.peek_token(2)) {
-            (Some(Token::Pub), Some(Token::Fn)) => FuncVisibility::Public,
-            (Some(Token::Pub), Some(t)) => panic!("Expected 'pub fn' but received {}", t),
-            (Some(Token::Pub), None) => panic!("Expected 'pub fn' but file ended"),
-            (Some(Token::Extern), Some(Token::Fn)) => FuncVisibility::Extern,
-            (Some(Token::Extern), Some(t)) => panic!("Expected 'extern fn' but received {}", t),
-            (Some(Token::Extern), None) => panic!("Expected 'extern fn' but file ended"),
-            (Some(Token::Fn), _) => FuncVisibility::Private,
-            (Some(t), _) => panic!("Expected 'fn' or 'pub' but received {:?}", t),
-            (None, _) => panic!("Expected 'fn' or 'pub' but file ended"),
+    fn parse_func_proto(&mut self) -> crate::Result<FuncProto> {
+        let start = self.peek_span();
+
+        let func_visibility = match self.next_token() {
+            Some(Token::Pub) => FuncVisibility::Public,
+            Some(Token::Extern) => FuncVisibility::Extern,
+            Some(Token::Fn) => FuncVisibility::Private,
+            t => return Err(self.expected_err(Expected::OneOf(vec![Token::Fn, Token::Pub, Token::Extern]), t)),
         };
 
         if func_visibility != FuncVisibility::Private {
-            self.skip_token(); // skip the 'extern' / 'pub' in 'extern fn' / 'pub fn'
+            self.assert_next_token(Token::Fn)?; // the 'fn' in 'extern fn' / 'pub fn'
         }
-        self.skip_token();  // skip the 'fn'
 
-        let name = self.parse_identifier();
-        let params = self.parse_func_params();
+        let name = self.parse_identifier()?;
+        let params = self.parse_func_params()?;
 
         let return_type = match self.peek_token(1) {
             Some(Token::LSquirly) => Type::Void,  // implicit void ret-type omitted before body opened
-            Some(Token::Newline) => Type::Void,   // implicit void ret-type omitted but no '{' because, e.g., extern fn
-            Some(Token::Type(_)) => self.parse_type(),
-            Some(t) => panic!(
-                "Expected return type for function '{}' but received {:?}",
-                name, t
-            ),
-            None => panic!(
-                "Expected return type for function '{}' but file ended",
-                name
-            ),
+            Some(Token::Newline) | None => Type::Void,   // implicit void ret-type omitted but no '{' because, e.g., extern fn
+            Some(Token::Type(_) | Token::Identifier(_) | Token::LBracket | Token::OperatorSymbol(Asterisk)) => {
+                self.parse_type()?
+            }
+            Some(t) => {
+                let found = ParsingError::Expected { expected: Expected::ReturnType, found: t.clone() };
+                return Err(self.err(found, self.peek_span()));
+            }
         };
 
-        FuncProto {
+        Ok(FuncProto {
             func_visibility,
             name,
             params,
             return_type: Box::new(return_type),
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses a global variable, which must be followed by a newline (or the end of the file).
+    ///
+    /// # Flick example code
+    /// - `const u32 MAX = 100`
+    /// - `pub static mut u64 counter = 0`
+    fn parse_global_var(&mut self) -> crate::Result<GlobalVar> {
+        let start = self.peek_span();
+        let kind = match self.next_token() {
+            Some(Token::Const) => GlobalVarKind::Const,
+            Some(Token::Pub) => {
+                self.assert_next_token(Token::Static)?;
+                GlobalVarKind::StaticMut { is_public: true }
+            }
+            Some(Token::Static) => GlobalVarKind::StaticMut { is_public: false },
+            t => return Err(self.expected_err(Expected::OneOf(vec![Token::Const, Token::Static, Token::Pub]), t)),
+        };
+        if let GlobalVarKind::StaticMut { .. } = kind {
+            self.assert_next_token(Token::Mut)?;
+        }
+
+        let var_type = self.parse_type()?;
+        let var_name = self.parse_identifier()?;
+        self.assert_next_token(Token::AssignmentSymbol(Eq))?;
+        let var_value = self.parse_expr()?;
+        let global_var = GlobalVar {
+            kind,
+            var_type,
+            var_name,
+            var_value,
+            span: self.span_from(start),
+        };
+
+        match self.next_token() {
+            Some(Token::Newline | Token::Comment(_) | Token::Docstring(_)) | None => Ok(global_var),
+            t => Err(self.expected_err(Expected::EndOfStatement, t)),
+        }
+    }
+
+    /// Parses a struct definition, whose fields are separated by commas (and optionally newlines).
+    ///
+    /// # Flick example code
+    /// ```text
+    /// struct Point {
+    ///     i64 x,
+    ///     i64 y,
+    /// }
+    fn parse_struct_def(&mut self) -> crate::Result<StructDef> {
+        let start = self.peek_span();
+        self.assert_next_token(Token::Struct)?;
+        let name = self.parse_identifier()?;
+        self.assert_next_token(Token::LSquirly)?;
+
+        let mut fields = Vec::new();
+        loop {
+            self.skip_newlines_comments_and_docstrings();
+            if let Some(Token::RSquirly) = self.peek_token(1) {
+                self.skip_token();
+                break;
+            }
+
+            let field_type = self.parse_type()?;
+            let field_name = self.parse_identifier()?;
+            fields.push(StructField { field_type, field_name });
+
+            self.skip_newlines_comments_and_docstrings();
+            match self.next_token() {
+                Some(Token::Comma) => continue,
+                Some(Token::RSquirly) => break,
+                t => return Err(self.expected_err(Expected::OneOf(vec![Token::Comma, Token::RSquirly]), t)),
+            }
+        }
+
+        Ok(StructDef {
+            name,
+            fields,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses an enum definition, like `enum Shape { Circle(u32), Rect(u32, u32), Empty }`, whose
+    /// variants are separated by commas (and optionally newlines).
+    fn parse_enum_def(&mut self) -> crate::Result<EnumDef> {
+        let start = self.peek_span();
+        self.assert_next_token(Token::Enum)?;
+        let name = self.parse_identifier()?;
+        self.assert_next_token(Token::LSquirly)?;
+
+        let mut variants = Vec::new();
+        loop {
+            self.skip_newlines_comments_and_docstrings();
+            if let Some(Token::RSquirly) = self.peek_token(1) {
+                self.skip_token();
+                break;
+            }
+
+            let variant_name = self.parse_identifier()?;
+            let mut payload_types = Vec::new();
+            if let Some(Token::LParen) = self.peek_token(1) {
+                self.skip_token();
+                loop {
+                    payload_types.push(self.parse_type()?);
+                    match self.next_token() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RParen) => break,
+                        t => return Err(self.expected_err(Expected::OneOf(vec![Token::Comma, Token::RParen]), t)),
+                    }
+                }
+            }
+            variants.push(EnumVariant { name: variant_name, payload_types });
+
+            self.skip_newlines_comments_and_docstrings();
+            match self.next_token() {
+                Some(Token::Comma) => continue,
+                Some(Token::RSquirly) => break,
+                t => return Err(self.expected_err(Expected::OneOf(vec![Token::Comma, Token::RSquirly]), t)),
+            }
         }
+
+        Ok(EnumDef {
+            name,
+            variants,
+            span: self.span_from(start),
+        })
     }
 
     // TODO: Split the first part into a parse_func_proto function?
@@ -132,10 +388,10 @@ impl<'a> Parser<'a> {
     ///     ret a - x * 2;
     /// }
     /// ```
-    fn parse_func_def(&mut self) -> FuncDef {
-        let proto = self.parse_func_proto();
-        let body = self.parse_body();
-        FuncDef { proto, body }
+    fn parse_func_def(&mut self) -> crate::Result<FuncDef> {
+        let proto = self.parse_func_proto()?;
+        let body = self.parse_body()?;
+        Ok(FuncDef { proto, body })
     }
 
     /// Parses function parameters, which is useful when parsing a function definition.
@@ -151,19 +407,19 @@ impl<'a> Parser<'a> {
     /// # Assumptions
     ///
     /// - The function parameters are wrapped in parentheses.
-    fn parse_func_params(&mut self) -> Vec<FuncParam> {
-        self.assert_next_token(Token::LParen);
+    fn parse_func_params(&mut self) -> crate::Result<Vec<FuncParam>> {
+        self.assert_next_token(Token::LParen)?;
 
         let mut params = Vec::new();
 
         if let Some(Token::RParen) = self.peek_token(1) {
             self.skip_token();
-            return params;
+            return Ok(params);
         }
 
         loop {
-            let param_type = self.parse_type();
-            let param_name = self.parse_identifier();
+            let param_type = self.parse_type()?;
+            let param_name = self.parse_identifier()?;
 
             let func_param = FuncParam {
                 param_type,
@@ -175,12 +431,11 @@ impl<'a> Parser<'a> {
             match self.next_token() {
                 Some(Token::RParen) => break,
                 Some(Token::Comma) => continue,
-                Some(token) => panic!("Expected ')' but received {}", token),
-                None => panic!("Expected ')' but file ended"),
+                t => return Err(self.expected_err(Expected::OneOf(vec![Token::Comma, Token::RParen]), t)),
             }
         }
 
-        params
+        Ok(params)
     }
 
     /// Parses the next statement, skipping comments and newlines.
@@ -188,54 +443,108 @@ impl<'a> Parser<'a> {
     /// # Flick example code
     /// - `print(x)`
     /// - `i += 1`
-    fn parse_statement(&mut self) -> Option<Statement> {
-        let statement = match (self.peek_token(1)?, self.peek_token(2)) {
-            (Token::Type(_), _) => Statement::VarDeclaration(self.parse_var_declaration()),
-            (Token::While, _) => Statement::WhileLoop(self.parse_while_loop()),
-            (Token::Fn, _) => panic!("Nested function definitions are not allowed"),
-            (Token::Ret, _) => Statement::Return(self.parse_return_statement()),
-            (Token::If, _) => Statement::If(self.parse_if_statement()),
-            (Token::Identifier(_), Some(Token::AssignmentSymbol(_))) => {
-                Statement::Assignment(self.parse_assignment())
+    fn parse_statement(&mut self) -> crate::Result<Statement> {
+        let start = self.peek_span();
+
+        let kind = match (self.peek_token(1), self.peek_token(2)) {
+            (Some(Token::Type(_) | Token::LBracket), _)
+            | (Some(Token::Identifier(_)), Some(Token::Identifier(_))) => {
+                StatementKind::VarDeclaration(self.parse_var_declaration()?)
+            }
+            (Some(Token::Let), _) => StatementKind::Let(self.parse_let()?),
+            (Some(Token::While), _) => StatementKind::WhileLoop(self.parse_while_loop(None)?),
+            (Some(Token::Loop), _) => StatementKind::Loop(self.parse_loop(None)?),
+            (Some(Token::For), _) => StatementKind::ForLoop(Box::new(self.parse_for_loop(None)?)),
+            (Some(Token::Label(_)), _) => self.parse_labeled_loop()?,
+            (Some(Token::Break), _) => {
+                self.skip_token();
+                StatementKind::Break(self.parse_jump_label())
+            }
+            (Some(Token::Continue), _) => {
+                self.skip_token();
+                StatementKind::Continue(self.parse_jump_label())
+            }
+            (Some(Token::Fn), _) => return Err(self.err(ParsingError::NestedFunctionDefinition, start)),
+            (Some(Token::Ret), _) => StatementKind::Return(self.parse_return_statement()?),
+            (Some(Token::If), _) => StatementKind::If(self.parse_if_statement()?),
+            (Some(Token::Match), _) => StatementKind::Match(self.parse_match(Self::parse_body)?),
+            (Some(Token::OperatorSymbol(Asterisk)), _) if self.is_pointer_declaration() => {
+                StatementKind::VarDeclaration(self.parse_var_declaration()?)
             }
-            (Token::Identifier(_), Some(Token::LParen)) => Statement::Call(self.parse_call()),
-            (s, _) => panic!("Unexpected token to start statement: {}", s), // TODO: skip this line and keep checking the file for errors
+            (Some(Token::Identifier(_)), Some(Token::AssignmentSymbol(_) | Token::Dot | Token::LBracket))
+            | (Some(Token::OperatorSymbol(Asterisk)), _)
+            | (Some(Token::LParen), Some(Token::OperatorSymbol(Asterisk))) => {
+                StatementKind::Assignment(self.parse_assignment()?)
+            }
+            (Some(Token::Identifier(_)), Some(Token::LParen)) => StatementKind::Call(self.parse_call()?),
+            (Some(t), _) => return Err(self.err(ParsingError::UnexpectedToken(t.clone()), start)),
+            (None, _) => return Err(self.expected_err(Expected::Statement, None)),
+        };
+
+        let statement = Statement {
+            kind,
+            span: self.span_from(start),
         };
 
         match self.next_token() {
-            Some(Token::Newline | Token::Comment(_) | Token::Docstring(_)) | None => Some(statement),
-            Some(token) => panic!("Expected newline or EOF but received {}", token),
+            Some(Token::Newline | Token::Comment(_) | Token::Docstring(_)) | None => Ok(statement),
+            t => Err(self.expected_err(Expected::EndOfStatement, t)),
         }
     }
 
-    /// Panics if the token stream ended or if the next token doesn't match `expected`.
-    fn assert_next_token(&mut self, expected: Token) {
+    /// Returns whether the next statement, which starts with a `*`, declares a variable of a
+    /// pointer type (like `*u8 p = q`) rather than assigning through a pointer (like `*p = 3`).
+    fn is_pointer_declaration(&self) -> bool {
+        let n = self.count_asterisks(1) + 1;
+        matches!(
+            (self.peek_token(n), self.peek_token(n + 1)),
+            (Some(Token::Type(_) | Token::LBracket), _) | (Some(Token::Identifier(_)), Some(Token::Identifier(_)))
+        )
+    }
+
+    /// Returns an error if the token stream ended or if the next token doesn't match `expected`.
+    fn assert_next_token(&mut self, expected: Token) -> crate::Result<()> {
         match self.next_token() {
-            Some(token) if *token == expected => (),
-            Some(token) => panic!("Expected {:?} but received {:?}", expected, token),
-            None => panic!("Expected {:?} but file ended", expected),
+            Some(token) if *token == expected => Ok(()),
+            t => Err(self.expected_err(Expected::Token(expected), t)),
         }
     }
 
-    /// Parses a built-in type, like [Type::Void], and panics if the next token isn't one.
-    fn parse_type(&mut self) -> Type {
+    /// Parses a built-in type, like [Type::Void], the name of a struct type, or an array or
+    /// pointer type, and returns an error if the next token isn't one.
+    fn parse_type(&mut self) -> crate::Result<Type> {
         match self.next_token() {
-            Some(Token::Type(var_type)) => var_type.clone(),
-            Some(t) => panic!("Expected type of variable but received {:?}", t),
-            None => panic!("Expected type of variable but file ended"),
+            Some(Token::Type(var_type)) => Ok(var_type.clone()),
+            Some(Token::OperatorSymbol(Asterisk)) => Ok(Type::Pointer(Box::new(self.parse_type()?))),
+            Some(Token::Identifier(type_name)) => Ok(Type::Named(type_name.clone())),
+            Some(Token::LBracket) => {
+                let element_type = self.parse_type()?;
+                self.assert_next_token(Token::Semicolon)?;
+                let length = self.parse_array_length()?;
+                self.assert_next_token(Token::RBracket)?;
+                Ok(Type::Array(Box::new(element_type), length))
+            }
+            t => Err(self.expected_err(Expected::Type, t)),
+        }
+    }
+
+    /// Parses the length of an array type or repeated array literal, like `16` in `[u8; 16]`,
+    /// which must be an integer literal that fits in a `u32` (the largest length LLVM supports).
+    fn parse_array_length(&mut self) -> crate::Result<u64> {
+        match self.next_token() {
+            Some(Token::IntLiteral(n)) if n.parse::<u32>().is_ok() => Ok(n.parse().unwrap()),
+            t => Err(self.expected_err(Expected::ArrayLength, t)),
         }
     }
 
-    // TODO: Error messages: split this function into several for caller to be more precise
-    /// Parses an identifier, like `foo` or `x`, and panics if the next token isn't one.
-    fn parse_identifier(&mut self) -> String {
+    /// Parses an identifier, like `foo` or `x`, and returns an error if the next token isn't one.
+    fn parse_identifier(&mut self) -> crate::Result<String> {
         match self.next_token() {
             // TODO: Can we somehow get rid of this clone -- I think we can, but we have a choice:
             //  1. either have each ast node own its strings (this is what we're doing) -> then we must clone
             //  2. ast has a lifetime that outlives the token stream, in which case each ast can store &'a str
-            Some(Token::Identifier(id)) => id.clone(),
-            Some(t) => panic!("Expected identifier but received {:?}", t),
-            None => panic!("Expected identifier but received end of file"),
+            Some(Token::Identifier(id)) => Ok(id.clone()),
+            t => Err(self.expected_err(Expected::Identifier, t)),
         }
     }
 
@@ -244,25 +553,48 @@ impl<'a> Parser<'a> {
     /// # Flick example code
     /// - `i64 ten = 10`
     /// - `i64 hundred = 10 * ten`
-    fn parse_var_declaration(&mut self) -> VarDeclaration {
-        let var_type = self.parse_type();
+    /// - `Point origin = Point { x: 0, y: 0 }`
+    fn parse_var_declaration(&mut self) -> crate::Result<VarDeclaration> {
+        let var_type = self.parse_type()?;
 
         // TODO: Error messages: Add custom error message to tell user that variables can't be named the same as types
         //  (e.g. "void" or "i64")
-        let var_name = self.parse_identifier();
+        let var_name = self.parse_identifier()?;
 
-        self.assert_next_token(Token::AssignmentSymbol(Eq));
+        self.assert_next_token(Token::AssignmentSymbol(Eq))?;
 
-        let var_value = self.parse_expr();
+        let var_value = self.parse_expr()?;
 
-        VarDeclaration {
+        Ok(VarDeclaration {
             var_name,
             var_type,
             var_value,
+        })
+    }
+
+    /// Parses 1 variable declaration whose type is inferred
+    ///
+    /// # Flick example code
+    /// - `let ten = 10`
+    /// - `let mut total = foo(ten)`
+    fn parse_let(&mut self) -> crate::Result<Let> {
+        self.assert_next_token(Token::Let)?;
+        let is_mutable = self.peek_token(1) == Some(&Token::Mut);
+        if is_mutable {
+            self.skip_token();
         }
+        let var_name = self.parse_identifier()?;
+        self.assert_next_token(Token::AssignmentSymbol(Eq))?;
+        let var_value = self.parse_expr()?;
+
+        Ok(Let {
+            var_name,
+            is_mutable,
+            var_value,
+        })
     }
 
-    /// Parses 0 or more statements surrounded by curly brackets, and panics if unsuccessful.
+    /// Parses 0 or more statements surrounded by curly brackets, and returns an error if unsuccessful.
     ///
     /// # Flick example code
     /// - `{}`
@@ -274,28 +606,30 @@ impl<'a> Parser<'a> {
     ///     print(a)
     /// }
     /// ```
-    fn parse_body(&mut self) -> Vec<Statement> {
+    fn parse_body(&mut self) -> crate::Result<Vec<Statement>> {
         let mut body = Vec::new();
-        self.assert_next_token(Token::LSquirly);
+        let opening_squirly = self.peek_span();
+        self.assert_next_token(Token::LSquirly)?;
 
         loop {
             self.skip_newlines_comments_and_docstrings();
 
-            if self.peek_token(1) == Some(&Token::RSquirly) {
-                break;
-            }
-
-            match self.parse_statement() {
-                Some(statement) => body.push(statement),
-                None => panic!("Expected body to be closed ('}}') but file ended"),
+            let statement_start = self.cursor;
+            match self.peek_token(1) {
+                Some(Token::RSquirly) => break,
+                Some(_) => match self.parse_statement() {
+                    Ok(statement) => body.push(statement),
+                    Err(err) => self.recover(err, statement_start),
+                },
+                None => return Err(self.err(ParsingError::UnterminatedBody, opening_squirly)),
             }
         }
 
-        self.assert_next_token(Token::RSquirly);
-        body
+        self.assert_next_token(Token::RSquirly)?;
+        Ok(body)
     }
 
-    /// Parses an if statement (`if [condition] [body]`) and panics if unsuccessful.
+    /// Parses an if statement (`if [condition] [body]`) and returns an error if unsuccessful.
     ///
     /// See also: [Parser::parse_expr], [Parser::parse_body]
     ///
@@ -304,32 +638,38 @@ impl<'a> Parser<'a> {
     /// if i * i < p {
     ///     i += 1
     /// }
-    fn parse_if_statement(&mut self) -> If {
-        self.assert_next_token(Token::If);
+    fn parse_if_statement(&mut self) -> crate::Result<If> {
+        self.assert_next_token(Token::If)?;
 
-        let condition = self.parse_expr();
-        let then_body = self.parse_body();
+        let condition = self.with_struct_literals(false, Self::parse_expr)?;
+        let then_body = self.parse_body()?;
 
         let else_body = match self.peek_token(1) {
-            Some(&Token::Else) => Some(self.parse_else_statement()),
+            Some(&Token::Else) => Some(self.parse_else_statement()?),
             _ => None
         };
 
-        If { condition, then_body, else_body }
+        Ok(If { condition, then_body, else_body })
     }
-    
-    fn parse_else_statement(&mut self) -> Vec<Statement> {
-        self.assert_next_token(Token::Else);
+
+    fn parse_else_statement(&mut self) -> crate::Result<Vec<Statement>> {
+        self.assert_next_token(Token::Else)?;
 
         match self.peek_token(1) {
-            Some(Token::If) => vec![Statement::If(self.parse_if_statement())],
+            Some(Token::If) => {
+                let start = self.peek_span();
+                let kind = StatementKind::If(self.parse_if_statement()?);
+                Ok(vec![Statement { kind, span: self.span_from(start) }])
+            }
             Some(Token::LSquirly) => self.parse_body(),
-            Some(t) => panic!("Unexpected token '{}' after 'else'; expected 'else {{' or 'else if'", t),
-            None => panic!("Unexpected end of file after 'else'; expected 'else {{' or 'else if'")
+            t => {
+                self.skip_token();
+                Err(self.expected_err(Expected::OneOf(vec![Token::LSquirly, Token::If]), t))
+            }
         }
     }
 
-    /// Parses a while loop (`while [expr] [body]`) and panics if unsuccessful.
+    /// Parses a while loop (`while [expr] [body]`) and returns an error if unsuccessful.
     ///
     /// See also: [Parser::parse_expr], [Parser::parse_body]
     ///
@@ -338,62 +678,211 @@ impl<'a> Parser<'a> {
     /// while i * i < p {
     ///     i += 1
     /// }
-    fn parse_while_loop(&mut self) -> WhileLoop {
-        self.assert_next_token(Token::While);
+    fn parse_while_loop(&mut self, label: Option<String>) -> crate::Result<WhileLoop> {
+        self.assert_next_token(Token::While)?;
+
+        let condition = self.with_struct_literals(false, Self::parse_expr)?;
+        let body = self.parse_body()?;
+
+        Ok(WhileLoop { label, condition, body })
+    }
+
+    /// Parses an infinite loop (`loop [body]`) and returns an error if unsuccessful.
+    ///
+    /// # Flick example code
+    /// ```text
+    /// loop {
+    ///     i += 1
+    /// }
+    fn parse_loop(&mut self, label: Option<String>) -> crate::Result<Loop> {
+        self.assert_next_token(Token::Loop)?;
+
+        let body = self.parse_body()?;
+
+        Ok(Loop { label, body })
+    }
+
+    /// Parses a counted loop (`for [type] [name] in [expr]..[expr] [step [expr]] [body]`, or
+    /// with `..=` for an inclusive range) and returns an error if unsuccessful.
+    ///
+    /// # Flick example code
+    /// ```text
+    /// for u8 i in 1..=10 step 3 {
+    ///     total += i
+    /// }
+    fn parse_for_loop(&mut self, label: Option<String>) -> crate::Result<ForLoop> {
+        self.assert_next_token(Token::For)?;
+
+        let var_type = match self.next_token() {
+            Some(Token::Type(var_type)) => var_type.clone(),
+            t => return Err(self.expected_err(Expected::Type, t)),
+        };
+        let var_name = self.parse_identifier()?;
+        self.assert_next_token(Token::In)?;
+
+        let start = self.with_struct_literals(false, Self::parse_expr)?;
+        let inclusive = match self.next_token() {
+            Some(Token::DotDot) => false,
+            Some(Token::DotDotEq) => true,
+            t => return Err(self.expected_err(Expected::OneOf(vec![Token::DotDot, Token::DotDotEq]), t)),
+        };
+        let end = self.with_struct_literals(false, Self::parse_expr)?;
+
+        let step = match self.peek_token(1) {
+            Some(Token::Step) => {
+                self.skip_token();
+                Some(self.with_struct_literals(false, Self::parse_expr)?)
+            }
+            _ => None,
+        };
+        let body = self.parse_body()?;
+
+        Ok(ForLoop { label, var_type, var_name, start, end, inclusive, step, body })
+    }
+
+    /// Parses a loop that starts with a label (`'[label]: [loop]`), where the loop is a `while`
+    /// loop, a `for` loop or a `loop`.
+    ///
+    /// # Flick example code
+    /// ```text
+    /// 'outer: while i < 10 {
+    ///     loop {
+    ///         break 'outer
+    ///     }
+    /// }
+    fn parse_labeled_loop(&mut self) -> crate::Result<StatementKind> {
+        let label = match self.next_token() {
+            Some(Token::Label(label)) => label.clone(),
+            _ => unreachable!("This function is called from parse_statement, which already checks the next token"),
+        };
+        self.assert_next_token(Token::Colon)?;
 
-        let condition = self.parse_expr();
-        let body = self.parse_body();
+        match self.peek_token(1) {
+            Some(Token::While) => Ok(StatementKind::WhileLoop(self.parse_while_loop(Some(label))?)),
+            Some(Token::Loop) => Ok(StatementKind::Loop(self.parse_loop(Some(label))?)),
+            Some(Token::For) => Ok(StatementKind::ForLoop(Box::new(self.parse_for_loop(Some(label))?))),
+            t => {
+                self.skip_token();
+                Err(self.expected_err(Expected::OneOf(vec![Token::While, Token::Loop, Token::For]), t))
+            }
+        }
+    }
 
-        WhileLoop { condition, body }
+    /// Parses the optional label after a `break` or `continue`, like `'outer` in `break 'outer`.
+    fn parse_jump_label(&mut self) -> Option<String> {
+        match self.peek_token(1) {
+            Some(Token::Label(label)) => {
+                let label = label.clone();
+                self.skip_token();
+                Some(label)
+            }
+            _ => None,
+        }
     }
 
-    /// Parses a return statement (`return [expr]` or just `return`), and panics if unsuccessful.
-    fn parse_return_statement(&mut self) -> Option<Expr> {
-        self.assert_next_token(Token::Ret);
+    /// Parses a return statement (`return [expr]` or just `return`), and returns an error if unsuccessful.
+    fn parse_return_statement(&mut self) -> crate::Result<Option<Expr>> {
+        self.assert_next_token(Token::Ret)?;
 
-        match self.peek_token(1)? {
-            Token::Newline => None,
-            _ => Some(self.parse_expr()),
+        match self.peek_token(1) {
+            Some(Token::Newline) | None => Ok(None),
+            _ => Ok(Some(self.parse_expr()?)),
         }
     }
 
-    /// Parses assignments like `a = b` or `_ = foo()`;
+    /// Parses assignments like `a = b`, `_ = foo()`, `p.x = 3` or `*p = 3`;
     /// see [Parser::parse_expr] for expression-parsing details.
-    fn parse_assignment(&mut self) -> Assignment {
-        let name = self.parse_identifier();
-        let operator_symbol = self.next_token().unwrap();
-
-        let name_expr = Expr::Identifier(name.clone());
+    fn parse_assignment(&mut self) -> crate::Result<Assignment> {
+        let start = self.peek_span();
+        // The expression being assigned to is needed for compound assignments
+        let (name, accessors, target_expr) = self.parse_place()?;
+
+        let operator = match self.next_token() {
+            Some(Token::AssignmentSymbol(PlusEq)) => Some(BinaryOperator::Add),
+            Some(Token::AssignmentSymbol(TimesEq)) => Some(BinaryOperator::Multiply),
+            Some(Token::AssignmentSymbol(MinusEq)) => Some(BinaryOperator::Subtract),
+            Some(Token::AssignmentSymbol(DivideEq)) => Some(BinaryOperator::Divide),
+            Some(Token::AssignmentSymbol(AndEq)) => Some(BinaryOperator::BitwiseAnd),
+            Some(Token::AssignmentSymbol(OrEq)) => Some(BinaryOperator::BitwiseOr),
+            Some(Token::AssignmentSymbol(XorEq)) => Some(BinaryOperator::BitwiseXor),
+            Some(Token::AssignmentSymbol(ShiftLeftEq)) => Some(BinaryOperator::ShiftLeft),
+            Some(Token::AssignmentSymbol(ShiftRightEq)) => Some(BinaryOperator::ShiftRight),
+            Some(Token::AssignmentSymbol(Eq)) => None,
+            t => return Err(self.expected_err(Expected::Token(Token::AssignmentSymbol(Eq)), t)),
+        };
 
-        let value = match operator_symbol {
-            Token::AssignmentSymbol(PlusEq) => Expr::Binary(Binary {
-                left: Box::new(name_expr),
-                operator: BinaryOperator::Add,
-                right: Box::new(self.parse_expr()),
-            }),
-            Token::AssignmentSymbol(TimesEq) => Expr::Binary(Binary {
-                left: Box::new(name_expr),
-                operator: BinaryOperator::Multiply,
-                right: Box::new(self.parse_expr()),
-            }),
-            Token::AssignmentSymbol(MinusEq) => Expr::Binary(Binary {
-                left: Box::new(name_expr),
-                operator: BinaryOperator::Subtract,
-                right: Box::new(self.parse_expr()),
-            }),
-            Token::AssignmentSymbol(DivideEq) => Expr::Binary(Binary {
-                left: Box::new(name_expr),
-                operator: BinaryOperator::Divide,
-                right: Box::new(self.parse_expr()),
-            }),
-            Token::AssignmentSymbol(Eq) => self.parse_expr(),
-            _ => unreachable!(),
+        // Compound assignments like `a += b` are parsed as `a = a + b`
+        let value = match operator {
+            Some(operator) => Expr {
+                kind: ExprKind::Binary(Binary {
+                    left: Box::new(target_expr),
+                    operator,
+                    right: Box::new(self.parse_expr()?),
+                }),
+                span: self.span_from(start),
+            },
+            None => self.parse_expr()?,
         };
 
-        Assignment {
+        Ok(Assignment {
             name,
+            accessors,
             value: Box::new(value),
+        })
+    }
+
+    /// Parses the part of a variable that an assignment writes to, like `x`, `p.points[i]` or
+    /// `(*p).x`, returning the variable's name, the accessors leading to that part, and the
+    /// equivalent expression.
+    fn parse_place(&mut self) -> crate::Result<(String, Vec<Accessor>, Expr)> {
+        let start = self.peek_span();
+        let (name, mut accessors, mut target_expr) = match self.peek_token(1) {
+            // A dereference applies to the whole place after it, so `*p.x` dereferences `p.x`
+            Some(Token::OperatorSymbol(Asterisk)) => {
+                self.skip_token();
+                let (name, mut accessors, operand) = self.parse_place()?;
+                accessors.push(Accessor::Deref);
+                let kind = ExprKind::Unary(Unary { operator: UnaryOperator::Deref, operand: Box::new(operand) });
+                return Ok((name, accessors, Expr { kind, span: self.span_from(start) }));
+            }
+            Some(Token::LParen) => {
+                self.skip_token();
+                let (name, accessors, mut target_expr) = self.parse_place()?;
+                self.assert_next_token(Token::RParen)?;
+                target_expr.span = self.span_from(start);
+                (name, accessors, target_expr)
+            }
+            _ => {
+                let name = self.parse_identifier()?;
+                let target_expr = Expr {
+                    kind: ExprKind::Identifier(name.clone()),
+                    span: self.prev_span(),
+                };
+                (name, Vec::new(), target_expr)
+            }
+        };
+
+        loop {
+            let kind = match self.peek_token(1) {
+                Some(Token::Dot) => {
+                    self.skip_token();
+                    let field_name = self.parse_identifier()?;
+                    accessors.push(Accessor::Field(field_name.clone()));
+                    ExprKind::FieldAccess(FieldAccess { expr: Box::new(target_expr), field_name })
+                }
+                Some(Token::LBracket) => {
+                    let index = self.parse_index()?;
+                    accessors.push(Accessor::Index(index.clone()));
+                    ExprKind::Index(Index { expr: Box::new(target_expr), index: Box::new(index) })
+                }
+                _ => break,
+            };
+            target_expr = Expr {
+                kind,
+                span: self.span_from(start),
+            };
         }
+        Ok((name, accessors, target_expr))
     }
 
     /// Parses an expression. Keep reading for order-of-operations details.
@@ -405,148 +894,616 @@ impl<'a> Parser<'a> {
     ///
     /// For example, when parsing `1 + 7 * 8`, `parse_add_sub_expr` (less deep) will call `parse_mul_div_expr`
     /// (more deep) to parse `1` and `7 * 8`,
-    fn parse_expr(&mut self) -> Expr {
+    fn parse_expr(&mut self) -> crate::Result<Expr> {
         self.parse_logical_or_expr()
     }
 
-    // TODO: implement logical or (rn we don't parse it bc it's not even lexed)
     /// Parses expressions like `A or B or C`;
     /// see [Parser::parse_expr] for expression-parsing details.
-    fn parse_logical_or_expr(&mut self) -> Expr {
-        self.parse_logical_and_expr()
+    fn parse_logical_or_expr(&mut self) -> crate::Result<Expr> {
+        let mut left_expr_so_far = self.parse_logical_and_expr()?;
+
+        while let Some(Token::Or) = self.peek_token(1) {
+            self.skip_token();
+            let right = self.parse_logical_and_expr()?;
+            left_expr_so_far = Self::logical_expr(left_expr_so_far, LogicalOperator::Or, right);
+        }
+
+        Ok(left_expr_so_far)
     }
 
-    // TODO: implement logical and (rn we don't parse it bc it's not even lexed)
     /// Parses expressions like `A and B and C`;
     /// see [Parser::parse_expr] for expression-parsing details.
-    fn parse_logical_and_expr(&mut self) -> Expr {
+    fn parse_logical_and_expr(&mut self) -> crate::Result<Expr> {
+        let mut left_expr_so_far = self.parse_logical_not_expr()?;
+
+        while let Some(Token::And) = self.peek_token(1) {
+            self.skip_token();
+            let right = self.parse_logical_not_expr()?;
+            left_expr_so_far = Self::logical_expr(left_expr_so_far, LogicalOperator::And, right);
+        }
+
+        Ok(left_expr_so_far)
+    }
+
+    /// Combines `left` and `right` into a [Logical] expression that spans both of them.
+    fn logical_expr(left: Expr, operator: LogicalOperator, right: Expr) -> Expr {
+        Expr {
+            span: left.span.to(right.span),
+            kind: ExprKind::Logical(Logical {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+            }),
+        }
+    }
+
+    /// Parses expressions like `not A`. Note that `not` binds more loosely than comparisons, so
+    /// `not A == B` means `not (A == B)`;
+    /// see [Parser::parse_expr] for expression-parsing details.
+    fn parse_logical_not_expr(&mut self) -> crate::Result<Expr> {
+        let start = self.peek_span();
+
+        if let Some(Token::Not) = self.peek_token(1) {
+            self.skip_token();
+            let operand = self.parse_logical_not_expr()?;
+            return Ok(Expr {
+                kind: ExprKind::Unary(Unary {
+                    operator: UnaryOperator::Not,
+                    operand: Box::new(operand),
+                }),
+                span: self.span_from(start),
+            });
+        }
+
         self.parse_comparison_expression()
     }
 
     /// Parses expressions like `L < R`;
     /// see [Parser::parse_expr] for expression-parsing details.
-    fn parse_comparison_expression(&mut self) -> Expr {
-        let left = self.parse_add_sub_expr();
+    fn parse_comparison_expression(&mut self) -> crate::Result<Expr> {
+        let left = self.parse_bitwise_or_expr()?;
 
         let operator = match self.peek_token(1) {
             Some(Token::ComparatorSymbol(s)) => ComparisonOperator::from(*s),
-            _ => return left,
+            _ => return Ok(left),
         };
 
         self.skip_token(); // skip the compare symbol
 
-        let right = self.parse_add_sub_expr();
+        let right = self.parse_bitwise_or_expr()?;
 
         if let Some(Token::ComparatorSymbol(_)) = self.peek_token(1) {
-            // TODO: Error messages: print a useful error message for the user
-            panic!("Comparison operators cannot be chained")
+            return Err(self.err(ParsingError::ChainedComparison, self.peek_span()));
         }
 
-        Expr::Comparison(Comparison {
-            left: Box::new(left),
-            operator,
-            right: Box::new(right),
+        Ok(Expr {
+            span: left.span.to(right.span),
+            kind: ExprKind::Comparison(Comparison {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+            }),
         })
     }
 
-    /// Parses expressions like `A - B + C`;
+    /// Parses expressions like `A | B | C`;
     /// see [Parser::parse_expr] for expression-parsing details.
-    fn parse_add_sub_expr(&mut self) -> Expr {
-        let mut left_expr_so_far = self.parse_mul_div_rem_expr();
+    fn parse_bitwise_or_expr(&mut self) -> crate::Result<Expr> {
+        let mut left_expr_so_far = self.parse_bitwise_xor_expr()?;
 
-        while let Some(Token::OperatorSymbol(s @ (Plus | Minus))) = self.peek_token(1) {
+        while let Some(Token::OperatorSymbol(s @ Pipe)) = self.peek_token(1) {
             let operator = BinaryOperator::from(*s);
             self.skip_token();
-            let right = self.parse_mul_div_rem_expr();
-
-            left_expr_so_far = Expr::Binary(Binary {
-                left: Box::new(left_expr_so_far),
-                operator,
-                right: Box::new(right),
-            })
+            let right = self.parse_bitwise_xor_expr()?;
+            left_expr_so_far = Self::binary_expr(left_expr_so_far, operator, right);
         }
 
-        left_expr_so_far
+        Ok(left_expr_so_far)
     }
 
-    /// Parses expressions like `A / B * C`.
-    ///
-    /// See [Parser::parse_expr] for expression-parsing details.
-    fn parse_mul_div_rem_expr(&mut self) -> Expr {
-        let mut left_expr_so_far = self.parse_unary_expr();
+    /// Parses expressions like `A ^ B ^ C`;
+    /// see [Parser::parse_expr] for expression-parsing details.
+    fn parse_bitwise_xor_expr(&mut self) -> crate::Result<Expr> {
+        let mut left_expr_so_far = self.parse_bitwise_and_expr()?;
 
-        while let Some(Token::OperatorSymbol(s @ (Asterisk | Slash | Modulo))) = self.peek_token(1) {
+        while let Some(Token::OperatorSymbol(s @ Caret)) = self.peek_token(1) {
             let operator = BinaryOperator::from(*s);
             self.skip_token();
-            let right = self.parse_unary_expr();
-
-            left_expr_so_far = Expr::Binary(Binary {
-                left: Box::new(left_expr_so_far),
-                operator,
-                right: Box::new(right),
-            })
+            let right = self.parse_bitwise_and_expr()?;
+            left_expr_so_far = Self::binary_expr(left_expr_so_far, operator, right);
         }
 
-        left_expr_so_far
-    }
-
-    /// Parses expressions like `-A` or `(u32) B`
-    fn parse_unary_expr(&mut self) -> Expr {
-        match (self.peek_token(1), self.peek_token(2)) {
-            (Some(Token::OperatorSymbol(Minus)), _) => Expr::Unary(self.parse_negation()),
-            (Some(Token::LParen), Some(Token::Type(_))) => Expr::Unary(self.parse_cast()),
-            _ => self.parse_primary_expr(),
-        }
+        Ok(left_expr_so_far)
     }
 
-    /// Parses negation expressions like `-A`.
-    fn parse_negation(&mut self) -> Unary {
-        self.assert_next_token(Token::OperatorSymbol(Minus));
-        let operand = self.parse_unary_expr();
+    /// Parses expressions like `A & B & C`;
+    /// see [Parser::parse_expr] for expression-parsing details.
+    fn parse_bitwise_and_expr(&mut self) -> crate::Result<Expr> {
+        let mut left_expr_so_far = self.parse_shift_expr()?;
 
-        Unary {
-            operator: UnaryOperator::Negate,
-            operand: Box::new(operand),
+        while let Some(Token::OperatorSymbol(s @ Ampersand)) = self.peek_token(1) {
+            let operator = BinaryOperator::from(*s);
+            self.skip_token();
+            let right = self.parse_shift_expr()?;
+            left_expr_so_far = Self::binary_expr(left_expr_so_far, operator, right);
         }
+
+        Ok(left_expr_so_far)
     }
 
-    /// Parses cast expressions like `(u32) A`.
-    fn parse_cast(&mut self) -> Unary {
-        self.assert_next_token(Token::LParen);
-        let cast_type = self.parse_type();
-        self.assert_next_token(Token::RParen);
-        let operand = self.parse_unary_expr();
+    /// Parses expressions like `A << B >> C`;
+    /// see [Parser::parse_expr] for expression-parsing details.
+    fn parse_shift_expr(&mut self) -> crate::Result<Expr> {
+        let mut left_expr_so_far = self.parse_add_sub_expr()?;
 
-        Unary {
-            operator: UnaryOperator::Cast(cast_type),
-            operand: Box::new(operand),
+        while let Some(Token::OperatorSymbol(s @ (ShiftLeft | ShiftRight))) = self.peek_token(1) {
+            let operator = BinaryOperator::from(*s);
+            self.skip_token();
+            let right = self.parse_add_sub_expr()?;
+            left_expr_so_far = Self::binary_expr(left_expr_so_far, operator, right);
         }
+
+        Ok(left_expr_so_far)
     }
 
-    /// Parses expressions like `(A + B)` or `foo()` or `x`;
+    /// Combines `left` and `right` into a [Binary] expression that spans both of them.
+    fn binary_expr(left: Expr, operator: BinaryOperator, right: Expr) -> Expr {
+        Expr {
+            span: left.span.to(right.span),
+            kind: ExprKind::Binary(Binary {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+            }),
+        }
+    }
+
+    /// Parses expressions like `A - B + C`;
     /// see [Parser::parse_expr] for expression-parsing details.
-    fn parse_primary_expr(&mut self) -> Expr {
-        match (self.peek_token(1), self.peek_token(2)) {
+    fn parse_add_sub_expr(&mut self) -> crate::Result<Expr> {
+        let mut left_expr_so_far = self.parse_mul_div_rem_expr()?;
+
+        while let Some(Token::OperatorSymbol(s @ (Plus | Minus))) = self.peek_token(1) {
+            let operator = BinaryOperator::from(*s);
+            self.skip_token();
+            let right = self.parse_mul_div_rem_expr()?;
+
+            left_expr_so_far = Expr {
+                span: left_expr_so_far.span.to(right.span),
+                kind: ExprKind::Binary(Binary {
+                    left: Box::new(left_expr_so_far),
+                    operator,
+                    right: Box::new(right),
+                }),
+            }
+        }
+
+        Ok(left_expr_so_far)
+    }
+
+    /// Parses expressions like `A / B * C`.
+    ///
+    /// See [Parser::parse_expr] for expression-parsing details.
+    fn parse_mul_div_rem_expr(&mut self) -> crate::Result<Expr> {
+        let mut left_expr_so_far = self.parse_unary_expr()?;
+
+        while let Some(Token::OperatorSymbol(s @ (Asterisk | Slash | Modulo))) = self.peek_token(1) {
+            let operator = BinaryOperator::from(*s);
+            self.skip_token();
+            let right = self.parse_unary_expr()?;
+
+            left_expr_so_far = Expr {
+                span: left_expr_so_far.span.to(right.span),
+                kind: ExprKind::Binary(Binary {
+                    left: Box::new(left_expr_so_far),
+                    operator,
+                    right: Box::new(right),
+                }),
+            }
+        }
+
+        Ok(left_expr_so_far)
+    }
+
+    /// Parses expressions like `-A`, `~A`, `*A`, `&A`, or `(u32) B`
+    fn parse_unary_expr(&mut self) -> crate::Result<Expr> {
+        let start = self.peek_span();
+
+        let kind = match (self.peek_token(1), self.peek_token(2)) {
+            (Some(Token::OperatorSymbol(Minus)), _) => ExprKind::Unary(self.parse_negation()?),
+            (Some(Token::OperatorSymbol(Tilde)), _) => ExprKind::Unary(self.parse_bitwise_not()?),
+            (Some(Token::OperatorSymbol(s @ (Asterisk | Ampersand))), _) => {
+                let operator = match s {
+                    Asterisk => UnaryOperator::Deref,
+                    _ => UnaryOperator::AddressOf,
+                };
+                self.skip_token();
+                let operand = self.parse_unary_expr()?;
+                ExprKind::Unary(Unary { operator, operand: Box::new(operand) })
+            }
+            (Some(Token::LParen), Some(Token::Type(_))) => ExprKind::Unary(self.parse_cast()?),
+            (Some(Token::LParen), Some(Token::OperatorSymbol(Asterisk))) if self.is_pointer_cast() => {
+                ExprKind::Unary(self.parse_cast()?)
+            }
+            _ => return self.parse_primary_expr(),
+        };
+
+        Ok(Expr {
+            kind,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Returns whether the next tokens, which start with `(*`, are a cast to a pointer type (like
+    /// `(*u8) p`) rather than a parenthesized dereference (like `(*p)`).
+    ///
+    /// Since `(*Node)` could be either, it's only a cast if it's followed by something that can
+    /// start an operand but can't continue an expression, like an identifier.
+    fn is_pointer_cast(&self) -> bool {
+        let n = self.count_asterisks(2) + 2;
+        match (self.peek_token(n), self.peek_token(n + 1), self.peek_token(n + 2)) {
+            (Some(Token::Type(_) | Token::LBracket), _, _) => true,
+            (Some(Token::Identifier(_)), Some(Token::RParen), Some(next)) => matches!(
+                next,
+                Token::Identifier(_)
+                    | Token::IntLiteral(_)
+                    | Token::FloatLiteral(_)
+                    | Token::StrLiteral(_)
+                    | Token::CharLiteral(_)
+                    | Token::True
+                    | Token::False
+                    | Token::Null
+                    | Token::LParen
+                    | Token::OperatorSymbol(Tilde)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Parses negation expressions like `-A`.
+    fn parse_negation(&mut self) -> crate::Result<Unary> {
+        self.assert_next_token(Token::OperatorSymbol(Minus))?;
+        let operand = self.parse_unary_expr()?;
+
+        Ok(Unary {
+            operator: UnaryOperator::Negate,
+            operand: Box::new(operand),
+        })
+    }
+
+    /// Parses bitwise negation expressions like `~A`.
+    fn parse_bitwise_not(&mut self) -> crate::Result<Unary> {
+        self.assert_next_token(Token::OperatorSymbol(Tilde))?;
+        let operand = self.parse_unary_expr()?;
+
+        Ok(Unary {
+            operator: UnaryOperator::BitwiseNot,
+            operand: Box::new(operand),
+        })
+    }
+
+    /// Parses cast expressions like `(u32) A` or `(*u8) A`.
+    fn parse_cast(&mut self) -> crate::Result<Unary> {
+        self.assert_next_token(Token::LParen)?;
+        let cast_type = self.parse_type()?;
+        self.assert_next_token(Token::RParen)?;
+        let operand = self.parse_unary_expr()?;
+
+        Ok(Unary {
+            operator: UnaryOperator::Cast(cast_type),
+            operand: Box::new(operand),
+        })
+    }
+
+    /// Parses expressions like `(A + B)` or `foo()` or `x`, followed by any number of field
+    /// accesses (like `.x`); see [Parser::parse_expr] for expression-parsing details.
+    fn parse_primary_expr(&mut self) -> crate::Result<Expr> {
+        let start = self.peek_span();
+
+        let mut expr = match (self.peek_token(1), self.peek_token(2)) {
             (Some(Token::LParen), _) => {
                 self.skip_token();
-                let expr = self.parse_expr();
-                self.assert_next_token(Token::RParen);
+                let mut expr = self.with_struct_literals(true, Self::parse_expr)?;
+                self.assert_next_token(Token::RParen)?;
+                // The span of a parenthesized expression includes its parentheses
+                expr.span = self.span_from(start);
                 expr
             }
-            (Some(Token::Identifier(_)), Some(Token::LParen)) => Expr::Call(self.parse_call()),
-            _ => self.parse_atom(),
+            (Some(Token::Identifier(_)), Some(Token::LParen)) => {
+                let kind = ExprKind::Call(self.parse_call()?);
+                Expr {
+                    kind,
+                    span: self.span_from(start),
+                }
+            }
+            (Some(Token::Identifier(_)), Some(Token::LSquirly)) if self.struct_literals_allowed => {
+                let kind = ExprKind::StructLiteral(self.parse_struct_literal()?);
+                Expr {
+                    kind,
+                    span: self.span_from(start),
+                }
+            }
+            (Some(Token::Identifier(_)), Some(Token::ColonColon)) => {
+                let kind = ExprKind::EnumLiteral(self.parse_enum_literal()?);
+                Expr {
+                    kind,
+                    span: self.span_from(start),
+                }
+            }
+            (Some(Token::Match), _) => {
+                let kind = ExprKind::Match(self.parse_match(Self::parse_expr)?);
+                Expr {
+                    kind,
+                    span: self.span_from(start),
+                }
+            }
+            (Some(Token::LBracket), _) => {
+                let kind = self.parse_array_literal()?;
+                Expr {
+                    kind,
+                    span: self.span_from(start),
+                }
+            }
+            _ => self.parse_atom()?,
+        };
+
+        loop {
+            let kind = match self.peek_token(1) {
+                Some(Token::Dot) => {
+                    self.skip_token();
+                    let field_name = self.parse_identifier()?;
+                    ExprKind::FieldAccess(FieldAccess { expr: Box::new(expr), field_name })
+                }
+                Some(Token::LBracket) => {
+                    let index = self.parse_index()?;
+                    ExprKind::Index(Index { expr: Box::new(expr), index: Box::new(index) })
+                }
+                _ => break,
+            };
+            expr = Expr {
+                kind,
+                span: self.span_from(start),
+            };
         }
+
+        Ok(expr)
+    }
+
+    /// Parses the index of an indexing expression (including its brackets), like `[i + 1]` in
+    /// `buf[i + 1]`.
+    fn parse_index(&mut self) -> crate::Result<Expr> {
+        self.assert_next_token(Token::LBracket)?;
+        let index = self.with_struct_literals(true, Self::parse_expr)?;
+        self.assert_next_token(Token::RBracket)?;
+        Ok(index)
+    }
+
+    /// Parses array literals, which either list their elements separated by commas (and
+    /// optionally newlines), or repeat one value a given number of times.
+    ///
+    /// # Flick example code
+    /// - `[1, 2, 3]`
+    /// - `[0; 16]`
+    fn parse_array_literal(&mut self) -> crate::Result<ExprKind> {
+        self.assert_next_token(Token::LBracket)?;
+
+        let mut elements = Vec::new();
+        loop {
+            self.skip_newlines_comments_and_docstrings();
+            if let Some(Token::RBracket) = self.peek_token(1) {
+                self.skip_token();
+                break;
+            }
+
+            let element = self.with_struct_literals(true, Self::parse_expr)?;
+            if elements.is_empty() && self.peek_token(1) == Some(&Token::Semicolon) {
+                self.skip_token();
+                let length = self.parse_array_length()?;
+                self.assert_next_token(Token::RBracket)?;
+                return Ok(ExprKind::ArrayRepeat(ArrayRepeat { value: Box::new(element), length }));
+            }
+            elements.push(element);
+
+            self.skip_newlines_comments_and_docstrings();
+            match self.next_token() {
+                Some(Token::Comma) => continue,
+                Some(Token::RBracket) => break,
+                t => return Err(self.expected_err(Expected::OneOf(vec![Token::Comma, Token::RBracket]), t)),
+            }
+        }
+
+        Ok(ExprKind::ArrayLiteral(elements))
+    }
+
+    /// Parses struct literals like `Point { x: 1, y: 2 }`, whose fields are separated by commas
+    /// (and optionally newlines).
+    fn parse_struct_literal(&mut self) -> crate::Result<StructLiteral> {
+        let struct_name = self.parse_identifier()?;
+        self.assert_next_token(Token::LSquirly)?;
+
+        let mut fields = Vec::new();
+        loop {
+            self.skip_newlines_comments_and_docstrings();
+            if let Some(Token::RSquirly) = self.peek_token(1) {
+                self.skip_token();
+                break;
+            }
+
+            let field_name = self.parse_identifier()?;
+            self.assert_next_token(Token::Colon)?;
+            let value = self.with_struct_literals(true, Self::parse_expr)?;
+            fields.push(FieldValue { field_name, value });
+
+            self.skip_newlines_comments_and_docstrings();
+            match self.next_token() {
+                Some(Token::Comma) => continue,
+                Some(Token::RSquirly) => break,
+                t => return Err(self.expected_err(Expected::OneOf(vec![Token::Comma, Token::RSquirly]), t)),
+            }
+        }
+
+        Ok(StructLiteral { struct_name, fields })
+    }
+
+    /// Parses enum literals like `Shape::Rect(2, 3)` or `Shape::Empty`.
+    fn parse_enum_literal(&mut self) -> crate::Result<EnumLiteral> {
+        let enum_name = self.parse_identifier()?;
+        self.assert_next_token(Token::ColonColon)?;
+        let variant_name = self.parse_identifier()?;
+        let payload = match self.peek_token(1) {
+            Some(Token::LParen) => self.with_struct_literals(true, Self::parse_func_args)?,
+            _ => Vec::new(),
+        };
+        Ok(EnumLiteral { enum_name, variant_name, payload })
     }
 
-    /// Parses expressions like `foo()` or `bar(7, 2)`; see [Parser::parse_expr] for 
+    /// Parses a match statement or expression, using `parse_arm_body` to parse the body of each
+    /// arm (which comes after its pattern and `=>`). Arms are separated by commas or newlines.
+    ///
+    /// # Flick example code
+    /// ```text
+    /// match shape {
+    ///     Shape::Circle(r) => 3 * r * r,
+    ///     Shape::Rect(w, h) => w * h,
+    ///     _ => 0,
+    /// }
+    /// ```
+    fn parse_match<Body>(
+        &mut self,
+        parse_arm_body: impl Fn(&mut Self) -> crate::Result<Body>,
+    ) -> crate::Result<Match<Body>> {
+        self.assert_next_token(Token::Match)?;
+        let scrutinee = self.with_struct_literals(false, Self::parse_expr)?;
+        self.assert_next_token(Token::LSquirly)?;
+
+        let mut arms = Vec::new();
+        loop {
+            self.skip_newlines_comments_and_docstrings();
+            if let Some(Token::RSquirly) = self.peek_token(1) {
+                self.skip_token();
+                break;
+            }
+
+            let pattern = self.parse_pattern()?;
+            self.assert_next_token(Token::FatArrow)?;
+            let body = self.with_struct_literals(true, &parse_arm_body)?;
+            arms.push(MatchArm { pattern, body });
+
+            if let Some(Token::RSquirly) = self.peek_token(1) {
+                continue;
+            }
+            match self.next_token() {
+                Some(Token::Comma | Token::Newline | Token::Comment(_) | Token::Docstring(_)) => {}
+                t => {
+                    let expected = vec![Token::Comma, Token::Newline, Token::RSquirly];
+                    return Err(self.expected_err(Expected::OneOf(expected), t));
+                }
+            }
+        }
+
+        Ok(Match {
+            scrutinee: Box::new(scrutinee),
+            arms,
+        })
+    }
+
+    /// Parses the pattern of a match arm, like `_`, `-3`, `1..=5`, `true` or `Shape::Rect(w, _)`.
+    fn parse_pattern(&mut self) -> crate::Result<Pattern> {
+        let start = self.peek_span();
+
+        let kind = match (self.peek_token(1), self.peek_token(2)) {
+            (Some(Token::Identifier(name)), _) if name == "_" => {
+                self.skip_token();
+                PatternKind::Wildcard
+            }
+            (Some(Token::Identifier(_)), Some(Token::ColonColon)) => {
+                let enum_name = self.parse_identifier()?;
+                self.skip_token();
+                let variant_name = self.parse_identifier()?;
+
+                let mut bindings = Vec::new();
+                if let Some(Token::LParen) = self.peek_token(1) {
+                    self.skip_token();
+                    loop {
+                        let binding = self.parse_identifier()?;
+                        bindings.push(Some(binding).filter(|name| name != "_"));
+                        match self.next_token() {
+                            Some(Token::Comma) => continue,
+                            Some(Token::RParen) => break,
+                            t => return Err(self.expected_err(Expected::OneOf(vec![Token::Comma, Token::RParen]), t)),
+                        }
+                    }
+                }
+                PatternKind::EnumVariant { enum_name, variant_name, bindings }
+            }
+            (Some(Token::True), _) => {
+                self.skip_token();
+                PatternKind::BoolLiteral(true)
+            }
+            (Some(Token::False), _) => {
+                self.skip_token();
+                PatternKind::BoolLiteral(false)
+            }
+            (Some(Token::IntLiteral(_) | Token::OperatorSymbol(Minus)), _) => {
+                let start = self.parse_int_pattern()?;
+                match self.peek_token(1) {
+                    Some(Token::DotDot | Token::DotDotEq) => {
+                        let inclusive = self.next_token() == Some(&Token::DotDotEq);
+                        let end = self.parse_int_pattern()?;
+                        PatternKind::Range { start, end, inclusive }
+                    }
+                    _ => PatternKind::IntLiteral(start),
+                }
+            }
+            _ => {
+                let t = self.next_token();
+                return Err(self.expected_err(Expected::Pattern, t));
+            }
+        };
+
+        Ok(Pattern {
+            kind,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses an integer in a pattern, like `3` or `-3`.
+    fn parse_int_pattern(&mut self) -> crate::Result<String> {
+        let negative = self.peek_token(1) == Some(&Token::OperatorSymbol(Minus));
+        if negative {
+            self.skip_token();
+        }
+        match self.next_token() {
+            Some(Token::IntLiteral(int)) if negative => Ok(format!("-{}", int)),
+            Some(Token::IntLiteral(int)) => Ok(int.clone()),
+            t => Err(self.expected_err(Expected::Pattern, t)),
+        }
+    }
+
+    /// Runs `parse` with struct literals allowed (or not), and then restores the previous setting.
+    ///
+    /// See [Parser::struct_literals_allowed] for details.
+    fn with_struct_literals<T>(
+        &mut self,
+        allowed: bool,
+        parse: impl FnOnce(&mut Self) -> crate::Result<T>,
+    ) -> crate::Result<T> {
+        let previous = std::mem::replace(&mut self.struct_literals_allowed, allowed);
+        let result = parse(self);
+        self.struct_literals_allowed = previous;
+        result
+    }
+
+    /// Parses expressions like `foo()` or `bar(7, 2)`; see [Parser::parse_expr] for
     /// expression-parsing details.
-    fn parse_call(&mut self) -> Call {
-        let function_name = self.parse_identifier();
-        let args = self.parse_func_args();
-        Call {
+    fn parse_call(&mut self) -> crate::Result<Call> {
+        let function_name = self.parse_identifier()?;
+        let args = self.with_struct_literals(true, Self::parse_func_args)?;
+        Ok(Call {
             function_name,
             args
-        }
+        })
     }
 
     /// Parses function args, which is useful during a function call.
@@ -560,46 +1517,60 @@ impl<'a> Parser<'a> {
     ///
     /// # Assumptions
     /// - The args are wrapped in parentheses and properly comma-separated.
-    fn parse_func_args(&mut self) -> Vec<Expr> {
-        self.assert_next_token(Token::LParen);
+    fn parse_func_args(&mut self) -> crate::Result<Vec<Expr>> {
+        self.assert_next_token(Token::LParen)?;
 
         let mut args = Vec::new();
 
         if let Some(Token::RParen) = self.peek_token(1) {
             self.skip_token();
-            return args;
+            return Ok(args);
         }
 
         loop {
-            args.push(self.parse_expr());
+            args.push(self.parse_expr()?);
 
             match self.next_token() {
                 Some(Token::RParen) => break,
                 Some(Token::Comma) => continue,
-                Some(token) => panic!("Expected ')' but received {:?}", token),
-                None => panic!("Expected ')' but file ended"),
+                t => return Err(self.expected_err(Expected::OneOf(vec![Token::Comma, Token::RParen]), t)),
             }
         }
 
-        args
+        Ok(args)
     }
 
-    /// Parses the most atomic expressions (identifiers/literals) and panics if unsuccessful.
+    /// Parses the most atomic expressions (identifiers/literals) and returns an error if unsuccessful.
     ///
     /// # Flick example code
     /// - `foo`
     /// - `42`
-    fn parse_atom(&mut self) -> Expr {
-        match (self.peek_token(1), self.peek_token(2)) {
-            (Some(Token::Identifier(_)), _) => Expr::Identifier(self.parse_identifier()),
-            (Some(Token::IntLiteral(_)), _) => Expr::IntLiteral(self.parse_int_literal()),
-            (Some(Token::True | Token::False), _) => Expr::BoolLiteral(self.parse_bool_literal()),
+    fn parse_atom(&mut self) -> crate::Result<Expr> {
+        let start = self.peek_span();
+
+        let kind = match (self.peek_token(1), self.peek_token(2)) {
+            (Some(Token::Identifier(_)), _) => ExprKind::Identifier(self.parse_identifier()?),
+            (Some(Token::IntLiteral(_)), _) => ExprKind::IntLiteral(self.parse_int_literal()),
+            (Some(Token::FloatLiteral(_)), _) => ExprKind::FloatLiteral(self.parse_float_literal()),
+            (Some(Token::True | Token::False), _) => ExprKind::BoolLiteral(self.parse_bool_literal()),
+
+            (Some(Token::StrLiteral(_)), _) => ExprKind::StrLiteral(self.parse_str_literal()),
+            (Some(Token::CharLiteral(_)), _) => ExprKind::CharLiteral(self.parse_char_literal()),
+            (Some(Token::Null), _) => {
+                self.skip_token();
+                ExprKind::Null
+            }
 
-            // todo Some(Token::StrLiteral())
+            (t, _) => {
+                self.skip_token();
+                return Err(self.expected_err(Expected::Expression, t));
+            }
+        };
 
-            (Some(token), _) => panic!("Expected identifier or literal but received '{}'", token),
-            (None, _) => panic!("Expected identifier or literal but file ended"),
-        }
+        Ok(Expr {
+            kind,
+            span: self.span_from(start),
+        })
     }
 
     /// Parses an integer literal, considering if it's negative by checking for a minus sign.
@@ -615,6 +1586,41 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parses a string literal (whose escape sequences were already replaced by the lexer).
+    ///
+    /// # Flick example code
+    /// - `"Hello, world!\n"`
+    fn parse_str_literal(&mut self) -> String {
+        match self.next_token() {
+            Some(Token::StrLiteral(string)) => string.clone(),
+            _ => unreachable!("This function is called from parse_atom, which already checks the next token")
+        }
+    }
+
+    /// Parses a character literal (whose escape sequence was already replaced by the lexer).
+    ///
+    /// # Flick example code
+    /// - `'a'`
+    /// - `'\u{1F600}'`
+    fn parse_char_literal(&mut self) -> char {
+        match self.next_token() {
+            Some(Token::CharLiteral(c)) => *c,
+            _ => unreachable!("This function is called from parse_atom, which already checks the next token")
+        }
+    }
+
+    /// Parses a float literal.
+    ///
+    /// # Flick example code
+    /// - `1.5`
+    /// - `2e-3`
+    fn parse_float_literal(&mut self) -> String {
+        match self.next_token() {
+            Some(Token::FloatLiteral(n)) => n.clone(),
+            _ => unreachable!("This function is called from parse_atom, which already checks the next token")
+        }
+    }
+
     fn parse_bool_literal(&mut self) -> bool {
         match self.next_token() {
             Some(Token::True) => true,
@@ -628,24 +1634,62 @@ impl<'a> Parser<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lexing::token::ComparatorSymbol;
     use crate::lexing::token::ComparatorSymbol::LessOrEqualTo;
-    use crate::types::IntType;
+    use crate::types::{FloatType, IntType};
+
+    /// Wraps each token with a placeholder span, since these tests only check the shape of the AST.
+    fn spanned(tokens: Vec<Token>) -> Vec<Spanned<Token>> {
+        tokens.into_iter().map(|t| Spanned::new(t, Span::default())).collect()
+    }
+
+    /// Creates an expression with a placeholder span.
+    fn expr(kind: ExprKind) -> Expr {
+        Expr { kind, span: Span::default() }
+    }
+
+    /// Creates a statement with a placeholder span.
+    fn statement(kind: StatementKind) -> Statement {
+        Statement { kind, span: Span::default() }
+    }
 
     #[test]
     fn var_declaration() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::Type(Type::Int(IntType { signed: true, width: 64 })),
             Token::Identifier("x".to_string()),
             Token::AssignmentSymbol(Eq),
             Token::IntLiteral("5".to_string()),
-        ];
-        let expected = Some(Statement::VarDeclaration(VarDeclaration {
+        ]);
+        let expected = Ok(statement(StatementKind::VarDeclaration(VarDeclaration {
             var_name: "x".to_string(),
             var_type: Type::Int(IntType { signed: true, width: 64 }),
-            var_value: Expr::IntLiteral("5".to_string()),
-        }));
+            var_value: expr(ExprKind::IntLiteral("5".to_string())),
+        })));
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_statement();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn let_declaration() {
+        // let mut total = x
+        let tokens = spanned(vec![
+            Token::Let,
+            Token::Mut,
+            Token::Identifier("total".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::Identifier("x".to_string()),
+        ]);
+        let expected = Ok(statement(StatementKind::Let(Let {
+            var_name: "total".to_string(),
+            is_mutable: true,
+            var_value: expr(ExprKind::Identifier("x".to_string())),
+        })));
+
+        let mut parser = Parser::new(&tokens);
         let ast = parser.parse_statement();
 
         assert_eq!(expected, ast);
@@ -653,17 +1697,18 @@ mod tests {
 
     #[test]
     fn var_modification() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::Identifier("num".to_string()),
             Token::AssignmentSymbol(Eq),
             Token::IntLiteral("10".to_string()),
-        ];
-        let expected = Some(Statement::Assignment(Assignment {
+        ]);
+        let expected = Ok(statement(StatementKind::Assignment(Assignment {
             name: "num".to_string(),
-            value: Box::new(Expr::IntLiteral("10".to_string())),
-        }));
+            accessors: vec![],
+            value: Box::new(expr(ExprKind::IntLiteral("10".to_string()))),
+        })));
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
+        let mut parser = Parser::new(&tokens);
         let ast = parser.parse_statement();
 
         assert_eq!(expected, ast);
@@ -671,32 +1716,464 @@ mod tests {
 
     #[test]
     fn empty_while_loop() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::While,
             Token::Identifier("i".to_string()),
             Token::ComparatorSymbol(LessOrEqualTo),
             Token::Identifier("N".to_string()),
             Token::LSquirly,
             Token::RSquirly,
-        ];
-        let expected = Some(Statement::WhileLoop(WhileLoop {
-            condition: Expr::Comparison(Comparison {
-                left: Box::new(Expr::Identifier("i".to_string())),
+        ]);
+        let expected = Ok(statement(StatementKind::WhileLoop(WhileLoop {
+            label: None,
+            condition: expr(ExprKind::Comparison(Comparison {
+                left: Box::new(expr(ExprKind::Identifier("i".to_string()))),
                 operator: ComparisonOperator::LessOrEqualTo,
-                right: Box::new(Expr::Identifier("N".to_string())),
-            }),
+                right: Box::new(expr(ExprKind::Identifier("N".to_string()))),
+            })),
+            body: vec![],
+        })));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_statement();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn labeled_loop() {
+        let tokens = spanned(vec![
+            Token::Label("outer".to_string()),
+            Token::Colon,
+            Token::Loop,
+            Token::LSquirly,
+            Token::Break,
+            Token::Label("outer".to_string()),
+            Token::Newline,
+            Token::Continue,
+            Token::Newline,
+            Token::RSquirly,
+        ]);
+        let expected = Ok(statement(StatementKind::Loop(Loop {
+            label: Some("outer".to_string()),
+            body: vec![
+                statement(StatementKind::Break(Some("outer".to_string()))),
+                statement(StatementKind::Continue(None)),
+            ],
+        })));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_statement();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn for_loop() {
+        let tokens = spanned(vec![
+            Token::For,
+            Token::Type(Type::Int(IntType { signed: false, width: 8 })),
+            Token::Identifier("i".to_string()),
+            Token::In,
+            Token::IntLiteral("1".to_string()),
+            Token::DotDotEq,
+            Token::Identifier("n".to_string()),
+            Token::Step,
+            Token::IntLiteral("2".to_string()),
+            Token::LSquirly,
+            Token::RSquirly,
+        ]);
+        let expected = Ok(statement(StatementKind::ForLoop(Box::new(ForLoop {
+            label: None,
+            var_type: Type::Int(IntType { signed: false, width: 8 }),
+            var_name: "i".to_string(),
+            start: expr(ExprKind::IntLiteral("1".to_string())),
+            end: expr(ExprKind::Identifier("n".to_string())),
+            inclusive: true,
+            step: Some(expr(ExprKind::IntLiteral("2".to_string()))),
             body: vec![],
+        }))));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_statement();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn field_assignment_with_struct_literal() {
+        // p.start.x = Point { x: 1, y: 2 }.y
+        let tokens = spanned(vec![
+            Token::Identifier("p".to_string()),
+            Token::Dot,
+            Token::Identifier("start".to_string()),
+            Token::Dot,
+            Token::Identifier("x".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::Identifier("Point".to_string()),
+            Token::LSquirly,
+            Token::Identifier("x".to_string()),
+            Token::Colon,
+            Token::IntLiteral("1".to_string()),
+            Token::Comma,
+            Token::Identifier("y".to_string()),
+            Token::Colon,
+            Token::IntLiteral("2".to_string()),
+            Token::RSquirly,
+            Token::Dot,
+            Token::Identifier("y".to_string()),
+        ]);
+        let literal = expr(ExprKind::StructLiteral(StructLiteral {
+            struct_name: "Point".to_string(),
+            fields: vec![
+                FieldValue { field_name: "x".to_string(), value: expr(ExprKind::IntLiteral("1".to_string())) },
+                FieldValue { field_name: "y".to_string(), value: expr(ExprKind::IntLiteral("2".to_string())) },
+            ],
         }));
+        let expected = Ok(statement(StatementKind::Assignment(Assignment {
+            name: "p".to_string(),
+            accessors: vec![Accessor::Field("start".to_string()), Accessor::Field("x".to_string())],
+            value: Box::new(expr(ExprKind::FieldAccess(FieldAccess {
+                expr: Box::new(literal),
+                field_name: "y".to_string(),
+            }))),
+        })));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_statement();
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn no_struct_literals_in_conditions() {
+        // if p {} (the braces are the body, not a struct literal)
+        let tokens = spanned(vec![
+            Token::If,
+            Token::Identifier("p".to_string()),
+            Token::LSquirly,
+            Token::RSquirly,
+        ]);
+        let expected = Ok(statement(StatementKind::If(If {
+            condition: expr(ExprKind::Identifier("p".to_string())),
+            then_body: vec![],
+            else_body: None,
+        })));
+
+        let mut parser = Parser::new(&tokens);
         let ast = parser.parse_statement();
 
         assert_eq!(expected, ast);
     }
 
+    /// Creates a pattern with a placeholder span.
+    fn pattern(kind: PatternKind) -> Pattern {
+        Pattern { kind, span: Span::default() }
+    }
+
+    #[test]
+    fn enum_definition() {
+        // enum Shape { Circle(u32), Empty }
+        let u32_type = Type::Int(IntType { signed: false, width: 32 });
+        let tokens = spanned(vec![
+            Token::Enum,
+            Token::Identifier("Shape".to_string()),
+            Token::LSquirly,
+            Token::Identifier("Circle".to_string()),
+            Token::LParen,
+            Token::Type(u32_type.clone()),
+            Token::RParen,
+            Token::Comma,
+            Token::Newline,
+            Token::Identifier("Empty".to_string()),
+            Token::RSquirly,
+        ]);
+        let expected = Ok(Some(GlobalStatement::Enum(EnumDef {
+            name: "Shape".to_string(),
+            variants: vec![
+                EnumVariant { name: "Circle".to_string(), payload_types: vec![u32_type] },
+                EnumVariant { name: "Empty".to_string(), payload_types: vec![] },
+            ],
+            span: Span::default(),
+        })));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_global_statement();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn global_vars() {
+        // const u32 MAX = 100
+        // pub static mut u64 counter = MAX
+        let u32_type = Type::Int(IntType { signed: false, width: 32 });
+        let u64_type = Type::Int(IntType { signed: false, width: 64 });
+        let tokens = spanned(vec![
+            Token::Const,
+            Token::Type(u32_type.clone()),
+            Token::Identifier("MAX".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::IntLiteral("100".to_string()),
+            Token::Newline,
+            Token::Pub,
+            Token::Static,
+            Token::Mut,
+            Token::Type(u64_type.clone()),
+            Token::Identifier("counter".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::Identifier("MAX".to_string()),
+        ]);
+        let expected = Program {
+            global_statements: vec![
+                GlobalStatement::GlobalVar(GlobalVar {
+                    kind: GlobalVarKind::Const,
+                    var_type: u32_type,
+                    var_name: "MAX".to_string(),
+                    var_value: expr(ExprKind::IntLiteral("100".to_string())),
+                    span: Span::default(),
+                }),
+                GlobalStatement::GlobalVar(GlobalVar {
+                    kind: GlobalVarKind::StaticMut { is_public: true },
+                    var_type: u64_type,
+                    var_name: "counter".to_string(),
+                    var_value: expr(ExprKind::Identifier("MAX".to_string())),
+                    span: Span::default(),
+                }),
+            ],
+        };
+
+        let (ast, errors) = Parser::parse_program(&tokens);
+        assert_eq!(errors, vec![]);
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn match_expression() {
+        // match s { Shape::Rect(w, _) => w, -1..=3 => Shape::Empty, _ => 0 }
+        let tokens = spanned(vec![
+            Token::Match,
+            Token::Identifier("s".to_string()),
+            Token::LSquirly,
+            Token::Identifier("Shape".to_string()),
+            Token::ColonColon,
+            Token::Identifier("Rect".to_string()),
+            Token::LParen,
+            Token::Identifier("w".to_string()),
+            Token::Comma,
+            Token::Identifier("_".to_string()),
+            Token::RParen,
+            Token::FatArrow,
+            Token::Identifier("w".to_string()),
+            Token::Comma,
+            Token::OperatorSymbol(Minus),
+            Token::IntLiteral("1".to_string()),
+            Token::DotDotEq,
+            Token::IntLiteral("3".to_string()),
+            Token::FatArrow,
+            Token::Identifier("Shape".to_string()),
+            Token::ColonColon,
+            Token::Identifier("Empty".to_string()),
+            Token::Newline,
+            Token::Identifier("_".to_string()),
+            Token::FatArrow,
+            Token::IntLiteral("0".to_string()),
+            Token::RSquirly,
+        ]);
+        let expected = Ok(expr(ExprKind::Match(Match {
+            scrutinee: Box::new(expr(ExprKind::Identifier("s".to_string()))),
+            arms: vec![
+                MatchArm {
+                    pattern: pattern(PatternKind::EnumVariant {
+                        enum_name: "Shape".to_string(),
+                        variant_name: "Rect".to_string(),
+                        bindings: vec![Some("w".to_string()), None],
+                    }),
+                    body: expr(ExprKind::Identifier("w".to_string())),
+                },
+                MatchArm {
+                    pattern: pattern(PatternKind::Range {
+                        start: "-1".to_string(),
+                        end: "3".to_string(),
+                        inclusive: true,
+                    }),
+                    body: expr(ExprKind::EnumLiteral(EnumLiteral {
+                        enum_name: "Shape".to_string(),
+                        variant_name: "Empty".to_string(),
+                        payload: vec![],
+                    })),
+                },
+                MatchArm {
+                    pattern: pattern(PatternKind::Wildcard),
+                    body: expr(ExprKind::IntLiteral("0".to_string())),
+                },
+            ],
+        })));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_expr();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn array_declaration() {
+        // [[u8; 2]; 2] grid = [[0; 2], [1, 2]]
+        let u8_type = Type::Int(IntType { signed: false, width: 8 });
+        let int = |n: &str| expr(ExprKind::IntLiteral(n.to_string()));
+        let tokens = spanned(vec![
+            Token::LBracket,
+            Token::LBracket,
+            Token::Type(u8_type.clone()),
+            Token::Semicolon,
+            Token::IntLiteral("2".to_string()),
+            Token::RBracket,
+            Token::Semicolon,
+            Token::IntLiteral("2".to_string()),
+            Token::RBracket,
+            Token::Identifier("grid".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::LBracket,
+            Token::LBracket,
+            Token::IntLiteral("0".to_string()),
+            Token::Semicolon,
+            Token::IntLiteral("2".to_string()),
+            Token::RBracket,
+            Token::Comma,
+            Token::LBracket,
+            Token::IntLiteral("1".to_string()),
+            Token::Comma,
+            Token::IntLiteral("2".to_string()),
+            Token::RBracket,
+            Token::RBracket,
+        ]);
+        let expected = Ok(statement(StatementKind::VarDeclaration(VarDeclaration {
+            var_name: "grid".to_string(),
+            var_type: Type::Array(Box::new(Type::Array(Box::new(u8_type), 2)), 2),
+            var_value: expr(ExprKind::ArrayLiteral(vec![
+                expr(ExprKind::ArrayRepeat(ArrayRepeat { value: Box::new(int("0")), length: 2 })),
+                expr(ExprKind::ArrayLiteral(vec![int("1"), int("2")])),
+            ])),
+        })));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_statement();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn str_declaration() {
+        // str s = "hi\n"
+        let tokens = spanned(vec![
+            Token::Type(Type::Str),
+            Token::Identifier("s".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::StrLiteral("hi\n".to_string()),
+        ]);
+        let expected = Ok(statement(StatementKind::VarDeclaration(VarDeclaration {
+            var_name: "s".to_string(),
+            var_type: Type::Str,
+            var_value: expr(ExprKind::StrLiteral("hi\n".to_string())),
+        })));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_statement();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn float_declaration() {
+        // f32 x = -1.5 * 2e3
+        let f32_type = Type::Float(FloatType { width: 32 });
+        let tokens = spanned(vec![
+            Token::Type(f32_type.clone()),
+            Token::Identifier("x".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::OperatorSymbol(Minus),
+            Token::FloatLiteral("1.5".to_string()),
+            Token::OperatorSymbol(Asterisk),
+            Token::FloatLiteral("2e3".to_string()),
+        ]);
+        let expected = Ok(statement(StatementKind::VarDeclaration(VarDeclaration {
+            var_name: "x".to_string(),
+            var_type: f32_type,
+            var_value: expr(ExprKind::Binary(Binary {
+                left: Box::new(expr(ExprKind::Unary(Unary {
+                    operator: UnaryOperator::Negate,
+                    operand: Box::new(expr(ExprKind::FloatLiteral("1.5".to_string()))),
+                }))),
+                operator: BinaryOperator::Multiply,
+                right: Box::new(expr(ExprKind::FloatLiteral("2e3".to_string()))),
+            })),
+        })));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_statement();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn index_assignment() {
+        // grid[i].x = row[0]
+        let identifier = |name: &str| expr(ExprKind::Identifier(name.to_string()));
+        let tokens = spanned(vec![
+            Token::Identifier("grid".to_string()),
+            Token::LBracket,
+            Token::Identifier("i".to_string()),
+            Token::RBracket,
+            Token::Dot,
+            Token::Identifier("x".to_string()),
+            Token::AssignmentSymbol(Eq),
+            Token::Identifier("row".to_string()),
+            Token::LBracket,
+            Token::IntLiteral("0".to_string()),
+            Token::RBracket,
+        ]);
+        let expected = Ok(statement(StatementKind::Assignment(Assignment {
+            name: "grid".to_string(),
+            accessors: vec![Accessor::Index(identifier("i")), Accessor::Field("x".to_string())],
+            value: Box::new(expr(ExprKind::Index(Index {
+                expr: Box::new(identifier("row")),
+                index: Box::new(expr(ExprKind::IntLiteral("0".to_string()))),
+            }))),
+        })));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_statement();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        // match b { x => {} }
+        let tokens = spanned(vec![
+            Token::Match,
+            Token::Identifier("b".to_string()),
+            Token::LSquirly,
+            Token::Identifier("x".to_string()),
+            Token::FatArrow,
+            Token::LSquirly,
+            Token::RSquirly,
+            Token::RSquirly,
+        ]);
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_statement();
+
+        assert!(matches!(
+            ast,
+            Err(FlickError { kind: ErrorKind::ParsingError(ParsingError::Expected { expected: Expected::Pattern, .. }), .. })
+        ));
+    }
+
     #[test]
     fn order_of_operations() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::IntLiteral("10".to_string()),
             Token::OperatorSymbol(Plus),
             Token::IntLiteral("3".to_string()),
@@ -708,38 +2185,119 @@ mod tests {
             Token::IntLiteral("13".to_string()),
             Token::OperatorSymbol(Plus),
             Token::IntLiteral("5".to_string()),
-        ];
-        let expected = Expr::Binary(Binary {
-            left: Box::new(Expr::Binary(Binary {
-                left: Box::new(Expr::Binary(Binary {
-                    left: Box::new(Expr::IntLiteral("10".to_string())),
+        ]);
+        let expected = expr(ExprKind::Binary(Binary {
+            left: Box::new(expr(ExprKind::Binary(Binary {
+                left: Box::new(expr(ExprKind::Binary(Binary {
+                    left: Box::new(expr(ExprKind::IntLiteral("10".to_string()))),
                     operator: BinaryOperator::Add,
-                    right: Box::new(Expr::Binary(Binary {
-                        left: Box::new(Expr::Binary(Binary {
-                            left: Box::new(Expr::IntLiteral("3".to_string())),
+                    right: Box::new(expr(ExprKind::Binary(Binary {
+                        left: Box::new(expr(ExprKind::Binary(Binary {
+                            left: Box::new(expr(ExprKind::IntLiteral("3".to_string()))),
                             operator: BinaryOperator::Multiply,
-                            right: Box::new(Expr::IntLiteral("8".to_string())),
-                        })),
+                            right: Box::new(expr(ExprKind::IntLiteral("8".to_string()))),
+                        }))),
                         operator: BinaryOperator::Divide,
-                        right: Box::new(Expr::IntLiteral("4".to_string())),
-                    })),
-                })),
+                        right: Box::new(expr(ExprKind::IntLiteral("4".to_string()))),
+                    }))),
+                }))),
                 operator: BinaryOperator::Subtract,
-                right: Box::new(Expr::IntLiteral("13".to_string())),
-            })),
+                right: Box::new(expr(ExprKind::IntLiteral("13".to_string()))),
+            }))),
             operator: BinaryOperator::Add,
-            right: Box::new(Expr::IntLiteral("5".to_string())),
-        });
+            right: Box::new(expr(ExprKind::IntLiteral("5".to_string()))),
+        }));
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
-        let ast = parser.parse_expr();
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_expr().unwrap();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn bitwise_precedence() {
+        // a | b ^ c & d << e < f
+        let tokens = spanned(vec![
+            Token::Identifier("a".to_string()),
+            Token::OperatorSymbol(Pipe),
+            Token::Identifier("b".to_string()),
+            Token::OperatorSymbol(Caret),
+            Token::Identifier("c".to_string()),
+            Token::OperatorSymbol(Ampersand),
+            Token::Identifier("d".to_string()),
+            Token::OperatorSymbol(ShiftLeft),
+            Token::Identifier("e".to_string()),
+            Token::ComparatorSymbol(ComparatorSymbol::LessThan),
+            Token::Identifier("f".to_string()),
+        ]);
+        let identifier = |name: &str| Box::new(expr(ExprKind::Identifier(name.to_string())));
+        let binary = |left, operator, right| {
+            Box::new(expr(ExprKind::Binary(Binary { left, operator, right })))
+        };
+        let expected = expr(ExprKind::Comparison(Comparison {
+            left: binary(
+                identifier("a"),
+                BinaryOperator::BitwiseOr,
+                binary(
+                    identifier("b"),
+                    BinaryOperator::BitwiseXor,
+                    binary(
+                        identifier("c"),
+                        BinaryOperator::BitwiseAnd,
+                        binary(identifier("d"), BinaryOperator::ShiftLeft, identifier("e")),
+                    ),
+                ),
+            ),
+            operator: ComparisonOperator::LessThan,
+            right: identifier("f"),
+        }));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_expr().unwrap();
+
+        assert_eq!(expected, ast);
+    }
+
+    #[test]
+    fn logical_operators() {
+        // not a == b and c or d
+        let tokens = spanned(vec![
+            Token::Not,
+            Token::Identifier("a".to_string()),
+            Token::ComparatorSymbol(ComparatorSymbol::EqualTo),
+            Token::Identifier("b".to_string()),
+            Token::And,
+            Token::Identifier("c".to_string()),
+            Token::Or,
+            Token::Identifier("d".to_string()),
+        ]);
+        let identifier = |name: &str| Box::new(expr(ExprKind::Identifier(name.to_string())));
+        let expected = expr(ExprKind::Logical(Logical {
+            left: Box::new(expr(ExprKind::Logical(Logical {
+                left: Box::new(expr(ExprKind::Unary(Unary {
+                    operator: UnaryOperator::Not,
+                    operand: Box::new(expr(ExprKind::Comparison(Comparison {
+                        left: identifier("a"),
+                        operator: ComparisonOperator::EqualTo,
+                        right: identifier("b"),
+                    }))),
+                }))),
+                operator: LogicalOperator::And,
+                right: identifier("c"),
+            }))),
+            operator: LogicalOperator::Or,
+            right: identifier("d"),
+        }));
+
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_expr().unwrap();
 
         assert_eq!(expected, ast);
     }
 
     #[test]
     fn parenthetical_expression() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::IntLiteral("9".to_string()),
             Token::OperatorSymbol(Asterisk),
             Token::LParen,
@@ -747,26 +2305,26 @@ mod tests {
             Token::OperatorSymbol(Plus),
             Token::IntLiteral("3".to_string()),
             Token::RParen,
-        ];
-        let expected = Expr::Binary(Binary {
-            left: Box::new(Expr::IntLiteral("9".to_string())),
+        ]);
+        let expected = expr(ExprKind::Binary(Binary {
+            left: Box::new(expr(ExprKind::IntLiteral("9".to_string()))),
             operator: BinaryOperator::Multiply,
-            right: Box::new(Expr::Binary(Binary {
-                left: Box::new(Expr::IntLiteral("2".to_string())),
+            right: Box::new(expr(ExprKind::Binary(Binary {
+                left: Box::new(expr(ExprKind::IntLiteral("2".to_string()))),
                 operator: BinaryOperator::Add,
-                right: Box::new(Expr::IntLiteral("3".to_string())),
-            })),
-        });
+                right: Box::new(expr(ExprKind::IntLiteral("3".to_string()))),
+            }))),
+        }));
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
-        let ast = parser.parse_expr();
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_expr().unwrap();
 
         assert_eq!(expected, ast);
     }
 
     #[test]
     fn spacing() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::LSquirly,
             Token::Newline,
             Token::Newline,
@@ -777,21 +2335,22 @@ mod tests {
             Token::Newline,
             Token::Newline,
             Token::RSquirly,
-        ];
-        let expected = vec![Statement::Assignment(Assignment {
+        ]);
+        let expected = vec![statement(StatementKind::Assignment(Assignment {
             name: "a".to_string(),
-            value: Box::new(Expr::IntLiteral("2".to_string())),
-        })];
+            accessors: vec![],
+            value: Box::new(expr(ExprKind::IntLiteral("2".to_string()))),
+        }))];
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
-        let ast = parser.parse_body();
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_body().unwrap();
 
         assert_eq!(expected, ast);
     }
 
     #[test]
     fn function_call() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::Identifier("print".to_string()),
             Token::LParen,
             Token::Identifier("f".to_string()),
@@ -803,28 +2362,28 @@ mod tests {
             Token::Comma,
             Token::IntLiteral("20".to_string()),
             Token::RParen,
-        ];
-        let expected = Expr::Call(Call {
+        ]);
+        let expected = expr(ExprKind::Call(Call {
             function_name: "print".to_string(),
             args: vec![
-                Expr::Call(Call {
+                expr(ExprKind::Call(Call {
                     function_name: "f".to_string(),
-                    args: vec![Expr::IntLiteral("1".to_string())],
-                }),
-                Expr::IntLiteral("10".to_string()),
-                Expr::IntLiteral("20".to_string()),
+                    args: vec![expr(ExprKind::IntLiteral("1".to_string()))],
+                })),
+                expr(ExprKind::IntLiteral("10".to_string())),
+                expr(ExprKind::IntLiteral("20".to_string())),
             ],
-        });
+        }));
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
-        let ast = parser.parse_expr();
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_expr().unwrap();
 
         assert_eq!(expected, ast);
     }
 
     #[test]
     fn function_definition() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::Pub,
             Token::Fn,
             Token::Identifier("test".to_string()),
@@ -835,7 +2394,7 @@ mod tests {
             Token::Type(Type::Int(IntType { signed: true, width: 64 })),
             Token::LSquirly,
             Token::RSquirly,
-        ];
+        ]);
         let expected = Program {
             global_statements: vec![GlobalStatement::FuncDef(FuncDef {
                 proto: FuncProto {
@@ -846,19 +2405,21 @@ mod tests {
                         param_name: "a".to_string(),
                     }],
                     return_type: Box::new(Type::Int(IntType { signed: true, width: 64 })),
+                    span: Span::default(),
                 },
                 body: vec![],
             })],
         };
 
-        let ast = Parser::parse_program(&tokens);
+        let (ast, errors) = Parser::parse_program(&tokens);
+        assert_eq!(errors, vec![]);
 
         assert_eq!(expected, ast);
     }
 
     #[test]
     fn if_statement() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::If,
             Token::Identifier("x".to_string()),
             Token::ComparatorSymbol(LessOrEqualTo),
@@ -881,28 +2442,28 @@ mod tests {
             Token::Ret,
             Token::Newline,
             Token::RSquirly,
-        ];
-        let expected = Some(Statement::If(If { 
-            condition: Expr::Comparison(Comparison { 
-                left: Box::new(Expr::Identifier("x".to_string())), 
+        ]);
+        let expected = Ok(statement(StatementKind::If(If { 
+            condition: expr(ExprKind::Comparison(Comparison { 
+                left: Box::new(expr(ExprKind::Identifier("x".to_string()))), 
                 operator: ComparisonOperator::LessOrEqualTo, 
-                right: Box::new(Expr::IntLiteral("5".to_string())) ,
-            }), 
-            then_body: vec![Statement::Return(None)], 
+                right: Box::new(expr(ExprKind::IntLiteral("5".to_string()))) ,
+            })), 
+            then_body: vec![statement(StatementKind::Return(None))], 
             else_body: Some(vec![
-                Statement::If(If { 
-                    condition: Expr::Comparison(Comparison { 
-                        left: Box::new(Expr::Identifier("x".to_string())), 
+                statement(StatementKind::If(If { 
+                    condition: expr(ExprKind::Comparison(Comparison { 
+                        left: Box::new(expr(ExprKind::Identifier("x".to_string()))), 
                         operator: ComparisonOperator::LessOrEqualTo, 
-                        right: Box::new(Expr::IntLiteral("10".to_string())) ,
-                    }),
-                    then_body: vec![Statement::Return(None)],
-                    else_body: Some(vec![Statement::Return(None)]),
-                })
+                        right: Box::new(expr(ExprKind::IntLiteral("10".to_string()))) ,
+                    })),
+                    then_body: vec![statement(StatementKind::Return(None))],
+                    else_body: Some(vec![statement(StatementKind::Return(None))]),
+                }))
             ]),
-        }));
+        })));
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
+        let mut parser = Parser::new(&tokens);
         let ast = parser.parse_statement();
 
         assert_eq!(expected, ast);
@@ -910,19 +2471,19 @@ mod tests {
 
     #[test]
     fn return_statement() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::Ret,
             Token::Identifier("x".to_string()),
             Token::OperatorSymbol(Plus),
             Token::IntLiteral("5".to_string()),
-        ];
-        let expected = Some(Statement::Return(Some(Expr::Binary(Binary {
-            left: Box::new(Expr::Identifier("x".to_string())),
+        ]);
+        let expected = Ok(statement(StatementKind::Return(Some(expr(ExprKind::Binary(Binary {
+            left: Box::new(expr(ExprKind::Identifier("x".to_string()))),
             operator: BinaryOperator::Add,
-            right: Box::new(Expr::IntLiteral("5".to_string())),
-        }))));
+            right: Box::new(expr(ExprKind::IntLiteral("5".to_string()))),
+        }))))));
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
+        let mut parser = Parser::new(&tokens);
         let ast = parser.parse_statement();
 
         assert_eq!(expected, ast);
@@ -930,21 +2491,22 @@ mod tests {
 
     #[test]
     fn plus_eq() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::Identifier("x".to_string()),
             Token::AssignmentSymbol(PlusEq),
             Token::IntLiteral("5".to_string()),
-        ];
-        let expected = Some(Statement::Assignment(Assignment {
+        ]);
+        let expected = Ok(statement(StatementKind::Assignment(Assignment {
             name: "x".to_string(),
-            value: Box::new(Expr::Binary(Binary {
-                left: Box::new(Expr::Identifier("x".to_string())),
+            accessors: vec![],
+            value: Box::new(expr(ExprKind::Binary(Binary {
+                left: Box::new(expr(ExprKind::Identifier("x".to_string()))),
                 operator: BinaryOperator::Add,
-                right: Box::new(Expr::IntLiteral("5".to_string())),
-            })),
-        }));
+                right: Box::new(expr(ExprKind::IntLiteral("5".to_string()))),
+            }))),
+        })));
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
+        let mut parser = Parser::new(&tokens);
         let ast = parser.parse_statement();
 
         assert_eq!(expected, ast);
@@ -953,7 +2515,7 @@ mod tests {
     #[test]
     fn arithmetic() {
         // x=(a+3)/4*5%3*(-2)-2
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::Identifier("x".to_string()),
             Token::AssignmentSymbol(Eq),
             Token::LParen,
@@ -974,41 +2536,42 @@ mod tests {
             Token::RParen,
             Token::OperatorSymbol(Minus),
             Token::IntLiteral("2".to_string()),
-        ];
+        ]);
 
-        let expected = Some(Statement::Assignment(Assignment {
+        let expected = Ok(statement(StatementKind::Assignment(Assignment {
             name: "x".to_string(),
-            value: Box::new(Expr::Binary(Binary { 
-                left: Box::new(Expr::Binary(Binary { 
-                    left: Box::new(Expr::Binary(Binary { 
-                        left: Box::new(Expr::Binary(Binary { 
-                            left: Box::new(Expr::Binary(Binary { 
-                                left: Box::new(Expr::Binary(Binary { 
-                                    left: Box::new(Expr::Identifier("a".to_string())), 
+            accessors: vec![],
+            value: Box::new(expr(ExprKind::Binary(Binary { 
+                left: Box::new(expr(ExprKind::Binary(Binary { 
+                    left: Box::new(expr(ExprKind::Binary(Binary { 
+                        left: Box::new(expr(ExprKind::Binary(Binary { 
+                            left: Box::new(expr(ExprKind::Binary(Binary { 
+                                left: Box::new(expr(ExprKind::Binary(Binary { 
+                                    left: Box::new(expr(ExprKind::Identifier("a".to_string()))), 
                                     operator: BinaryOperator::Add, 
-                                    right: Box::new(Expr::IntLiteral("3".to_string())) 
-                                })), 
+                                    right: Box::new(expr(ExprKind::IntLiteral("3".to_string()))) 
+                                }))), 
                                 operator: BinaryOperator::Divide, 
-                                right: Box::new(Expr::IntLiteral("4".to_string())) 
-                            })), 
+                                right: Box::new(expr(ExprKind::IntLiteral("4".to_string()))) 
+                            }))), 
                             operator: BinaryOperator::Multiply, 
-                            right: Box::new(Expr::IntLiteral("5".to_string())) 
-                        })), 
+                            right: Box::new(expr(ExprKind::IntLiteral("5".to_string()))) 
+                        }))), 
                         operator: BinaryOperator::Remainder, 
-                        right: Box::new(Expr::IntLiteral("3".to_string())) 
-                    })), 
+                        right: Box::new(expr(ExprKind::IntLiteral("3".to_string()))) 
+                    }))), 
                     operator: BinaryOperator::Multiply, 
-                    right: Box::new(Expr::Unary(Unary {
+                    right: Box::new(expr(ExprKind::Unary(Unary {
                         operator: UnaryOperator::Negate, 
-                        operand: Box::new(Expr::IntLiteral("2".to_string()))
-                    }))
-                })), 
+                        operand: Box::new(expr(ExprKind::IntLiteral("2".to_string())))
+                    })))
+                }))), 
                 operator: BinaryOperator::Subtract, 
-                right: Box::new(Expr::IntLiteral("2".to_string()))
-            }))
-        }));
+                right: Box::new(expr(ExprKind::IntLiteral("2".to_string())))
+            })))
+        })));
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
+        let mut parser = Parser::new(&tokens);
         let ast = parser.parse_statement();
 
         assert_eq!(expected, ast);
@@ -1016,7 +2579,7 @@ mod tests {
 
     #[test]
     fn unary_cast_of_call() {
-        let tokens = vec![
+        let tokens = spanned(vec![
             Token::LParen,
             Token::Type(Type::Int(IntType { width: 64, signed: true })),
             Token::RParen,
@@ -1024,19 +2587,206 @@ mod tests {
             Token::LParen,
             Token::IntLiteral("1".to_string()),
             Token::RParen,
-        ];
+        ]);
 
-        let expected = Expr::Unary(Unary {
+        let expected = expr(ExprKind::Unary(Unary {
             operator: UnaryOperator::Cast(Type::Int(IntType { width: 64, signed: true })),
-            operand: Box::new(Expr::Call(Call {
+            operand: Box::new(expr(ExprKind::Call(Call {
                 function_name: "foo".to_string(),
-                args: vec![Expr::IntLiteral("1".to_string())],
-            })),
-        });
+                args: vec![expr(ExprKind::IntLiteral("1".to_string()))],
+            }))),
+        }));
 
-        let mut parser = Parser { tokens: &tokens, cursor: 0 };
-        let ast = parser.parse_expr();
+        let mut parser = Parser::new(&tokens);
+        let ast = parser.parse_expr().unwrap();
 
         assert_eq!(expected, ast);
     }
+
+    #[test]
+    fn spans() {
+        let source_code: Vec<_> = "x += (a + 3) * b\n".chars().collect();
+        let (tokens, _) = crate::Lexer::lex(&source_code, 0);
+
+        let mut parser = Parser::new(&tokens);
+        let statement = parser.parse_statement().unwrap();
+        let columns = |span: Span| (span.start.column, span.end.column);
+
+        // The statement's span shouldn't include the newline that ends it
+        assert_eq!(columns(statement.span), (1, 17));
+
+        let StatementKind::Assignment(assignment) = statement.kind else { panic!() };
+        let ExprKind::Binary(plus_eq) = assignment.value.kind else { panic!() };
+        assert_eq!(columns(plus_eq.left.span), (1, 2));  // x
+        assert_eq!(columns(plus_eq.right.span), (6, 17));  // (a + 3) * b
+
+        let ExprKind::Binary(times) = plus_eq.right.kind else { panic!() };
+        assert_eq!(columns(times.left.span), (6, 13));  // (a + 3)
+        assert_eq!(columns(times.right.span), (16, 17));  // b
+    }
+
+    #[test]
+    fn pointers() {
+        let parse_statement = |source_code: &str| {
+            let source_code: Vec<_> = source_code.chars().collect();
+            let (tokens, _) = crate::Lexer::lex(&source_code, 0);
+            Parser::new(&tokens).parse_statement().unwrap().kind
+        };
+        let node_pointer = Type::Pointer(Box::new(Type::Named("Node".to_string())));
+
+        // A statement starting with `*` either declares a pointer or assigns through one
+        let StatementKind::VarDeclaration(declaration) = parse_statement("*Node n = (*Node) p") else { panic!() };
+        assert_eq!(declaration.var_type, node_pointer);
+        let ExprKind::Unary(cast) = declaration.var_value.kind else { panic!() };
+        assert_eq!(cast.operator, UnaryOperator::Cast(node_pointer));
+
+        let StatementKind::VarDeclaration(declaration) = parse_statement("**u8 pp = &*p") else { panic!() };
+        let u8_pointer = Type::Pointer(Box::new(Type::Int(IntType { signed: false, width: 8 })));
+        assert_eq!(declaration.var_type, Type::Pointer(Box::new(u8_pointer)));
+
+        // A dereference applies to everything after it, unless it's in parentheses
+        let StatementKind::Assignment(assignment) = parse_statement("*p.next = q") else { panic!() };
+        assert_eq!(assignment.accessors, vec![Accessor::Field("next".to_string()), Accessor::Deref]);
+        let StatementKind::Assignment(assignment) = parse_statement("(*p).next = q") else { panic!() };
+        assert_eq!(assignment.accessors, vec![Accessor::Deref, Accessor::Field("next".to_string())]);
+        let StatementKind::Assignment(assignment) = parse_statement("**pp += 1") else { panic!() };
+        assert_eq!(assignment.accessors, vec![Accessor::Deref, Accessor::Deref]);
+
+        // `(*n)` followed by an operator is a dereference, not a cast to a pointer to an `n`
+        let StatementKind::VarDeclaration(declaration) = parse_statement("i64 x = (*n) - 1") else { panic!() };
+        let ExprKind::Binary(minus) = declaration.var_value.kind else { panic!() };
+        assert!(matches!(minus.left.kind, ExprKind::Unary(Unary { operator: UnaryOperator::Deref, .. })));
+    }
+
+    /// Lexes and parses `source_code`, returning each [ParsingError] and the (line, column) where
+    /// it occurred.
+    fn parsing_errors(source_code: &str) -> Vec<(ParsingError, (usize, usize))> {
+        let source_code: Vec<_> = source_code.chars().collect();
+        let (tokens, _) = crate::Lexer::lex(&source_code, 0);
+        let (_, errors) = Parser::parse_program(&tokens);
+
+        errors
+            .into_iter()
+            .map(|err| match err.kind {
+                ErrorKind::ParsingError(kind) => (kind, (err.span.start.line, err.span.start.column)),
+                kind => panic!("Expected a parsing error but got {:?}", kind),
+            })
+            .collect()
+    }
+
+    /// Like [parsing_errors], but expects exactly one error.
+    fn parsing_error(source_code: &str) -> (ParsingError, (usize, usize)) {
+        let mut errors = parsing_errors(source_code);
+        assert_eq!(errors.len(), 1, "expected exactly one error, got {:?}", errors);
+        errors.remove(0)
+    }
+
+    #[test]
+    fn chained_comparison() {
+        let (err, location) = parsing_error("pub fn main() bool {\n    ret 1 < 2 < 3\n}");
+        assert_eq!(err, ParsingError::ChainedComparison);
+        assert_eq!(location, (2, 15));
+    }
+
+    #[test]
+    fn expected_token() {
+        let (err, location) = parsing_error("fn foo() {\n    i64 x 5\n}");
+        let expected = ParsingError::Expected {
+            expected: Expected::Token(Token::AssignmentSymbol(Eq)),
+            found: Token::IntLiteral("5".to_string()),
+        };
+        assert_eq!(err, expected);
+        assert_eq!(location, (2, 11));
+    }
+
+    #[test]
+    fn array_length_must_be_a_literal() {
+        let (err, location) = parsing_error("fn foo() {\n    [u8; n] x = y\n}");
+        let expected = ParsingError::Expected {
+            expected: Expected::ArrayLength,
+            found: Token::Identifier("n".to_string()),
+        };
+        assert_eq!(err, expected);
+        assert_eq!(location, (2, 10));
+    }
+
+    #[test]
+    fn unexpected_token() {
+        let (err, location) = parsing_error("fn foo() {\n    else\n}");
+        assert_eq!(err, ParsingError::UnexpectedToken(Token::Else));
+        assert_eq!(location, (2, 5));
+
+        let (err, location) = parsing_error("\n\nret 0");
+        assert_eq!(err, ParsingError::UnexpectedToken(Token::Ret));
+        assert_eq!(location, (3, 1));
+    }
+
+    #[test]
+    fn unterminated_body() {
+        let (err, location) = parsing_error("fn foo() {\n    if x {\n        foo()\n}");
+        assert_eq!(err, ParsingError::UnterminatedBody);
+        // The last '}' closes the if statement, so the error points at the function's '{'
+        assert_eq!(location, (1, 10));
+    }
+
+    #[test]
+    fn unexpected_end_of_file() {
+        let (err, _) = parsing_error("fn foo(i64 x, ");
+        assert_eq!(err, ParsingError::UnexpectedEndOfFile { expected: Expected::Type });
+    }
+
+    #[test]
+    fn recovers_at_statement_boundaries() {
+        let source_code = "fn foo() {\n    i64 x 5\n    while x + {\n        ret\n    }\n    else\n    foo()\n}\n}\nfn bar() {\n    ret 1 < 2 < 3\n}";
+        let errors = parsing_errors(source_code);
+
+        let expected_errors = vec![
+            (
+                ParsingError::Expected {
+                    expected: Expected::Token(Token::AssignmentSymbol(Eq)),
+                    found: Token::IntLiteral("5".to_string()),
+                },
+                (2, 11),
+            ),
+            (ParsingError::Expected { expected: Expected::Expression, found: Token::LSquirly }, (3, 15)),
+            (ParsingError::UnexpectedToken(Token::Else), (6, 5)),
+            (ParsingError::UnexpectedToken(Token::RSquirly), (9, 1)),
+            (ParsingError::ChainedComparison, (11, 15)),
+        ];
+        assert_eq!(errors, expected_errors);
+    }
+
+    #[test]
+    fn recovers_from_errors_inside_multi_line_braces() {
+        // The errors are inside of a match's arms and a struct literal, whose closing '}'s must
+        // be skipped along with the rest of the statement
+        let source_code = "fn foo() u8 {\n    u8 x = match y {\n        1 => 2 +,\n        _ => 0,\n    }\n    Point p = Point {\n        x: 1 <,\n        y: 2,\n    }\n    ret 1 < 2 < 3\n}\nfn bar() {\n    ret 1 < 2 < 3\n}";
+        let errors = parsing_errors(source_code);
+
+        let expected_errors = vec![
+            (ParsingError::Expected { expected: Expected::Expression, found: Token::Comma }, (3, 17)),
+            (ParsingError::Expected { expected: Expected::Expression, found: Token::Comma }, (7, 15)),
+            (ParsingError::ChainedComparison, (10, 15)),
+            (ParsingError::ChainedComparison, (13, 15)),
+        ];
+        assert_eq!(errors, expected_errors);
+    }
+
+    #[test]
+    fn recovered_programs_can_be_typed() {
+        let source_code = "pub fn main() u8 {\n    i64 x 5\n    u8 y = true\n    ret 0\n}";
+        let source_code: Vec<_> = source_code.chars().collect();
+        let (tokens, _) = crate::Lexer::lex(&source_code, 0);
+        let (program, errors) = Parser::parse_program(&tokens);
+        assert_eq!(errors.len(), 1);
+
+        // The broken declaration is left out, but the rest of the body is kept
+        let [GlobalStatement::FuncDef(main)] = program.global_statements.as_slice() else { panic!() };
+        assert_eq!(main.body.len(), 2);
+        assert!(matches!(&main.body[0].kind, StatementKind::VarDeclaration(VarDeclaration { var_name, .. }) if var_name == "y"));
+
+        let typing_errors = crate::Typer::new().type_program(&program).unwrap_err();
+        let typing_errors: Vec<_> = typing_errors.into_iter().map(|err| err.span.start.line).collect();
+        assert_eq!(typing_errors, vec![3]);
+    }
 }
@@ -63,6 +63,24 @@ use std::collections::HashMap;
 ///
 /// assert_eq!(scope_manager.get("x"), Some(&outer_val));
 /// ```
+///
+/// The outermost scope is the global scope, where functions and global variables live. Values
+/// in it can be looked up even while they're shadowed:
+///
+/// ```
+/// use flick::ScopeManager;
+///
+/// let mut scope_manager = ScopeManager::new();
+/// scope_manager.set("foo", "function");
+///
+/// scope_manager.enter_scope();
+/// assert!(scope_manager.is_global("foo"));
+/// scope_manager.set("foo", "variable");
+/// assert!(!scope_manager.is_global("foo"));
+/// assert_eq!(scope_manager.get("foo"), Some(&"variable"));
+/// assert_eq!(scope_manager.get_global("foo"), Some(&"function"));
+/// ```
+#[derive(Clone)]
 pub struct ScopeManager<T> {
     values: Vec<HashMap<String, T>>,
 }
@@ -80,16 +98,17 @@ impl<T> ScopeManager<T> {
         self.values.push(HashMap::new());
     }
 
-    /// Pops the current scope; re-enters the next-innermost scope.
+    /// Pops the current scope (returning the values it contained); re-enters the
+    /// next-innermost scope.
     ///
     /// Note: if this function is called with just one scope on the stack, this function
     /// will panic.
-    pub fn exit_scope(&mut self) {
+    pub fn exit_scope(&mut self) -> HashMap<String, T> {
         if self.values.len() == 1 {
             panic!("cannot exit the global scope")
         }
 
-        self.values.pop();
+        self.values.pop().unwrap()
     }
 
     /// Searches through all scopes (starting with the innermost scope) for a value named `name`.
@@ -97,12 +116,30 @@ impl<T> ScopeManager<T> {
         self.values.iter().rev().find_map(|s| s.get(name.as_ref()))
     }
 
+    /// Like [get()](ScopeManager::get()), but returns a mutable reference.
+    pub fn get_mut(&mut self, name: impl AsRef<str>) -> Option<&mut T> {
+        self.values.iter_mut().rev().find_map(|s| s.get_mut(name.as_ref()))
+    }
+
     /// Sets a value named `name` in the current scope.
     pub fn set(&mut self, name: impl AsRef<str>, value: T) {
         let cur_scope = self.values.last_mut().unwrap();
         // TODO: Remove to_string (by accepting references with lifetimes?)
         cur_scope.insert(name.as_ref().to_string(), value);
     }
+
+    /// Returns the value named `name` in the global (outermost) scope, even if it's shadowed by
+    /// a value in an inner scope.
+    pub fn get_global(&self, name: impl AsRef<str>) -> Option<&T> {
+        self.values[0].get(name.as_ref())
+    }
+
+    /// Returns whether `name` refers to a value in the global scope, i.e. whether there's such a
+    /// value and no value in an inner scope shadows it.
+    pub fn is_global(&self, name: impl AsRef<str>) -> bool {
+        let name = name.as_ref();
+        self.values.iter().rposition(|s| s.contains_key(name)) == Some(0)
+    }
 }
 
 /// As suggested by Clippy's [new_without_default][a], since [ScopeManager::new()] doesn't
@@ -0,0 +1,92 @@
+/// Identifies which source file a [Span] points into.
+pub type FileId = usize;
+
+/// A single location in a source file.
+///
+/// Note: a default-constructed position (line `0`, column `0`) doesn't point anywhere real; it's
+/// used for code that wasn't written by a user (e.g. syntax trees built by hand in tests).
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Position {
+    /// Byte offset from the start of the file
+    pub offset: usize,
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Column number (counted in characters), starting at 1
+    pub column: usize,
+}
+
+impl Position {
+    /// Returns the position of the very first character of a file.
+    pub fn start_of_file() -> Self {
+        Self {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+/// A range of source code, from `start` (inclusive) to `end` (exclusive).
+///
+/// For example, in the source code `i64 x = 5`, the span of the token `x` starts at line 1,
+/// column 5 and ends at line 1, column 6.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Span {
+    pub file_id: FileId,
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Span {
+    pub fn new(file_id: FileId, start: Position, end: Position) -> Self {
+        Self {
+            file_id,
+            start,
+            end,
+        }
+    }
+
+    /// Returns a span that starts where `self` starts and ends where `other` ends.
+    ///
+    /// For example, joining the spans of `1` and `2` in `1 + 2` gives the span of the entire
+    /// binary expression.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            file_id: self.file_id,
+            start: self.start,
+            end: other.end,
+        }
+    }
+
+    /// Returns the empty span located right before the start of `self`.
+    pub fn shrink_to_start(self) -> Span {
+        Span {
+            file_id: self.file_id,
+            start: self.start,
+            end: self.start,
+        }
+    }
+
+    /// Returns the empty span located right after the end of `self`.
+    pub fn shrink_to_end(self) -> Span {
+        Span {
+            file_id: self.file_id,
+            start: self.end,
+            end: self.end,
+        }
+    }
+}
+
+/// A value (like a [Token](crate::token::Token)) along with the span of source code it was
+/// read from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Self {
+        Self { node, span }
+    }
+}
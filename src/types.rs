@@ -1,23 +1,56 @@
 use std::fmt;
 use crate::ast::FuncProto;
 
-/// An enum to store the built-in Flick types, like `void`
+/// An enum to store the Flick types, like `void` or a user-defined struct or enum
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Type {
     /// Variable-size int type, with `width` bits.
     Int(IntType),
+    /// IEEE 754 floating-point type, with `width` bits (32 or 64).
+    Float(FloatType),
+    /// A Unicode scalar value (any code point except the surrogates), stored in 32 bits
+    Char,
     Bool,
     Void,
-    Func(FuncProto),
+    Func(Box<FuncProto>),
+    /// A user-defined type (a struct or an enum), referred to by its name (its definition is
+    /// looked up by name in the typer's type registry)
+    Named(String),
+    /// A fixed-size array, like `[u8; 16]`, whose elements are stored inline
+    Array(Box<Type>, u64),
+    /// A raw pointer to a value of the given type, like `*u8`; a `*void` points to memory of an
+    /// unknown type (like C's `void *`), and can't be dereferenced
+    Pointer(Box<Type>),
+    /// A string of UTF-8 bytes (a pointer to its first byte and its length in bytes), which is
+    /// always followed by a NUL byte so that it can be passed to C functions
+    Str,
+    /// The type of an integer variable that the typer hasn't inferred yet, like `x` in `let x = 5`
+    /// before it's used as a particular integer type; the number identifies the variable.
+    ///
+    /// Like [Type::Error], it never reaches the compiler.
+    InferredInt(usize),
+    /// The type of something that failed to type-check, like an undeclared variable.
+    ///
+    /// The typer accepts this type wherever any other type is expected, so that one mistake
+    /// doesn't cause a cascade of errors. It never reaches the compiler.
+    Error,
 }
 
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Int(int_type) => write!(f, "{}", int_type),
+            Self::Float(float_type) => write!(f, "{}", float_type),
+            Self::Char => write!(f, "char"),
             Self::Bool => write!(f, "bool"),
             Self::Void => write!(f, "void"),
             Self::Func(func_type) => write!(f, "{}", func_type),
+            Self::Named(name) => write!(f, "{}", name),
+            Self::Array(element_type, length) => write!(f, "[{}; {}]", element_type, length),
+            Self::Pointer(pointee_type) => write!(f, "*{}", pointee_type),
+            Self::Str => write!(f, "str"),
+            Self::InferredInt(_) => write!(f, "{{integer}}"),
+            Self::Error => write!(f, "{{error}}"),
         }
     }
 }
@@ -29,6 +62,11 @@ pub struct IntType {
     pub width: u32,
 }
 
+impl IntType {
+    /// The widest integer type LLVM supports, in bits
+    pub const MAX_WIDTH: u32 = 1 << 23;
+}
+
 impl fmt::Display for IntType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.signed {
@@ -38,4 +76,16 @@ impl fmt::Display for IntType {
     }
 }
 
+/// A struct to store the built-in float types, `f32` and `f64`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FloatType {
+    pub width: u32,
+}
+
+impl fmt::Display for FloatType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "f{}", self.width)
+    }
+}
+
 
@@ -0,0 +1,100 @@
+use crate::types::IntType;
+
+/// An unsigned integer of any size, like an integer literal that's too large for a `u128`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigUint {
+    /// The integer's 32-bit digits, least significant first, without any trailing zero digits
+    /// (so zero has no digits at all)
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    /// Parses a string of decimal digits, like an integer literal, or returns `None` if the
+    /// string is empty or contains anything other than digits.
+    pub fn from_decimal(decimal: &str) -> Option<Self> {
+        if decimal.is_empty() {
+            return None;
+        }
+
+        let mut limbs: Vec<u32> = Vec::new();
+        for c in decimal.chars() {
+            // Multiplies the integer so far by 10 and adds the new digit
+            let mut carry = c.to_digit(10)? as u64;
+            for limb in limbs.iter_mut() {
+                let result = *limb as u64 * 10 + carry;
+                *limb = result as u32;
+                carry = result >> 32;
+            }
+            if carry > 0 {
+                limbs.push(carry as u32);
+            }
+        }
+        Some(Self { limbs })
+    }
+
+    /// Returns the number of bits needed to write the integer in binary (0 for zero).
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(last) => (self.limbs.len() as u64 - 1) * 32 + (32 - last.leading_zeros()) as u64,
+            None => 0,
+        }
+    }
+
+    /// Returns whether the integer is a power of two, like 1 or 128.
+    pub fn is_power_of_two(&self) -> bool {
+        match self.limbs.split_last() {
+            Some((last, rest)) => last.is_power_of_two() && rest.iter().all(|&limb| limb == 0),
+            None => false,
+        }
+    }
+
+    /// Returns whether the integer (or its negation, if `is_negated`) is a value of `int_type`.
+    pub fn fits_in(&self, int_type: IntType, is_negated: bool) -> bool {
+        let bits = self.bits();
+        let width = int_type.width as u64;
+        match (int_type.signed, is_negated) {
+            (false, false) => bits <= width,
+            (false, true) => bits == 0,
+            (true, false) => bits < width,
+            // The smallest value, -2^(width - 1), has no positive counterpart
+            (true, true) => bits < width || (bits == width && self.is_power_of_two()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_decimal() {
+        assert_eq!(BigUint::from_decimal("0").unwrap().bits(), 0);
+        assert_eq!(BigUint::from_decimal("007").unwrap().bits(), 3);
+        assert_eq!(BigUint::from_decimal("4294967296").unwrap().bits(), 33);
+        assert_eq!(BigUint::from_decimal(&u128::MAX.to_string()).unwrap().bits(), 128);
+        assert_eq!(BigUint::from_decimal(""), None);
+        assert_eq!(BigUint::from_decimal("12a"), None);
+    }
+
+    #[test]
+    fn fits_in() {
+        let u8_type = IntType { signed: false, width: 8 };
+        let i8_type = IntType { signed: true, width: 8 };
+        let i200_type = IntType { signed: true, width: 200 };
+        let int = |decimal: &str| BigUint::from_decimal(decimal).unwrap();
+
+        assert!(int("255").fits_in(u8_type, false));
+        assert!(!int("256").fits_in(u8_type, false));
+        assert!(!int("1").fits_in(u8_type, true));
+        assert!(int("127").fits_in(i8_type, false));
+        assert!(!int("128").fits_in(i8_type, false));
+        assert!(int("128").fits_in(i8_type, true));
+        assert!(!int("129").fits_in(i8_type, true));
+
+        // 2^199 is the negation of the smallest i200
+        let two_to_the_199 = "803469022129495137770981046170581301261101496891396417650688";
+        assert!(int(two_to_the_199).fits_in(i200_type, true));
+        assert!(!int(two_to_the_199).fits_in(i200_type, false));
+        assert!(!int(&format!("{}0", two_to_the_199)).fits_in(i200_type, true));
+    }
+}
@@ -0,0 +1,306 @@
+use std::cmp::Ordering;
+
+use crate::ast::{BinaryOperator, ComparisonOperator, LogicalOperator, UnaryOperator};
+use crate::error::{ErrorKind, FlickError, TypingError};
+use crate::span::Span;
+use crate::typed_ast::{
+    TypedArrayLiteral, TypedArrayRepeat, TypedBinary, TypedComparison, TypedExpr, TypedExprKind, TypedFloatLiteral,
+    TypedIntLiteral, TypedLogical, TypedStructLiteral, TypedUnary,
+};
+use crate::types::{FloatType, IntType, Type};
+
+use super::exhaustiveness::int_bounds;
+
+/// Evaluates a typed expression at compile time, returning an equivalent expression that only
+/// consists of literals (like `300` for `(u16) 3 * 100`).
+///
+/// Constant expressions are made of literals (including `null` and string literals), operators
+/// (other than `*` and `&`), casts, and struct and array literals whose values are constant
+/// expressions. Uses of other constants are fine too, since the typer replaces them with their
+/// values before they get here.
+///
+/// Integer arithmetic is checked: overflowing the result's type, dividing by zero, or shifting by
+/// at least the integer's width is an error (rather than wrapping, or being undefined behavior).
+/// Casts between integers wrap, like they do at runtime. Integers that don't fit in an `i128`
+/// (like large `u128`s) aren't supported.
+pub fn evaluate(expr: &TypedExpr) -> crate::Result<TypedExpr> {
+    let span = expr.span;
+    let kind = match &expr.kind {
+        TypedExprKind::IntLiteral(_)
+        | TypedExprKind::FloatLiteral(_)
+        | TypedExprKind::BoolLiteral(_)
+        | TypedExprKind::StrLiteral(_)
+        | TypedExprKind::CharLiteral(_)
+        | TypedExprKind::Null(_) => expr.kind.clone(),
+        TypedExprKind::Unary(unary) => evaluate_unary(unary, span)?.into_literal(),
+        TypedExprKind::Binary(binary) => evaluate_binary(binary, span)?.into_literal(),
+        TypedExprKind::Comparison(comparison) => TypedExprKind::BoolLiteral(evaluate_comparison(comparison)?),
+        TypedExprKind::Logical(logical) => TypedExprKind::BoolLiteral(evaluate_logical(logical)?),
+        TypedExprKind::StructLiteral(struct_literal) => TypedExprKind::StructLiteral(TypedStructLiteral {
+            struct_name: struct_literal.struct_name.clone(),
+            fields: struct_literal
+                .fields
+                .iter()
+                .map(|(index, value)| Ok((*index, evaluate(value)?)))
+                .collect::<crate::Result<_>>()?,
+        }),
+        TypedExprKind::ArrayLiteral(array_literal) => TypedExprKind::ArrayLiteral(TypedArrayLiteral {
+            elements: array_literal.elements.iter().map(evaluate).collect::<crate::Result<_>>()?,
+            element_type: array_literal.element_type.clone(),
+        }),
+        TypedExprKind::ArrayRepeat(array_repeat) => TypedExprKind::ArrayRepeat(TypedArrayRepeat {
+            value: Box::new(evaluate(&array_repeat.value)?),
+            length: array_repeat.length,
+        }),
+        _ => return Err(err(TypingError::NonConstantExpr, span)),
+    };
+    Ok(TypedExpr { kind, span })
+}
+
+/// The value of a constant expression that operators can be applied to.
+enum Value {
+    Int(i128, IntType),
+    Float(f64, FloatType),
+    Bool(bool),
+    Char(char),
+    Null(Type),
+}
+
+impl Value {
+    /// Evaluates `expr`, which the typer has already checked to have a scalar type.
+    fn of(expr: &TypedExpr) -> crate::Result<Value> {
+        let value = evaluate(expr)?;
+        Ok(match value.kind {
+            TypedExprKind::IntLiteral(int_literal) => {
+                let int_type = int_literal.int_type;
+                let int_value = int_literal
+                    .int_value
+                    .parse()
+                    .map_err(|_| err(TypingError::ConstOverflow(Type::Int(int_type)), expr.span))?;
+                Value::Int(int_value, int_type)
+            }
+            TypedExprKind::FloatLiteral(float_literal) => {
+                let float_type = float_literal.float_type;
+                let float_value: f64 = float_literal.float_value.parse().unwrap();
+                Value::Float(round(float_value, float_type), float_type)
+            }
+            TypedExprKind::BoolLiteral(bool_value) => Value::Bool(bool_value),
+            TypedExprKind::CharLiteral(char_value) => Value::Char(char_value),
+            TypedExprKind::Null(pointer_type) => Value::Null(pointer_type),
+            _ => return Err(err(TypingError::NonConstantExpr, expr.span)),
+        })
+    }
+
+    fn into_literal(self) -> TypedExprKind {
+        match self {
+            Value::Int(int_value, int_type) => TypedExprKind::IntLiteral(TypedIntLiteral {
+                int_value: int_value.to_string(),
+                int_type,
+            }),
+            Value::Float(float_value, float_type) => TypedExprKind::FloatLiteral(TypedFloatLiteral {
+                float_value: format!("{:?}", float_value),
+                float_type,
+            }),
+            Value::Bool(bool_value) => TypedExprKind::BoolLiteral(bool_value),
+            Value::Char(char_value) => TypedExprKind::CharLiteral(char_value),
+            Value::Null(pointer_type) => TypedExprKind::Null(pointer_type),
+        }
+    }
+}
+
+fn evaluate_unary(unary: &TypedUnary, span: Span) -> crate::Result<Value> {
+    let operand = Value::of(&unary.operand)?;
+    match (&unary.operator, operand) {
+        (UnaryOperator::Negate, Value::Int(int_value, int_type)) => checked_int(int_value.checked_neg(), int_type, span),
+        (UnaryOperator::Negate, Value::Float(float_value, float_type)) => Ok(Value::Float(-float_value, float_type)),
+        (UnaryOperator::Not, Value::Bool(bool_value)) => Ok(Value::Bool(!bool_value)),
+        (UnaryOperator::BitwiseNot, Value::Int(int_value, int_type)) => {
+            Ok(Value::Int(wrap(!int_value, int_type), int_type))
+        }
+        (UnaryOperator::Cast(to), operand) => evaluate_cast(operand, to, span),
+        _ => Err(err(TypingError::NonConstantExpr, span)),
+    }
+}
+
+/// Evaluates a cast that the typer has already checked to be valid.
+fn evaluate_cast(operand: Value, to: &Type, span: Span) -> crate::Result<Value> {
+    Ok(match (operand, to) {
+        (Value::Int(int_value, _), Type::Int(int_type)) => Value::Int(wrap(int_value, *int_type), *int_type),
+        (Value::Int(int_value, _), Type::Float(float_type)) => match float_type.width {
+            32 => Value::Float(int_value as f32 as f64, *float_type),
+            _ => Value::Float(int_value as f64, *float_type),
+        },
+        (Value::Int(int_value, _), Type::Char) => Value::Char(char::from(int_value as u8)),
+        (Value::Float(float_value, _), Type::Int(int_type)) => {
+            // Like LLVM's `fptosi`/`fptoui`, which truncate towards zero, except that values that
+            // don't fit are an error rather than poison
+            let truncated = float_value.trunc();
+            let end = 2f64.powi(int_type.width as i32 - int_type.signed as i32);
+            let start = if int_type.signed { -end } else { 0.0 };
+            if !(start..end).contains(&truncated) {
+                return Err(err(TypingError::ConstOverflow(to.clone()), span));
+            }
+            checked_int(Some(truncated as i128), *int_type, span)?
+        }
+        (Value::Float(float_value, _), Type::Float(float_type)) => {
+            Value::Float(round(float_value, *float_type), *float_type)
+        }
+        (Value::Char(char_value), Type::Int(int_type)) => Value::Int(wrap(char_value as i128, *int_type), *int_type),
+        (Value::Null(_), Type::Pointer(_)) => Value::Null(to.clone()),
+        _ => return Err(err(TypingError::NonConstantExpr, span)),
+    })
+}
+
+fn evaluate_binary(binary: &TypedBinary, span: Span) -> crate::Result<Value> {
+    let left = Value::of(&binary.left)?;
+    let right = Value::of(&binary.right)?;
+    match (left, right) {
+        (Value::Int(left, int_type), Value::Int(right, _)) => {
+            evaluate_int_binary(left, binary.operator, right, int_type, span)
+        }
+        (Value::Float(left, float_type), Value::Float(right, _)) => {
+            evaluate_float_binary(left, binary.operator, right, float_type, span)
+        }
+        _ => Err(err(TypingError::NonConstantExpr, span)),
+    }
+}
+
+fn evaluate_int_binary(
+    left: i128,
+    operator: BinaryOperator,
+    right: i128,
+    int_type: IntType,
+    span: Span,
+) -> crate::Result<Value> {
+    let is_division = matches!(operator, BinaryOperator::Divide | BinaryOperator::Remainder);
+    if is_division && right == 0 {
+        return Err(err(TypingError::ConstDivisionByZero, span));
+    }
+    let is_shift = matches!(operator, BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight);
+    if is_shift && !(0..int_type.width as i128).contains(&right) {
+        return Err(err(TypingError::ConstOverflow(Type::Int(int_type)), span));
+    }
+
+    match operator {
+        BinaryOperator::Add => checked_int(left.checked_add(right), int_type, span),
+        BinaryOperator::Subtract => checked_int(left.checked_sub(right), int_type, span),
+        BinaryOperator::Multiply => checked_int(left.checked_mul(right), int_type, span),
+        BinaryOperator::Divide => checked_int(left.checked_div(right), int_type, span),
+        BinaryOperator::Remainder => checked_int(left.checked_rem(right), int_type, span),
+        BinaryOperator::BitwiseAnd => Ok(Value::Int(left & right, int_type)),
+        BinaryOperator::BitwiseOr => Ok(Value::Int(left | right, int_type)),
+        BinaryOperator::BitwiseXor => Ok(Value::Int(left ^ right, int_type)),
+        // Bits shifted past the integer's width are discarded, like at runtime
+        BinaryOperator::ShiftLeft => Ok(Value::Int(wrap(left.wrapping_shl(right as u32), int_type), int_type)),
+        // Unsigned values are never negative here, so this is a logical shift for them
+        BinaryOperator::ShiftRight => Ok(Value::Int(left >> right, int_type)),
+    }
+}
+
+fn evaluate_float_binary(
+    left: f64,
+    operator: BinaryOperator,
+    right: f64,
+    float_type: FloatType,
+    span: Span,
+) -> crate::Result<Value> {
+    let is_division = matches!(operator, BinaryOperator::Divide | BinaryOperator::Remainder);
+    if is_division && right == 0.0 {
+        return Err(err(TypingError::ConstDivisionByZero, span));
+    }
+
+    // An `f64` has more than twice the precision of an `f32`, so computing an `f32` operation as
+    // an `f64` and then rounding it gives the same result as computing it as an `f32`
+    let result = match operator {
+        BinaryOperator::Add => left + right,
+        BinaryOperator::Subtract => left - right,
+        BinaryOperator::Multiply => left * right,
+        BinaryOperator::Divide => left / right,
+        BinaryOperator::Remainder => left % right,
+        _ => return Err(err(TypingError::NonConstantExpr, span)),
+    };
+    let result = round(result, float_type);
+    if result.is_infinite() {
+        return Err(err(TypingError::ConstOverflow(Type::Float(float_type)), span));
+    }
+    Ok(Value::Float(result, float_type))
+}
+
+fn evaluate_comparison(comparison: &TypedComparison) -> crate::Result<bool> {
+    let left = Value::of(&comparison.left)?;
+    let right = Value::of(&comparison.right)?;
+    let ordering = match (left, right) {
+        (Value::Int(left, _), Value::Int(right, _)) => left.partial_cmp(&right),
+        (Value::Float(left, _), Value::Float(right, _)) => left.partial_cmp(&right),
+        (Value::Bool(left), Value::Bool(right)) => left.partial_cmp(&right),
+        (Value::Char(left), Value::Char(right)) => left.partial_cmp(&right),
+        (Value::Null(_), Value::Null(_)) => Some(Ordering::Equal),
+        _ => None,
+    };
+
+    // An unordered comparison (with NaN) is always false, like the compiler's ordered predicates
+    let Some(ordering) = ordering else {
+        return Ok(false);
+    };
+    Ok(match comparison.operator {
+        ComparisonOperator::NotEqualTo => ordering != Ordering::Equal,
+        ComparisonOperator::EqualTo => ordering == Ordering::Equal,
+        ComparisonOperator::LessThan => ordering == Ordering::Less,
+        ComparisonOperator::GreaterThan => ordering == Ordering::Greater,
+        ComparisonOperator::LessOrEqualTo => ordering != Ordering::Greater,
+        ComparisonOperator::GreaterOrEqualTo => ordering != Ordering::Less,
+    })
+}
+
+fn evaluate_logical(logical: &TypedLogical) -> crate::Result<bool> {
+    // Both sides are evaluated (even when the left side decides the result), since they both
+    // have to be constant
+    let (Value::Bool(left), Value::Bool(right)) = (Value::of(&logical.left)?, Value::of(&logical.right)?) else {
+        return Err(err(TypingError::NonConstantExpr, logical.left.span));
+    };
+    Ok(match logical.operator {
+        LogicalOperator::And => left && right,
+        LogicalOperator::Or => left || right,
+    })
+}
+
+/// Returns the result of an integer operation, or an overflow error if it's `None` (because it
+/// overflowed an `i128`) or doesn't fit in `int_type`.
+fn checked_int(result: Option<i128>, int_type: IntType, span: Span) -> crate::Result<Value> {
+    let (min, max) = int_bounds(int_type).unwrap_or((0, i128::MAX));
+    match result {
+        Some(result) if (min..=max).contains(&result) => Ok(Value::Int(result, int_type)),
+        _ => Err(err(TypingError::ConstOverflow(Type::Int(int_type)), span)),
+    }
+}
+
+/// Truncates `int_value` to the width of `int_type`, reinterpreting the remaining bits as
+/// `int_type` (which is what a cast between integers does).
+fn wrap(int_value: i128, int_type: IntType) -> i128 {
+    if int_type.width >= 128 {
+        return int_value;
+    }
+    let mask = (1u128 << int_type.width) - 1;
+    let bits = int_value as u128 & mask;
+    let is_negative = int_type.signed && bits >> (int_type.width - 1) == 1;
+    if is_negative {
+        (bits | !mask) as i128
+    } else {
+        bits as i128
+    }
+}
+
+/// Rounds `float_value` to the nearest value of `float_type`.
+fn round(float_value: f64, float_type: FloatType) -> f64 {
+    match float_type.width {
+        32 => float_value as f32 as f64,
+        _ => float_value,
+    }
+}
+
+fn err(kind: TypingError, span: Span) -> FlickError {
+    FlickError {
+        span,
+        kind: ErrorKind::TypingError(Box::new(kind)),
+    }
+}
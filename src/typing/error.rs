@@ -0,0 +1,515 @@
+use std::fmt;
+
+use crate::ast::{BinaryOperator, ComparisonOperator};
+use crate::span::Span;
+use crate::types::{FloatType, IntType, Type};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TypingError {
+    /// The program doesn't define a `main` function
+    MissingMain,
+    /// Something named `main` exists, but it isn't a function
+    MainNotAFunction(Type),
+    /// The `main` function isn't declared with `pub fn`
+    MainNotPublic,
+    /// The `main` function takes parameters
+    MainHasParams,
+    /// The `main` function returns something other than `u8`
+    InvalidMainReturnType(Type),
+
+    /// A function is defined twice (`previous_definition` is the span of the first definition)
+    FunctionRedefinition { name: String, previous_definition: Span },
+    /// A non-void function has a control flow path that doesn't end with `ret`
+    MissingReturn { function_name: String },
+    /// A void function returns a value, like `ret 5`
+    ReturnValueInVoidFunction { function_name: String },
+    /// A non-void function has a `ret` without a value
+    MissingReturnValue { function_name: String, expected: Type },
+
+    /// An identifier is used before it's declared
+    UndeclaredIdentifier(String),
+    /// A function is called but never declared
+    UndefinedFunction(String),
+    /// Something that isn't a function is called, like `x()` where `x` is an `i64`
+    NotAFunction { name: String, found: Type },
+    /// A function is called with the wrong number of arguments
+    WrongArgumentCount { function_name: String, expected: usize, found: usize },
+
+    /// An expression has a different type than the one its context requires
+    TypeMismatch { expected: Type, found: Type },
+    /// A variable is used where a value of a different type is required
+    IdentifierTypeMismatch { name: String, expected: Type, found: Type },
+    /// A function call is used where a value of a different type is required
+    ReturnTypeMismatch { function_name: String, expected: Type, found: Type },
+    /// An integer literal is used where a non-integer value is required
+    IntLiteralTypeMismatch { literal: String, expected: Type },
+
+    /// The operands of a binary operator like `+` have different types
+    BinaryOperandMismatch { operator: BinaryOperator, left: Type, right: Type },
+    /// A binary operator like `+` is applied to operands that don't support it, like `bool`s
+    InvalidBinaryOperand { operator: BinaryOperator, operand_type: Type },
+    /// The operands of a comparison like `<` have different types
+    ComparisonOperandMismatch { operator: ComparisonOperator, left: Type, right: Type },
+    /// A cast that isn't allowed, like from a signed to an unsigned integer
+    InvalidCast { from: Type, to: Type },
+    /// A negation of something that can't be negative, like an unsigned integer
+    InvalidNegation(Type),
+    /// A bitwise negation (`~`) of something that isn't an integer, like a `bool`
+    InvalidBitwiseNot(Type),
+
+    /// A `break` or `continue` (the `keyword`) that isn't inside of a loop
+    JumpOutsideLoop { keyword: String },
+    /// A `break` or `continue` refers to a label that no enclosing loop has
+    UndeclaredLabel(String),
+    /// A `for` loop's variable isn't an integer, like `for bool b in ...`
+    NonIntegerRange(Type),
+
+    /// A type name that isn't a built-in type, a defined struct or a defined enum
+    UndefinedType(String),
+    /// A struct or enum name is defined twice (`previous_definition` is the span of the first
+    /// definition)
+    TypeRedefinition { name: String, previous_definition: Span },
+    /// A field appears twice in a struct's definition, or in a struct literal
+    DuplicateField { struct_name: String, field_name: String },
+    /// A struct or enum contains itself (directly or through other types), so it would be
+    /// infinitely large
+    RecursiveType(String),
+    /// A struct field has a type that values can't have, like `void`
+    InvalidFieldType { struct_name: String, field_name: String, field_type: Type },
+    /// A struct literal or field access names a field that the struct doesn't have
+    UnknownField { struct_name: String, field_name: String },
+    /// A struct literal doesn't give a value to every field
+    MissingFields { struct_name: String, field_names: Vec<String> },
+    /// A field is accessed on something that isn't a struct, like `x.y` where `x` is an `i64`
+    NotAStruct { field_name: String, found: Type },
+    /// A comparison like `<` is applied to operands that don't support it, like structs
+    InvalidComparisonOperand { operator: ComparisonOperator, operand_type: Type },
+
+    /// A variant appears twice in an enum's definition
+    DuplicateVariant { enum_name: String, variant_name: String },
+    /// An enum variant's payload has a type that values can't have, like `void` (`variant_name`
+    /// is qualified, like `Shape::Circle`)
+    InvalidPayloadType { variant_name: String, payload_type: Type },
+    /// An enum literal or pattern names a variant that the enum doesn't have, or an enum that
+    /// doesn't exist
+    UndefinedVariant { enum_name: String, variant_name: String },
+    /// An enum literal or pattern has a different number of payload values than its variant
+    /// (`variant_name` is qualified, like `Shape::Circle`)
+    PayloadCountMismatch { variant_name: String, expected: usize, found: usize },
+    /// A pattern can't match values of the scrutinee's type, like `true` when matching an integer
+    /// (or an integer literal that's out of the type's range)
+    PatternTypeMismatch { pattern: String, expected: Type },
+    /// A range pattern whose start is greater than its end, so it matches nothing
+    EmptyRangePattern(String),
+    /// A `match` doesn't cover every possible value of its scrutinee
+    NonExhaustiveMatch { missing_patterns: Vec<String> },
+    /// A `match` scrutinee has a type that can't be matched on, like a struct
+    InvalidMatchType(Type),
+
+    /// Something that isn't an array is indexed, like `x[0]` where `x` is an `i64`
+    NotAnArray(Type),
+    /// An array is indexed by something that isn't an integer, like `buf[true]`
+    InvalidIndexType(Type),
+    /// An array literal has a different number of elements than the array type it's used as
+    ArrayLengthMismatch { expected: u64, found: u64 },
+    /// An empty array literal is used where its element type can't be inferred
+    UnknownArrayType,
+    /// An array is indexed by an integer literal that's past its end
+    IndexOutOfBounds { index: String, length: u64 },
+    /// An array type has elements of a type that values can't have, like `void`
+    InvalidElementType(Type),
+
+    /// Something that isn't a pointer (or is a `*void`) is dereferenced, like `*x` where `x` is
+    /// an `i64`
+    InvalidDereference(Type),
+    /// The address of a value that isn't stored in a variable is taken, like `&foo()`
+    InvalidAddressOf,
+    /// `null` is used where its pointer type can't be inferred
+    UnknownNullType,
+    /// `null` is used where a value that isn't a pointer is expected
+    NullTypeMismatch(Type),
+
+    /// A float literal is used where a non-float value is required
+    FloatLiteralTypeMismatch { literal: String, expected: Type },
+    /// A float literal is too large for its type, like `1e39` as an `f32`
+    FloatLiteralOutOfRange { literal: String, float_type: FloatType },
+
+    /// A global variable has the same name as a function or another global variable
+    GlobalRedefinition { name: String, previous_definition: Span },
+    /// A global variable's value can't be evaluated at compile time, like `const u64 X = foo()`
+    NonConstantExpr,
+    /// Evaluating a constant expression overflows its type, like `const u8 X = 200 + 100`
+    ConstOverflow(Type),
+    /// A constant expression divides by zero, like `const u64 X = 1 / 0`
+    ConstDivisionByZero,
+    /// A constant is assigned to, like `MAX = 5`
+    AssignmentToConst(String),
+
+    /// A variable declared without `mut` is assigned to, like `x = 5` after `let x = 4`
+    AssignmentToImmutable(String),
+    /// A variable is declared with a type that values can't have, like `void x = foo()`
+    InvalidVarType(Type),
+
+    /// An integer literal (negated, if it's preceded by a minus) isn't a value of its type, like
+    /// `300` as a `u8` or `-129` as an `i8`
+    IntLiteralOutOfRange { literal: String, int_type: IntType },
+
+    /// A built-in integer arithmetic function, like `wrapping_add`, is called with operands that
+    /// aren't integers
+    NonIntegerArithmetic { function_name: String, found: Type },
+
+    /// A `for` loop's `step` is a literal that isn't positive, like `step 0` (which would never
+    /// reach the end) or `step -1` (since loops only count up)
+    NonPositiveStep(String),
+
+    /// An integer type without any bits, like `u0` or `i0`
+    ZeroWidthInt(IntType),
+
+    /// The right operand of a shift is a literal that's negative or at least the width of the
+    /// shifted integer's type, like `8` in `x << 8` where `x` is a `u8`
+    ShiftAmountOutOfRange { amount: String, int_type: IntType },
+
+    /// An integer type is wider than LLVM supports, like `u10000000`
+    TooWideInt(IntType),
+}
+
+impl TypingError {
+    /// Returns the error's stable code (see [ErrorKind::code](crate::error::ErrorKind::code)).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingMain => "F0008",
+            Self::MainNotAFunction(_) => "F0009",
+            Self::MainNotPublic => "F0010",
+            Self::MainHasParams => "F0011",
+            Self::InvalidMainReturnType(_) => "F0012",
+
+            Self::FunctionRedefinition { .. } => "F0013",
+            Self::MissingReturn { .. } => "F0014",
+            Self::ReturnValueInVoidFunction { .. } => "F0015",
+            Self::MissingReturnValue { .. } => "F0016",
+
+            Self::UndeclaredIdentifier(_) => "F0017",
+            Self::UndefinedFunction(_) => "F0018",
+            Self::NotAFunction { .. } => "F0019",
+            Self::WrongArgumentCount { .. } => "F0020",
+
+            Self::TypeMismatch { .. } => "F0021",
+            Self::IdentifierTypeMismatch { .. } => "F0022",
+            Self::ReturnTypeMismatch { .. } => "F0023",
+            Self::IntLiteralTypeMismatch { .. } => "F0024",
+
+            Self::BinaryOperandMismatch { .. } => "F0025",
+            Self::InvalidBinaryOperand { .. } => "F0026",
+            Self::ComparisonOperandMismatch { .. } => "F0027",
+            Self::InvalidCast { .. } => "F0028",
+            Self::InvalidNegation(_) => "F0029",
+            Self::InvalidBitwiseNot(_) => "F0034",
+
+            Self::JumpOutsideLoop { .. } => "F0035",
+            Self::UndeclaredLabel(_) => "F0036",
+            Self::NonIntegerRange(_) => "F0037",
+
+            Self::UndefinedType(_) => "F0038",
+            Self::TypeRedefinition { .. } => "F0039",
+            Self::DuplicateField { .. } => "F0040",
+            Self::RecursiveType(_) => "F0041",
+            Self::InvalidFieldType { .. } => "F0042",
+            Self::UnknownField { .. } => "F0043",
+            Self::MissingFields { .. } => "F0044",
+            Self::NotAStruct { .. } => "F0045",
+            Self::InvalidComparisonOperand { .. } => "F0046",
+
+            Self::DuplicateVariant { .. } => "F0047",
+            Self::InvalidPayloadType { .. } => "F0048",
+            Self::UndefinedVariant { .. } => "F0049",
+            Self::PayloadCountMismatch { .. } => "F0050",
+            Self::PatternTypeMismatch { .. } => "F0051",
+            Self::EmptyRangePattern(_) => "F0052",
+            Self::NonExhaustiveMatch { .. } => "F0053",
+            Self::InvalidMatchType(_) => "F0054",
+            Self::NotAnArray(_) => "F0055",
+            Self::InvalidIndexType(_) => "F0056",
+            Self::ArrayLengthMismatch { .. } => "F0057",
+            Self::UnknownArrayType => "F0058",
+            Self::IndexOutOfBounds { .. } => "F0059",
+            Self::InvalidElementType(_) => "F0060",
+
+            Self::InvalidDereference(_) => "F0063",
+            Self::InvalidAddressOf => "F0064",
+            Self::UnknownNullType => "F0065",
+            Self::NullTypeMismatch(_) => "F0066",
+
+            Self::FloatLiteralTypeMismatch { .. } => "F0067",
+            Self::FloatLiteralOutOfRange { .. } => "F0068",
+
+            Self::GlobalRedefinition { .. } => "F0070",
+            Self::NonConstantExpr => "F0071",
+            Self::ConstOverflow(_) => "F0072",
+            Self::ConstDivisionByZero => "F0073",
+            Self::AssignmentToConst(_) => "F0074",
+
+            Self::AssignmentToImmutable(_) => "F0076",
+            Self::InvalidVarType(_) => "F0077",
+
+            Self::IntLiteralOutOfRange { .. } => "F0078",
+
+            Self::NonIntegerArithmetic { .. } => "F0079",
+
+            Self::NonPositiveStep(_) => "F0080",
+
+            Self::ZeroWidthInt(_) => "F0081",
+
+            Self::ShiftAmountOutOfRange { .. } => "F0082",
+
+            Self::TooWideInt(_) => "F0083",
+        }
+    }
+}
+
+impl fmt::Display for TypingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingMain => write!(f, "no 'main' function defined"),
+            Self::MainNotAFunction(t) => {
+                write!(f, "expected 'main' to be a function, but it has type '{}'", t)
+            }
+            Self::MainNotPublic => write!(f, "the 'main' function should be public"),
+            Self::MainHasParams => write!(f, "the 'main' function should not accept any parameters"),
+            Self::InvalidMainReturnType(t) => {
+                write!(f, "the 'main' function should return a u8, not '{}'", t)
+            }
+
+            Self::FunctionRedefinition { name, .. } => {
+                write!(f, "function '{}' is defined more than once", name)
+            }
+            Self::MissingReturn { function_name } => {
+                write!(f, "function '{}' does not always return a value", function_name)
+            }
+            Self::ReturnValueInVoidFunction { function_name } => write!(
+                f,
+                "function '{}' should return nothing, but found 'ret' with a value",
+                function_name
+            ),
+            Self::MissingReturnValue { function_name, expected } => write!(
+                f,
+                "function '{}' should return a '{}', but found 'ret' without a value",
+                function_name, expected
+            ),
+
+            Self::UndeclaredIdentifier(name) => {
+                write!(f, "identifier '{}' has not been declared", name)
+            }
+            Self::UndefinedFunction(name) => {
+                write!(f, "function '{}' is called but has not been defined", name)
+            }
+            Self::NotAFunction { name, found } => {
+                write!(f, "'{}' has type '{}', so it cannot be called", name, found)
+            }
+            Self::WrongArgumentCount { function_name, expected, found } => write!(
+                f,
+                "function '{}' takes {} argument(s) but {} were given",
+                function_name, expected, found
+            ),
+
+            Self::TypeMismatch { expected, found } => {
+                write!(f, "expected type '{}', found '{}'", expected, found)
+            }
+            Self::IdentifierTypeMismatch { name, expected, found } => write!(
+                f,
+                "identifier '{}' of type '{}' cannot be used as type '{}'",
+                name, found, expected
+            ),
+            Self::ReturnTypeMismatch { function_name, expected, found } => write!(
+                f,
+                "expected function '{}' to return '{}', but it has return type '{}'",
+                function_name, expected, found
+            ),
+            Self::IntLiteralTypeMismatch { literal, expected } => write!(
+                f,
+                "expected type '{}', found integer literal '{}'",
+                expected, literal
+            ),
+
+            Self::BinaryOperandMismatch { operator, left, right } => write!(
+                f,
+                "operator '{}' needs left-hand-side ({}) and right-hand-side ({}) to be the same type",
+                operator, left, right
+            ),
+            Self::InvalidBinaryOperand { operator, operand_type } => write!(
+                f,
+                "operator '{}' cannot be applied to type '{}'",
+                operator, operand_type
+            ),
+            Self::ComparisonOperandMismatch { operator, left, right } => write!(
+                f,
+                "comparison '{}' needs left-hand-side ({}) and right-hand-side ({}) to be the same type",
+                operator, left, right
+            ),
+            Self::InvalidCast { from, to } => match (from, to) {
+                (Type::Int(IntType { signed: false, .. }), Type::Int(IntType { signed: true, .. })) => {
+                    write!(f, "cannot cast from unsigned type '{}' to signed type '{}'", from, to)
+                }
+                (Type::Int(IntType { signed: true, .. }), Type::Int(IntType { signed: false, .. })) => {
+                    write!(f, "cannot cast from signed type '{}' to unsigned type '{}'", from, to)
+                }
+                _ => write!(f, "cannot cast from type '{}' to type '{}'", from, to),
+            },
+            Self::InvalidNegation(t @ Type::Int(IntType { signed: false, .. })) => {
+                write!(f, "cannot negate an unsigned type '{}'", t)
+            }
+            Self::InvalidNegation(t) => write!(f, "cannot negate a non-integer type '{}'", t),
+            Self::InvalidBitwiseNot(t) => {
+                write!(f, "operator '~' cannot be applied to non-integer type '{}'", t)
+            }
+
+            Self::JumpOutsideLoop { keyword } => write!(f, "'{}' outside of a loop", keyword),
+            Self::UndeclaredLabel(label) => write!(f, "use of undeclared label ''{}'", label),
+            Self::NonIntegerRange(t) => {
+                write!(f, "for loops can only count with integers, not '{}'", t)
+            }
+
+            Self::UndefinedType(name) => write!(f, "type '{}' has not been defined", name),
+            Self::TypeRedefinition { name, .. } => {
+                write!(f, "type '{}' is defined more than once", name)
+            }
+            Self::DuplicateField { struct_name, field_name } => write!(
+                f,
+                "field '{}' appears more than once in struct '{}'",
+                field_name, struct_name
+            ),
+            Self::RecursiveType(name) => {
+                write!(f, "type '{}' contains itself, so it would be infinitely large", name)
+            }
+            Self::InvalidFieldType { struct_name, field_name, field_type } => write!(
+                f,
+                "field '{}' of struct '{}' cannot have type '{}'",
+                field_name, struct_name, field_type
+            ),
+            Self::UnknownField { struct_name, field_name } => {
+                write!(f, "struct '{}' has no field named '{}'", struct_name, field_name)
+            }
+            Self::MissingFields { struct_name, field_names } => {
+                let field_names: Vec<_> = field_names.iter().map(|name| format!("'{}'", name)).collect();
+                write!(f, "missing field(s) {} in literal of struct '{}'", field_names.join(", "), struct_name)
+            }
+            Self::NotAStruct { field_name, found } => write!(
+                f,
+                "cannot access field '{}' of type '{}', which isn't a struct",
+                field_name, found
+            ),
+            Self::InvalidComparisonOperand { operator, operand_type } => write!(
+                f,
+                "operator '{}' cannot be applied to type '{}'",
+                operator, operand_type
+            ),
+
+            Self::DuplicateVariant { enum_name, variant_name } => write!(
+                f,
+                "variant '{}' appears more than once in enum '{}'",
+                variant_name, enum_name
+            ),
+            Self::InvalidPayloadType { variant_name, payload_type } => write!(
+                f,
+                "payload of variant '{}' cannot have type '{}'",
+                variant_name, payload_type
+            ),
+            Self::UndefinedVariant { enum_name, variant_name } => {
+                write!(f, "no variant '{}::{}' has been defined", enum_name, variant_name)
+            }
+            Self::PayloadCountMismatch { variant_name, expected, found } => write!(
+                f,
+                "variant '{}' has {} payload value(s), but {} were given",
+                variant_name, expected, found
+            ),
+            Self::PatternTypeMismatch { pattern, expected } => {
+                write!(f, "pattern '{}' cannot match values of type '{}'", pattern, expected)
+            }
+            Self::EmptyRangePattern(pattern) => {
+                write!(f, "range pattern '{}' is empty, since its start is after its end", pattern)
+            }
+            Self::NonExhaustiveMatch { missing_patterns } => {
+                let missing_patterns: Vec<_> = missing_patterns.iter().map(|p| format!("'{}'", p)).collect();
+                write!(f, "match is not exhaustive: {} not covered", missing_patterns.join(", "))
+            }
+            Self::InvalidMatchType(t) => write!(f, "cannot match on values of type '{}'", t),
+
+            Self::NotAnArray(t) => write!(f, "cannot index into type '{}', which isn't an array", t),
+            Self::InvalidIndexType(t) => write!(f, "arrays cannot be indexed by type '{}'", t),
+            Self::ArrayLengthMismatch { expected, found } => write!(
+                f,
+                "expected an array of {} element(s), but the literal has {}",
+                expected, found
+            ),
+            Self::UnknownArrayType => write!(f, "cannot infer the element type of an empty array literal"),
+            Self::IndexOutOfBounds { index, length } => {
+                write!(f, "index {} is out of bounds for an array of length {}", index, length)
+            }
+            Self::InvalidElementType(t) => write!(f, "arrays cannot have elements of type '{}'", t),
+
+            Self::InvalidDereference(t) => write!(f, "cannot dereference a value of type '{}'", t),
+            Self::InvalidAddressOf => {
+                write!(f, "cannot take the address of a value that isn't stored in a variable")
+            }
+            Self::UnknownNullType => write!(f, "cannot infer the pointer type of 'null'"),
+            Self::NullTypeMismatch(t) => write!(f, "expected '{}', found 'null' (which can only be a pointer)", t),
+
+            Self::FloatLiteralTypeMismatch { literal, expected } => {
+                write!(f, "expected type '{}', found float literal '{}'", expected, literal)
+            }
+            Self::FloatLiteralOutOfRange { literal, float_type } => {
+                write!(f, "float literal '{}' is too large for type '{}'", literal, float_type)
+            }
+
+            Self::GlobalRedefinition { name, .. } => {
+                write!(f, "'{}' is defined more than once in the global scope", name)
+            }
+            Self::NonConstantExpr => write!(f, "expected a value that can be evaluated at compile time"),
+            Self::ConstOverflow(t) => write!(f, "evaluating this constant expression overflows type '{}'", t),
+            Self::ConstDivisionByZero => write!(f, "division by zero in a constant expression"),
+            Self::AssignmentToConst(name) => write!(f, "cannot assign to constant '{}'", name),
+            Self::AssignmentToImmutable(name) => write!(f, "cannot assign twice to immutable variable '{}'", name),
+            Self::InvalidVarType(t) => write!(f, "variables can't have type '{}'", t),
+            Self::IntLiteralOutOfRange { literal, int_type } => {
+                let (min, max) = int_range(*int_type);
+                write!(f, "integer literal '{}' is out of range for type '{}' ({} to {})", literal, int_type, min, max)
+            }
+            Self::NonIntegerArithmetic { function_name, found } => {
+                write!(f, "built-in function '{}' only works on integers, not '{}'", function_name, found)
+            }
+
+            Self::NonPositiveStep(step) => write!(f, "for loop step must be positive, not '{}'", step),
+
+            Self::ZeroWidthInt(int_type) => write!(f, "integer type '{}' has no bits", Type::Int(*int_type)),
+
+            Self::ShiftAmountOutOfRange { amount, int_type } => write!(
+                f,
+                "shift amount '{}' is out of range for type '{}' (0 to {})",
+                amount,
+                Type::Int(*int_type),
+                int_type.width - 1
+            ),
+
+            Self::TooWideInt(int_type) => write!(
+                f,
+                "integer type '{}' is too wide (at most {} bits)",
+                Type::Int(*int_type),
+                IntType::MAX_WIDTH
+            ),
+        }
+    }
+}
+
+/// Returns the smallest and largest values of `int_type`, written in decimal if they fit in a
+/// `u128` or `i128`, and as powers of two otherwise (like `2^200 - 1`).
+fn int_range(int_type: IntType) -> (String, String) {
+    let width = int_type.width;
+    match (int_type.signed, width) {
+        (false, 0..=128) => ("0".to_string(), u128::MAX.checked_shr(128 - width).unwrap_or(0).to_string()),
+        (false, _) => ("0".to_string(), format!("2^{} - 1", width)),
+        (true, 1..=128) => {
+            let max = i128::MAX >> (128 - width);
+            ((-max - 1).to_string(), max.to_string())
+        }
+        (true, _) => (format!("-2^{}", width.saturating_sub(1)), format!("2^{} - 1", width.saturating_sub(1))),
+    }
+}
@@ -0,0 +1,157 @@
+use crate::typed_ast::TypedPattern;
+use crate::types::{IntType, Type};
+
+use super::type_registry::TypeRegistry;
+
+/// Returns the smallest and largest values of `int_type`, or `None` if they don't fit in an
+/// `i128` (in which case integer patterns can't cover the whole type).
+pub fn int_bounds(int_type: IntType) -> Option<(i128, i128)> {
+    match (int_type.signed, int_type.width) {
+        (true, 128) => Some((i128::MIN, i128::MAX)),
+        (true, width @ 1..=127) => Some((-(1 << (width - 1)), (1 << (width - 1)) - 1)),
+        (false, 127) => Some((0, i128::MAX)),
+        (false, width @ 1..=126) => Some((0, (1 << width) - 1)),
+        _ => None,
+    }
+}
+
+/// Returns the patterns that would have to be added to `patterns` (the patterns of a `match`'s
+/// arms, in order) for every value of `scrutinee_type` to be matched, formatted like they would
+/// be written in Flick (like `false`, `Shape::Rect(_, _)`, or `10..=255`).
+///
+/// An empty result means that the match is exhaustive.
+pub fn missing_patterns(scrutinee_type: &Type, patterns: &[&TypedPattern], types: &TypeRegistry) -> Vec<String> {
+    if patterns.iter().any(|pattern| **pattern == TypedPattern::Wildcard) {
+        return Vec::new();
+    }
+
+    match scrutinee_type {
+        Type::Bool => [false, true]
+            .into_iter()
+            .filter(|b| !patterns.contains(&&TypedPattern::Bool(*b)))
+            .map(|b| b.to_string())
+            .collect(),
+        Type::Named(enum_name) => {
+            let Some(enum_def) = types.get_enum(enum_name) else {
+                return Vec::new();
+            };
+            let covers = |index| {
+                patterns
+                    .iter()
+                    .any(|pattern| matches!(pattern, TypedPattern::EnumVariant { variant_index, .. } if *variant_index == index))
+            };
+            enum_def
+                .variants
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !covers(*index))
+                .map(|(_, variant)| match variant.payload_types.len() {
+                    0 => format!("{}::{}", enum_name, variant.name),
+                    n => format!("{}::{}({})", enum_name, variant.name, vec!["_"; n].join(", ")),
+                })
+                .collect()
+        }
+        Type::Int(int_type) => match int_bounds(*int_type) {
+            Some((min, max)) => missing_int_ranges(patterns, min, max),
+            None => vec!["_".to_string()],
+        },
+        // Other types can't be matched on, which is reported elsewhere
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the ranges of integers from `min` to `max` that none of `patterns` cover.
+fn missing_int_ranges(patterns: &[&TypedPattern], min: i128, max: i128) -> Vec<String> {
+    let mut ranges: Vec<_> = patterns
+        .iter()
+        .filter_map(|pattern| match pattern {
+            TypedPattern::IntRange { start, end } => Some((*start, *end)),
+            _ => None,
+        })
+        .collect();
+    ranges.sort();
+
+    let format_range = |start: i128, end: i128| match start == end {
+        true => start.to_string(),
+        false => format!("{}..={}", start, end),
+    };
+
+    let mut missing = Vec::new();
+    // The smallest integer that might not be covered yet (`None` once everything up to `max` is)
+    let mut next = Some(min);
+    for (start, end) in ranges {
+        let Some(uncovered) = next else { break };
+        if start > uncovered {
+            missing.push(format_range(uncovered, start - 1));
+        }
+        if end >= uncovered {
+            next = end.checked_add(1).filter(|&n| n <= max);
+        }
+    }
+    if let Some(uncovered) = next {
+        missing.push(format_range(uncovered, max));
+    }
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{EnumDef, EnumVariant};
+    use crate::span::Span;
+    use crate::typing::type_registry::TypeDef;
+
+    fn int_range(start: i128, end: i128) -> TypedPattern {
+        TypedPattern::IntRange { start, end }
+    }
+
+    #[test]
+    fn int_bounds_of_each_width() {
+        assert_eq!(int_bounds(IntType { signed: false, width: 8 }), Some((0, 255)));
+        assert_eq!(int_bounds(IntType { signed: true, width: 8 }), Some((-128, 127)));
+        assert_eq!(int_bounds(IntType { signed: true, width: 1 }), Some((-1, 0)));
+        assert_eq!(int_bounds(IntType { signed: true, width: 128 }), Some((i128::MIN, i128::MAX)));
+        assert_eq!(int_bounds(IntType { signed: false, width: 128 }), None);
+    }
+
+    #[test]
+    fn missing_ints() {
+        let u8_type = Type::Int(IntType { signed: false, width: 8 });
+        let (zero, low, high) = (int_range(0, 0), int_range(1, 9), int_range(20, 255));
+        let registry = TypeRegistry::new();
+
+        assert_eq!(missing_patterns(&u8_type, &[&low, &high], &registry), vec!["0", "10..=19"]);
+        assert_eq!(missing_patterns(&u8_type, &[&high, &zero], &registry), vec!["1..=19"]);
+        assert!(missing_patterns(&u8_type, &[&high, &low, &zero, &int_range(5, 30)], &registry).is_empty());
+        assert!(missing_patterns(&u8_type, &[&zero, &TypedPattern::Wildcard], &registry).is_empty());
+
+        let i128_type = Type::Int(IntType { signed: true, width: 128 });
+        let everything = int_range(i128::MIN, i128::MAX);
+        assert!(missing_patterns(&i128_type, &[&everything], &registry).is_empty());
+    }
+
+    #[test]
+    fn missing_bools_and_variants() {
+        let mut registry = TypeRegistry::new();
+        let u32_type = Type::Int(IntType { signed: false, width: 32 });
+        let variant = |name: &str, payload_types| EnumVariant { name: name.to_string(), payload_types };
+        registry
+            .register(TypeDef::Enum(EnumDef {
+                name: "Shape".to_string(),
+                variants: vec![
+                    variant("Circle", vec![u32_type.clone()]),
+                    variant("Rect", vec![u32_type.clone(), u32_type]),
+                    variant("Empty", vec![]),
+                ],
+                span: Span::default(),
+            }))
+            .unwrap();
+
+        let circle = TypedPattern::EnumVariant { variant_index: 0, bindings: vec![] };
+        let shape_type = Type::Named("Shape".to_string());
+        assert_eq!(missing_patterns(&shape_type, &[&circle], &registry), vec!["Shape::Rect(_, _)", "Shape::Empty"]);
+
+        let true_pattern = TypedPattern::Bool(true);
+        assert_eq!(missing_patterns(&Type::Bool, &[&true_pattern], &registry), vec!["false"]);
+    }
+}
@@ -11,20 +11,44 @@
 ///
 /// ```
 /// use flick::ast::BinaryOperator;
+/// use flick::span::Span;
 /// use flick::typed_ast::*;
 /// use flick::types::{Type, IntType};
-/// let _ = TypedExpr::Binary(TypedBinary {
-///     left: Box::new(TypedExpr::IntLiteral(TypedIntLiteral { int_value: "9".to_string(), int_type: IntType { signed: false, width: 64 }})),
-///     operator: BinaryOperator::Multiply,
-///     right: Box::new(TypedExpr::Binary(TypedBinary {
-///         left: Box::new(TypedExpr::IntLiteral(TypedIntLiteral { int_value: "2".to_string(), int_type: IntType { signed: false, width: 64 }})),
-///         operator: BinaryOperator::Add,
-///         right: Box::new(TypedExpr::IntLiteral(TypedIntLiteral { int_value: "3".to_string(), int_type: IntType { signed: false, width: 64 }})),
+/// let u64_literal = |int_value: &str| TypedExpr {
+///     kind: TypedExprKind::IntLiteral(TypedIntLiteral {
+///         int_value: int_value.to_string(),
+///         int_type: IntType { signed: false, width: 64 },
+///     }),
+///     span: Span::default(),
+/// };
+/// let _ = TypedExpr {
+///     kind: TypedExprKind::Binary(TypedBinary {
+///         left: Box::new(u64_literal("9")),
+///         operator: BinaryOperator::Multiply,
+///         right: Box::new(TypedExpr {
+///             kind: TypedExprKind::Binary(TypedBinary {
+///                 left: Box::new(u64_literal("2")),
+///                 operator: BinaryOperator::Add,
+///                 right: Box::new(u64_literal("3")),
+///                 result_type: Type::Int(IntType { signed: false, width: 64 }),
+///             }),
+///             span: Span::default(),
+///         }),
 ///         result_type: Type::Int(IntType { signed: false, width: 64 }),
-///     })),
-///     result_type: Type::Int(IntType { signed: false, width: 64 }),
-/// });
+///     }),
+///     span: Span::default(),
+/// };
 /// ```
 pub mod typed_ast;
 /// Module that defines the [typer::Typer] struct for adding types to an abstract syntax tree.
 pub mod typer;
+/// Module that defines the typer errors.
+pub mod error;
+/// Module that defines the [type_registry::TypeRegistry], which stores user-defined types.
+pub mod type_registry;
+/// Module that checks whether the arms of a `match` cover every possible value.
+pub mod exhaustiveness;
+/// Module that evaluates constant expressions, like the values of global variables.
+pub mod const_eval;
+/// Module that defines [big_uint::BigUint], which checks that integer literals fit in their types.
+pub mod big_uint;
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use crate::ast::{EnumDef, EnumVariant, StructDef, StructField};
+use crate::span::Span;
+use crate::types::Type;
+
+/// The definition of a user-defined type.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TypeDef {
+    Struct(StructDef),
+    Enum(EnumDef),
+}
+
+impl TypeDef {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Struct(struct_def) => &struct_def.name,
+            Self::Enum(enum_def) => &enum_def.name,
+        }
+    }
+
+    /// Returns the span of the whole definition.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Struct(struct_def) => struct_def.span,
+            Self::Enum(enum_def) => enum_def.span,
+        }
+    }
+
+    /// Returns the types that values of this type contain by value (its fields' or its variants'
+    /// payloads' types).
+    fn contained_types(&self) -> Box<dyn Iterator<Item = &Type> + '_> {
+        match self {
+            Self::Struct(struct_def) => Box::new(struct_def.fields.iter().map(|field| &field.field_type)),
+            Self::Enum(enum_def) => Box::new(enum_def.variants.iter().flat_map(|variant| &variant.payload_types)),
+        }
+    }
+}
+
+/// The user-defined types of a program (its structs and enums, which share a namespace), which
+/// the [Typer][a] looks up by name.
+///
+/// [a]: crate::Typer
+#[derive(Debug, Default, Clone)]
+pub struct TypeRegistry {
+    types: HashMap<String, TypeDef>,
+}
+
+impl TypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `type_def` under its name, unless a type with that name already exists, in
+    /// which case the existing definition is returned as an `Err()`.
+    pub fn register(&mut self, type_def: TypeDef) -> Result<(), &TypeDef> {
+        let name = type_def.name().to_string();
+        if self.types.contains_key(&name) {
+            return Err(&self.types[&name]);
+        }
+        self.types.insert(name, type_def);
+        Ok(())
+    }
+
+    /// Returns the definition of the struct named `name`, if there is one.
+    pub fn get_struct(&self, name: &str) -> Option<&StructDef> {
+        match self.types.get(name)? {
+            TypeDef::Struct(struct_def) => Some(struct_def),
+            TypeDef::Enum(_) => None,
+        }
+    }
+
+    /// Returns the definition of the enum named `name`, if there is one.
+    pub fn get_enum(&self, name: &str) -> Option<&EnumDef> {
+        match self.types.get(name)? {
+            TypeDef::Enum(enum_def) => Some(enum_def),
+            TypeDef::Struct(_) => None,
+        }
+    }
+
+    /// Returns the index and definition of the field named `field_name` in the struct named
+    /// `struct_name`, if there is one.
+    pub fn get_field(&self, struct_name: &str, field_name: &str) -> Option<(usize, &StructField)> {
+        self.get_struct(struct_name)?
+            .fields
+            .iter()
+            .enumerate()
+            .find(|(_, field)| field.field_name == field_name)
+    }
+
+    /// Returns the index and definition of the variant named `variant_name` in the enum named
+    /// `enum_name`, if there is one.
+    pub fn get_variant(&self, enum_name: &str, variant_name: &str) -> Option<(usize, &EnumVariant)> {
+        self.get_enum(enum_name)?
+            .variants
+            .iter()
+            .enumerate()
+            .find(|(_, variant)| variant.name == variant_name)
+    }
+
+    /// Returns whether `t` refers to a type that exists, namely a built-in type, a registered
+    /// struct or enum, or an array of (or pointer to) one of those.
+    pub fn contains(&self, t: &Type) -> bool {
+        match t {
+            Type::Named(name) => self.types.contains_key(name),
+            Type::Array(element_type, _) | Type::Pointer(element_type) => self.contains(element_type),
+            _ => true,
+        }
+    }
+
+    /// Returns the name of a type that contains itself (directly or through other types' fields
+    /// or payloads), starting the search at the type named `name`, or `None` if there's no such
+    /// cycle.
+    ///
+    /// Such types would be infinitely large, since fields, payloads and array elements are stored
+    /// by value (unlike the values that pointers point to, so a type can contain pointers to
+    /// itself).
+    pub fn find_cycle(&self, name: &str) -> Option<&str> {
+        let type_def = self.types.get(name)?;
+        self.find_cycle_from(type_def.name(), &mut Vec::new())
+    }
+
+    /// A helper for [TypeRegistry::find_cycle] that tracks the types currently being visited.
+    fn find_cycle_from<'a>(&'a self, name: &'a str, visiting: &mut Vec<&'a str>) -> Option<&'a str> {
+        if visiting.contains(&name) {
+            return Some(name);
+        }
+        let type_def = self.types.get(name)?;
+
+        visiting.push(name);
+        let cycle = type_def.contained_types().find_map(|mut t| {
+            while let Type::Array(element_type, _) = t {
+                t = element_type;
+            }
+            match t {
+                Type::Named(name) => self.find_cycle_from(name, visiting),
+                _ => None,
+            }
+        });
+        visiting.pop();
+        cycle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::IntType;
+
+    fn struct_def(name: &str, fields: Vec<(Type, &str)>) -> TypeDef {
+        TypeDef::Struct(StructDef {
+            name: name.to_string(),
+            fields: fields
+                .into_iter()
+                .map(|(field_type, field_name)| StructField { field_type, field_name: field_name.to_string() })
+                .collect(),
+            span: Span::default(),
+        })
+    }
+
+    fn enum_def(name: &str, variants: Vec<(&str, Vec<Type>)>) -> TypeDef {
+        TypeDef::Enum(EnumDef {
+            name: name.to_string(),
+            variants: variants
+                .into_iter()
+                .map(|(name, payload_types)| EnumVariant { name: name.to_string(), payload_types })
+                .collect(),
+            span: Span::default(),
+        })
+    }
+
+    #[test]
+    fn fields_and_cycles() {
+        let i64_type = Type::Int(IntType { signed: true, width: 64 });
+        let mut registry = TypeRegistry::new();
+        registry.register(struct_def("Point", vec![(i64_type.clone(), "x"), (i64_type, "y")])).unwrap();
+        registry.register(struct_def("A", vec![(Type::Named("B".to_string()), "b")])).unwrap();
+        registry.register(struct_def("B", vec![(Type::Named("A".to_string()), "a")])).unwrap();
+        let grid_type = Type::Array(Box::new(Type::Array(Box::new(Type::Named("Grid".to_string())), 2)), 2);
+        registry.register(struct_def("Grid", vec![(grid_type, "cells")])).unwrap();
+
+        assert!(registry.register(struct_def("Point", vec![])).is_err());
+        assert_eq!(registry.get_field("Point", "y").map(|(i, _)| i), Some(1));
+        assert!(registry.get_field("Point", "z").is_none());
+        assert!(!registry.contains(&Type::Named("Line".to_string())));
+        assert!(!registry.contains(&Type::Array(Box::new(Type::Named("Line".to_string())), 4)));
+        assert!(registry.contains(&Type::Array(Box::new(Type::Named("Point".to_string())), 4)));
+
+        assert_eq!(registry.find_cycle("Point"), None);
+        assert_eq!(registry.find_cycle("A"), Some("A"));
+        assert_eq!(registry.find_cycle("Grid"), Some("Grid"));
+
+        // Types can point to themselves
+        let next_type = Type::Pointer(Box::new(Type::Named("Node".to_string())));
+        registry.register(struct_def("Node", vec![(next_type.clone(), "next")])).unwrap();
+        assert!(registry.contains(&next_type));
+        assert!(!registry.contains(&Type::Pointer(Box::new(Type::Named("Line".to_string())))));
+        assert_eq!(registry.find_cycle("Node"), None);
+    }
+
+    #[test]
+    fn variants_and_cycles() {
+        let u32_type = Type::Int(IntType { signed: false, width: 32 });
+        let mut registry = TypeRegistry::new();
+        registry.register(enum_def("Shape", vec![("Circle", vec![u32_type]), ("Empty", vec![])])).unwrap();
+        registry.register(enum_def("List", vec![("Cons", vec![Type::Named("Node".to_string())]), ("Nil", vec![])])).unwrap();
+        registry.register(struct_def("Node", vec![(Type::Named("List".to_string()), "next")])).unwrap();
+
+        // Structs and enums share a namespace
+        assert!(registry.register(struct_def("Shape", vec![])).is_err());
+        assert!(registry.get_struct("Shape").is_none());
+        assert_eq!(registry.get_variant("Shape", "Empty").map(|(i, _)| i), Some(1));
+        assert!(registry.get_variant("Shape", "Rect").is_none());
+        assert!(registry.get_variant("Node", "next").is_none());
+
+        assert_eq!(registry.find_cycle("Shape"), None);
+        assert_eq!(registry.find_cycle("List"), Some("List"));
+    }
+}
@@ -1,5 +1,8 @@
-use crate::ast::{BinaryOperator, ComparisonOperator, FuncProto, UnaryOperator};
-use crate::types::IntType;
+use crate::ast::{
+    BinaryOperator, ComparisonOperator, EnumDef, FuncProto, GlobalVarKind, LogicalOperator, StructDef, UnaryOperator,
+};
+use crate::span::Span;
+use crate::types::{FloatType, IntType};
 use crate::types::Type;
 
 /// A typed version of [Program](crate::ast::Program)
@@ -12,7 +15,22 @@ pub struct TypedProgram {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum TypedGlobalStatement {
     Extern(FuncProto),
-    FuncDef(TypedFuncDef)
+    FuncDef(TypedFuncDef),
+    /// A struct definition, whose field types have all been checked
+    Struct(StructDef),
+    /// An enum definition, whose payload types have all been checked
+    Enum(EnumDef),
+    GlobalVar(TypedGlobalVar),
+}
+
+/// A typed version of [GlobalVar](crate::ast::GlobalVar), whose value has been evaluated at
+/// compile time (so it only consists of literals).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedGlobalVar {
+    pub kind: GlobalVarKind,
+    pub var_type: Type,
+    pub var_name: String,
+    pub var_value: TypedExpr,
 }
 
 /// A typed version of [crate::ast::FuncDef]
@@ -31,13 +49,27 @@ pub struct TypedFuncParam {
 
 /// A typed version of [Statement](crate::ast::Statement).
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub enum TypedStatement {
+pub struct TypedStatement {
+    pub kind: TypedStatementKind,
+    pub span: Span,
+}
+
+/// A typed version of [StatementKind](crate::ast::StatementKind).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TypedStatementKind {
     VarDeclaration(TypedVarDeclaration),
     WhileLoop(TypedWhileLoop),
+    Loop(TypedLoop),
+    ForLoop(Box<TypedForLoop>),
+    /// A `break`, with the label of the loop to exit (the innermost loop if `None`)
+    Break(Option<String>),
+    /// A `continue`, with the label of the loop to continue (the innermost loop if `None`)
+    Continue(Option<String>),
     Assignment(TypedAssignment),
     Return(Option<TypedExpr>),
     Call(TypedCall),
     If(TypedIf),
+    Match(TypedMatch<Vec<TypedStatement>>),
 
     /// Compiles to LLVM's UnreachableInst
     /// 
@@ -52,26 +84,66 @@ pub fn some_statement_always_returns(statements: &[TypedStatement]) -> bool {
     statements.iter().any(|stmt| stmt.always_returns())
 }
 
+/// Returns true if any of the statements in the slice can `break` out of the loop labeled `label`
+/// that encloses them (or out of the unlabeled loop that encloses them, if `label` is `None`).
+///
+/// `in_nested_loop` is true if the statements are inside another loop within that loop, in which
+/// case unlabeled breaks exit the nested loop instead.
+fn some_statement_breaks(statements: &[TypedStatement], label: Option<&str>, in_nested_loop: bool) -> bool {
+    use TypedStatementKind::*;
+
+    // A nested loop with the same label shadows the outer loop's label
+    let nested_label = |nested: &Option<String>| match nested.as_deref() == label {
+        true => None,
+        false => label,
+    };
+
+    statements.iter().any(|stmt| match &stmt.kind {
+        Break(None) => !in_nested_loop,
+        Break(Some(target)) => label == Some(target.as_str()),
+        If(TypedIf { then_body, else_body, .. }) => {
+            some_statement_breaks(then_body, label, in_nested_loop)
+            || else_body.as_ref().is_some_and(|body| some_statement_breaks(body, label, in_nested_loop))
+        }
+        WhileLoop(w) => some_statement_breaks(&w.body, nested_label(&w.label), true),
+        Loop(l) => some_statement_breaks(&l.body, nested_label(&l.label), true),
+        ForLoop(f) => some_statement_breaks(&f.body, nested_label(&f.label), true),
+        Match(m) => m.arms.iter().any(|arm| some_statement_breaks(&arm.body, label, in_nested_loop)),
+        _ => false,
+    })
+}
+
 
 impl TypedStatement {
     /// Returns true if this statement always returns, no matter the control flow.
     pub fn always_returns(&self) -> bool {
-        match self {
-            Self::VarDeclaration(_) | Self::Assignment(_) | Self::Call(_) => false,
-            Self::Return(_) => true,
+        use TypedStatementKind::*;
+
+        match &self.kind {
+            VarDeclaration(_) | Assignment(_) | Call(_) => false,
+            Return(_) => true,
 
-            // While loops can't always return; their condition might be false
-            Self::WhileLoop(_) => false,
+            // These jump elsewhere in the function, so control flow continues after the loop
+            Break(_) | Continue(_) => false,
+
+            // While and for loops can't always return; their condition might be false (or their
+            // range might be empty)
+            WhileLoop(_) | ForLoop(_) => false,
+            // Infinite loops never fall through, so they can only be left by returning (unless
+            // something breaks out of them)
+            Loop(l) => !some_statement_breaks(&l.body, l.label.as_deref(), false),
 
             // Without an 'else' branch, an if statement doesn't always return
-            Self::If(TypedIf { else_body: None, .. }) => false,
+            If(TypedIf { else_body: None, .. }) => false,
             // With an 'else' branch, it always returns if both branches always return
-            Self::If(TypedIf { else_body: Some(else_body), then_body, .. }) => {
+            If(TypedIf { else_body: Some(else_body), then_body, .. }) => {
                 some_statement_always_returns(else_body)
-                && some_statement_always_returns(&then_body)
+                && some_statement_always_returns(then_body)
             },
+            // Matches are exhaustive, so one of the arms always runs
+            Match(m) => m.arms.iter().all(|arm| some_statement_always_returns(&arm.body)),
 
-            Self::Unreachable => panic!("Unreachable statements should not be analyzed for always_returns"),
+            Unreachable => panic!("Unreachable statements should not be analyzed for always_returns"),
         }
     }
 }
@@ -87,10 +159,32 @@ pub struct TypedVarDeclaration {
 /// A typed version of [WhileLoop](crate::ast::WhileLoop).
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TypedWhileLoop {
+    pub label: Option<String>,
     pub condition: TypedExpr,
     pub body: Vec<TypedStatement>,
 }
 
+/// A typed version of [ForLoop](crate::ast::ForLoop).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedForLoop {
+    pub label: Option<String>,
+    pub var_type: IntType,
+    pub var_name: String,
+    pub start: TypedExpr,
+    pub end: TypedExpr,
+    pub inclusive: bool,
+    /// How much the loop variable increases by after each iteration (1 if `None`)
+    pub step: Option<TypedExpr>,
+    pub body: Vec<TypedStatement>,
+}
+
+/// A typed version of [Loop](crate::ast::Loop).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedLoop {
+    pub label: Option<String>,
+    pub body: Vec<TypedStatement>,
+}
+
 /// A typed version of [If](crate::ast::If).
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TypedIf {
@@ -101,27 +195,66 @@ pub struct TypedIf {
 
 /// A typed version of [Expr](crate::ast::Expr)
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub enum TypedExpr {
+pub struct TypedExpr {
+    pub kind: TypedExprKind,
+    pub span: Span,
+}
+
+/// A typed version of [ExprKind](crate::ast::ExprKind)
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TypedExprKind {
     Identifier(TypedIdentifier),
     IntLiteral(TypedIntLiteral),
+    FloatLiteral(TypedFloatLiteral),
     BoolLiteral(bool),
+    StrLiteral(String),
+    CharLiteral(char),
+    /// `null`, with the pointer type it's used as
+    Null(Type),
     Binary(TypedBinary),
     Comparison(TypedComparison),
+    Logical(TypedLogical),
     Call(TypedCall),
+    ArithmeticCall(TypedArithmeticCall),
     Unary(TypedUnary),
+    StructLiteral(TypedStructLiteral),
+    FieldAccess(TypedFieldAccess),
+    EnumLiteral(TypedEnumLiteral),
+    Match(TypedMatch<TypedExpr>),
+    ArrayLiteral(TypedArrayLiteral),
+    ArrayRepeat(TypedArrayRepeat),
+    Index(TypedIndex),
 }
 
 impl TypedExpr {
     /// Returns the type of this expression upon evaluation
     pub fn get_result_type(&self) -> Type {
-        match self {
-            Self::Identifier(id) => id.id_type.clone(),
-            Self::IntLiteral(int) => Type::Int(int.int_type),
-            Self::BoolLiteral(_) => Type::Bool,
-            Self::Binary(binary) => binary.result_type.clone(),
-            Self::Comparison(_) => Type::Bool,
-            Self::Call(call) => *call.function_proto.return_type.clone(),
-            Self::Unary(unary) => unary.result_type.clone(),
+        use TypedExprKind::*;
+
+        match &self.kind {
+            Identifier(id) => id.id_type.clone(),
+            IntLiteral(int) => Type::Int(int.int_type),
+            FloatLiteral(float) => Type::Float(float.float_type),
+            BoolLiteral(_) => Type::Bool,
+            StrLiteral(_) => Type::Str,
+            CharLiteral(_) => Type::Char,
+            Null(pointer_type) => pointer_type.clone(),
+            Binary(binary) => binary.result_type.clone(),
+            Comparison(_) => Type::Bool,
+            Logical(_) => Type::Bool,
+            Call(call) => *call.function_proto.return_type.clone(),
+            ArithmeticCall(call) => match call.overflow {
+                Overflow::Checked => Type::Bool,
+                Overflow::Wrapping | Overflow::Saturating => call.binary.result_type.clone(),
+            },
+            Unary(unary) => unary.result_type.clone(),
+            StructLiteral(literal) => Type::Named(literal.struct_name.clone()),
+            FieldAccess(access) => access.field_type.clone(),
+            EnumLiteral(literal) => Type::Named(literal.enum_name.clone()),
+            Match(m) => m.result_type.clone(),
+            ArrayLiteral(literal) => Type::Array(Box::new(literal.element_type.clone()), literal.elements.len() as u64),
+            ArrayRepeat(repeat) => Type::Array(Box::new(repeat.value.get_result_type()), repeat.length),
+            Index(index) => index.element_type.clone(),
         }
     }
 }
@@ -130,7 +263,110 @@ impl TypedExpr {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TypedAssignment {
     pub name: String,
+    /// The path to the part of the variable being assigned to (empty when assigning to the
+    /// variable itself)
+    pub accessors: Vec<TypedAccessor>,
+    pub value: Box<TypedExpr>,
+}
+
+/// A typed version of [Accessor](crate::ast::Accessor).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TypedAccessor {
+    /// The index of a field in its struct's definition
+    Field(usize),
+    /// The index of an array element (an expression of any integer type)
+    Index(Box<TypedExpr>),
+    /// The value (of the given type) that a pointer points to
+    Deref(Type),
+}
+
+/// A typed version of [StructLiteral](crate::ast::StructLiteral).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedStructLiteral {
+    pub struct_name: String,
+    /// The value of each field (along with the field's index in the struct's definition), in the
+    /// order they were written
+    pub fields: Vec<(usize, TypedExpr)>,
+}
+
+/// A typed version of [FieldAccess](crate::ast::FieldAccess).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedFieldAccess {
+    pub expr: Box<TypedExpr>,
+    /// The index of the field in the struct's definition
+    pub field_index: usize,
+    pub field_type: Type,
+}
+
+/// A typed version of an [array literal](crate::ast::ExprKind::ArrayLiteral).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedArrayLiteral {
+    pub elements: Vec<TypedExpr>,
+    /// The type of each element (which is needed when there are none)
+    pub element_type: Type,
+}
+
+/// A typed version of [ArrayRepeat](crate::ast::ArrayRepeat).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedArrayRepeat {
     pub value: Box<TypedExpr>,
+    pub length: u64,
+}
+
+/// A typed version of [Index](crate::ast::Index).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedIndex {
+    /// The array being indexed
+    pub expr: Box<TypedExpr>,
+    /// The index (an expression of any integer type)
+    pub index: Box<TypedExpr>,
+    pub element_type: Type,
+}
+
+/// A typed version of [EnumLiteral](crate::ast::EnumLiteral).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedEnumLiteral {
+    pub enum_name: String,
+    /// The index of the variant in the enum's definition
+    pub variant_index: usize,
+    pub payload: Vec<TypedExpr>,
+}
+
+/// A typed version of [Match](crate::ast::Match).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedMatch<Body> {
+    pub scrutinee: Box<TypedExpr>,
+    pub arms: Vec<TypedMatchArm<Body>>,
+    /// The type that a match expression evaluates to ([Type::Void] for match statements)
+    pub result_type: Type,
+}
+
+/// A typed version of [MatchArm](crate::ast::MatchArm).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedMatchArm<Body> {
+    pub pattern: TypedPattern,
+    pub body: Body,
+}
+
+/// A typed version of [Pattern](crate::ast::Pattern).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TypedPattern {
+    Wildcard,
+    /// The integers from `start` to `end`, both included (so a single integer has `start == end`)
+    IntRange { start: i128, end: i128 },
+    Bool(bool),
+    /// A variant of an enum (its index in the enum's definition), and the variables that parts of
+    /// its payload are bound to
+    EnumVariant { variant_index: usize, bindings: Vec<PatternBinding> },
+}
+
+/// A variable bound to part of an enum variant's payload by a [TypedPattern].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PatternBinding {
+    /// The index of the bound value in the variant's payload
+    pub payload_index: usize,
+    pub name: String,
+    pub binding_type: Type,
 }
 
 /// A typed version of [Binary](crate::ast::Binary).
@@ -142,13 +378,20 @@ pub struct TypedBinary {
     pub result_type: Type,
 }
 
-/// A typed version of [IntLiteral](crate::ast::Expr::IntLiteral)
+/// A typed version of [IntLiteral](crate::ast::ExprKind::IntLiteral)
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TypedIntLiteral {
     pub int_value: String,
     pub int_type: IntType,
 }
 
+/// A typed version of [FloatLiteral](crate::ast::ExprKind::FloatLiteral)
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedFloatLiteral {
+    pub float_value: String,
+    pub float_type: FloatType,
+}
+
 /// A comparison expression (the operator and the left/right-hand sides).
 ///
 /// For example, `a < foo(1)` breaks down into:
@@ -163,6 +406,14 @@ pub struct TypedComparison {
     pub operand_type: Type,
 }
 
+/// A typed version of [Logical](crate::ast::Logical); both sides are always `bool`s.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedLogical {
+    pub left: Box<TypedExpr>,
+    pub operator: LogicalOperator,
+    pub right: Box<TypedExpr>,
+}
+
 /// A call expression (the name of the function to call and the arguments to pass).
 ///
 /// For example, `foo(a, 12 - b, "test")` is a call expression with 3 args.
@@ -173,6 +424,32 @@ pub struct TypedCall {
     pub args: Vec<TypedExpr>,
 }
 
+/// How a built-in arithmetic function, like `wrapping_add`, handles a result that doesn't fit in
+/// its integer type.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Overflow {
+    /// The result wraps around, keeping only its lowest bits (like `+` does in release builds)
+    Wrapping,
+    /// The result isn't stored, and the function returns `false` instead of `true`
+    Checked,
+    /// The result is clamped to the smallest or largest value of the type
+    Saturating,
+}
+
+/// A call to a built-in integer arithmetic function, like `saturating_sub(a, b)`, which computes
+/// the binary expression `a - b` with the given [Overflow] behavior.
+///
+/// `checked_` functions take a pointer as a third argument, like `checked_mul(a, b, &product)`,
+/// which the result is stored into unless it overflows. They return whether it didn't.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypedArithmeticCall {
+    pub function_name: String,
+    pub overflow: Overflow,
+    pub binary: TypedBinary,
+    /// Where `checked_` functions store their result
+    pub result_ptr: Option<Box<TypedExpr>>,
+}
+
 /// An identifier, like `x` or `cur_count`, along with its type.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TypedIdentifier {
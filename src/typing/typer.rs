@@ -1,205 +1,895 @@
 use crate::ast::{
-    Assignment, Binary, Call, Comparison, Expr, FuncDef, FuncProto, FuncVisibility,
-    GlobalStatement, If, Program, Statement, Unary, UnaryOperator, VarDeclaration,
-    WhileLoop,
+    Accessor, ArrayRepeat, Assignment, Binary, BinaryOperator, Call, Comparison, EnumDef, EnumLiteral, Expr, ExprKind, FieldAccess, FuncDef, FuncProto,
+    FuncVisibility, ForLoop, GlobalStatement, GlobalVar, GlobalVarKind, If, Index, Logical, Loop, Match, Pattern, PatternKind, Program, StructDef,
+    Let, StructLiteral, Statement, StatementKind, Unary, UnaryOperator, VarDeclaration, WhileLoop,
 };
+use crate::diagnostics::lint::{UNREACHABLE_CODE, UNUSED_VARIABLES};
+use crate::diagnostics::{Diagnostic, DiagnosticSink};
+use crate::error::{ErrorKind, FlickError, TypingError};
 use crate::scope_manager::ScopeManager;
+use crate::span::Span;
 use crate::typed_ast::{
-    TypedAssignment, TypedBinary, TypedCall, TypedComparison, TypedExpr, TypedFuncDef,
-    TypedGlobalStatement, TypedIdentifier, TypedIf, TypedIntLiteral, TypedProgram, TypedStatement,
-    TypedUnary, TypedVarDeclaration, TypedWhileLoop, some_statement_always_returns,
+    Overflow, TypedAccessor, TypedArithmeticCall, TypedArrayLiteral, TypedArrayRepeat, TypedAssignment, TypedBinary, TypedCall, TypedComparison, TypedExpr, TypedExprKind, TypedFieldAccess,
+    TypedEnumLiteral, TypedForLoop, TypedFuncDef, TypedGlobalStatement, TypedGlobalVar, TypedMatch, TypedMatchArm, TypedPattern,
+    PatternBinding, TypedStructLiteral, TypedIdentifier, TypedFloatLiteral, TypedIf, TypedIndex, TypedIntLiteral, TypedLogical, TypedLoop, TypedProgram,
+    TypedStatement, TypedStatementKind, TypedUnary, TypedVarDeclaration, TypedWhileLoop,
+    some_statement_always_returns,
 };
-use crate::types::IntType;
+use crate::types::{FloatType, IntType};
 use crate::types::Type;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::big_uint::BigUint;
+use super::const_eval;
+use super::exhaustiveness;
+use super::type_registry::{TypeDef, TypeRegistry};
 
 /// This struct handles the conversion from a regular [abstract syntax tree](crate::ast) to a
 /// [typed abstract syntax tree](crate::typed_ast). See [Typer::type_program] for details.
 pub struct Typer {
     scope_manager: ScopeManager<Type>,
+    /// The user-defined types (structs and enums) of the program being typed
+    types: TypeRegistry,
+    /// The global variables declared so far, by name (see [Typer::type_global_var])
+    global_vars: HashMap<String, GlobalVarInfo>,
+    /// Where each variable was declared and whether it has been read, kept in lockstep with
+    /// `scope_manager` (see [Typer::enter_scope])
+    var_usages: ScopeManager<VarUsage>,
+    /// The integer variables of the current function whose types are inferred from their uses,
+    /// indexed by the number in their [Type::InferredInt] (see [Typer::type_let])
+    inferred_ints: Vec<InferredInt>,
+    /// The spans of the `let` declarations of the current function whose types are being
+    /// inferred, along with the numbers of their inferred integer variables
+    inferred_lets: Vec<(Span, usize)>,
+    /// The types inferred for the `let` declarations of the current function (by the span of the
+    /// declaration), which they're given when the function is typed again (see
+    /// [Typer::type_func_def])
+    let_types: HashMap<Span, Type>,
+    /// Whether the type of an inferred integer variable was needed before it was known, so that
+    /// the current function has to be typed again once it is (see [Typer::check_inferred])
+    needs_retyping: bool,
+    /// The labels of the loops enclosing the current statement, from outermost to innermost
+    /// (`None` for unlabeled loops)
+    loop_labels: Vec<Option<String>>,
+    /// Errors from statements that the typer has already skipped over
+    errors: Vec<FlickError>,
+    /// Non-fatal findings, like unused variables
+    sink: DiagnosticSink,
+}
+
+/// Where a variable was declared, whether it has been read since, and whether it can be
+/// assigned to.
+struct VarUsage {
+    span: Span,
+    used: bool,
+    is_mutable: bool,
+}
+
+/// An integer variable declared with `let`, whose exact integer type is inferred from the first
+/// use that requires one.
+struct InferredInt {
+    var_name: String,
+    /// The variable's type, once it's known ([Type::Error] if it couldn't be inferred, and that
+    /// has already been reported)
+    var_type: Option<Type>,
+    /// Another inferred variable that this one must have the same type as (like `y` for `x` in
+    /// `x + y`), which holds the type of both instead (see [Typer::root])
+    same_as: Option<usize>,
+    /// The values stored in the variable before its type was known, in order, which are typed
+    /// once the rest of the function has been typed (see [Typer::type_deferred_values]), along
+    /// with the variables that were in scope for each
+    deferred_values: VecDeque<(Expr, ScopeManager<Type>)>,
+}
+
+/// What the typer needs to know about a global variable to resolve its uses.
+struct GlobalVarInfo {
+    kind: GlobalVarKind,
+    span: Span,
+    /// The value of a constant, which replaces each use of it (`None` for statics, and for
+    /// constants whose value failed to type-check)
+    const_value: Option<TypedExpr>,
 }
 
 impl Typer {
     pub fn new() -> Self {
-        let scope_manager = ScopeManager::new();
-        Self { scope_manager }
+        Self::with_sink(DiagnosticSink::default())
+    }
+
+    /// Creates a typer that reports non-fatal findings (like warnings) into `sink`, which
+    /// decides how each lint is reported.
+    pub fn with_sink(sink: DiagnosticSink) -> Self {
+        Self {
+            scope_manager: ScopeManager::new(),
+            types: TypeRegistry::new(),
+            global_vars: HashMap::new(),
+            var_usages: ScopeManager::new(),
+            inferred_ints: Vec::new(),
+            inferred_lets: Vec::new(),
+            let_types: HashMap::new(),
+            needs_retyping: false,
+            loop_labels: Vec::new(),
+            errors: Vec::new(),
+            sink,
+        }
+    }
+
+    /// Returns the non-fatal diagnostics (like warnings) found while typing.
+    pub fn sink(&self) -> &DiagnosticSink {
+        &self.sink
     }
 
     /// This method goes through the entire `program` and converts it to a [TypedProgram],
-    /// panicking if any type mismatches or undefined identifiers are uncovered.
+    /// returning a [TypingError] for every type mismatch or undefined identifier uncovered.
     ///
-    /// In the future, this method may also coerce types as necessary, such as when `i32` and
-    /// `i32` are summed and placed into an `i64` (currently, programs can only store `i32 + i32`
-    /// in another `i32`).
+    /// A statement that fails to type-check is skipped so that the rest of the program can still
+    /// be checked. Names involved in such errors are given the type [Type::Error], which
+    /// suppresses any further errors that they would otherwise cause.
+    ///
+    /// Findings that don't stop the program from being compiled, like unused variables, are
+    /// reported into the typer's [sink](Typer::sink) instead.
+    ///
+    /// Integers are implicitly widened where a wider integer type is expected, like when `i32`
+    /// and `i32` are summed and placed into an `i64`, by inserting casts into the typed program.
     ///
     /// # Assumptions
     ///
-    /// This method assumes that `program` represents a well-parsed program; for example, one
-    /// returned by [Parser::parse_program()](crate::Parser::parse_program).
-    pub fn type_program(&mut self, program: &Program) -> TypedProgram {
+    /// This method assumes that `program` was returned by
+    /// [Parser::parse_program()](crate::Parser::parse_program). If parsing failed, it's missing the
+    /// statements that didn't parse, so uses of what they declared are reported as errors too.
+    pub fn type_program(&mut self, program: &Program) -> Result<TypedProgram, Vec<FlickError>> {
         let mut global_statements = Vec::with_capacity(program.global_statements.len());
 
-        self.scope_manager.enter_scope();
+        // Types are registered first, so that they can be used anywhere in the program
+        for global_statement in program.global_statements.iter() {
+            let registered = match global_statement {
+                GlobalStatement::Struct(struct_def) => self.register_type(TypeDef::Struct(struct_def.clone())),
+                GlobalStatement::Enum(enum_def) => self.register_type(TypeDef::Enum(enum_def.clone())),
+                _ => continue,
+            };
+            self.record(registered);
+        }
         for global_statement in program.global_statements.iter() {
-            match global_statement {
+            let registered = match global_statement {
                 GlobalStatement::Extern(proto) => self.register_func_proto(proto),
                 GlobalStatement::FuncDef(f) => self.register_func_proto(&f.proto),
+                GlobalStatement::Struct(struct_def) => self.check_valid_struct(struct_def),
+                GlobalStatement::Enum(enum_def) => self.check_valid_enum(enum_def),
+                GlobalStatement::GlobalVar(_) => continue,
+            };
+            self.record(registered);
+        }
+        // Global variables are typed once every function is known, so that calls in their values
+        // are reported as non-constant (rather than undefined)
+        for global_statement in program.global_statements.iter() {
+            if let GlobalStatement::GlobalVar(global_var) = global_statement {
+                let typed_global_var = self.type_global_var(global_var);
+                if let Some(global_var) = self.record(typed_global_var) {
+                    global_statements.push(TypedGlobalStatement::GlobalVar(global_var));
+                }
             }
         }
         for global_statement in program.global_statements.iter() {
-            global_statements.push(self.type_global_statement(global_statement))
+            // Global variables were already typed
+            if let GlobalStatement::GlobalVar(_) = global_statement {
+                continue;
+            }
+            let typed_global_statement = self.type_global_statement(global_statement);
+            if let Some(s) = self.record(typed_global_statement) {
+                global_statements.push(s);
+            }
+        }
+        let valid_main_func = self.check_valid_main_func();
+        self.record(valid_main_func);
+
+        match self.errors.is_empty() {
+            true => Ok(TypedProgram { global_statements }),
+            false => Err(std::mem::take(&mut self.errors)),
+        }
+    }
+
+    /// Returns the value inside of `result`, or records the error and returns `None`.
+    fn record<T>(&mut self, result: crate::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.errors.push(err);
+                None
+            }
         }
-        self.check_valid_main_func();
+    }
+
+    /// Enters a new scope for both types and variable usages.
+    fn enter_scope(&mut self) {
+        self.scope_manager.enter_scope();
+        self.var_usages.enter_scope();
+    }
+
+    /// Exits the current scope, reporting any variables declared in it that were never read.
+    fn exit_scope(&mut self) {
         self.scope_manager.exit_scope();
 
-        TypedProgram { global_statements }
+        let mut unused: Vec<_> = self
+            .var_usages
+            .exit_scope()
+            .into_iter()
+            .filter(|(name, usage)| !usage.used && !name.starts_with('_'))
+            .collect();
+        unused.sort_by_key(|(_, usage)| usage.span.start.offset);
+
+        for (name, usage) in unused {
+            let diagnostic = Diagnostic::new(format!("unused variable '{}'", name), usage.span)
+                .with_help(format!("if this is intentional, rename it to '_{}'", name));
+            self.sink.lint(&UNUSED_VARIABLES, diagnostic);
+        }
+    }
+
+    /// Wraps a [TypingError] as a [FlickError] located at `span`.
+    fn err(kind: TypingError, span: Span) -> FlickError {
+        FlickError {
+            span,
+            kind: ErrorKind::TypingError(Box::new(kind)),
+        }
     }
 
-    fn check_valid_main_func(&self) {
+    fn check_valid_main_func(&self) -> crate::Result<()> {
         let func_proto = match self.scope_manager.get("main") {
             Some(Type::Func(proto)) => proto,
-            Some(t) => panic!("Expected 'main' to be a function; found 'main' to be of type {}", t),
-            None => panic!("No main function defined")
+            Some(t) => return Err(Self::err(TypingError::MainNotAFunction(t.clone()), Span::default())),
+            None => return Err(Self::err(TypingError::MissingMain, Span::default())),
         };
 
         if func_proto.func_visibility != FuncVisibility::Public {
-            panic!("The 'main' function should be public");
+            return Err(Self::err(TypingError::MainNotPublic, func_proto.span));
         }
 
         if !func_proto.params.is_empty() {
-            panic!("The 'main' function should not accept any parameters");
+            return Err(Self::err(TypingError::MainHasParams, func_proto.span));
         }
 
         if *func_proto.return_type != Type::Int(IntType { width: 8, signed: false }) {
-            panic!("The 'main' function should return a u8");
+            return Err(Self::err(
+                TypingError::InvalidMainReturnType(*func_proto.return_type.clone()),
+                func_proto.span,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// This method adds a struct or enum to the type registry, confirming the type isn't being
+    /// redefined.
+    fn register_type(&mut self, type_def: TypeDef) -> crate::Result<()> {
+        let (name, span) = (type_def.name().to_string(), type_def.span());
+        match self.types.register(type_def) {
+            Ok(()) => Ok(()),
+            Err(previous) => Err(Self::err(
+                TypingError::TypeRedefinition { name, previous_definition: previous.span() },
+                span,
+            )),
+        }
+    }
+
+    /// This method checks that a struct's fields have distinct names and valid types, and that the
+    /// struct doesn't contain itself.
+    fn check_valid_struct(&self, struct_def: &StructDef) -> crate::Result<()> {
+        let struct_name = &struct_def.name;
+        let mut field_names = HashSet::new();
+        for field in struct_def.fields.iter() {
+            if !field_names.insert(&field.field_name) {
+                return Err(Self::err(
+                    TypingError::DuplicateField {
+                        struct_name: struct_name.clone(),
+                        field_name: field.field_name.clone(),
+                    },
+                    struct_def.span,
+                ));
+            }
+            self.check_valid_type(&field.field_type, struct_def.span)?;
+            if field.field_type == Type::Void {
+                return Err(Self::err(
+                    TypingError::InvalidFieldType {
+                        struct_name: struct_name.clone(),
+                        field_name: field.field_name.clone(),
+                        field_type: Type::Void,
+                    },
+                    struct_def.span,
+                ));
+            }
+        }
+
+        if self.types.find_cycle(struct_name) == Some(struct_name) {
+            return Err(Self::err(TypingError::RecursiveType(struct_name.clone()), struct_def.span));
+        }
+        Ok(())
+    }
+
+    /// This method checks that an enum's variants have distinct names and valid payload types,
+    /// and that the enum doesn't contain itself.
+    fn check_valid_enum(&self, enum_def: &EnumDef) -> crate::Result<()> {
+        let enum_name = &enum_def.name;
+        let mut variant_names = HashSet::new();
+        for variant in enum_def.variants.iter() {
+            if !variant_names.insert(&variant.name) {
+                return Err(Self::err(
+                    TypingError::DuplicateVariant {
+                        enum_name: enum_name.clone(),
+                        variant_name: variant.name.clone(),
+                    },
+                    enum_def.span,
+                ));
+            }
+            for payload_type in variant.payload_types.iter() {
+                self.check_valid_type(payload_type, enum_def.span)?;
+                if *payload_type == Type::Void {
+                    return Err(Self::err(
+                        TypingError::InvalidPayloadType {
+                            variant_name: format!("{}::{}", enum_name, variant.name),
+                            payload_type: Type::Void,
+                        },
+                        enum_def.span,
+                    ));
+                }
+            }
+        }
+
+        if self.types.find_cycle(enum_name) == Some(enum_name) {
+            return Err(Self::err(TypingError::RecursiveType(enum_name.clone()), enum_def.span));
+        }
+        Ok(())
+    }
+
+    /// Returns an error if `t` is a struct or enum type that hasn't been defined, an integer type
+    /// without any bits (like `u0`), or an array whose elements can't have values (like
+    /// `[void; 4]`), or a pointer to one of those.
+    fn check_valid_type(&self, t: &Type, span: Span) -> crate::Result<()> {
+        match t {
+            Type::Named(name) if !self.types.contains(t) => {
+                Err(Self::err(TypingError::UndefinedType(name.clone()), span))
+            }
+            Type::Array(element_type, _) if **element_type == Type::Void => {
+                Err(Self::err(TypingError::InvalidElementType(Type::Void), span))
+            }
+            Type::Array(element_type, _) => self.check_valid_type(element_type, span),
+            Type::Pointer(pointee_type) if **pointee_type == Type::Void => Ok(()),
+            Type::Pointer(pointee_type) => self.check_valid_type(pointee_type, span),
+            Type::Int(int_type) if int_type.width == 0 => Err(Self::err(TypingError::ZeroWidthInt(*int_type), span)),
+            Type::Int(int_type) if int_type.width > IntType::MAX_WIDTH => {
+                Err(Self::err(TypingError::TooWideInt(*int_type), span))
+            }
+            _ => Ok(()),
         }
     }
 
     /// This method processes the prototype of a function, updating the internal scope manager
     /// and confirming the function isn't being redeclared.
-    fn register_func_proto(&mut self, func_proto: &FuncProto) {
+    ///
+    /// The function is registered even if its prototype uses undefined types (which are reported),
+    /// to avoid cascading errors at its call sites.
+    fn register_func_proto(&mut self, func_proto: &FuncProto) -> crate::Result<()> {
         let func_name = &func_proto.name;
-        match self.scope_manager.get(func_name) {
-            Some(Type::Func(_)) => panic!("Cannot redefine function '{}'", func_name),
-            Some(_) => panic!(
-                "Cannot define function '{}' because variable with same name already exists",
-                func_name
-            ),
-            None => {}
+        // Only functions live in the global scope so far (global variables are registered after
+        // them), so any existing entry is a redefinition
+        if let Some(Type::Func(previous)) = self.scope_manager.get_global(func_name) {
+            return Err(Self::err(
+                TypingError::FunctionRedefinition {
+                    name: func_name.clone(),
+                    previous_definition: previous.span,
+                },
+                func_proto.span,
+            ));
         }
 
-        let func_type = Type::Func(func_proto.clone());
+        let func_type = Type::Func(Box::new(func_proto.clone()));
         self.scope_manager.set(func_name, func_type);
+
+        let param_types = func_proto.params.iter().map(|p| &p.param_type);
+        param_types
+            .chain(std::iter::once(func_proto.return_type.as_ref()))
+            .try_for_each(|t| self.check_valid_type(t, func_proto.span))
     }
 
-    fn type_global_statement(&mut self, global_statement: &GlobalStatement) -> TypedGlobalStatement {
-        match global_statement {
-            GlobalStatement::Extern(proto) => TypedGlobalStatement::Extern(proto.clone()),
-            GlobalStatement::FuncDef(f) => TypedGlobalStatement::FuncDef(self.type_func_def(f)),
+    /// This method types a global variable's value and evaluates it at compile time (see
+    /// [const_eval::evaluate]), declaring the variable in the global scope.
+    ///
+    /// Like a function, the variable is declared even if its value fails to type-check (as
+    /// [Type::Error]), to avoid cascading errors where it's used. Global variables can only use
+    /// the constants declared before them, and can't have the same name as a function (or
+    /// another global variable).
+    fn type_global_var(&mut self, global_var: &GlobalVar) -> crate::Result<TypedGlobalVar> {
+        let var_name = &global_var.var_name;
+        let previous_definition = match (self.global_vars.get(var_name), self.scope_manager.get_global(var_name)) {
+            (Some(previous), _) => Some(previous.span),
+            (None, Some(Type::Func(previous))) => Some(previous.span),
+            _ => None,
+        };
+        if let Some(previous_definition) = previous_definition {
+            return Err(Self::err(
+                TypingError::GlobalRedefinition { name: var_name.clone(), previous_definition },
+                global_var.span,
+            ));
+        }
+
+        let var_value = self.type_const_expr(&global_var.var_value, &global_var.var_type, global_var.span);
+        let var_type = match var_value {
+            Ok(_) => global_var.var_type.clone(),
+            Err(_) => Type::Error,
+        };
+        self.scope_manager.set(var_name, var_type.clone());
+        let const_value = match global_var.kind {
+            GlobalVarKind::Const => var_value.as_ref().ok().cloned(),
+            GlobalVarKind::StaticMut { .. } => None,
+        };
+        let info = GlobalVarInfo { kind: global_var.kind, span: global_var.span, const_value };
+        self.global_vars.insert(var_name.clone(), info);
+
+        Ok(TypedGlobalVar {
+            kind: global_var.kind,
+            var_type,
+            var_name: var_name.clone(),
+            var_value: var_value?,
+        })
+    }
+
+    /// Types `expr` as `expr_type` and evaluates it at compile time.
+    fn type_const_expr(&mut self, expr: &Expr, expr_type: &Type, span: Span) -> crate::Result<TypedExpr> {
+        self.check_valid_type(expr_type, span)?;
+        let typed_expr = self.type_expr(expr, Some(expr_type))?;
+        const_eval::evaluate(&typed_expr)
+    }
+
+    /// Returns whether `name` refers to a constant (rather than a variable that shadows it).
+    fn is_const(&self, name: &str) -> bool {
+        let global_var = self.global_vars.get(name).filter(|_| self.scope_manager.is_global(name));
+        matches!(global_var, Some(GlobalVarInfo { kind: GlobalVarKind::Const, .. }))
+    }
+
+    /// Returns the value of the constant that `name` refers to, if it does (and if the
+    /// constant's value type-checked).
+    fn const_value(&self, name: &str) -> Option<&TypedExpr> {
+        match self.is_const(name) {
+            true => self.global_vars[name].const_value.as_ref(),
+            false => None,
         }
     }
 
+    fn type_global_statement(&mut self, global_statement: &GlobalStatement) -> crate::Result<TypedGlobalStatement> {
+        Ok(match global_statement {
+            GlobalStatement::Extern(proto) => TypedGlobalStatement::Extern(proto.clone()),
+            GlobalStatement::FuncDef(f) => TypedGlobalStatement::FuncDef(self.type_func_def(f)?),
+            GlobalStatement::Struct(struct_def) => TypedGlobalStatement::Struct(struct_def.clone()),
+            GlobalStatement::Enum(enum_def) => TypedGlobalStatement::Enum(enum_def.clone()),
+            GlobalStatement::GlobalVar(_) => unreachable!("global variables are typed before other statements"),
+        })
+    }
+
     /// This method processes a function definition by processing each statement within the body,
     /// and by confirming that it always returns the correct type (according to its prototype).
-    fn type_func_def(&mut self, func_def: &FuncDef) -> TypedFuncDef {
-        self.scope_manager.enter_scope();
-
-        for param in func_def.proto.params.iter() {
-            let param_name = &param.param_name;
-            let param_type = param.param_type.clone();
-            self.scope_manager.set(param_name, param_type);
+    ///
+    /// If the type of a variable declared with `let` was needed before it was inferred (like
+    /// `i` in `i < 10`, before `ret i` decides that it's a `u8`), the body is typed again once
+    /// every variable's type is known, without the errors and warnings of the first attempt.
+    fn type_func_def(&mut self, func_def: &FuncDef) -> crate::Result<TypedFuncDef> {
+        let previous_error_count = self.errors.len();
+        let previous_diagnostic_count = self.sink.diagnostics().len();
+        let mut func_body = self.type_func_body(func_def);
+        // This terminates: an attempt only asks for another one if it needed the type of an
+        // inferred integer variable, which only a `let` declaration without a type in `let_types`
+        // creates, and every such declaration is added to `let_types` at the end of the attempt.
+        // So each attempt knows the types of more declarations than the last one, and there can
+        // be at most one more attempt than there are `let` declarations in the function.
+        while std::mem::take(&mut self.needs_retyping) {
+            self.errors.truncate(previous_error_count);
+            self.sink.truncate(previous_diagnostic_count);
+            func_body = self.type_func_body(func_def);
         }
+        self.let_types.clear();
 
-        let mut func_body = self.type_body(&func_def.body, &func_def.proto.return_type);
+        // Statements inserted by the typer are attributed to the function's prototype
+        let implicit_statement = |kind| TypedStatement {
+            kind,
+            span: func_def.proto.span,
+        };
 
         if Type::Void == *func_def.proto.return_type {
             // Void functions: implicitly return to make sure the basic block is terminated
             match func_body.last() {
-                Some(TypedStatement::Return(_)) => {}
-                _ => func_body.push(TypedStatement::Return(None)),
+                Some(TypedStatement { kind: TypedStatementKind::Return(_), .. }) => {}
+                _ => func_body.push(implicit_statement(TypedStatementKind::Return(None))),
             }
         } else {
-            // Non-void functions: make sure all control paths lead to a return
-            if !some_statement_always_returns(&func_body) {
-                panic!("Function '{}' does not always return a value", func_def.proto.name);
+            // Non-void functions: make sure all control paths lead to a return (unless the body
+            // had errors, since the skipped statements might have been the returns)
+            let body_had_errors = self.errors.len() > previous_error_count;
+            if !body_had_errors && !some_statement_always_returns(&func_body) {
+                return Err(Self::err(
+                    TypingError::MissingReturn { function_name: func_def.proto.name.clone() },
+                    func_def.proto.span,
+                ));
             }
 
-           if !func_body.last().is_some_and(|s| s.always_returns()) {
-                func_body.push(TypedStatement::Unreachable)
-           }
+            // The last statement might always return without being a `ret` itself (like an
+            // if-else that returns in both branches, or an infinite loop), in which case the
+            // block that follows it must still be terminated
+            match func_body.last() {
+                Some(TypedStatement { kind: TypedStatementKind::Return(_), .. }) => {}
+                _ => func_body.push(implicit_statement(TypedStatementKind::Unreachable)),
+            }
         }
 
-        self.scope_manager.exit_scope();
-
-        TypedFuncDef {
+        Ok(TypedFuncDef {
             proto: func_def.proto.clone(),
             body: func_body,
+        })
+    }
+
+    /// Types the body of a function (see [Typer::type_func_def]), inferring the types of its
+    /// `let` declarations, which are added to `let_types` in case the body is typed again.
+    fn type_func_body(&mut self, func_def: &FuncDef) -> Vec<TypedStatement> {
+        self.enter_scope();
+
+        for param in func_def.proto.params.iter() {
+            let param_name = &param.param_name;
+            // Undefined parameter types were already reported when the prototype was registered
+            let param_type = match self.types.contains(&param.param_type) {
+                true => param.param_type.clone(),
+                false => Type::Error,
+            };
+            self.scope_manager.set(param_name, param_type);
+        }
+
+        let mut func_body = self.type_body(&func_def.body, &func_def.proto);
+        self.exit_scope();
+        self.default_inferred_ints();
+        self.type_deferred_values(&mut func_body);
+
+        for (let_span, id) in std::mem::take(&mut self.inferred_lets) {
+            let var_type = self.resolve_inferred(&Type::InferredInt(id));
+            self.let_types.insert(let_span, var_type);
         }
+        self.inferred_ints.clear();
+        func_body
     }
 
     /// This method processes a statement and makes sure that its internals are well-typed.
     ///
     /// # Notes
     ///
-    /// This method takes `function_return_type` so that it can type-check return statements, or
-    /// statements like while loops that might contain return statements.
+    /// This method takes `func_proto` (the prototype of the enclosing function) so that it can
+    /// type-check return statements, or statements like while loops that might contain return
+    /// statements.
     fn type_statement(
         &mut self,
         statement: &Statement,
-        function_return_type: &Type,
-    ) -> TypedStatement {
-        match statement {
-            Statement::VarDeclaration(v) => {
-                TypedStatement::VarDeclaration(self.type_var_declaration(v))
+        func_proto: &FuncProto,
+    ) -> crate::Result<TypedStatement> {
+        let span = statement.span;
+        let kind = match &statement.kind {
+            StatementKind::VarDeclaration(v) => {
+                TypedStatementKind::VarDeclaration(self.type_var_declaration(v, span)?)
             }
-            Statement::WhileLoop(w) => {
-                TypedStatement::WhileLoop(self.type_while_loop(w, function_return_type))
+            StatementKind::Let(l) => TypedStatementKind::VarDeclaration(self.type_let(l, span)?),
+            StatementKind::WhileLoop(w) => {
+                TypedStatementKind::WhileLoop(self.type_while_loop(w, func_proto)?)
             }
-            Statement::Assignment(a) => TypedStatement::Assignment(self.type_assignment(a)),
-            Statement::Return(r) => {
-                TypedStatement::Return(self.type_return(r.as_ref(), function_return_type))
+            StatementKind::Loop(l) => TypedStatementKind::Loop(self.type_loop(l, func_proto)),
+            StatementKind::ForLoop(f) => {
+                TypedStatementKind::ForLoop(Box::new(self.type_for_loop(f, span, func_proto)?))
+            }
+            StatementKind::Break(label) => {
+                self.check_valid_jump("break", label.as_deref(), span)?;
+                TypedStatementKind::Break(label.clone())
+            }
+            StatementKind::Continue(label) => {
+                self.check_valid_jump("continue", label.as_deref(), span)?;
+                TypedStatementKind::Continue(label.clone())
+            }
+            StatementKind::Assignment(a) => {
+                TypedStatementKind::Assignment(self.type_assignment(a, span)?)
+            }
+            StatementKind::Return(r) => {
+                TypedStatementKind::Return(self.type_return(r.as_ref(), span, func_proto)?)
             }
             // Below, the desired_type of the call is None because the value returned by the call is never used
-            Statement::Call(c) => TypedStatement::Call(self.type_call(c, None)),
-            Statement::If(i) => TypedStatement::If(self.type_if_statement(i, function_return_type)),
-        }
+            StatementKind::Call(c) => TypedStatementKind::Call(self.type_call(c, span, None)?),
+            StatementKind::If(i) => TypedStatementKind::If(self.type_if_statement(i, func_proto)?),
+            StatementKind::Match(m) => {
+                TypedStatementKind::Match(self.type_match(m, span, |typer, body| Ok(typer.type_body(body, func_proto)))?)
+            }
+        };
+
+        Ok(TypedStatement { kind, span })
     }
 
     /// This method checks that the variable introduced by `var_declaration` is being set to a value
     /// of its declared type.
-    fn type_var_declaration(&mut self, var_declaration: &VarDeclaration) -> TypedVarDeclaration {
+    fn type_var_declaration(
+        &mut self,
+        var_declaration: &VarDeclaration,
+        span: Span,
+    ) -> crate::Result<TypedVarDeclaration> {
         let var_name = var_declaration.var_name.clone();
         let var_type = var_declaration.var_type.clone();
+        if let Err(err) = self.check_valid_type(&var_type, span) {
+            self.scope_manager.set(&var_name, Type::Error);
+            return Err(err);
+        }
+        if var_type == Type::Void {
+            self.scope_manager.set(&var_name, Type::Error);
+            return Err(Self::err(TypingError::InvalidVarType(var_type), span));
+        }
         let var_value = self.type_expr(&var_declaration.var_value, Some(&var_type));
+        // The variable is declared even if its value is ill-typed, to avoid cascading errors
         self.scope_manager.set(&var_name, var_type.clone());
+        self.var_usages.set(&var_name, VarUsage { span, used: false, is_mutable: true });
+        let var_value = var_value?;
 
-        TypedVarDeclaration {
+        Ok(TypedVarDeclaration {
             var_name,
             var_type,
             var_value,
+        })
+    }
+
+    /// This method declares the variable introduced by `let_declaration`, whose type is the type
+    /// of its value.
+    ///
+    /// A value made only of integer literals (like `5` or `-(1 << 3)`) could have any integer
+    /// type, so the variable's type is instead inferred from the first use that requires a
+    /// particular one, like being passed to a function or compared with a typed variable, and
+    /// defaults to `u64` or `i64` if no use does (see [Typer::default_inferred_ints]). The typed
+    /// declaration holds a placeholder until then (see [Typer::defer_value]). When the function
+    /// is typed again (see [Typer::type_func_def]), the inferred type is used instead.
+    fn type_let(&mut self, let_declaration: &Let, span: Span) -> crate::Result<TypedVarDeclaration> {
+        let var_name = let_declaration.var_name.clone();
+        let inferred_type = self.let_types.get(&span).cloned();
+        let var_value = if let Some(var_type) = &inferred_type {
+            self.type_expr(&let_declaration.var_value, Some(var_type))
+        } else if Self::default_int_type(&let_declaration.var_value).is_some() {
+            self.inferred_ints.push(InferredInt {
+                var_name: var_name.clone(),
+                var_type: None,
+                same_as: None,
+                deferred_values: VecDeque::new(),
+            });
+            Ok(self.defer_value(self.inferred_ints.len() - 1, &let_declaration.var_value))
+        } else {
+            self.type_inferable_expr(&let_declaration.var_value, None).and_then(|value| match value.get_result_type() {
+                // The variable has the same type as the inferred variable in its value (like
+                // `let y = x + 1`)
+                Type::InferredInt(id) => Ok(self.defer_value(id, &let_declaration.var_value)),
+                t @ (Type::Void | Type::Func(_)) => Err(Self::err(TypingError::InvalidVarType(t), span)),
+                _ => Ok(value),
+            })
+        };
+        if let Ok(Type::InferredInt(id)) = var_value.as_ref().map(|value| value.get_result_type()) {
+            self.inferred_lets.push((span, id));
+        }
+        // The variable is declared even if its value is ill-typed, to avoid cascading errors
+        let var_type = match (inferred_type, &var_value) {
+            (Some(var_type), _) => var_type,
+            (_, Ok(value)) => value.get_result_type(),
+            (_, Err(_)) => Type::Error,
+        };
+        self.scope_manager.set(&var_name, var_type.clone());
+        let is_mutable = let_declaration.is_mutable;
+        self.var_usages.set(&var_name, VarUsage { span, used: false, is_mutable });
+
+        Ok(TypedVarDeclaration {
+            var_name,
+            var_type,
+            var_value: var_value?,
+        })
+    }
+
+    /// If `expr` only consists of integer literals (and operators applied to them), meaning that
+    /// it could have any integer type, returns the type it has when nothing decides its type:
+    /// `i64` if it negates a literal, and `u64` otherwise.
+    ///
+    /// Returns `None` for any other expression.
+    fn default_int_type(expr: &Expr) -> Option<IntType> {
+        match &expr.kind {
+            ExprKind::IntLiteral(_) => Some(IntType { signed: false, width: 64 }),
+            ExprKind::Unary(unary) => match unary.operator {
+                UnaryOperator::Negate => Self::default_int_type(&unary.operand).map(|t| IntType { signed: true, ..t }),
+                UnaryOperator::BitwiseNot => Self::default_int_type(&unary.operand),
+                _ => None,
+            },
+            ExprKind::Binary(binary) => {
+                let left = Self::default_int_type(&binary.left)?;
+                let right = Self::default_int_type(&binary.right)?;
+                Some(IntType { signed: left.signed || right.signed, width: 64 })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether `expr` only consists of float literals (and operators applied to them),
+    /// meaning that it could have any float type, like `-1.5` or `2.0 * 3.0`.
+    fn is_float_literal_expr(expr: &Expr) -> bool {
+        match &expr.kind {
+            ExprKind::FloatLiteral(_) => true,
+            ExprKind::Unary(Unary { operator: UnaryOperator::Negate, operand }) => Self::is_float_literal_expr(operand),
+            ExprKind::Binary(binary) => Self::is_float_literal_expr(&binary.left) && Self::is_float_literal_expr(&binary.right),
+            _ => false,
+        }
+    }
+
+    /// Returns the number of the inferred integer variable that holds the type of the one
+    /// numbered `id` (which is `id` itself, unless it must have the same type as another).
+    fn root(&self, mut id: usize) -> usize {
+        while let Some(other) = self.inferred_ints[id].same_as {
+            id = other;
+        }
+        id
+    }
+
+    /// Gives the inferred integer variables whose types weren't decided by any of their uses
+    /// their default types (see [Typer::default_inferred_type]).
+    fn default_inferred_ints(&mut self) {
+        for id in 0..self.inferred_ints.len() {
+            if self.inferred_ints[id].same_as.is_none() && self.inferred_ints[id].var_type.is_none() {
+                let default_type = self.default_inferred_type(id);
+                self.inferred_ints[id].var_type = Some(Type::Int(default_type));
+            }
+        }
+    }
+
+    /// Returns the type that the inferred integer variable numbered `root` (see [Typer::root])
+    /// has if none of its uses decide it: the default type of its values (see
+    /// [Typer::default_int_type]), which is signed if any of those values is.
+    fn default_inferred_type(&self, root: usize) -> IntType {
+        let signed = (0..self.inferred_ints.len())
+            .filter(|&id| self.root(id) == root)
+            .flat_map(|id| self.inferred_ints[id].deferred_values.iter())
+            .filter_map(|(value, _)| Self::default_int_type(value))
+            .any(|int_type| int_type.signed);
+        IntType { signed, width: 64 }
+    }
+
+    /// Returns a placeholder for `value`, which is stored in the inferred integer variable
+    /// numbered `id` before that variable's type is known.
+    ///
+    /// The placeholder is an identifier of type [Type::InferredInt], which
+    /// [Typer::type_deferred_values] later replaces with `value`, typed as the variable's type.
+    fn defer_value(&mut self, id: usize, value: &Expr) -> TypedExpr {
+        let scope = self.scope_manager.clone();
+        let inferred_int = &mut self.inferred_ints[id];
+        inferred_int.deferred_values.push_back((value.clone(), scope));
+        TypedExpr {
+            kind: TypedExprKind::Identifier(TypedIdentifier {
+                name: inferred_int.var_name.clone(),
+                id_type: Type::InferredInt(id),
+            }),
+            span: value.span,
+        }
+    }
+
+    /// Replaces the placeholders left in `body` by [Typer::defer_value] with their values, now
+    /// that the whole function has been typed (and the types of its variables inferred).
+    ///
+    /// The placeholders of variables whose types couldn't be inferred are left as they are,
+    /// since those variables are reported as errors anyway.
+    fn type_deferred_values(&mut self, body: &mut [TypedStatement]) {
+        for statement in body.iter_mut() {
+            match &mut statement.kind {
+                TypedStatementKind::VarDeclaration(declaration) => {
+                    if let Some(int_type) = self.type_deferred_value(&mut declaration.var_value) {
+                        declaration.var_type = Type::Int(int_type);
+                    }
+                }
+                TypedStatementKind::Assignment(assignment) => {
+                    self.type_deferred_value(&mut assignment.value);
+                }
+                TypedStatementKind::If(if_statement) => {
+                    self.type_deferred_values(&mut if_statement.then_body);
+                    if let Some(else_body) = if_statement.else_body.as_mut() {
+                        self.type_deferred_values(else_body);
+                    }
+                }
+                TypedStatementKind::WhileLoop(while_loop) => self.type_deferred_values(&mut while_loop.body),
+                TypedStatementKind::Loop(loop_statement) => self.type_deferred_values(&mut loop_statement.body),
+                TypedStatementKind::ForLoop(for_loop) => self.type_deferred_values(&mut for_loop.body),
+                TypedStatementKind::Match(m) => {
+                    for arm in m.arms.iter_mut() {
+                        self.type_deferred_values(&mut arm.body);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// If `value` is a placeholder for a deferred value (see [Typer::defer_value]) whose
+    /// variable's type is known, replaces it with that value and returns the variable's type.
+    fn type_deferred_value(&mut self, value: &mut TypedExpr) -> Option<IntType> {
+        let Type::InferredInt(id) = value.get_result_type() else {
+            return None;
+        };
+        let Some(Type::Int(int_type)) = self.inferred_ints[self.root(id)].var_type else {
+            return None;
+        };
+        let (deferred_value, scope) = self.inferred_ints[id].deferred_values.pop_front()?;
+        // The value is typed in the scope where it appeared, which has been exited since
+        let current_scope = std::mem::replace(&mut self.scope_manager, scope);
+        let typed_value = self.type_expr(&deferred_value, Some(&Type::Int(int_type)));
+        self.scope_manager = current_scope;
+        *value = self.record(typed_value)?;
+        Some(int_type)
+    }
+
+    /// Returns `var_type`, or the inferred type of the variable if `var_type` is a
+    /// [Type::InferredInt] that has since been inferred.
+    ///
+    /// (The scope manager still holds [Type::InferredInt] for those variables.)
+    fn resolve_inferred(&self, var_type: &Type) -> Type {
+        match var_type {
+            Type::InferredInt(id) => {
+                let root = self.root(*id);
+                self.inferred_ints[root].var_type.clone().unwrap_or(Type::InferredInt(root))
+            }
+            t => t.clone(),
+        }
+    }
+
+    /// Handles `typed_expr` being used where its type is needed but nothing decides it (like an
+    /// array index), in case its integer type is still being inferred.
+    ///
+    /// The variable's type is then still inferred from its other uses (or defaults), and the
+    /// function is typed again once it's known (see [Typer::type_func_def]). Until then,
+    /// `typed_expr` is cast to the variable's default type, so that the rest of the function can
+    /// be typed.
+    fn check_inferred(&mut self, typed_expr: TypedExpr) -> TypedExpr {
+        match typed_expr.get_result_type() {
+            Type::InferredInt(id) => {
+                self.needs_retyping = true;
+                let default_type = self.default_inferred_type(self.root(id));
+                Self::widen(typed_expr, default_type)
+            }
+            _ => typed_expr,
         }
     }
 
     /// This method confirms that a given body (consisting of one or more [Statement][a]) 
     /// is well-typed.
+    ///
+    /// Statements that fail to type-check are recorded and left out of the returned body.
+    /// Statements that come after one that always returns (or that jumps with `break` or
+    /// `continue`) are reported as unreachable.
     /// 
     /// [a]: crate::ast::Statement
-    fn type_body(&mut self, body: &[Statement], function_return_type: &Type) -> Vec<TypedStatement> {
-        self.scope_manager.enter_scope();
-        let typed_body: Vec<_> = body
-            .iter()
-            .map(|s| self.type_statement(s, function_return_type))
-            .collect();
-        self.scope_manager.exit_scope();
+    fn type_body(&mut self, body: &[Statement], func_proto: &FuncProto) -> Vec<TypedStatement> {
+        self.enter_scope();
+        let mut typed_body = Vec::with_capacity(body.len());
+        let mut reported_unreachable = false;
+        for (i, statement) in body.iter().enumerate() {
+            let typed_statement = self.type_statement(statement, func_proto);
+            let Some(s) = self.record(typed_statement) else {
+                continue;
+            };
+
+            let diverges = s.always_returns()
+                || matches!(s.kind, TypedStatementKind::Break(_) | TypedStatementKind::Continue(_));
+            if diverges && !reported_unreachable && i + 1 < body.len() {
+                reported_unreachable = true;
+                let unreachable_span = body[i + 1].span.to(body[body.len() - 1].span);
+                let diagnostic = Diagnostic::new("unreachable code", unreachable_span)
+                    .with_primary_message("this code will never run")
+                    .with_label(s.span, "any code after this statement is unreachable");
+                self.sink.lint(&UNREACHABLE_CODE, diagnostic);
+            }
+            typed_body.push(s);
+        }
+        self.exit_scope();
         typed_body
     }
 
     /// This method checks that an if statement has a *boolean* condition and a collection of body
     /// statements that are well-typed.
-    fn type_if_statement(&mut self, if_statement: &If, function_return_type: &Type) -> TypedIf {
-        let condition = self.type_expr(&if_statement.condition, Some(&Type::Bool));
-        let then_body = self.type_body(&if_statement.then_body, function_return_type);
-        let else_body = if_statement.else_body.as_ref().map(|body| self.type_body(body, function_return_type));
-        TypedIf { condition, then_body, else_body }
+    fn type_if_statement(&mut self, if_statement: &If, func_proto: &FuncProto) -> crate::Result<TypedIf> {
+        let condition = self.type_expr(&if_statement.condition, Some(&Type::Bool))?;
+        let then_body = self.type_body(&if_statement.then_body, func_proto);
+        let else_body = if_statement.else_body.as_ref().map(|body| self.type_body(body, func_proto));
+        Ok(TypedIf { condition, then_body, else_body })
     }
 
     /// This method checks that a while loop has a *boolean* condition and a collection of body
@@ -207,29 +897,250 @@ impl Typer {
     fn type_while_loop(
         &mut self,
         while_loop: &WhileLoop,
-        function_return_type: &Type,
-    ) -> TypedWhileLoop {
-        let condition = self.type_expr(&while_loop.condition, Some(&Type::Bool));
-        let body = self.type_body(&while_loop.body, function_return_type);
-        TypedWhileLoop { condition, body }
+        func_proto: &FuncProto,
+    ) -> crate::Result<TypedWhileLoop> {
+        let condition = self.type_expr(&while_loop.condition, Some(&Type::Bool))?;
+        self.loop_labels.push(while_loop.label.clone());
+        let body = self.type_body(&while_loop.body, func_proto);
+        self.loop_labels.pop();
+        Ok(TypedWhileLoop { label: while_loop.label.clone(), condition, body })
+    }
+
+    /// This method checks that the body of an infinite loop is well-typed.
+    fn type_loop(&mut self, loop_statement: &Loop, func_proto: &FuncProto) -> TypedLoop {
+        self.loop_labels.push(loop_statement.label.clone());
+        let body = self.type_body(&loop_statement.body, func_proto);
+        self.loop_labels.pop();
+        TypedLoop { label: loop_statement.label.clone(), body }
+    }
+
+    /// This method checks that a for loop counts with an integer variable, that its range (and
+    /// step) have the same type as that variable, and that its body is well-typed.
+    ///
+    /// The loop variable is declared in a scope of its own, so it's only visible in the body.
+    fn type_for_loop(
+        &mut self,
+        for_loop: &ForLoop,
+        span: Span,
+        func_proto: &FuncProto,
+    ) -> crate::Result<TypedForLoop> {
+        let var_type = match &for_loop.var_type {
+            Type::Int(int_type) => *int_type,
+            t => return Err(Self::err(TypingError::NonIntegerRange(t.clone()), span)),
+        };
+        self.check_valid_type(&for_loop.var_type, span)?;
+
+        let desired_type = Type::Int(var_type);
+        let start = self.type_expr(&for_loop.start, Some(&desired_type))?;
+        let end = self.type_expr(&for_loop.end, Some(&desired_type))?;
+        let step = match &for_loop.step {
+            Some(step) => Some(self.type_for_loop_step(step, &desired_type)?),
+            None => None,
+        };
+
+        self.enter_scope();
+        self.scope_manager.set(&for_loop.var_name, desired_type);
+        self.var_usages.set(&for_loop.var_name, VarUsage { span, used: false, is_mutable: true });
+        self.loop_labels.push(for_loop.label.clone());
+        let body = self.type_body(&for_loop.body, func_proto);
+        self.loop_labels.pop();
+        self.exit_scope();
+
+        Ok(TypedForLoop {
+            label: for_loop.label.clone(),
+            var_type,
+            var_name: for_loop.var_name.clone(),
+            start,
+            end,
+            inclusive: for_loop.inclusive,
+            step,
+            body,
+        })
+    }
+
+    /// Types the `step` of a `for` loop, which must be positive; this is checked here if it's a
+    /// literal, and at runtime otherwise.
+    fn type_for_loop_step(&mut self, step: &Expr, desired_type: &Type) -> crate::Result<TypedExpr> {
+        let typed_step = self.type_expr(step, Some(desired_type))?;
+        let non_positive_literal = match &step.kind {
+            // The lexer only produces literals made of digits
+            ExprKind::IntLiteral(int) if BigUint::from_decimal(int).unwrap().bits() == 0 => Some(int.clone()),
+            ExprKind::Unary(Unary { operator: UnaryOperator::Negate, operand }) => match &operand.kind {
+                ExprKind::IntLiteral(int) => Some(format!("-{}", int)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(literal) = non_positive_literal {
+            return Err(Self::err(TypingError::NonPositiveStep(literal), typed_step.span));
+        }
+        Ok(typed_step)
+    }
+
+    /// This method checks that a `break` or `continue` (the `keyword`) is inside of a loop, and
+    /// that its `label` (if any) belongs to one of the enclosing loops.
+    fn check_valid_jump(&self, keyword: &str, label: Option<&str>, span: Span) -> crate::Result<()> {
+        if self.loop_labels.is_empty() {
+            return Err(Self::err(TypingError::JumpOutsideLoop { keyword: keyword.to_string() }, span));
+        }
+
+        match label {
+            Some(label) if !self.loop_labels.iter().any(|l| l.as_deref() == Some(label)) => {
+                Err(Self::err(TypingError::UndeclaredLabel(label.to_string()), span))
+            }
+            _ => Ok(()),
+        }
     }
 
-    /// This method checks that an assignment is assigning to a declared variable, and that the new
-    /// value matches the variable's declared type.
-    fn type_assignment(&mut self, assignment: &Assignment) -> TypedAssignment {
+    /// This method checks that an assignment is assigning to a declared variable (or to one of
+    /// its fields or elements), and that the new value matches the declared type of that variable
+    /// (or field, or element).
+    fn type_assignment(&mut self, assignment: &Assignment, span: Span) -> crate::Result<TypedAssignment> {
         let name = assignment.name.clone();
         let var_type = match self.scope_manager.get(&name) {
-            Some(t) => t.clone(),
-            None => panic!("Variable '{}' has not been declared yet", name),
+            Some(t) => self.resolve_inferred(t),
+            None => {
+                self.scope_manager.set(&name, Type::Error);
+                return Err(Self::err(TypingError::UndeclaredIdentifier(name), span));
+            }
+        };
+        if self.is_const(&name) {
+            return Err(Self::err(TypingError::AssignmentToConst(name), span));
+        }
+        // Writing through a pointer doesn't change the variable itself
+        let is_immutable = self.var_usages.get(&name).is_some_and(|usage| !usage.is_mutable);
+        if is_immutable && !assignment.accessors.contains(&Accessor::Deref) {
+            return Err(Self::err(TypingError::AssignmentToImmutable(name), span));
+        }
+
+        let mut target_type = var_type.clone();
+        let mut accessors = Vec::with_capacity(assignment.accessors.len());
+        for accessor in assignment.accessors.iter() {
+            match accessor {
+                Accessor::Field(field_name) => {
+                    let (field_index, field_type) = self.type_field(&target_type, field_name, span)?;
+                    accessors.push(TypedAccessor::Field(field_index));
+                    target_type = field_type;
+                }
+                Accessor::Index(index) => {
+                    let (element_type, length) = self.type_element(&target_type, span)?;
+                    accessors.push(TypedAccessor::Index(Box::new(self.type_array_index(index, length)?)));
+                    target_type = element_type;
+                }
+                Accessor::Deref => {
+                    // Writing through a pointer reads the pointer itself
+                    if let Some(usage) = self.var_usages.get_mut(&name) {
+                        usage.used = true;
+                    }
+                    target_type = self.type_pointee(&target_type, span)?;
+                    accessors.push(TypedAccessor::Deref(target_type.clone()));
+                }
+            }
+        }
+        let value = match target_type {
+            // Values made of integer literals wait for the variable's type, like its initial value
+            Type::InferredInt(id) if Self::default_int_type(&assignment.value).is_some() => {
+                self.defer_value(id, &assignment.value)
+            }
+            // Otherwise, the value determines the variable's type, unless it also depends on that
+            // type (like `count + 1`)
+            Type::InferredInt(id) => {
+                let value = self.type_inferable_expr(assignment.value.as_ref(), None)?;
+                match value.get_result_type() {
+                    // Both variables must then have the same type (like `x` and `y` in `x = y`)
+                    value_type @ Type::InferredInt(_) => {
+                        self.unify_inferred_ints(&target_type, &value_type);
+                        self.defer_value(id, &assignment.value)
+                    }
+                    Type::Int(int_type) => {
+                        self.inferred_ints[id].var_type = Some(Type::Int(int_type));
+                        value
+                    }
+                    Type::Error => value,
+                    found => {
+                        return Err(Self::err(TypingError::TypeMismatch { expected: target_type, found }, span));
+                    }
+                }
+            }
+            _ => self.type_expr(assignment.value.as_ref(), Some(&target_type))?,
         };
-        let value = self.type_expr(assignment.value.as_ref(), Some(&var_type));
 
         self.scope_manager.set(&name, var_type);
 
-        TypedAssignment {
+        Ok(TypedAssignment {
             name,
+            accessors,
             value: Box::new(value),
+        })
+    }
+
+    /// Returns the index and type of the field named `field_name` of a value of type
+    /// `struct_type`, or an error if that type isn't a struct with such a field.
+    ///
+    /// If `struct_type` is [Type::Error], then the field's type is also [Type::Error].
+    fn type_field(&self, struct_type: &Type, field_name: &str, span: Span) -> crate::Result<(usize, Type)> {
+        match struct_type {
+            Type::Error => Ok((0, Type::Error)),
+            Type::Named(struct_name) => match self.types.get_field(struct_name, field_name) {
+                Some((field_index, field)) => Ok((field_index, field.field_type.clone())),
+                None => Err(Self::err(
+                    TypingError::UnknownField {
+                        struct_name: struct_name.clone(),
+                        field_name: field_name.to_string(),
+                    },
+                    span,
+                )),
+            },
+            t => Err(Self::err(
+                TypingError::NotAStruct { field_name: field_name.to_string(), found: t.clone() },
+                span,
+            )),
+        }
+    }
+
+    /// Returns the type that `pointer_type` points to, or an error if it isn't a pointer (or if
+    /// it's a `*void`, which points to a value of an unknown type).
+    ///
+    /// If `pointer_type` is [Type::Error], then the pointee's type is also [Type::Error].
+    fn type_pointee(&self, pointer_type: &Type, span: Span) -> crate::Result<Type> {
+        match pointer_type {
+            Type::Error => Ok(Type::Error),
+            Type::Pointer(pointee_type) if **pointee_type != Type::Void => Ok(pointee_type.as_ref().clone()),
+            t => Err(Self::err(TypingError::InvalidDereference(t.clone()), span)),
+        }
+    }
+
+    /// Returns the element type and length of `array_type`, or an error if it isn't an array.
+    ///
+    /// If `array_type` is [Type::Error], then the element type is also [Type::Error] (and the
+    /// length is unknown).
+    fn type_element(&self, array_type: &Type, span: Span) -> crate::Result<(Type, Option<u64>)> {
+        match array_type {
+            Type::Error => Ok((Type::Error, None)),
+            Type::Array(element_type, length) => Ok((element_type.as_ref().clone(), Some(*length))),
+            t => Err(Self::err(TypingError::NotAnArray(t.clone()), span)),
+        }
+    }
+
+    /// Checks that `index` is an integer, and that it's in bounds if it's a literal (and the
+    /// array's `length` is known). Indices of any integer type are allowed, since the compiler
+    /// checks that they're in bounds at runtime.
+    fn type_array_index(&mut self, index: &Expr, length: Option<u64>) -> crate::Result<TypedExpr> {
+        let typed_index = self.type_expr(index, None)?;
+        match typed_index.get_result_type() {
+            Type::Int(_) | Type::Error => {}
+            t => return Err(Self::err(TypingError::InvalidIndexType(t), index.span)),
+        }
+
+        if let (ExprKind::IntLiteral(n), Some(length)) = (&index.kind, length) {
+            if n.parse::<u64>().map_or(true, |n| n >= length) {
+                return Err(Self::err(
+                    TypingError::IndexOutOfBounds { index: n.clone(), length },
+                    index.span,
+                ));
+            }
         }
+        Ok(typed_index)
     }
 
     /// Processes a return statement by confirming that the returned expression matches the return
@@ -237,15 +1148,21 @@ impl Typer {
     fn type_return(
         &mut self,
         ret: Option<&Expr>,
-        function_return_type: &Type,
-    ) -> Option<TypedExpr> {
-        match (ret, function_return_type) {
-            (Some(_), Type::Void) => panic!("Expected function to return nothing, but found 'ret' with a value"),
-            (Some(expr), desired) => Some(self.type_expr(expr, Some(desired))),
-            (None, Type::Void) => None,
-            (None, _) => panic!(
-                "Expected function to return a '{}', but found 'ret' without a value", function_return_type
-            )
+        span: Span,
+        func_proto: &FuncProto,
+    ) -> crate::Result<Option<TypedExpr>> {
+        let function_name = func_proto.name.clone();
+        match (ret, func_proto.return_type.as_ref()) {
+            (Some(_), Type::Void) => Err(Self::err(
+                TypingError::ReturnValueInVoidFunction { function_name },
+                span,
+            )),
+            (Some(expr), desired) => Ok(Some(self.type_expr(expr, Some(desired))?)),
+            (None, Type::Void) => Ok(None),
+            (None, expected) => Err(Self::err(
+                TypingError::MissingReturnValue { function_name, expected: expected.clone() },
+                span,
+            )),
         }
     }
 
@@ -254,136 +1171,981 @@ impl Typer {
     ///
     /// Note, if the provided `desired_type` is `None`, then the returned `TypedExpr` is allowed to
     /// be of any type.
-    fn type_expr(&mut self, expr: &Expr, desired_type: Option<&Type>) -> TypedExpr {
-        match expr {
-            Expr::Identifier(name) => {
-                TypedExpr::Identifier(self.type_identifier(name, desired_type))
+    fn type_expr(&mut self, expr: &Expr, desired_type: Option<&Type>) -> crate::Result<TypedExpr> {
+        let typed_expr = self.type_inferable_expr(expr, desired_type)?;
+        Ok(self.check_inferred(typed_expr))
+    }
+
+    /// Like [Typer::type_expr], except that the returned `TypedExpr` can also be of an integer
+    /// type that's still being inferred (see [Type::InferredInt]), like `x + 1` where `x` was
+    /// declared with `let x = 5`. The caller must then type `expr` again once it knows which
+    /// integer type it should be.
+    fn type_inferable_expr(&mut self, expr: &Expr, desired_type: Option<&Type>) -> crate::Result<TypedExpr> {
+        // Anything can be used where an erroneous type is expected, since the error that produced
+        // that type has already been reported
+        let desired_type = desired_type.filter(|&t| *t != Type::Error);
+        // Integer literals only fall back to a default type when nothing else decides it
+        let default_type = Self::default_int_type(expr).map(Type::Int);
+        let desired_type = desired_type.or(default_type.as_ref());
+        // An integer can be used where a wider integer is expected, as long as every value of its
+        // type fits in the wider one
+        if let (Some(Type::Int(wide_type)), true) = (desired_type, self.has_own_type(expr)) {
+            let typed_expr = self.type_inferable_expr(expr, None)?;
+            match typed_expr.get_result_type() {
+                Type::Int(int_type) if int_type == *wide_type => return Ok(typed_expr),
+                Type::Int(int_type) if Self::is_lossless_widening(int_type, *wide_type) => {
+                    return Ok(Self::widen(typed_expr, *wide_type));
+                }
+                // Otherwise, the expression is typed again below to report the mismatch (or to
+                // infer its type, if it's still being inferred)
+                _ => {}
             }
-            Expr::IntLiteral(int) => {
-                TypedExpr::IntLiteral(self.type_int_literal(int, desired_type))
+        }
+        let span = expr.span;
+        let kind = match &expr.kind {
+            ExprKind::Identifier(name) => {
+                let identifier = self.type_identifier(name, span, desired_type)?;
+                // Constants don't exist at runtime, so each use is replaced by the value
+                match self.const_value(name) {
+                    Some(value) => value.kind.clone(),
+                    None => TypedExprKind::Identifier(identifier),
+                }
             }
-            Expr::BoolLiteral(b) => TypedExpr::BoolLiteral(*b),
-            Expr::Binary(b) => TypedExpr::Binary(self.type_binary_expr(b, desired_type)),
-            Expr::Comparison(c) => {
-                TypedExpr::Comparison(self.type_comparison_expr(c, desired_type))
+            ExprKind::IntLiteral(int) => {
+                TypedExprKind::IntLiteral(Self::type_int_literal(int, span, desired_type, false)?)
             }
-            Expr::Call(c) => TypedExpr::Call(self.type_call(c, desired_type)),
-            Expr::Unary(u) => TypedExpr::Unary(self.type_unary_expr(u, desired_type)),
-        }
-    }
-
-    /// Checks that the unary expression is of the correct type, and wraps it as a `TypedUnary`.
-    /// 
-    /// For example, if the unary operator is a cast, then the operand must be castable to the
-    /// desired type.
-    fn type_unary_expr(&mut self, unary: &Unary, desired_type: Option<&Type>) -> TypedUnary {
-        // desired_operand_type will be used as the desired type when typing the operand
-        let desired_operand_type = match (&unary.operator, desired_type) {
-            (UnaryOperator::Cast(_), None) => None,
-            (UnaryOperator::Cast(cast_type), Some(desired)) if cast_type == desired => None,
-            (UnaryOperator::Cast(cast_type), Some(desired)) => {
-                panic!(
-                    "Expected expression of type '{}', but found a cast to type '{}'",
-                    desired,
-                    cast_type
-                )
-            }
-
-            (UnaryOperator::Negate, None) => Some(&Type::Int(IntType { signed: true, width: 64 })),
-            (UnaryOperator::Negate, Some(t @ Type::Int(IntType { signed: false, .. }))) => {
-               panic!("Expected an unsigned expression of type '{}', but found a negation", t)
+            ExprKind::FloatLiteral(float) => {
+                TypedExprKind::FloatLiteral(Self::type_float_literal(float, span, desired_type)?)
+            }
+            ExprKind::BoolLiteral(b) => {
+                if let Some(desired) = desired_type.filter(|&t| *t != Type::Bool) {
+                    return Err(Self::err(
+                        TypingError::TypeMismatch { expected: desired.clone(), found: Type::Bool },
+                        span,
+                    ));
+                }
+                TypedExprKind::BoolLiteral(*b)
+            }
+            ExprKind::StrLiteral(string) => {
+                if let Some(desired) = desired_type.filter(|&t| *t != Type::Str) {
+                    return Err(Self::err(
+                        TypingError::TypeMismatch { expected: desired.clone(), found: Type::Str },
+                        span,
+                    ));
+                }
+                TypedExprKind::StrLiteral(string.clone())
+            }
+            ExprKind::CharLiteral(c) => {
+                if let Some(desired) = desired_type.filter(|&t| *t != Type::Char) {
+                    return Err(Self::err(
+                        TypingError::TypeMismatch { expected: desired.clone(), found: Type::Char },
+                        span,
+                    ));
+                }
+                TypedExprKind::CharLiteral(*c)
+            }
+            ExprKind::Null => match desired_type {
+                Some(pointer_type @ Type::Pointer(_)) => TypedExprKind::Null(pointer_type.clone()),
+                Some(t) => return Err(Self::err(TypingError::NullTypeMismatch(t.clone()), span)),
+                None => return Err(Self::err(TypingError::UnknownNullType, span)),
+            },
+            ExprKind::Binary(b) => {
+                TypedExprKind::Binary(self.type_binary_expr(b, span, desired_type)?)
+            }
+            ExprKind::Comparison(c) => {
+                TypedExprKind::Comparison(self.type_comparison_expr(c, span, desired_type)?)
+            }
+            ExprKind::Logical(l) => {
+                TypedExprKind::Logical(self.type_logical_expr(l, span, desired_type)?)
+            }
+            ExprKind::Call(c) => match self.arithmetic_builtin(&c.function_name) {
+                Some((overflow, operator)) => TypedExprKind::ArithmeticCall(
+                    self.type_arithmetic_call(c, span, desired_type, overflow, operator)?,
+                ),
+                None => TypedExprKind::Call(self.type_call(c, span, desired_type)?),
             },
-            (UnaryOperator::Negate, Some(t @ Type::Int(_))) => Some(t),
-            (UnaryOperator::Negate, Some(t )) => panic!("Cannot negate a non-integer type '{}'", t),
+            ExprKind::Unary(u) => TypedExprKind::Unary(self.type_unary_expr(u, span, desired_type)?),
+            ExprKind::StructLiteral(l) => {
+                TypedExprKind::StructLiteral(self.type_struct_literal(l, span, desired_type)?)
+            }
+            ExprKind::FieldAccess(a) => {
+                TypedExprKind::FieldAccess(self.type_field_access(a, span, desired_type)?)
+            }
+            ExprKind::EnumLiteral(l) => {
+                TypedExprKind::EnumLiteral(self.type_enum_literal(l, span, desired_type)?)
+            }
+            ExprKind::Match(m) => {
+                // Every arm must have the desired type, or else the type of the first arm
+                let mut result_type = desired_type.cloned();
+                let typed_match = self.type_match(m, span, |typer, arm_expr| {
+                    let arm_expr = typer.type_expr(arm_expr, result_type.as_ref())?;
+                    result_type.get_or_insert_with(|| arm_expr.get_result_type());
+                    Ok(arm_expr)
+                })?;
+                let result_type = result_type.unwrap_or(Type::Void);
+                TypedExprKind::Match(TypedMatch { result_type, ..typed_match })
+            }
+            ExprKind::ArrayLiteral(elements) => {
+                TypedExprKind::ArrayLiteral(self.type_array_literal(elements, span, desired_type)?)
+            }
+            ExprKind::ArrayRepeat(r) => {
+                TypedExprKind::ArrayRepeat(self.type_array_repeat(r, span, desired_type)?)
+            }
+            ExprKind::Index(i) => TypedExprKind::Index(self.type_index(i, span, desired_type)?),
         };
 
-        let typed_operand = self.type_expr(&unary.operand, desired_operand_type);
-        let operand_type = typed_operand.get_result_type();
+        Ok(TypedExpr { kind, span })
+    }
 
-        // Now that we know the type of the operand, we can check if the unary operator is valid
-        match &unary.operator {
-            UnaryOperator::Cast(cast_type) => Self::check_valid_cast(&cast_type, &operand_type),
-            UnaryOperator::Negate => Self::check_valid_negation(&operand_type)
-        }
+    /// Checks that a struct literal gives a well-typed value to each field of a defined struct
+    /// (exactly once), and that the struct is the desired type.
+    fn type_struct_literal(
+        &mut self,
+        literal: &StructLiteral,
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedStructLiteral> {
+        let struct_name = &literal.struct_name;
+        let struct_def = match self.types.get_struct(struct_name) {
+            Some(struct_def) => struct_def.clone(),
+            None => return Err(Self::err(TypingError::UndefinedType(struct_name.clone()), span)),
+        };
+
+        let struct_type = Type::Named(struct_name.clone());
+        if let Some(desired) = desired_type.filter(|&t| *t != struct_type) {
+            return Err(Self::err(
+                TypingError::TypeMismatch { expected: desired.clone(), found: struct_type },
+                span,
+            ));
+        }
+
+        let mut fields: Vec<(usize, TypedExpr)> = Vec::with_capacity(literal.fields.len());
+        for field_value in literal.fields.iter() {
+            let field_name = &field_value.field_name;
+            let field_error = |kind| Err(Self::err(kind, span));
+            let Some((field_index, field)) = struct_def.fields.iter().enumerate().find(|(_, f)| f.field_name == *field_name) else {
+                return field_error(TypingError::UnknownField { struct_name: struct_name.clone(), field_name: field_name.clone() });
+            };
+            if fields.iter().any(|(i, _)| *i == field_index) {
+                return field_error(TypingError::DuplicateField { struct_name: struct_name.clone(), field_name: field_name.clone() });
+            }
+
+            let value = self.type_expr(&field_value.value, Some(&field.field_type))?;
+            fields.push((field_index, value));
+        }
+
+        let missing_fields: Vec<_> = struct_def
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(field_index, _)| !fields.iter().any(|(i, _)| i == field_index))
+            .map(|(_, field)| field.field_name.clone())
+            .collect();
+        if !missing_fields.is_empty() {
+            return Err(Self::err(
+                TypingError::MissingFields { struct_name: struct_name.clone(), field_names: missing_fields },
+                span,
+            ));
+        }
+
+        Ok(TypedStructLiteral { struct_name: struct_name.clone(), fields })
+    }
+
+    /// Checks that every element of an array literal has the same type (the desired element type,
+    /// or else the type of the first element), and that the literal has the desired length.
+    fn type_array_literal(
+        &mut self,
+        elements: &[Expr],
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedArrayLiteral> {
+        let mut element_type = match desired_type {
+            Some(Type::Array(element_type, length)) => {
+                if *length != elements.len() as u64 {
+                    return Err(Self::err(
+                        TypingError::ArrayLengthMismatch { expected: *length, found: elements.len() as u64 },
+                        span,
+                    ));
+                }
+                Some(element_type.as_ref().clone())
+            }
+            Some(desired) => {
+                let literal = self.type_array_literal(elements, span, None)?;
+                let found = Type::Array(Box::new(literal.element_type), elements.len() as u64);
+                return Err(Self::err(TypingError::TypeMismatch { expected: desired.clone(), found }, span));
+            }
+            None => None,
+        };
+
+        let mut typed_elements = Vec::with_capacity(elements.len());
+        for element in elements.iter() {
+            let element = self.type_expr(element, element_type.as_ref())?;
+            element_type.get_or_insert_with(|| element.get_result_type());
+            typed_elements.push(element);
+        }
+
+        match element_type {
+            None => Err(Self::err(TypingError::UnknownArrayType, span)),
+            Some(Type::Void) => Err(Self::err(TypingError::InvalidElementType(Type::Void), span)),
+            Some(element_type) => Ok(TypedArrayLiteral { elements: typed_elements, element_type }),
+        }
+    }
+
+    /// Checks that a repeated array literal (like `[0; 16]`) repeats a value of the desired
+    /// element type, and that it has the desired length.
+    fn type_array_repeat(
+        &mut self,
+        repeat: &ArrayRepeat,
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedArrayRepeat> {
+        let value = match desired_type {
+            Some(Type::Array(element_type, length)) => {
+                if *length != repeat.length {
+                    return Err(Self::err(
+                        TypingError::ArrayLengthMismatch { expected: *length, found: repeat.length },
+                        span,
+                    ));
+                }
+                self.type_expr(&repeat.value, Some(element_type))?
+            }
+            Some(desired) => {
+                let value = self.type_expr(&repeat.value, None)?;
+                let found = Type::Array(Box::new(value.get_result_type()), repeat.length);
+                return Err(Self::err(TypingError::TypeMismatch { expected: desired.clone(), found }, span));
+            }
+            None => self.type_expr(&repeat.value, None)?,
+        };
+
+        if value.get_result_type() == Type::Void {
+            return Err(Self::err(TypingError::InvalidElementType(Type::Void), span));
+        }
+        Ok(TypedArrayRepeat { value: Box::new(value), length: repeat.length })
+    }
+
+    /// Checks that an indexing expression (like `buf[i]`) indexes into an array with an integer,
+    /// and that the array's elements have the desired type.
+    fn type_index(
+        &mut self,
+        index: &Index,
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedIndex> {
+        let expr = self.type_expr(&index.expr, None)?;
+        let (element_type, length) = self.type_element(&expr.get_result_type(), span)?;
+        let typed_index = self.type_array_index(&index.index, length)?;
+
+        if let Some(desired) = desired_type.filter(|&t| *t != element_type && element_type != Type::Error) {
+            return Err(Self::err(
+                TypingError::TypeMismatch { expected: desired.clone(), found: element_type },
+                span,
+            ));
+        }
+
+        Ok(TypedIndex {
+            expr: Box::new(expr),
+            index: Box::new(typed_index),
+            element_type,
+        })
+    }
+
+    /// Checks that an enum literal names a variant of a defined enum, that it gives a well-typed
+    /// value to each part of the variant's payload, and that the enum is the desired type.
+    fn type_enum_literal(
+        &mut self,
+        literal: &EnumLiteral,
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedEnumLiteral> {
+        let enum_name = &literal.enum_name;
+        let (variant_index, variant) = match self.types.get_variant(enum_name, &literal.variant_name) {
+            Some((variant_index, variant)) => (variant_index, variant.clone()),
+            None => {
+                return Err(Self::err(
+                    TypingError::UndefinedVariant {
+                        enum_name: enum_name.clone(),
+                        variant_name: literal.variant_name.clone(),
+                    },
+                    span,
+                ));
+            }
+        };
+
+        let enum_type = Type::Named(enum_name.clone());
+        if let Some(desired) = desired_type.filter(|&t| *t != enum_type) {
+            return Err(Self::err(
+                TypingError::TypeMismatch { expected: desired.clone(), found: enum_type },
+                span,
+            ));
+        }
+
+        if literal.payload.len() != variant.payload_types.len() {
+            return Err(Self::err(
+                TypingError::PayloadCountMismatch {
+                    variant_name: format!("{}::{}", enum_name, variant.name),
+                    expected: variant.payload_types.len(),
+                    found: literal.payload.len(),
+                },
+                span,
+            ));
+        }
+
+        let payload = literal
+            .payload
+            .iter()
+            .zip(variant.payload_types.iter())
+            .map(|(value, payload_type)| self.type_expr(value, Some(payload_type)))
+            .collect::<crate::Result<_>>()?;
+
+        Ok(TypedEnumLiteral { enum_name: enum_name.clone(), variant_index, payload })
+    }
+
+    /// Checks that a match's scrutinee can be matched on, that each arm's pattern can match the
+    /// scrutinee's type, and that the arms cover every possible value of the scrutinee. Each arm's
+    /// body is typed by `type_arm`, in a scope where the variables bound by its pattern exist.
+    ///
+    /// The returned match's `result_type` is [Type::Void]; callers typing a match expression
+    /// replace it with the type of its arms.
+    fn type_match<Body, TypedBody>(
+        &mut self,
+        m: &Match<Body>,
+        span: Span,
+        mut type_arm: impl FnMut(&mut Self, &Body) -> crate::Result<TypedBody>,
+    ) -> crate::Result<TypedMatch<TypedBody>> {
+        let scrutinee = self.type_expr(&m.scrutinee, None)?;
+        let scrutinee_type = scrutinee.get_result_type();
+        match &scrutinee_type {
+            Type::Int(_) | Type::Bool | Type::Error => {}
+            Type::Named(name) if self.types.get_enum(name).is_some() => {}
+            t => return Err(Self::err(TypingError::InvalidMatchType(t.clone()), m.scrutinee.span)),
+        }
+
+        let mut arms = Vec::with_capacity(m.arms.len());
+        let mut had_pattern_errors = false;
+        for arm in m.arms.iter() {
+            self.enter_scope();
+            let pattern = match self.type_pattern(&arm.pattern, &scrutinee_type) {
+                Ok(pattern) => pattern,
+                Err(err) => {
+                    // The arm's body is still typed, with its bindings treated as erroneous
+                    self.errors.push(err);
+                    had_pattern_errors = true;
+                    if let PatternKind::EnumVariant { bindings, .. } = &arm.pattern.kind {
+                        for name in bindings.iter().flatten() {
+                            self.scope_manager.set(name, Type::Error);
+                        }
+                    }
+                    TypedPattern::Wildcard
+                }
+            };
+            if let TypedPattern::EnumVariant { bindings, .. } = &pattern {
+                for binding in bindings.iter() {
+                    self.scope_manager.set(&binding.name, binding.binding_type.clone());
+                    self.var_usages.set(&binding.name, VarUsage { span: arm.pattern.span, used: false, is_mutable: true });
+                }
+            }
+            let body = type_arm(self, &arm.body);
+            self.exit_scope();
+            arms.push(TypedMatchArm { pattern, body: body? });
+        }
+
+        if !had_pattern_errors && scrutinee_type != Type::Error {
+            let patterns: Vec<_> = arms.iter().map(|arm| &arm.pattern).collect();
+            let missing_patterns = exhaustiveness::missing_patterns(&scrutinee_type, &patterns, &self.types);
+            if !missing_patterns.is_empty() {
+                return Err(Self::err(TypingError::NonExhaustiveMatch { missing_patterns }, span));
+            }
+        }
+
+        Ok(TypedMatch {
+            scrutinee: Box::new(scrutinee),
+            arms,
+            result_type: Type::Void,
+        })
+    }
+
+    /// Checks that `pattern` can match values of `scrutinee_type`, and returns its typed version.
+    ///
+    /// If `scrutinee_type` is [Type::Error], every pattern is treated like a wildcard.
+    fn type_pattern(&self, pattern: &Pattern, scrutinee_type: &Type) -> crate::Result<TypedPattern> {
+        let mismatch = || {
+            Self::err(
+                TypingError::PatternTypeMismatch {
+                    pattern: pattern.kind.to_string(),
+                    expected: scrutinee_type.clone(),
+                },
+                pattern.span,
+            )
+        };
+        // Parses an integer in the pattern, confirming that it's a value of the scrutinee's type
+        let parse_int = |int: &str, int_type: &IntType| {
+            let value = int.parse::<i128>().map_err(|_| mismatch())?;
+            match exhaustiveness::int_bounds(*int_type) {
+                Some((min, max)) if value < min || value > max => Err(mismatch()),
+                None if value < 0 => Err(mismatch()),
+                _ => Ok(value),
+            }
+        };
+
+        Ok(match (&pattern.kind, scrutinee_type) {
+            (PatternKind::Wildcard, _) | (_, Type::Error) => TypedPattern::Wildcard,
+            (PatternKind::BoolLiteral(b), Type::Bool) => TypedPattern::Bool(*b),
+            (PatternKind::IntLiteral(int), Type::Int(int_type)) => {
+                let value = parse_int(int, int_type)?;
+                TypedPattern::IntRange { start: value, end: value }
+            }
+            (PatternKind::Range { start, end, inclusive }, Type::Int(int_type)) => {
+                let start = parse_int(start, int_type)?;
+                let end = parse_int(end, int_type)?;
+                // An exclusive range's end can't overflow, since the range would be empty anyway
+                let end = match inclusive {
+                    true => Some(end),
+                    false => end.checked_sub(1),
+                };
+                match end {
+                    Some(end) if start <= end => TypedPattern::IntRange { start, end },
+                    _ => {
+                        return Err(Self::err(
+                            TypingError::EmptyRangePattern(pattern.kind.to_string()),
+                            pattern.span,
+                        ));
+                    }
+                }
+            }
+            (PatternKind::EnumVariant { enum_name, variant_name, bindings }, Type::Named(scrutinee_name)) => {
+                if enum_name != scrutinee_name {
+                    return Err(mismatch());
+                }
+                let Some((variant_index, variant)) = self.types.get_variant(enum_name, variant_name) else {
+                    return Err(Self::err(
+                        TypingError::UndefinedVariant {
+                            enum_name: enum_name.clone(),
+                            variant_name: variant_name.clone(),
+                        },
+                        pattern.span,
+                    ));
+                };
+                if bindings.len() != variant.payload_types.len() {
+                    return Err(Self::err(
+                        TypingError::PayloadCountMismatch {
+                            variant_name: format!("{}::{}", enum_name, variant_name),
+                            expected: variant.payload_types.len(),
+                            found: bindings.len(),
+                        },
+                        pattern.span,
+                    ));
+                }
+
+                let bindings = bindings
+                    .iter()
+                    .zip(variant.payload_types.iter())
+                    .enumerate()
+                    .filter_map(|(payload_index, (name, binding_type))| {
+                        let name = name.clone()?;
+                        Some(PatternBinding { payload_index, name, binding_type: binding_type.clone() })
+                    })
+                    .collect();
+                TypedPattern::EnumVariant { variant_index, bindings }
+            }
+            _ => return Err(mismatch()),
+        })
+    }
+
+    /// Checks that a field access (like `p.x`) names a field of a struct, and that the field has
+    /// the desired type.
+    fn type_field_access(
+        &mut self,
+        access: &FieldAccess,
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedFieldAccess> {
+        let expr = self.type_expr(&access.expr, None)?;
+        let (field_index, field_type) = self.type_field(&expr.get_result_type(), &access.field_name, span)?;
+
+        if let Some(desired) = desired_type.filter(|&t| *t != field_type && field_type != Type::Error) {
+            return Err(Self::err(
+                TypingError::TypeMismatch { expected: desired.clone(), found: field_type },
+                span,
+            ));
+        }
+
+        Ok(TypedFieldAccess {
+            expr: Box::new(expr),
+            field_index,
+            field_type,
+        })
+    }
+
+    /// Checks that the unary expression is of the correct type, and wraps it as a `TypedUnary`.
+    /// 
+    /// For example, if the unary operator is a cast, then the operand must be castable to the
+    /// desired type.
+    fn type_unary_expr(
+        &mut self,
+        unary: &Unary,
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedUnary> {
+        // desired_operand_type will be used as the desired type when typing the operand
+        let desired_operand_type = match (&unary.operator, desired_type) {
+            (UnaryOperator::Cast(cast_type), Some(desired)) if cast_type != desired => {
+                return Err(Self::err(
+                    TypingError::TypeMismatch { expected: desired.clone(), found: cast_type.clone() },
+                    span,
+                ));
+            }
+            // Only bytes can be cast to chars, so a literal cast to one (like `(char) 65`) is a `u8`
+            (UnaryOperator::Cast(Type::Char), _) if matches!(unary.operand.kind, ExprKind::IntLiteral(_)) => {
+                Some(&Type::Int(IntType { signed: false, width: 8 }))
+            }
+            (UnaryOperator::Cast(_), _) => None,
+
+            (UnaryOperator::Negate, None) => None,
+            (UnaryOperator::Negate, Some(t @ (Type::Int(IntType { signed: true, .. }) | Type::Float(_)))) => Some(t),
+            (UnaryOperator::Negate, Some(t)) => {
+                return Err(Self::err(TypingError::InvalidNegation(t.clone()), span));
+            }
+
+            (UnaryOperator::BitwiseNot, None) => None,
+            (UnaryOperator::BitwiseNot, Some(t @ Type::Int(_))) => Some(t),
+            (UnaryOperator::BitwiseNot, Some(t)) => {
+                return Err(Self::err(TypingError::InvalidBitwiseNot(t.clone()), span));
+            }
+
+            (UnaryOperator::Not, None | Some(Type::Bool)) => Some(&Type::Bool),
+            (UnaryOperator::Not, Some(desired)) => {
+                return Err(Self::err(
+                    TypingError::TypeMismatch { expected: desired.clone(), found: Type::Bool },
+                    span,
+                ));
+            }
+
+            // A mismatch with the desired type is reported once the operand's type is known
+            (UnaryOperator::AddressOf, Some(Type::Pointer(pointee_type))) if **pointee_type != Type::Void => {
+                Some(pointee_type.as_ref())
+            }
+            (UnaryOperator::AddressOf | UnaryOperator::Deref, _) => None,
+        };
+
+        if unary.operator == UnaryOperator::AddressOf && !self.is_place(&unary.operand) {
+            return Err(Self::err(TypingError::InvalidAddressOf, span));
+        }
+        // Only the innermost minus is part of the literal, so `--128` is 128, and it isn't an `i8`
+        // even though `-128` is (negating that again overflows). It's reported as the value it
+        // folds to, at the literal itself
+        if let (UnaryOperator::Negate, ExprKind::Unary(Unary { operator: UnaryOperator::Negate, operand })) =
+            (&unary.operator, &unary.operand.kind)
+        {
+            if let (ExprKind::IntLiteral(int), Some(Type::Int(int_type))) = (&operand.kind, desired_operand_type) {
+                // The lexer only produces literals made of digits
+                if !BigUint::from_decimal(int).unwrap().fits_in(*int_type, false) {
+                    let literal = int.clone();
+                    return Err(Self::err(TypingError::IntLiteralOutOfRange { literal, int_type: *int_type }, operand.span));
+                }
+            }
+        }
+        let typed_operand = match (&unary.operator, &unary.operand.kind) {
+            // A negated literal can be one larger than the largest positive value, like `-128` as
+            // an `i8`, so its range is checked along with the minus
+            (UnaryOperator::Negate, ExprKind::IntLiteral(int)) => TypedExpr {
+                kind: TypedExprKind::IntLiteral(Self::type_int_literal(int, span, desired_operand_type, true)?),
+                span: unary.operand.span,
+            },
+            _ => self.type_expr(&unary.operand, desired_operand_type)?,
+        };
+        let operand_type = typed_operand.get_result_type();
+
+        // Now that we know the type of the operand, we can check if the unary operator is valid
+        match &unary.operator {
+            UnaryOperator::Cast(cast_type) => {
+                self.check_valid_type(cast_type, span)?;
+                Self::check_valid_cast(cast_type, &operand_type, span)?
+            }
+            UnaryOperator::Negate => Self::check_valid_negation(&operand_type, span)?,
+            UnaryOperator::BitwiseNot => Self::check_valid_bitwise_not(&operand_type, span)?,
+            // The operand was already typed as a bool
+            UnaryOperator::Not => {}
+            // Functions aren't stored in variables
+            UnaryOperator::AddressOf if matches!(operand_type, Type::Func(_)) => {
+                return Err(Self::err(TypingError::InvalidAddressOf, span));
+            }
+            UnaryOperator::AddressOf | UnaryOperator::Deref => {}
+        }
 
         let result_type = match &unary.operator {
             UnaryOperator::Cast(cast_type) => cast_type.clone(),
-            UnaryOperator::Negate => operand_type,
+            UnaryOperator::Negate | UnaryOperator::BitwiseNot => operand_type,
+            UnaryOperator::Not => Type::Bool,
+            UnaryOperator::AddressOf if operand_type == Type::Error => Type::Error,
+            UnaryOperator::AddressOf => Type::Pointer(Box::new(operand_type)),
+            UnaryOperator::Deref => self.type_pointee(&operand_type, span)?,
         };
+        if let Some(desired) = desired_type.filter(|&t| *t != result_type && result_type != Type::Error) {
+            return Err(Self::err(
+                TypingError::TypeMismatch { expected: desired.clone(), found: result_type },
+                span,
+            ));
+        }
 
-        TypedUnary {
+        Ok(TypedUnary {
             operator: unary.operator.clone(),
             operand: Box::new(typed_operand),
             result_type,
+        })
+    }
+
+    /// Returns whether `expr` refers to memory that its address can be taken of, namely a
+    /// variable (but not a constant), a field or element of one, or a dereferenced pointer.
+    fn is_place(&self, expr: &Expr) -> bool {
+        match &expr.kind {
+            ExprKind::Identifier(name) => !self.is_const(name),
+            ExprKind::FieldAccess(access) => self.is_place(&access.expr),
+            ExprKind::Index(index) => self.is_place(&index.expr),
+            ExprKind::Unary(unary) => unary.operator == UnaryOperator::Deref,
+            _ => false,
         }
     }
 
     /// Checks that the identifier labeled `name` can be interpreted as the type `desired_type`, and
     /// wraps it as a `TypedIdentifier`.
     ///
-    /// Note: currently, this function will fail unless `name` was declared to be `desired_type`.
-    /// In the future, this function might support type escalation, like letting an `name` of type
-    /// `i32` but be allowed to be typed as `i64`.
-    fn type_identifier(&self, name: &str, desired_type: Option<&Type>) -> TypedIdentifier {
-        let actual_type = self.scope_manager.get(name);
-        let id_type = match (actual_type, desired_type) {
-            (None, _) => panic!("Identifier '{}' has not been declared yet.", name),
+    /// Note: this function fails unless `name` was declared to be `desired_type`; identifiers of
+    /// narrower integer types are widened by [Typer::type_expr] instead.
+    fn type_identifier(
+        &mut self,
+        name: &str,
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedIdentifier> {
+        if let Some(usage) = self.var_usages.get_mut(name) {
+            usage.used = true;
+        }
+
+        let actual_type = self.scope_manager.get(name).map(|t| self.resolve_inferred(t));
+        let id_type = match (actual_type.as_ref(), desired_type) {
+            (None, _) => {
+                self.scope_manager.set(name, Type::Error);
+                return Err(Self::err(TypingError::UndeclaredIdentifier(name.to_string()), span));
+            }
+            (Some(Type::Error), _) => Type::Error,
+            // The first use that requires a particular integer type decides the variable's type
+            (Some(Type::InferredInt(id)), Some(Type::Int(int_type))) => {
+                self.inferred_ints[*id].var_type = Some(Type::Int(*int_type));
+                Type::Int(*int_type)
+            }
             (Some(actual), Some(desired)) if actual == desired => actual.clone(),
-            (Some(actual), Some(desired)) => panic!(
-                "Identifier '{}' of type '{}' cannot be used as type '{}'",
-                name, actual, desired
-            ),
+            (Some(actual), Some(desired)) => {
+                return Err(Self::err(
+                    TypingError::IdentifierTypeMismatch {
+                        name: name.to_string(),
+                        expected: desired.clone(),
+                        found: actual.clone(),
+                    },
+                    span,
+                ));
+            }
             (Some(actual), None) => actual.clone(),
         };
 
-        TypedIdentifier {
+        Ok(TypedIdentifier {
             name: name.to_string(),
             id_type,
-        }
+        })
     }
 
-    /// Checks that `desired_type` is a valid type (namely, an integer type) and wraps the
-    /// `int_literal` as a `TypedIntLiteral`.
-    fn type_int_literal(&self, int_literal: &str, desired_type: Option<&Type>) -> TypedIntLiteral {
+    /// Checks that `desired_type` is a valid type (namely, an integer type) that can represent
+    /// `int_literal` (negated, if `is_negated`), and wraps the `int_literal` as a
+    /// `TypedIntLiteral`.
+    fn type_int_literal(
+        int_literal: &str,
+        span: Span,
+        desired_type: Option<&Type>,
+        is_negated: bool,
+    ) -> crate::Result<TypedIntLiteral> {
         let int_type = match desired_type {
             Some(Type::Int(int_type)) => *int_type,
             Some(t) => {
-                panic!(
-                    "Expected integer type for literal '{}', but the desired type is '{}'",
-                    int_literal, t
-                )
+                return Err(Self::err(
+                    TypingError::IntLiteralTypeMismatch {
+                        literal: int_literal.to_string(),
+                        expected: t.clone(),
+                    },
+                    span,
+                ));
             }
             None => IntType { signed: false, width: 64 },
         };
 
-        // TODO: Ensure that the int_literal.value fits within the desired width
+        // The lexer only produces literals made of digits
+        let value = BigUint::from_decimal(int_literal).unwrap();
+        if !value.fits_in(int_type, is_negated) {
+            let literal = match is_negated {
+                true => format!("-{}", int_literal),
+                false => int_literal.to_string(),
+            };
+            return Err(Self::err(TypingError::IntLiteralOutOfRange { literal, int_type }, span));
+        }
 
-        TypedIntLiteral {
+        Ok(TypedIntLiteral {
             int_value: int_literal.to_string(),
             int_type,
+        })
+    }
+
+    /// Checks that `desired_type` is a valid type (namely, a float type, or `f64` if there's no
+    /// desired type) that can represent `float_literal`, and wraps it as a `TypedFloatLiteral`.
+    fn type_float_literal(
+        float_literal: &str,
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedFloatLiteral> {
+        let float_type = match desired_type {
+            Some(Type::Float(float_type)) => *float_type,
+            Some(t) => {
+                return Err(Self::err(
+                    TypingError::FloatLiteralTypeMismatch {
+                        literal: float_literal.to_string(),
+                        expected: t.clone(),
+                    },
+                    span,
+                ));
+            }
+            None => FloatType { width: 64 },
+        };
+
+        // The lexer only produces valid literals, but they can round to infinity
+        let value: f64 = float_literal.parse().unwrap();
+        let is_infinite = match float_type.width {
+            32 => (value as f32).is_infinite(),
+            _ => value.is_infinite(),
+        };
+        if is_infinite {
+            return Err(Self::err(
+                TypingError::FloatLiteralOutOfRange { literal: float_literal.to_string(), float_type },
+                span,
+            ));
         }
+
+        Ok(TypedFloatLiteral {
+            float_value: float_literal.to_string(),
+            float_type,
+        })
     }
 
     /// Types a binary expression; see [Typer::type_expr] for details.
-    fn type_binary_expr(&mut self, binary_expr: &Binary, desired_type: Option<&Type>) -> TypedBinary {
-        let left = self.type_expr(&binary_expr.left, desired_type);
+    fn type_binary_expr(
+        &mut self,
+        binary_expr: &Binary,
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedBinary> {
         let operator = binary_expr.operator;
-        let right = self.type_expr(&binary_expr.right, desired_type);
+        let (left, right) = self.type_operands(&binary_expr.left, &binary_expr.right, desired_type)?;
 
         let left_type = left.get_result_type();
         let right_type = right.get_result_type();
+        if left_type == Type::Error || right_type == Type::Error {
+            return Ok(TypedBinary {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+                result_type: Type::Error,
+            });
+        }
+        // The whole expression is typed again once the operands' type is inferred
+        if let Some(result_type) = self.unify_inferred_ints(&left_type, &right_type) {
+            return Ok(TypedBinary {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+                result_type,
+            });
+        }
         if left_type != right_type {
-            panic!(
-                "Operator '{}' needs left-hand-side ({}) and right-hand-side ({}) to be the same type",
-                operator, left_type, right_type
-            );
+            return Err(Self::err(
+                TypingError::BinaryOperandMismatch { operator, left: left_type, right: right_type },
+                span,
+            ));
+        }
+
+        // Arithmetic is only defined on numbers, and bitwise operators only on integers
+        let is_arithmetic = matches!(
+            operator,
+            BinaryOperator::Add
+                | BinaryOperator::Subtract
+                | BinaryOperator::Multiply
+                | BinaryOperator::Divide
+                | BinaryOperator::Remainder
+        );
+        if !matches!((&left_type, is_arithmetic), (Type::Int(_), _) | (Type::Float(_), true)) {
+            return Err(Self::err(
+                TypingError::InvalidBinaryOperand { operator, operand_type: left_type },
+                span,
+            ));
+        }
+        if let (BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight, Type::Int(int_type)) = (operator, &left_type) {
+            Self::check_literal_shift_amount(&binary_expr.right, *int_type)?;
         }
 
-        TypedBinary {
+        Ok(TypedBinary {
             left: Box::new(left),
             operator,
             right: Box::new(right),
             result_type: left_type,  // since both types must be equal
+        })
+    }
+
+    /// Returns an error if `amount`, the right operand of a shift on an `int_type`, is a literal
+    /// that's negative or at least the type's width, like `8` in `x << 8` for a `u8` (which would
+    /// shift every bit out). Other amounts are checked at runtime.
+    fn check_literal_shift_amount(amount: &Expr, int_type: IntType) -> crate::Result<()> {
+        let out_of_range_literal = match &amount.kind {
+            ExprKind::IntLiteral(int) if int.parse::<u32>().map_or(true, |int| int >= int_type.width) => Some(int.clone()),
+            ExprKind::Unary(Unary { operator: UnaryOperator::Negate, operand }) => match &operand.kind {
+                ExprKind::IntLiteral(int) if int.parse::<u32>() != Ok(0) => Some(format!("-{}", int)),
+                _ => None,
+            },
+            _ => None,
+        };
+        match out_of_range_literal {
+            Some(amount_literal) => Err(Self::err(
+                TypingError::ShiftAmountOutOfRange { amount: amount_literal, int_type },
+                amount.span,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns whether the type of `expr` doesn't depend on the type it's expected to have, like
+    /// the type of a variable or a call (unlike a literal, whose type is whichever is expected).
+    ///
+    /// Built-in arithmetic functions like `wrapping_add` are typed like the binary expressions
+    /// they compute, so their type depends on the expected type too.
+    fn has_own_type(&self, expr: &Expr) -> bool {
+        match &expr.kind {
+            ExprKind::Call(call) => self.arithmetic_builtin(&call.function_name).is_none(),
+            ExprKind::Identifier(_) | ExprKind::FieldAccess(_) | ExprKind::Index(_) => true,
+            ExprKind::Unary(unary) => matches!(unary.operator, UnaryOperator::Cast(_) | UnaryOperator::Deref),
+            _ => false,
+        }
+    }
+
+    /// Returns whether every value of `from` is also a value of `to`, so that integers of type
+    /// `from` can implicitly be used as `to`s: `to` must be wider, and signed unless `from` isn't.
+    fn is_lossless_widening(from: IntType, to: IntType) -> bool {
+        from.width < to.width && (to.signed || !from.signed)
+    }
+
+    /// Wraps `typed_expr`, an integer, in a cast to the wider `int_type` (see
+    /// [Typer::is_lossless_widening]).
+    fn widen(typed_expr: TypedExpr, int_type: IntType) -> TypedExpr {
+        let span = typed_expr.span;
+        TypedExpr {
+            kind: TypedExprKind::Unary(TypedUnary {
+                operator: UnaryOperator::Cast(Type::Int(int_type)),
+                operand: Box::new(typed_expr),
+                result_type: Type::Int(int_type),
+            }),
+            span,
+        }
+    }
+
+    /// Types the operands of a binary or comparison expression, which must have the same type.
+    ///
+    /// If both operands are integers and one of them is narrower, it's widened to the other's
+    /// type when that's lossless (see [Typer::is_lossless_widening]), like `a` in `a + b` if `a`
+    /// is an `i32` and `b` is an `i64`.
+    ///
+    /// An operand made only of integer literals (see [Typer::default_int_type]) takes the type of
+    /// the other operand if nothing else decides it, like `3` in `3 < x`, so the other operand is
+    /// typed first. If both operands are made only of integer literals, they share a default type.
+    /// Likewise, an operand made only of float literals takes the float type of the other operand,
+    /// like `1.0` in `x < 1.0` where `x` is an `f32` (and is an `f64` otherwise).
+    fn type_operands(
+        &mut self,
+        left: &Expr,
+        right: &Expr,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<(TypedExpr, TypedExpr)> {
+        // The type of an operand, unless it's still being inferred
+        let peer_type = |operand: &TypedExpr| Some(operand.get_result_type()).filter(|t| !matches!(t, Type::InferredInt(_)));
+        let (typed_left, typed_right) = match (desired_type, Self::default_int_type(left), Self::default_int_type(right)) {
+            (None, Some(left_default), Some(right_default)) => {
+                let signed = left_default.signed || right_default.signed;
+                let default_type = Type::Int(IntType { signed, width: 64 });
+                (self.type_inferable_expr(left, Some(&default_type))?, self.type_inferable_expr(right, Some(&default_type))?)
+            }
+            (None, Some(_), None) => {
+                let typed_right = self.type_inferable_expr(right, None)?;
+                (self.type_inferable_expr(left, peer_type(&typed_right).as_ref())?, typed_right)
+            }
+            (None, None, Some(_)) => {
+                let typed_left = self.type_inferable_expr(left, None)?;
+                let typed_right = self.type_inferable_expr(right, peer_type(&typed_left).as_ref())?;
+                (typed_left, typed_right)
+            }
+            (None, _, _) if Self::is_float_literal_expr(left) && !Self::is_float_literal_expr(right) => {
+                let typed_right = self.type_inferable_expr(right, None)?;
+                let float_type = Some(typed_right.get_result_type()).filter(|t| matches!(t, Type::Float(_)));
+                (self.type_inferable_expr(left, float_type.as_ref())?, typed_right)
+            }
+            (None, _, _) if Self::is_float_literal_expr(right) && !Self::is_float_literal_expr(left) => {
+                let typed_left = self.type_inferable_expr(left, None)?;
+                let float_type = Some(typed_left.get_result_type()).filter(|t| matches!(t, Type::Float(_)));
+                let typed_right = self.type_inferable_expr(right, float_type.as_ref())?;
+                (typed_left, typed_right)
+            }
+            _ => (self.type_inferable_expr(left, desired_type)?, self.type_inferable_expr(right, desired_type)?),
+        };
+        let (typed_left, typed_right) = self.unify_operands((left, typed_left), (right, typed_right))?;
+        Ok(match (typed_left.get_result_type(), typed_right.get_result_type()) {
+            (Type::Int(left_type), Type::Int(right_type)) if Self::is_lossless_widening(left_type, right_type) => {
+                (Self::widen(typed_left, right_type), typed_right)
+            }
+            (Type::Int(left_type), Type::Int(right_type)) if Self::is_lossless_widening(right_type, left_type) => {
+                (typed_left, Self::widen(typed_right, left_type))
+            }
+            _ => (typed_left, typed_right),
+        })
+    }
+
+    /// If either of two operand types is still being inferred, returns the type of the expression
+    /// they're the operands of (which is typed again once that type is known). If both are, the
+    /// two inferred variables must have the same type.
+    fn unify_inferred_ints(&mut self, left_type: &Type, right_type: &Type) -> Option<Type> {
+        match (left_type, right_type) {
+            (Type::InferredInt(left_id), Type::InferredInt(right_id)) => {
+                let (left_root, right_root) = (self.root(*left_id), self.root(*right_id));
+                if left_root != right_root {
+                    self.inferred_ints[right_root].same_as = Some(left_root);
+                }
+                Some(Type::InferredInt(left_root))
+            }
+            (Type::InferredInt(id), _) | (_, Type::InferredInt(id)) => Some(Type::InferredInt(self.root(*id))),
+            _ => None,
+        }
+    }
+
+    /// If one of two operands has an integer type that's still being inferred (see
+    /// [Type::InferredInt]) and the other has a known integer type, types the first operand again
+    /// as that type, so that both operands have the same type.
+    ///
+    /// An operand made only of integer literals doesn't count as having a known type, since it's
+    /// only typed as `u64` by default.
+    fn unify_operands(
+        &mut self,
+        left: (&Expr, TypedExpr),
+        right: (&Expr, TypedExpr),
+    ) -> crate::Result<(TypedExpr, TypedExpr)> {
+        let known_type = |(expr, typed_expr): &(&Expr, TypedExpr)| match typed_expr.get_result_type() {
+            Type::Int(int_type) if Self::default_int_type(expr).is_none() => Some(Type::Int(int_type)),
+            _ => None,
+        };
+        let is_inferred = |(_, typed_expr): &(&Expr, TypedExpr)| matches!(typed_expr.get_result_type(), Type::InferredInt(_));
+        match (known_type(&left), known_type(&right)) {
+            (Some(t), _) if is_inferred(&right) => Ok((left.1, self.type_expr(right.0, Some(&t))?)),
+            (_, Some(t)) if is_inferred(&left) => Ok((self.type_expr(left.0, Some(&t))?, right.1)),
+            _ => Ok((left.1, right.1)),
         }
     }
 
@@ -391,118 +2153,310 @@ impl Typer {
     fn type_comparison_expr(
         &mut self,
         comparison: &Comparison,
+        span: Span,
         desired_type: Option<&Type>,
-    ) -> TypedComparison {
-        if desired_type.is_some() && desired_type != Some(&Type::Bool) {
-            panic!(
-                "Comparison expressions return an i1 but expected '{}'",
-                desired_type.unwrap()
-            );
+    ) -> crate::Result<TypedComparison> {
+        if let Some(desired) = desired_type.filter(|&t| *t != Type::Bool) {
+            return Err(Self::err(
+                TypingError::TypeMismatch { expected: desired.clone(), found: Type::Bool },
+                span,
+            ));
         }
         // TODO for future: Find common type (by casting/coalescing), like i64 can fit both i64 and i32
 
-        let left = self.type_expr(&comparison.left, None);
+        // `null` takes the type of the pointer it's compared with
         let operator = comparison.operator;
-        let right = self.type_expr(&comparison.right, None);
+        let (left, right) = match (&comparison.left.kind, &comparison.right.kind) {
+            (ExprKind::Null, _) => {
+                let right = self.type_expr(&comparison.right, None)?;
+                (self.type_expr(&comparison.left, Some(&right.get_result_type()))?, right)
+            }
+            (_, ExprKind::Null) => {
+                let left = self.type_expr(&comparison.left, None)?;
+                let right = self.type_expr(&comparison.right, Some(&left.get_result_type()))?;
+                (left, right)
+            }
+            _ => self.type_operands(&comparison.left, &comparison.right, None)?,
+        };
 
         let left_type = left.get_result_type();
         let right_type = right.get_result_type();
+        // The function is typed again once the operands' type is inferred (see
+        // [Typer::check_inferred])
+        if let Some(operand_type) = self.unify_inferred_ints(&left_type, &right_type) {
+            self.needs_retyping = true;
+            return Ok(TypedComparison {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+                operand_type,
+            });
+        }
 
-        if left_type != right_type {
-            panic!(
-                "Comparison '{}' needs left-hand-side ({}) and right-hand-side ({}) to be the same type",
-                comparison.operator, left_type, right_type
-            );
+        let has_error_operand = left_type == Type::Error || right_type == Type::Error;
+        if left_type != right_type && !has_error_operand {
+            return Err(Self::err(
+                TypingError::ComparisonOperandMismatch { operator, left: left_type, right: right_type },
+                span,
+            ));
+        }
+        if let Type::Named(_) | Type::Array(..) | Type::Str = left_type {
+            return Err(Self::err(
+                TypingError::InvalidComparisonOperand { operator, operand_type: left_type },
+                span,
+            ));
         }
 
-        TypedComparison {
+        Ok(TypedComparison {
             left: Box::new(left),
             operator,
             right: Box::new(right),
             operand_type: left_type, // since both types must be equal
+        })
+    }
+
+    /// Checks that both sides of a logical expression (like `a and b`) are `bool`s, and that a
+    /// `bool` is what's desired.
+    fn type_logical_expr(
+        &mut self,
+        logical: &Logical,
+        span: Span,
+        desired_type: Option<&Type>,
+    ) -> crate::Result<TypedLogical> {
+        if let Some(desired) = desired_type.filter(|&t| *t != Type::Bool) {
+            return Err(Self::err(
+                TypingError::TypeMismatch { expected: desired.clone(), found: Type::Bool },
+                span,
+            ));
         }
+
+        let left = self.type_expr(&logical.left, Some(&Type::Bool))?;
+        let right = self.type_expr(&logical.right, Some(&Type::Bool))?;
+
+        Ok(TypedLogical {
+            left: Box::new(left),
+            operator: logical.operator,
+            right: Box::new(right),
+        })
     }
 
     /// Types a call expression, making sure that it matches the function's prototype, and that the
     /// return type matches the `desired_type`.[^note]
     ///
     /// [^note]: See also [Typer::type_expr] for details about `desired_type`.
-    fn type_call(&mut self, call: &Call, desired_type: Option<&Type>) -> TypedCall {
+    fn type_call(&mut self, call: &Call, span: Span, desired_type: Option<&Type>) -> crate::Result<TypedCall> {
         let function_name = call.function_name.clone();
 
         let function_proto = match self.scope_manager.get(&function_name) {
-            Some(Type::Func(f)) => f.clone(),
-            Some(_) => panic!("Variable '{}' is not a function", function_name),
-            None => panic!(
-                "Function '{}' is called but has not been defined",
-                function_name
-            ),
+            Some(Type::Func(f)) => *f.clone(),
+            Some(Type::Error) => return self.type_erroneous_call(call, span),
+            Some(t) => {
+                return Err(Self::err(
+                    TypingError::NotAFunction { name: function_name, found: t.clone() },
+                    span,
+                ));
+            }
+            None => {
+                self.scope_manager.set(&function_name, Type::Error);
+                return Err(Self::err(TypingError::UndefinedFunction(function_name), span));
+            }
         };
 
-        if desired_type.is_some() && Some(function_proto.return_type.as_ref()) != desired_type {
-            panic!(
-                "Expected function '{}' to return '{}' but it has return type '{}'",
-                function_name,
-                desired_type.unwrap(),
-                function_proto.return_type
-            )
+        if let Some(desired) = desired_type.filter(|&t| t != function_proto.return_type.as_ref()) {
+            return Err(Self::err(
+                TypingError::ReturnTypeMismatch {
+                    function_name,
+                    expected: desired.clone(),
+                    found: *function_proto.return_type,
+                },
+                span,
+            ));
         }
 
         let num_params = function_proto.params.len();
         if num_params != call.args.len() {
-            panic!(
-                "Expected {} argument(s) to function '{}'; got {} argument(s)",
-                num_params,
-                call.function_name,
-                call.args.len()
-            );
+            return Err(Self::err(
+                TypingError::WrongArgumentCount {
+                    function_name,
+                    expected: num_params,
+                    found: call.args.len(),
+                },
+                span,
+            ));
         }
 
-        let args: Vec<_> = call
+        let args = call
             .args
             .iter()
             .zip(function_proto.params.iter())
             .map(|(e, p)| self.type_expr(e, Some(&p.param_type)))
-            .collect();
+            .collect::<crate::Result<Vec<_>>>()?;
 
-        TypedCall {
+        Ok(TypedCall {
             function_name,
             function_proto,
             args,
+        })
+    }
+
+    /// Returns the overflow behavior and operator of the built-in arithmetic function named
+    /// `function_name`, like [Overflow::Wrapping] and `+` for `wrapping_add`, unless the program
+    /// defines something with that name (which hides the built-in function).
+    fn arithmetic_builtin(&self, function_name: &str) -> Option<(Overflow, BinaryOperator)> {
+        if self.scope_manager.get(function_name).is_some() {
+            return None;
+        }
+        let (overflow, operation) = function_name.split_once('_')?;
+        let overflow = match overflow {
+            "wrapping" => Overflow::Wrapping,
+            "checked" => Overflow::Checked,
+            "saturating" => Overflow::Saturating,
+            _ => return None,
+        };
+        let operator = match operation {
+            "add" => BinaryOperator::Add,
+            "sub" => BinaryOperator::Subtract,
+            "mul" => BinaryOperator::Multiply,
+            _ => return None,
+        };
+        Some((overflow, operator))
+    }
+
+    /// Types a call to a built-in arithmetic function (see [Typer::arithmetic_builtin]), like
+    /// `saturating_sub(a, b)`, whose operands are typed like the binary expression `a - b`.
+    ///
+    /// `checked_` functions return a `bool`, and also take a pointer to the integer type of the
+    /// operands, like `&product` in `checked_mul(a, b, &product)`. The operands take the type
+    /// that the pointer points to if nothing else decides it.
+    fn type_arithmetic_call(
+        &mut self,
+        call: &Call,
+        span: Span,
+        desired_type: Option<&Type>,
+        overflow: Overflow,
+        operator: BinaryOperator,
+    ) -> crate::Result<TypedArithmeticCall> {
+        let function_name = call.function_name.clone();
+        let num_params = if overflow == Overflow::Checked { 3 } else { 2 };
+        if call.args.len() != num_params {
+            return Err(Self::err(
+                TypingError::WrongArgumentCount { function_name, expected: num_params, found: call.args.len() },
+                span,
+            ));
+        }
+
+        let (operand_type, result_ptr) = match overflow {
+            Overflow::Checked => {
+                if let Some(desired) = desired_type.filter(|&t| *t != Type::Bool) {
+                    return Err(Self::err(
+                        TypingError::TypeMismatch { expected: desired.clone(), found: Type::Bool },
+                        span,
+                    ));
+                }
+                let result_ptr = self.type_expr(&call.args[2], None)?;
+                let operand_type = match result_ptr.get_result_type() {
+                    Type::Pointer(pointee) if matches!(*pointee, Type::Int(_)) => Some(*pointee),
+                    _ => None,
+                };
+                (operand_type, Some(result_ptr))
+            }
+            Overflow::Wrapping | Overflow::Saturating => (desired_type.cloned(), None),
+        };
+
+        let binary = Binary {
+            left: Box::new(call.args[0].clone()),
+            operator,
+            right: Box::new(call.args[1].clone()),
+        };
+        let binary = self.type_binary_expr(&binary, span, operand_type.as_ref())?;
+        if let Type::Float(_) = binary.result_type {
+            return Err(Self::err(
+                TypingError::NonIntegerArithmetic { function_name, found: binary.result_type },
+                span,
+            ));
+        }
+
+        if let Some(result_ptr) = &result_ptr {
+            let ptr_type = result_ptr.get_result_type();
+            let expected = Type::Pointer(Box::new(binary.result_type.clone()));
+            if ptr_type != expected && ptr_type != Type::Error && binary.result_type != Type::Error {
+                return Err(Self::err(
+                    TypingError::TypeMismatch { expected, found: ptr_type },
+                    result_ptr.span,
+                ));
+            }
         }
+
+        Ok(TypedArithmeticCall {
+            function_name,
+            overflow,
+            binary,
+            result_ptr: result_ptr.map(Box::new),
+        })
+    }
+
+    /// Types a call to a name whose type is [Type::Error] (e.g. an undefined function that was
+    /// already reported), checking its arguments but nothing else.
+    fn type_erroneous_call(&mut self, call: &Call, span: Span) -> crate::Result<TypedCall> {
+        let args = call
+            .args
+            .iter()
+            .map(|e| self.type_expr(e, None))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let function_proto = FuncProto {
+            func_visibility: FuncVisibility::Private,
+            name: call.function_name.clone(),
+            params: vec![],
+            return_type: Box::new(Type::Error),
+            span,
+        };
+
+        Ok(TypedCall {
+            function_name: call.function_name.clone(),
+            function_proto,
+            args,
+        })
     }
 
-    /// Panics if the cast is invalid, like casting from an unsigned type to a signed type.
-    fn check_valid_cast(cast_type: &Type, operand_type: &Type) {
+    /// Returns an error if the cast is invalid, like casting from a signed type to an unsigned
+    /// type.
+    fn check_valid_cast(cast_type: &Type, operand_type: &Type, span: Span) -> crate::Result<()> {
         match (cast_type, operand_type) {
-            (Type::Int(IntType { signed: true, .. }), Type::Int(IntType { signed: false, .. })) => {
-                panic!(
-                    "Cannot cast from unsigned type '{}' to signed type '{}'",
-                    operand_type, cast_type
-                )
-            }
-            (Type::Int(IntType { signed: false, .. }), Type::Int(IntType { signed: true, .. })) => {
-                panic!(
-                    "Cannot cast from signed type '{}' to unsigned type '{}'",
-                    operand_type, cast_type
-                )
-            }
-            (Type::Int(_), Type::Int(_)) => (), // valid cast
-            _ => panic!(
-                "Cannot cast from type '{}' to type '{}'",
-                operand_type, cast_type
-            ),
+            (_, Type::Error) => Ok(()),
+            (Type::Int(IntType { signed: to_signed, .. }), Type::Int(IntType { signed: from_signed, .. }))
+                if to_signed == from_signed => Ok(()), // valid cast
+            // Anything that would be widened implicitly can also be cast explicitly, like a `u8` to
+            // an `i16`
+            (Type::Int(to), Type::Int(from)) if Self::is_lossless_widening(*from, *to) => Ok(()),
+            // Numbers can be converted between integers and floats of any width
+            (Type::Int(_) | Type::Float(_), Type::Float(_)) | (Type::Float(_), Type::Int(_)) => Ok(()),
+            // A char is its code point, which any integer can hold (truncated if it's too narrow),
+            // but only bytes are always valid code points
+            (Type::Int(_), Type::Char) | (Type::Char, Type::Int(IntType { signed: false, width: 8 })) => Ok(()),
+            // Pointers can be reinterpreted as pointing to another type, like `*void` to `*u8`
+            (Type::Pointer(_), Type::Pointer(_)) => Ok(()),
+            _ => Err(Self::err(
+                TypingError::InvalidCast { from: operand_type.clone(), to: cast_type.clone() },
+                span,
+            )),
         }
     }
 
-    /// Panics if the operand type cannot be negated
+    /// Returns an error if the operand type cannot be negated
     ///
     /// For example, unsigned integers cannot be negated
-    fn check_valid_negation(operand_type: &Type) {
+    fn check_valid_negation(operand_type: &Type, span: Span) -> crate::Result<()> {
+        match operand_type {
+            Type::Int(IntType { signed: true, .. }) | Type::Float(_) | Type::Error => Ok(()),
+            t => Err(Self::err(TypingError::InvalidNegation(t.clone()), span)),
+        }
+    }
+
+    fn check_valid_bitwise_not(operand_type: &Type, span: Span) -> crate::Result<()> {
         match operand_type {
-            Type::Int(IntType { signed: true, .. }) => (),
-            Type::Int(IntType { signed: false, .. }) => panic!("Cannot negate an unsigned type: '{}'", operand_type),
-            t => panic!("Cannot negate a non-integer type '{}'", t)
+            Type::Int(_) | Type::Error => Ok(()),
+            t => Err(Self::err(TypingError::InvalidBitwiseNot(t.clone()), span)),
         }
     }
 }
@@ -521,32 +2475,181 @@ impl Default for Typer {
 mod tests {
     use super::*;
     use crate::ast::*;
+    use crate::error::ErrorKind;
+    use crate::span::{Position, Span};
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
-    #[test]
-    #[should_panic(expected = "No main function defined")]
-    fn missing_main_function() {
-        // pub fn not_main() i32 {
-        //   ret 0
-        // }
+    /// Creates an expression with a placeholder span.
+    fn expr(kind: ExprKind) -> Expr {
+        Expr { kind, span: Span::default() }
+    }
 
-        let program = Program {
+    /// Creates a statement with a placeholder span.
+    fn statement(kind: StatementKind) -> Statement {
+        Statement { kind, span: Span::default() }
+    }
+
+    /// Returns the `u8` type, which most of the test programs return from `main`.
+    fn u8_type() -> Type {
+        Type::Int(IntType { width: 8, signed: false })
+    }
+
+    /// Creates an integer literal with a placeholder span.
+    fn int(i: &str) -> Expr {
+        expr(ExprKind::IntLiteral(i.to_string()))
+    }
+
+    /// Creates a negated integer literal, like `-1`, with placeholder spans.
+    fn negated(i: &str) -> Expr {
+        expr(ExprKind::Unary(Unary { operator: UnaryOperator::Negate, operand: Box::new(int(i)) }))
+    }
+
+    /// Creates a float literal with a placeholder span.
+    fn float(f: &str) -> Expr {
+        expr(ExprKind::FloatLiteral(f.to_string()))
+    }
+
+    /// Creates an identifier with a placeholder span.
+    fn id(name: &str) -> Expr {
+        expr(ExprKind::Identifier(name.to_string()))
+    }
+
+    /// Creates a variable declaration with a placeholder span.
+    fn var_declaration(var_name: &str, var_type: Type, var_value: Expr) -> Statement {
+        statement(StatementKind::VarDeclaration(VarDeclaration { var_name: var_name.to_string(), var_type, var_value }))
+    }
+
+    /// Creates a `let` declaration. Since inferred types are looked up by the span of their
+    /// declaration, each one gets a placeholder span on a line of its own.
+    fn let_declaration(var_name: &str, is_mutable: bool, var_value: Expr) -> Statement {
+        static LINE: AtomicUsize = AtomicUsize::new(1);
+        let position = Position { offset: 0, line: LINE.fetch_add(1, Ordering::Relaxed), column: 1 };
+        Statement {
+            kind: StatementKind::Let(Let { var_name: var_name.to_string(), is_mutable, var_value }),
+            span: Span::new(0, position, position),
+        }
+    }
+
+    /// Creates a typed expression with a placeholder span.
+    fn typed_expr(kind: TypedExprKind) -> TypedExpr {
+        TypedExpr { kind, span: Span::default() }
+    }
+
+    /// Creates a typed statement with a placeholder span.
+    fn typed_statement(kind: TypedStatementKind) -> TypedStatement {
+        TypedStatement { kind, span: Span::default() }
+    }
+
+    /// Types `program`, expecting it to fail with one or more [TypingError]s.
+    fn typing_errors(program: &Program) -> Vec<TypingError> {
+        let mut typer = Typer::new();
+        let errors = typer.type_program(program).unwrap_err();
+        errors
+            .into_iter()
+            .map(|err| match err.kind {
+                ErrorKind::TypingError(err) => *err,
+                kind => panic!("Expected a typing error, found {:?}", kind),
+            })
+            .collect()
+    }
+
+    /// Types `program`, expecting it to fail with exactly one [TypingError].
+    fn typing_error(program: &Program) -> TypingError {
+        let mut errors = typing_errors(program);
+        assert_eq!(errors.len(), 1, "expected exactly one error, got {:?}", errors);
+        errors.remove(0)
+    }
+
+    /// Creates a program whose only function is `pub fn main() u8` with the given `body`.
+    fn main_with_body(body: Vec<Statement>) -> Program {
+        Program {
+            global_statements: vec![GlobalStatement::FuncDef(FuncDef {
+                proto: FuncProto {
+                    func_visibility: FuncVisibility::Public,
+                    name: "main".to_string(),
+                    params: vec![],
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
+                },
+                body,
+            })],
+        }
+    }
+
+    /// Creates a struct definition with a placeholder span.
+    fn struct_def(name: &str, fields: Vec<(Type, &str)>) -> GlobalStatement {
+        GlobalStatement::Struct(StructDef {
+            name: name.to_string(),
+            fields: fields
+                .into_iter()
+                .map(|(field_type, field_name)| StructField { field_type, field_name: field_name.to_string() })
+                .collect(),
+            span: Span::default(),
+        })
+    }
+
+    /// Creates an enum definition with a placeholder span.
+    fn enum_def(name: &str, variants: Vec<(&str, Vec<Type>)>) -> GlobalStatement {
+        GlobalStatement::Enum(EnumDef {
+            name: name.to_string(),
+            variants: variants
+                .into_iter()
+                .map(|(name, payload_types)| EnumVariant { name: name.to_string(), payload_types })
+                .collect(),
+            span: Span::default(),
+        })
+    }
+
+    /// Creates a match arm with a placeholder span.
+    fn arm<Body>(kind: PatternKind, body: Body) -> MatchArm<Body> {
+        MatchArm {
+            pattern: Pattern { kind, span: Span::default() },
+            body,
+        }
+    }
+
+    /// Creates a global variable with a placeholder span.
+    fn global_var(kind: GlobalVarKind, var_type: Type, var_name: &str, var_value: Expr) -> GlobalStatement {
+        GlobalStatement::GlobalVar(GlobalVar {
+            kind,
+            var_type,
+            var_name: var_name.to_string(),
+            var_value,
+            span: Span::default(),
+        })
+    }
+
+    /// Types `program`, expecting it to succeed, and returns the messages of the diagnostics
+    /// reported into the typer's sink.
+    fn lint_messages(program: &Program) -> Vec<String> {
+        let mut typer = Typer::new();
+        typer.type_program(program).unwrap();
+        typer.sink().diagnostics().iter().map(|d| d.message.clone()).collect()
+    }
+
+    #[test]
+    fn missing_main_function() {
+        // pub fn not_main() i32 {
+        //   ret 0
+        // }
+
+        let program = Program {
             global_statements: vec![GlobalStatement::FuncDef(FuncDef {
                 proto: FuncProto {
                     func_visibility: FuncVisibility::Public,
                     name: "not_main".to_string(),
                     params: vec![],
                     return_type: Box::new(Type::Int(IntType { width: 32, signed: true })),
+                    span: Span::default(),
                 },
-                body: vec![Statement::Return(Some(Expr::IntLiteral("0".to_string())))],
+                body: vec![statement(StatementKind::Return(Some(int("0"))))],
             })],
         };
 
-        let mut typer = Typer::new();
-        let _ = typer.type_program(&program);
+        assert_eq!(typing_error(&program), TypingError::MissingMain);
     }
 
     #[test]
-    #[should_panic(expected = "The 'main' function should return a u8")]
     fn invalid_main_ret_type() {
         // pub fn main() i32 {
         //    ret 0
@@ -559,17 +2662,19 @@ mod tests {
                     name: "main".to_string(),
                     params: vec![],
                     return_type: Box::new(Type::Int(IntType { width: 32, signed: true })),
+                    span: Span::default(),
                 },
-                body: vec![Statement::Return(Some(Expr::IntLiteral("0".to_string())))],
+                body: vec![statement(StatementKind::Return(Some(int("0"))))],
             })],
         };
 
-        let mut typer = Typer::new();
-        let _ = typer.type_program(&program);
+        assert_eq!(
+            typing_error(&program),
+            TypingError::InvalidMainReturnType(Type::Int(IntType { width: 32, signed: true }))
+        );
     }
 
     #[test]
-    #[should_panic(expected = "The 'main' function should not accept any parameters")]
     fn invalid_main_params() {
         // pub fn main(i32 a) i32 {
         //    ret 0
@@ -587,23 +2692,24 @@ mod tests {
                         }
                     ],
                     return_type: Box::new(Type::Int(IntType { width: 32, signed: true })),
+                    span: Span::default(),
                 },
-                body: vec![Statement::Return(Some(Expr::IntLiteral("0".to_string())))],
+                body: vec![statement(StatementKind::Return(Some(int("0"))))],
             })],
         };
 
-        let mut typer = Typer::new();
-        let _ = typer.type_program(&program);
+        assert_eq!(typing_error(&program), TypingError::MainHasParams);
     }
 
     #[test]
-    #[should_panic(expected = "Identifier 'b' of type 'i64' cannot be used as type 'i32'")]
     fn assignment_with_mismatched_types() {
         // pub fn main() i32 {
         //     i64 a = 3
         //     i64 b = a
         //     i32 c = b  // should error
         // }
+        //
+        // (main should also return a u8, so that's a second error)
 
         let program = Program {
             global_statements: vec![GlobalStatement::FuncDef(FuncDef {
@@ -612,29 +2718,29 @@ mod tests {
                     name: "main".to_string(),
                     params: vec![],
                     return_type: Box::new(Type::Int(IntType { width: 32, signed: true })),
+                    span: Span::default(),
                 },
                 body: vec![
-                    Statement::VarDeclaration(VarDeclaration {
-                        var_name: "a".to_string(),
-                        var_value: Expr::IntLiteral("3".to_string()),
-                        var_type: Type::Int(IntType { signed: true, width: 64 }),
-                    }),
-                    Statement::VarDeclaration(VarDeclaration {
-                        var_name: "b".to_string(),
-                        var_value: Expr::Identifier("a".to_string()),
-                        var_type: Type::Int(IntType { signed: true, width: 64 }),
-                    }),
-                    Statement::VarDeclaration(VarDeclaration {
+                    var_declaration("a", Type::Int(IntType { signed: true, width: 64 }), int("3")),
+                    var_declaration("b", Type::Int(IntType { signed: true, width: 64 }), id("a")),
+                    statement(StatementKind::VarDeclaration(VarDeclaration {
                         var_name: "c".to_string(),
-                        var_value: Expr::Identifier("b".to_string()), // this should panic, since b (i64) can't be in c (i32)
+                        var_value: id("b"), // this should error, since b (i64) can't be in c (i32)
                         var_type: Type::Int(IntType { width: 32, signed: true }),
-                    }),
+                    })),
                 ],
             })],
         };
 
-        let mut typer = Typer::new();
-        let _ = typer.type_program(&program);
+        let expected_errors = vec![
+            TypingError::IdentifierTypeMismatch {
+                name: "b".to_string(),
+                expected: Type::Int(IntType { width: 32, signed: true }),
+                found: Type::Int(IntType { width: 64, signed: true }),
+            },
+            TypingError::InvalidMainReturnType(Type::Int(IntType { width: 32, signed: true })),
+        ];
+        assert_eq!(typing_errors(&program), expected_errors);
     }
 
     #[test]
@@ -651,20 +2757,13 @@ mod tests {
                     func_visibility: FuncVisibility::Public,
                     name: "main".to_string(),
                     params: vec![],
-                    return_type: Box::new(Type::Int(IntType { width: 8, signed: false })),
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
                 },
                 body: vec![
-                    Statement::VarDeclaration(VarDeclaration {
-                        var_name: "a".to_string(),
-                        var_value: Expr::IntLiteral("3".to_string()),
-                        var_type: Type::Int(IntType { width: 8, signed: false }),
-                    }),
-                    Statement::VarDeclaration(VarDeclaration {
-                        var_name: "b".to_string(),
-                        var_value: Expr::Identifier("a".to_string()),
-                        var_type: Type::Int(IntType { width: 8, signed: false }),
-                    }),
-                    Statement::Return(Some(Expr::Identifier("b".to_string()))),
+                    var_declaration("a", u8_type(), int("3")),
+                    var_declaration("b", u8_type(), id("a")),
+                    statement(StatementKind::Return(Some(id("b")))),
                 ],
             })],
         };
@@ -675,40 +2774,40 @@ mod tests {
                     func_visibility: FuncVisibility::Public,
                     name: "main".to_string(),
                     params: vec![],
-                    return_type: Box::new(Type::Int(IntType { width: 8, signed: false })),
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
                 },
                 body: vec![
-                    TypedStatement::VarDeclaration(TypedVarDeclaration {
+                    typed_statement(TypedStatementKind::VarDeclaration(TypedVarDeclaration {
                         var_name: "a".to_string(),
-                        var_value: TypedExpr::IntLiteral(TypedIntLiteral {
+                        var_value: typed_expr(TypedExprKind::IntLiteral(TypedIntLiteral {
                             int_value: "3".to_string(),
                             int_type: IntType { width: 8, signed: false },
-                        }),
-                        var_type: Type::Int(IntType { width: 8, signed: false }),
-                    }),
-                    TypedStatement::VarDeclaration(TypedVarDeclaration {
+                        })),
+                        var_type: u8_type(),
+                    })),
+                    typed_statement(TypedStatementKind::VarDeclaration(TypedVarDeclaration {
                         var_name: "b".to_string(),
-                        var_type: Type::Int(IntType { width: 8, signed: false }),
-                        var_value: TypedExpr::Identifier(TypedIdentifier {
+                        var_type: u8_type(),
+                        var_value: typed_expr(TypedExprKind::Identifier(TypedIdentifier {
                             name: "a".to_string(),
-                            id_type: Type::Int(IntType { width: 8, signed: false }),
-                        }),
-                    }),
-                    TypedStatement::Return(Some(TypedExpr::Identifier(TypedIdentifier {
+                            id_type: u8_type(),
+                        })),
+                    })),
+                    typed_statement(TypedStatementKind::Return(Some(typed_expr(TypedExprKind::Identifier(TypedIdentifier {
                         name: "b".to_string(),
-                        id_type: Type::Int(IntType { width: 8, signed: false }),
-                    }))),
+                        id_type: u8_type(),
+                    }))))),
                 ],
             })],
         };
 
         let mut typer = Typer::new();
-        let actual_typed_program = typer.type_program(&program);
+        let actual_typed_program = typer.type_program(&program).unwrap();
         assert_eq!(expected_typed_program, actual_typed_program);
     }
 
     #[test]
-    #[should_panic(expected = "Cannot cast from signed type 'i32' to unsigned type 'u8'")]
     fn cast_signed_to_unsigned() {
         // pub fn main() u8 {
         //     i32 a = 3
@@ -721,24 +2820,26 @@ mod tests {
                     func_visibility: FuncVisibility::Public,
                     name: "main".to_string(),
                     params: vec![],
-                    return_type: Box::new(Type::Int(IntType { width: 8, signed: false })),
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
                 },
                 body: vec![
-                    Statement::VarDeclaration(VarDeclaration {
-                        var_name: "a".to_string(),
-                        var_value: Expr::IntLiteral("3".to_string()),
-                        var_type: Type::Int(IntType { width: 32, signed: true }),
-                    }),
-                    Statement::Return(Some(Expr::Unary(Unary {
-                        operator: UnaryOperator::Cast(Type::Int(IntType { width: 8, signed: false })),
-                        operand: Box::new(Expr::Identifier("a".to_string())),
-                    }))),
+                    var_declaration("a", Type::Int(IntType { width: 32, signed: true }), int("3")),
+                    statement(StatementKind::Return(Some(expr(ExprKind::Unary(Unary {
+                        operator: UnaryOperator::Cast(u8_type()),
+                        operand: Box::new(id("a")),
+                    }))))),
                 ],
             })],
         };
 
-        let mut typer = Typer::new();
-        let _ = typer.type_program(&program);  // should panic
+        assert_eq!(
+            typing_error(&program),
+            TypingError::InvalidCast {
+                from: Type::Int(IntType { width: 32, signed: true }),
+                to: u8_type(),
+            }
+        );
     }
 
     #[test]
@@ -754,18 +2855,15 @@ mod tests {
                     func_visibility: FuncVisibility::Public,
                     name: "main".to_string(),
                     params: vec![],
-                    return_type: Box::new(Type::Int(IntType { width: 8, signed: false })),
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
                 },
                 body: vec![
-                    Statement::VarDeclaration(VarDeclaration {
-                        var_name: "a".to_string(),
-                        var_value: Expr::IntLiteral("3".to_string()),
-                        var_type: Type::Int(IntType { width: 32, signed: false }),
-                    }),
-                    Statement::Return(Some(Expr::Unary(Unary {
-                        operator: UnaryOperator::Cast(Type::Int(IntType { width: 8, signed: false })),
-                        operand: Box::new(Expr::Identifier("a".to_string())),
-                    }))),
+                    var_declaration("a", Type::Int(IntType { width: 32, signed: false }), int("3")),
+                    statement(StatementKind::Return(Some(expr(ExprKind::Unary(Unary {
+                        operator: UnaryOperator::Cast(u8_type()),
+                        operand: Box::new(id("a")),
+                    }))))),
                 ],
             })],
         };
@@ -776,36 +2874,105 @@ mod tests {
                     func_visibility: FuncVisibility::Public,
                     name: "main".to_string(),
                     params: vec![],
-                    return_type: Box::new(Type::Int(IntType { width: 8, signed: false })),
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
                 },
                 body: vec![
-                    TypedStatement::VarDeclaration(TypedVarDeclaration {
+                    typed_statement(TypedStatementKind::VarDeclaration(TypedVarDeclaration {
                         var_name: "a".to_string(),
-                        var_value: TypedExpr::IntLiteral(TypedIntLiteral {
+                        var_value: typed_expr(TypedExprKind::IntLiteral(TypedIntLiteral {
                             int_value: "3".to_string(),
                             int_type: IntType { width: 32, signed: false },
-                        }),
+                        })),
                         var_type: Type::Int(IntType { width: 32, signed: false }),
-                    }),
-                    TypedStatement::Return(Some(TypedExpr::Unary(TypedUnary {
-                        operator: UnaryOperator::Cast(Type::Int(IntType { width: 8, signed: false })),
-                        operand: Box::new(TypedExpr::Identifier(TypedIdentifier {
+                    })),
+                    typed_statement(TypedStatementKind::Return(Some(typed_expr(TypedExprKind::Unary(TypedUnary {
+                        operator: UnaryOperator::Cast(u8_type()),
+                        operand: Box::new(typed_expr(TypedExprKind::Identifier(TypedIdentifier {
                             name: "a".to_string(),
                             id_type: Type::Int(IntType { width: 32, signed: false }),
-                        })),
-                        result_type: Type::Int(IntType { width: 8, signed: false }),
-                    }))),
+                        }))),
+                        result_type: u8_type(),
+                    }))))),
                 ],
             })],
         };
 
         let mut typer = Typer::new();
-        let actual_typed_program = typer.type_program(&program);
+        let actual_typed_program = typer.type_program(&program).unwrap();
         assert_eq!(expected_typed_program, actual_typed_program);
     }
 
     #[test]
-    #[should_panic(expected = "Function 'main' does not always return a value")]
+    fn casts_can_widen_unsigned_to_signed() {
+        // pub fn main() u8 {
+        //     u8 a = 3
+        //     i16 b = (i16) a
+        //     ret (u8) a
+        // }
+
+        let i16_type = Type::Int(IntType { width: 16, signed: true });
+        let cast_declaration = |var_name: &str, var_type: &Type, operand_name: &str| {
+            statement(StatementKind::VarDeclaration(VarDeclaration {
+                var_name: var_name.to_string(),
+                var_value: expr(ExprKind::Unary(Unary {
+                    operator: UnaryOperator::Cast(var_type.clone()),
+                    operand: Box::new(expr(ExprKind::Identifier(operand_name.to_string()))),
+                })),
+                var_type: var_type.clone(),
+            }))
+        };
+        let program = |extra_statement: Option<Statement>| {
+            let mut body = vec![
+                var_declaration("a", u8_type(), int("3")),
+                cast_declaration("b", &i16_type, "a"),
+            ];
+            body.extend(extra_statement);
+            body.push(statement(StatementKind::Return(Some(expr(ExprKind::Unary(Unary {
+                operator: UnaryOperator::Cast(u8_type()),
+                operand: Box::new(id("a")),
+            }))))));
+            Program {
+                global_statements: vec![GlobalStatement::FuncDef(FuncDef {
+                    proto: FuncProto {
+                        func_visibility: FuncVisibility::Public,
+                        name: "main".to_string(),
+                        params: vec![],
+                        return_type: Box::new(u8_type()),
+                        span: Span::default(),
+                    },
+                    body,
+                })],
+            }
+        };
+
+        let mut typer = Typer::new();
+        let typed_program = typer.type_program(&program(None)).unwrap();
+        let TypedGlobalStatement::FuncDef(typed_func_def) = &typed_program.global_statements[0] else {
+            panic!("Expected a function definition");
+        };
+        assert!(matches!(
+            &typed_func_def.body[1].kind,
+            TypedStatementKind::VarDeclaration(TypedVarDeclaration {
+                var_value: TypedExpr { kind: TypedExprKind::Unary(TypedUnary { result_type, .. }), .. },
+                ..
+            }) if *result_type == i16_type
+        ));
+
+        // Narrowing to a signed type or widening a signed type to an unsigned one still isn't
+        // allowed, since that could change the value
+        let i8_type = Type::Int(IntType { width: 8, signed: true });
+        let u32_type = Type::Int(IntType { width: 32, signed: false });
+        for (var_type, operand_name) in [(i8_type, "a"), (u32_type, "b")] {
+            let extra_statement = cast_declaration("c", &var_type, operand_name);
+            assert!(matches!(
+                typing_error(&program(Some(extra_statement))),
+                TypingError::InvalidCast { to, .. } if to == var_type
+            ));
+        }
+    }
+
+    #[test]
     fn main_without_return() {
         // pub fn main() u8 {
         // }
@@ -816,13 +2983,1603 @@ mod tests {
                     func_visibility: FuncVisibility::Public,
                     name: "main".to_string(),
                     params: vec![],
-                    return_type: Box::new(Type::Int(IntType { width: 8, signed: false })),
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
                 },
                 body: vec![],
             })],
         };
 
+        assert_eq!(
+            typing_error(&program),
+            TypingError::MissingReturn { function_name: "main".to_string() }
+        );
+    }
+
+    #[test]
+    fn errors_do_not_cascade() {
+        // pub fn main() u8 {
+        //     u8 a = x       // should error, since x is undeclared
+        //     u8 b = x + a   // no error, since x was already reported
+        //     bool c = 5     // should error
+        //     ret a
+        // }
+
+        let program = Program {
+            global_statements: vec![GlobalStatement::FuncDef(FuncDef {
+                proto: FuncProto {
+                    func_visibility: FuncVisibility::Public,
+                    name: "main".to_string(),
+                    params: vec![],
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
+                },
+                body: vec![
+                    var_declaration("a", u8_type(), id("x")),
+                    statement(StatementKind::VarDeclaration(VarDeclaration {
+                        var_name: "b".to_string(),
+                        var_value: expr(ExprKind::Binary(Binary {
+                            left: Box::new(id("x")),
+                            operator: BinaryOperator::Add,
+                            right: Box::new(id("a")),
+                        })),
+                        var_type: u8_type(),
+                    })),
+                    var_declaration("c", Type::Bool, int("5")),
+                    statement(StatementKind::Return(Some(id("a")))),
+                ],
+            })],
+        };
+
+        let expected_errors = vec![
+            TypingError::UndeclaredIdentifier("x".to_string()),
+            TypingError::IntLiteralTypeMismatch { literal: "5".to_string(), expected: Type::Bool },
+        ];
+        assert_eq!(typing_errors(&program), expected_errors);
+    }
+
+    #[test]
+    fn logical_operands_must_be_bools() {
+        // pub fn main() u8 {
+        //     u8 x = 1
+        //     bool b = not x or true
+        //     ret x
+        // }
+
+        let program = Program {
+            global_statements: vec![GlobalStatement::FuncDef(FuncDef {
+                proto: FuncProto {
+                    func_visibility: FuncVisibility::Public,
+                    name: "main".to_string(),
+                    params: vec![],
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
+                },
+                body: vec![
+                    var_declaration("x", u8_type(), int("1")),
+                    statement(StatementKind::VarDeclaration(VarDeclaration {
+                        var_name: "b".to_string(),
+                        var_value: expr(ExprKind::Logical(Logical {
+                            left: Box::new(expr(ExprKind::Unary(Unary {
+                                operator: UnaryOperator::Not,
+                                operand: Box::new(id("x")),
+                            }))),
+                            operator: LogicalOperator::Or,
+                            right: Box::new(expr(ExprKind::BoolLiteral(true))),
+                        })),
+                        var_type: Type::Bool,
+                    })),
+                    statement(StatementKind::Return(Some(id("x")))),
+                ],
+            })],
+        };
+
+        assert_eq!(
+            typing_error(&program),
+            TypingError::IdentifierTypeMismatch {
+                name: "x".to_string(),
+                expected: Type::Bool,
+                found: u8_type(),
+            }
+        );
+    }
+
+    #[test]
+    fn bitwise_operators_reject_bools() {
+        // pub fn main() u8 {
+        //     bool a = true >> false
+        //     bool b = ~true
+        //     ret 0
+        // }
+
+        let program = Program {
+            global_statements: vec![GlobalStatement::FuncDef(FuncDef {
+                proto: FuncProto {
+                    func_visibility: FuncVisibility::Public,
+                    name: "main".to_string(),
+                    params: vec![],
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
+                },
+                body: vec![
+                    statement(StatementKind::VarDeclaration(VarDeclaration {
+                        var_name: "a".to_string(),
+                        var_value: expr(ExprKind::Binary(Binary {
+                            left: Box::new(expr(ExprKind::BoolLiteral(true))),
+                            operator: BinaryOperator::ShiftRight,
+                            right: Box::new(expr(ExprKind::BoolLiteral(false))),
+                        })),
+                        var_type: Type::Bool,
+                    })),
+                    statement(StatementKind::VarDeclaration(VarDeclaration {
+                        var_name: "b".to_string(),
+                        var_value: expr(ExprKind::Unary(Unary {
+                            operator: UnaryOperator::BitwiseNot,
+                            operand: Box::new(expr(ExprKind::BoolLiteral(true))),
+                        })),
+                        var_type: Type::Bool,
+                    })),
+                    statement(StatementKind::Return(Some(int("0")))),
+                ],
+            })],
+        };
+
+        let expected_errors = vec![
+            TypingError::InvalidBinaryOperand { operator: BinaryOperator::ShiftRight, operand_type: Type::Bool },
+            TypingError::InvalidBitwiseNot(Type::Bool),
+        ];
+        assert_eq!(typing_errors(&program), expected_errors);
+    }
+
+    #[test]
+    fn unused_variables() {
+        // pub fn main() u8 {
+        //     u8 a = 1
+        //     u8 b = a     // unused
+        //     u8 _c = 2    // not reported, since it starts with '_'
+        //     ret 0
+        // }
+
+        let program = Program {
+            global_statements: vec![GlobalStatement::FuncDef(FuncDef {
+                proto: FuncProto {
+                    func_visibility: FuncVisibility::Public,
+                    name: "main".to_string(),
+                    params: vec![],
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
+                },
+                body: vec![
+                    var_declaration("a", u8_type(), int("1")),
+                    var_declaration("b", u8_type(), id("a")),
+                    var_declaration("_c", u8_type(), int("2")),
+                    statement(StatementKind::Return(Some(int("0")))),
+                ],
+            })],
+        };
+
+        assert_eq!(lint_messages(&program), vec!["unused variable 'b'".to_string()]);
+    }
+
+    #[test]
+    fn unreachable_code() {
+        // pub fn main() u8 {
+        //     ret 0
+        //     ret 1     // unreachable
+        //     ret 2     // unreachable, but only reported once
+        // }
+
+        let ret = |value: &str| {
+            statement(StatementKind::Return(Some(expr(ExprKind::IntLiteral(value.to_string())))))
+        };
+        let program = Program {
+            global_statements: vec![GlobalStatement::FuncDef(FuncDef {
+                proto: FuncProto {
+                    func_visibility: FuncVisibility::Public,
+                    name: "main".to_string(),
+                    params: vec![],
+                    return_type: Box::new(u8_type()),
+                    span: Span::default(),
+                },
+                body: vec![ret("0"), ret("1"), ret("2")],
+            })],
+        };
+
+        assert_eq!(lint_messages(&program), vec!["unreachable code".to_string()]);
+    }
+
+    #[test]
+    fn loop_without_break_always_returns() {
+        // pub fn main() u8 {
+        //     'outer: loop {
+        //         loop {
+        //             break       // only exits the inner loop
+        //         }
+        //         ret 0
+        //     }
+        // }
+
+        let inner_loop = statement(StatementKind::Loop(Loop {
+            label: None,
+            body: vec![statement(StatementKind::Break(None))],
+        }));
+        let ret_zero = statement(StatementKind::Return(Some(int("0"))));
+        let outer_loop = |body| statement(StatementKind::Loop(Loop { label: Some("outer".to_string()), body }));
+
+        let program = main_with_body(vec![outer_loop(vec![inner_loop.clone(), ret_zero.clone()])]);
+        assert!(Typer::new().type_program(&program).is_ok());
+
+        // Breaking out of the outer loop means main can reach its end without returning
+        let break_outer = statement(StatementKind::Break(Some("outer".to_string())));
+        let inner_loop = statement(StatementKind::Loop(Loop { label: None, body: vec![break_outer] }));
+        let program = main_with_body(vec![outer_loop(vec![inner_loop, ret_zero])]);
+        assert_eq!(
+            typing_error(&program),
+            TypingError::MissingReturn { function_name: "main".to_string() }
+        );
+    }
+
+    #[test]
+    fn jumps_must_target_enclosing_loops() {
+        // pub fn main() u8 {
+        //     break
+        //     loop {
+        //         continue 'missing
+        //         ret 0
+        //     }
+        // }
+
+        let program = main_with_body(vec![
+            statement(StatementKind::Break(None)),
+            statement(StatementKind::Loop(Loop {
+                label: None,
+                body: vec![
+                    statement(StatementKind::Continue(Some("missing".to_string()))),
+                    statement(StatementKind::Return(Some(int("0")))),
+                ],
+            })),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::JumpOutsideLoop { keyword: "break".to_string() },
+                TypingError::UndeclaredLabel("missing".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn for_loop_variable_is_scoped_to_body() {
+        // pub fn main() u8 {
+        //     for u8 i in 0..10 {
+        //         u8 _copy = i
+        //     }
+        //     ret i        // 'i' is no longer declared
+        // }
+
+        let program = main_with_body(vec![
+            statement(StatementKind::ForLoop(Box::new(ForLoop {
+                label: None,
+                var_type: u8_type(),
+                var_name: "i".to_string(),
+                start: int("0"),
+                end: int("10"),
+                inclusive: false,
+                step: None,
+                body: vec![var_declaration("_copy", u8_type(), id("i"))],
+            }))),
+            statement(StatementKind::Return(Some(id("i")))),
+        ]);
+
+        assert_eq!(typing_error(&program), TypingError::UndeclaredIdentifier("i".to_string()));
+    }
+
+    #[test]
+    fn for_loop_literal_steps_must_be_positive() {
+        // pub fn main() u8 {
+        //     for i8 i in 0..10 step 0 {}
+        //     for i8 j in 0..10 step -2 {}
+        //     for i8 k in 0..10 step 2 {}
+        //     i8 by = -1
+        //     for i8 l in 0..10 step by {}   // checked at runtime instead
+        //     ret 0
+        // }
+
+        let i8_type = Type::Int(IntType { width: 8, signed: true });
+        let for_loop = |var_name: &str, step: Expr| {
+            statement(StatementKind::ForLoop(Box::new(ForLoop {
+                label: None,
+                var_type: i8_type.clone(),
+                var_name: var_name.to_string(),
+                start: int("0"),
+                end: int("10"),
+                inclusive: false,
+                step: Some(step),
+                body: vec![],
+            })))
+        };
+        let program = main_with_body(vec![
+            for_loop("_i", int("0")),
+            for_loop("_j", negated("2")),
+            for_loop("_k", int("2")),
+            var_declaration("by", i8_type.clone(), negated("1")),
+            for_loop("_l", id("by")),
+            statement(StatementKind::Return(Some(int("0")))),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![TypingError::NonPositiveStep("0".to_string()), TypingError::NonPositiveStep("-2".to_string())]
+        );
+    }
+
+    #[test]
+    fn struct_definitions_are_checked() {
+        // struct Point { i64 x, i64 y }
+        // struct Point { i64 z }
+        // struct A { B b }
+        // struct B { A a }
+        // struct C { Missing m }
+        //
+        // pub fn main() u8 {
+        //     ret 0
+        // }
+
+        let i64_type = Type::Int(IntType { width: 64, signed: true });
+        let struct_type = |name: &str| Type::Named(name.to_string());
+        let mut program = main_with_body(vec![statement(StatementKind::Return(Some(expr(
+            ExprKind::IntLiteral("0".to_string()),
+        ))))]);
+        program.global_statements.extend([
+            struct_def("Point", vec![(i64_type.clone(), "x"), (i64_type.clone(), "y")]),
+            struct_def("Point", vec![(i64_type, "z")]),
+            struct_def("A", vec![(struct_type("B"), "b")]),
+            struct_def("B", vec![(struct_type("A"), "a")]),
+            struct_def("C", vec![(struct_type("Missing"), "m")]),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::TypeRedefinition {
+                    name: "Point".to_string(),
+                    previous_definition: Span::default(),
+                },
+                TypingError::RecursiveType("A".to_string()),
+                TypingError::RecursiveType("B".to_string()),
+                TypingError::UndefinedType("Missing".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn struct_literals_and_fields() {
+        // struct Point { i64 x, i64 y }
+        //
+        // pub fn main() u8 {
+        //     Point p = Point { x: 1 }
+        //     p.z = 2
+        //     i64 _x = p.x
+        //     bool _y = p.y
+        //     ret p.x.y
+        // }
+
+        let i64_type = Type::Int(IntType { width: 64, signed: true });
+        let field_access = |accessed: Expr, field_name: &str| {
+            expr(ExprKind::FieldAccess(FieldAccess {
+                expr: Box::new(accessed),
+                field_name: field_name.to_string(),
+            }))
+        };
+        let p = || id("p");
+
+        let literal = expr(ExprKind::StructLiteral(StructLiteral {
+            struct_name: "Point".to_string(),
+            fields: vec![FieldValue {
+                field_name: "x".to_string(),
+                value: int("1"),
+            }],
+        }));
+        let mut program = main_with_body(vec![
+            var_declaration("p", Type::Named("Point".to_string()), literal),
+            statement(StatementKind::Assignment(Assignment {
+                name: "p".to_string(),
+                accessors: vec![Accessor::Field("z".to_string())],
+                value: Box::new(int("2")),
+            })),
+            var_declaration("_x", i64_type.clone(), field_access(p(), "x")),
+            var_declaration("_y", Type::Bool, field_access(p(), "y")),
+            statement(StatementKind::Return(Some(field_access(field_access(p(), "x"), "y")))),
+        ]);
+        program
+            .global_statements
+            .push(struct_def("Point", vec![(i64_type.clone(), "x"), (i64_type.clone(), "y")]));
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::MissingFields {
+                    struct_name: "Point".to_string(),
+                    field_names: vec!["y".to_string()],
+                },
+                TypingError::UnknownField { struct_name: "Point".to_string(), field_name: "z".to_string() },
+                TypingError::TypeMismatch { expected: Type::Bool, found: i64_type.clone() },
+                TypingError::NotAStruct { field_name: "y".to_string(), found: i64_type },
+            ]
+        );
+    }
+
+    #[test]
+    fn enum_definitions_are_checked() {
+        // struct Shape { i64 x }
+        // enum Shape { Empty }
+        // enum Duplicate { A, A }
+        // enum Nothing { A(void) }
+        // enum List { Cons(u8, List), Nil }
+        //
+        // pub fn main() u8 {
+        //     ret 0
+        // }
+
+        let mut program = main_with_body(vec![statement(StatementKind::Return(Some(expr(
+            ExprKind::IntLiteral("0".to_string()),
+        ))))]);
+        program.global_statements.extend([
+            struct_def("Shape", vec![(Type::Int(IntType { width: 64, signed: true }), "x")]),
+            enum_def("Shape", vec![("Empty", vec![])]),
+            enum_def("Duplicate", vec![("A", vec![]), ("A", vec![])]),
+            enum_def("Nothing", vec![("A", vec![Type::Void])]),
+            enum_def("List", vec![("Cons", vec![u8_type(), Type::Named("List".to_string())]), ("Nil", vec![])]),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::TypeRedefinition {
+                    name: "Shape".to_string(),
+                    previous_definition: Span::default(),
+                },
+                TypingError::DuplicateVariant { enum_name: "Duplicate".to_string(), variant_name: "A".to_string() },
+                TypingError::InvalidPayloadType { variant_name: "Nothing::A".to_string(), payload_type: Type::Void },
+                TypingError::RecursiveType("List".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn matches_are_checked() {
+        // enum Shape { Circle(u32), Rect(u32, u32), Empty }
+        //
+        // pub fn main() u8 {
+        //     Shape s = Shape::Rect(1)
+        //     u8 n = 0
+        //     match n { true => {}, 300 => {}, 5..5 => {}, _ => {} }
+        //     match s { Shape::Square(x) => {}, Shape::Circle(r, _) => {}, _ => {} }
+        //     u32 _area = match s { Shape::Rect(w, h) => w * h, Shape::Circle(r) => r }
+        //     ret match n { 0..=9 => 1, 20..=255 => 2 }
+        // }
+
+        let u32_type = Type::Int(IntType { width: 32, signed: false });
+        let shape_type = Type::Named("Shape".to_string());
+        let identifier = |name: &str| expr(ExprKind::Identifier(name.to_string()));
+        let variant_pattern = |variant_name: &str, bindings: Vec<&str>| PatternKind::EnumVariant {
+            enum_name: "Shape".to_string(),
+            variant_name: variant_name.to_string(),
+            bindings: bindings.into_iter().map(|b| Some(b.to_string()).filter(|b| b != "_")).collect(),
+        };
+        let range = |start: &str, end: &str, inclusive| PatternKind::Range {
+            start: start.to_string(),
+            end: end.to_string(),
+            inclusive,
+        };
+        let match_statement = |scrutinee: &str, arms| {
+            statement(StatementKind::Match(Match { scrutinee: Box::new(identifier(scrutinee)), arms }))
+        };
+
+        let mut program = main_with_body(vec![
+            statement(StatementKind::VarDeclaration(VarDeclaration {
+                var_name: "s".to_string(),
+                var_type: shape_type.clone(),
+                var_value: expr(ExprKind::EnumLiteral(EnumLiteral {
+                    enum_name: "Shape".to_string(),
+                    variant_name: "Rect".to_string(),
+                    payload: vec![int("1")],
+                })),
+            })),
+            var_declaration("n", u8_type(), int("0")),
+            match_statement("n", vec![
+                arm(PatternKind::BoolLiteral(true), vec![]),
+                arm(PatternKind::IntLiteral("300".to_string()), vec![]),
+                arm(range("5", "5", false), vec![]),
+                arm(PatternKind::Wildcard, vec![]),
+            ]),
+            match_statement("s", vec![
+                arm(variant_pattern("Square", vec!["x"]), vec![]),
+                arm(variant_pattern("Circle", vec!["r", "_"]), vec![]),
+                arm(PatternKind::Wildcard, vec![]),
+            ]),
+            statement(StatementKind::VarDeclaration(VarDeclaration {
+                var_name: "_area".to_string(),
+                var_type: u32_type,
+                var_value: expr(ExprKind::Match(Match {
+                    scrutinee: Box::new(identifier("s")),
+                    arms: vec![
+                        arm(
+                            variant_pattern("Rect", vec!["w", "h"]),
+                            expr(ExprKind::Binary(Binary {
+                                left: Box::new(identifier("w")),
+                                operator: BinaryOperator::Multiply,
+                                right: Box::new(identifier("h")),
+                            })),
+                        ),
+                        arm(variant_pattern("Circle", vec!["r"]), identifier("r")),
+                    ],
+                })),
+            })),
+            statement(StatementKind::Return(Some(expr(ExprKind::Match(Match {
+                scrutinee: Box::new(identifier("n")),
+                arms: vec![arm(range("0", "9", true), int("1")), arm(range("20", "255", true), int("2"))],
+            }))))),
+        ]);
+        program.global_statements.push(enum_def(
+            "Shape",
+            vec![
+                ("Circle", vec![Type::Int(IntType { width: 32, signed: false })]),
+                ("Rect", vec![Type::Int(IntType { width: 32, signed: false }); 2]),
+                ("Empty", vec![]),
+            ],
+        ));
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::PayloadCountMismatch { variant_name: "Shape::Rect".to_string(), expected: 2, found: 1 },
+                TypingError::PatternTypeMismatch { pattern: "true".to_string(), expected: u8_type() },
+                TypingError::PatternTypeMismatch { pattern: "300".to_string(), expected: u8_type() },
+                TypingError::EmptyRangePattern("5..5".to_string()),
+                TypingError::UndefinedVariant { enum_name: "Shape".to_string(), variant_name: "Square".to_string() },
+                TypingError::PayloadCountMismatch { variant_name: "Shape::Circle".to_string(), expected: 1, found: 2 },
+                TypingError::NonExhaustiveMatch { missing_patterns: vec!["Shape::Empty".to_string()] },
+                TypingError::NonExhaustiveMatch { missing_patterns: vec!["10..=19".to_string()] },
+            ]
+        );
+    }
+
+    #[test]
+    fn arrays_are_checked() {
+        // pub fn main() u8 {
+        //     [u8; 4] buf = [0; 4]
+        //     buf[1] = true
+        //     [u8; 2] _pair = [1, 2, 3]
+        //     u8 _a = buf[4]
+        //     u8 _b = buf[true]
+        //     u8 _c = buf[0][0]
+        //     [void; 2] _d = []
+        //     i64 i = -1
+        //     buf[i] = buf[3]
+        //     ret [][0]
+        // }
+
+        let buf = || id("buf");
+        let index = |array: Expr, index: Expr| {
+            expr(ExprKind::Index(Index { expr: Box::new(array), index: Box::new(index) }))
+        };
+        let assign_element = |index: Expr, value: Expr| {
+            statement(StatementKind::Assignment(Assignment {
+                name: "buf".to_string(),
+                accessors: vec![Accessor::Index(index)],
+                value: Box::new(value),
+            }))
+        };
+
+        let array_type = |element_type: &Type, length| Type::Array(Box::new(element_type.clone()), length);
+        let program = main_with_body(vec![
+            var_declaration(
+                "buf",
+                array_type(&u8_type(), 4),
+                expr(ExprKind::ArrayRepeat(ArrayRepeat { value: Box::new(int("0")), length: 4 })),
+            ),
+            assign_element(int("1"), expr(ExprKind::BoolLiteral(true))),
+            var_declaration("_pair", array_type(&u8_type(), 2), expr(ExprKind::ArrayLiteral(vec![int("1"), int("2"), int("3")]))),
+            var_declaration("_a", u8_type(), index(buf(), int("4"))),
+            var_declaration("_b", u8_type(), index(buf(), expr(ExprKind::BoolLiteral(true)))),
+            var_declaration("_c", u8_type(), index(index(buf(), int("0")), int("0"))),
+            var_declaration("_d", array_type(&Type::Void, 2), expr(ExprKind::ArrayLiteral(vec![]))),
+            // Indices of any integer type are allowed (negative ones fail at runtime)
+            var_declaration("i", Type::Int(IntType { width: 64, signed: true }), negated("1")),
+            assign_element(id("i"), index(buf(), int("3"))),
+            statement(StatementKind::Return(Some(index(expr(ExprKind::ArrayLiteral(vec![])), int("0"))))),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::TypeMismatch { expected: u8_type(), found: Type::Bool },
+                TypingError::ArrayLengthMismatch { expected: 2, found: 3 },
+                TypingError::IndexOutOfBounds { index: "4".to_string(), length: 4 },
+                TypingError::InvalidIndexType(Type::Bool),
+                TypingError::NotAnArray(u8_type()),
+                TypingError::InvalidElementType(Type::Void),
+                TypingError::UnknownArrayType,
+            ]
+        );
+    }
+
+    #[test]
+    fn strings_are_checked() {
+        // pub fn main() u8 {
+        //     str _s = "ok"
+        //     u8 _a = "hi"
+        //     str _b = 1
+        //     bool _c = "a" == "b"
+        //     ret 0
+        // }
+
+        let string = |s: &str| expr(ExprKind::StrLiteral(s.to_string()));
+        let comparison = Comparison {
+            left: Box::new(string("a")),
+            operator: ComparisonOperator::EqualTo,
+            right: Box::new(string("b")),
+        };
+
+        let program = main_with_body(vec![
+            var_declaration("_s", Type::Str, string("ok")),
+            var_declaration("_a", u8_type(), string("hi")),
+            var_declaration("_b", Type::Str, int("1")),
+            var_declaration("_c", Type::Bool, expr(ExprKind::Comparison(comparison))),
+            statement(StatementKind::Return(Some(int("0")))),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::TypeMismatch { expected: u8_type(), found: Type::Str },
+                TypingError::IntLiteralTypeMismatch { literal: "1".to_string(), expected: Type::Str },
+                TypingError::InvalidComparisonOperand { operator: ComparisonOperator::EqualTo, operand_type: Type::Str },
+            ]
+        );
+    }
+
+    #[test]
+    fn floats_are_checked() {
+        // pub fn main() u8 {
+        //     f32 _a = 1.5 % 2e-3
+        //     f64 _b = -1.5
+        //     u8 _c = 1.5
+        //     f32 _d = 1e39
+        //     f64 _e = 1.5 & 1.0
+        //     i32 _f = (i32) 1.5
+        //     ret 0
+        // }
+
+        let f32_type = Type::Float(FloatType { width: 32 });
+        let f64_type = Type::Float(FloatType { width: 64 });
+        let i32_type = Type::Int(IntType { width: 32, signed: true });
+        let binary = |left: &str, operator: BinaryOperator, right: &str| {
+            expr(ExprKind::Binary(Binary { left: Box::new(float(left)), operator, right: Box::new(float(right)) }))
+        };
+        let unary = |operator: UnaryOperator, operand: &str| {
+            expr(ExprKind::Unary(Unary { operator, operand: Box::new(float(operand)) }))
+        };
+
+        let program = main_with_body(vec![
+            var_declaration("_a", f32_type.clone(), binary("1.5", BinaryOperator::Remainder, "2e-3")),
+            var_declaration("_b", f64_type.clone(), unary(UnaryOperator::Negate, "1.5")),
+            var_declaration("_c", u8_type(), float("1.5")),
+            var_declaration("_d", f32_type.clone(), float("1e39")),
+            var_declaration("_e", f64_type.clone(), binary("1.5", BinaryOperator::BitwiseAnd, "1.0")),
+            var_declaration("_f", i32_type.clone(), unary(UnaryOperator::Cast(i32_type), "1.5")),
+            statement(StatementKind::Return(Some(int("0")))),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::FloatLiteralTypeMismatch { literal: "1.5".to_string(), expected: u8_type() },
+                TypingError::FloatLiteralOutOfRange { literal: "1e39".to_string(), float_type: FloatType { width: 32 } },
+                TypingError::InvalidBinaryOperand { operator: BinaryOperator::BitwiseAnd, operand_type: f64_type },
+            ]
+        );
+    }
+
+    #[test]
+    fn float_literals_take_the_type_of_the_other_operand() {
+        // pub fn main() u8 {
+        //     f32 a = 1.5
+        //     bool _b = a * 2.0 > a
+        //     bool _c = 0.5 < a
+        //     bool _d = a < 1e39      // out of range for an f32
+        //     ret 0
+        // }
+
+        let f32_type = Type::Float(FloatType { width: 32 });
+        let a = || id("a");
+        let comparison = |left: Expr, operator: ComparisonOperator, right: Expr| {
+            expr(ExprKind::Comparison(Comparison { left: Box::new(left), operator, right: Box::new(right) }))
+        };
+        let doubled = expr(ExprKind::Binary(Binary {
+            left: Box::new(a()),
+            operator: BinaryOperator::Multiply,
+            right: Box::new(float("2.0")),
+        }));
+        let mut body = vec![
+            var_declaration("a", f32_type.clone(), float("1.5")),
+            var_declaration("_b", Type::Bool, comparison(doubled, ComparisonOperator::GreaterThan, a())),
+            var_declaration("_c", Type::Bool, comparison(float("0.5"), ComparisonOperator::LessThan, a())),
+            statement(StatementKind::Return(Some(int("0")))),
+        ];
+
         let mut typer = Typer::new();
-        let _ = typer.type_program(&program);
+        let typed_program = typer.type_program(&main_with_body(body.clone())).unwrap();
+        let TypedGlobalStatement::FuncDef(typed_func_def) = &typed_program.global_statements[0] else {
+            panic!("Expected a function definition");
+        };
+        for statement in &typed_func_def.body[1..3] {
+            assert!(matches!(
+                &statement.kind,
+                TypedStatementKind::VarDeclaration(TypedVarDeclaration {
+                    var_value: TypedExpr { kind: TypedExprKind::Comparison(TypedComparison { operand_type, .. }), .. },
+                    ..
+                }) if *operand_type == f32_type
+            ));
+        }
+
+        body.insert(3, var_declaration("_d", Type::Bool, comparison(a(), ComparisonOperator::LessThan, float("1e39"))));
+        assert_eq!(
+            typing_error(&main_with_body(body)),
+            TypingError::FloatLiteralOutOfRange { literal: "1e39".to_string(), float_type: FloatType { width: 32 } }
+        );
+    }
+
+    #[test]
+    fn int_literals_are_range_checked() {
+        // pub fn main() u8 {
+        //     i8 _a = -128
+        //     i8 _b = -129
+        //     i8 _c = 128
+        //     u7 _d = 128
+        //     u128 _e = 340282366920938463463374607431768211456     // 2^128
+        //     i8 _f = --127
+        //     i8 _g = --128     // only the innermost minus is part of the literal
+        //     ret 255
+        // }
+
+        let int_type = |signed, width| IntType { signed, width };
+        let negate = |operand| expr(ExprKind::Unary(Unary { operator: UnaryOperator::Negate, operand: Box::new(operand) }));
+        let two_to_the_128 = "340282366920938463463374607431768211456";
+
+        let program = main_with_body(vec![
+            var_declaration("_a", Type::Int(int_type(true, 8)), negated("128")),
+            var_declaration("_b", Type::Int(int_type(true, 8)), negated("129")),
+            var_declaration("_c", Type::Int(int_type(true, 8)), int("128")),
+            var_declaration("_d", Type::Int(int_type(false, 7)), int("128")),
+            var_declaration("_e", Type::Int(int_type(false, 128)), int(two_to_the_128)),
+            var_declaration("_f", Type::Int(int_type(true, 8)), negate(negated("127"))),
+            var_declaration("_g", Type::Int(int_type(true, 8)), negate(negated("128"))),
+            statement(StatementKind::Return(Some(int("255")))),
+        ]);
+
+        let out_of_range = |literal: &str, int_type| TypingError::IntLiteralOutOfRange { literal: literal.to_string(), int_type };
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                out_of_range("-129", int_type(true, 8)),
+                out_of_range("128", int_type(true, 8)),
+                out_of_range("128", int_type(false, 7)),
+                out_of_range(two_to_the_128, int_type(false, 128)),
+                out_of_range("128", int_type(true, 8)),
+            ]
+        );
+        assert_eq!(
+            out_of_range("128", int_type(true, 8)).to_string(),
+            "integer literal '128' is out of range for type 'i8' (-128 to 127)"
+        );
+    }
+
+    #[test]
+    fn zero_width_ints_are_rejected() {
+        // fn f(u0 _x) u8 {
+        //     ret 0
+        // }
+        //
+        // pub fn main() u8 {
+        //     i0 _a = 0
+        //     for u0 _i in 0..1 {}
+        //     ret (u8) (i0) 1
+        // }
+
+        let u0_type = IntType { signed: false, width: 0 };
+        let i0_type = IntType { signed: true, width: 0 };
+        let mut program = main_with_body(vec![
+            var_declaration("_a", Type::Int(i0_type), int("0")),
+            statement(StatementKind::ForLoop(Box::new(ForLoop {
+                label: None,
+                var_type: Type::Int(u0_type),
+                var_name: "_i".to_string(),
+                start: int("0"),
+                end: int("1"),
+                inclusive: false,
+                step: None,
+                body: vec![],
+            }))),
+            statement(StatementKind::Return(Some(expr(ExprKind::Unary(Unary {
+                operator: UnaryOperator::Cast(u8_type()),
+                operand: Box::new(expr(ExprKind::Unary(Unary {
+                    operator: UnaryOperator::Cast(Type::Int(i0_type)),
+                    operand: Box::new(int("1")),
+                }))),
+            }))))),
+        ]);
+        program.global_statements.push(GlobalStatement::FuncDef(FuncDef {
+            proto: FuncProto {
+                func_visibility: FuncVisibility::Private,
+                name: "f".to_string(),
+                params: vec![FuncParam { param_type: Type::Int(u0_type), param_name: "_x".to_string() }],
+                return_type: Box::new(u8_type()),
+                span: Span::default(),
+            },
+            body: vec![statement(StatementKind::Return(Some(int("0"))))],
+        }));
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::ZeroWidthInt(u0_type),
+                TypingError::ZeroWidthInt(i0_type),
+                TypingError::ZeroWidthInt(u0_type),
+                TypingError::ZeroWidthInt(i0_type),
+            ]
+        );
+        assert_eq!(TypingError::ZeroWidthInt(u0_type).to_string(), "integer type 'u0' has no bits");
+    }
+
+    #[test]
+    fn too_wide_ints_are_rejected() {
+        // pub fn main() u8 {
+        //     u8388608 _a = 0
+        //     i8388609 _b = 0
+        //     ret (u8) (u9000000) 1
+        // }
+
+        let widest_type = IntType { signed: false, width: IntType::MAX_WIDTH };
+        let too_wide_type = IntType { signed: true, width: IntType::MAX_WIDTH + 1 };
+        let u9000000_type = IntType { signed: false, width: 9_000_000 };
+        let program = main_with_body(vec![
+            var_declaration("_a", Type::Int(widest_type), int("0")),
+            var_declaration("_b", Type::Int(too_wide_type), int("0")),
+            statement(StatementKind::Return(Some(expr(ExprKind::Unary(Unary {
+                operator: UnaryOperator::Cast(u8_type()),
+                operand: Box::new(expr(ExprKind::Unary(Unary {
+                    operator: UnaryOperator::Cast(Type::Int(u9000000_type)),
+                    operand: Box::new(int("1")),
+                }))),
+            }))))),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![TypingError::TooWideInt(too_wide_type), TypingError::TooWideInt(u9000000_type)]
+        );
+        assert_eq!(
+            TypingError::TooWideInt(too_wide_type).to_string(),
+            "integer type 'i8388609' is too wide (at most 8388608 bits)"
+        );
+    }
+
+    #[test]
+    fn literal_shift_amounts_are_range_checked() {
+        // pub fn main() u8 {
+        //     u8 x = 1
+        //     i8 y = -1
+        //     u8 _a = x << 8
+        //     i8 _b = y >> -1
+        //     u8 _c = x << 7
+        //     i8 _d = y >> y     // checked at runtime instead
+        //     ret 0
+        // }
+
+        let u8_int_type = IntType { signed: false, width: 8 };
+        let i8_int_type = IntType { signed: true, width: 8 };
+        let shift = |value: &str, operator: BinaryOperator, amount: Expr| {
+            expr(ExprKind::Binary(Binary { left: Box::new(id(value)), operator, right: Box::new(amount) }))
+        };
+        let program = main_with_body(vec![
+            var_declaration("x", Type::Int(u8_int_type), int("1")),
+            var_declaration("y", Type::Int(i8_int_type), negated("1")),
+            var_declaration("_a", Type::Int(u8_int_type), shift("x", BinaryOperator::ShiftLeft, int("8"))),
+            var_declaration("_b", Type::Int(i8_int_type), shift("y", BinaryOperator::ShiftRight, negated("1"))),
+            var_declaration("_c", Type::Int(u8_int_type), shift("x", BinaryOperator::ShiftLeft, int("7"))),
+            var_declaration("_d", Type::Int(i8_int_type), shift("y", BinaryOperator::ShiftRight, id("y"))),
+            statement(StatementKind::Return(Some(int("0")))),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::ShiftAmountOutOfRange { amount: "8".to_string(), int_type: u8_int_type },
+                TypingError::ShiftAmountOutOfRange { amount: "-1".to_string(), int_type: i8_int_type },
+            ]
+        );
+        assert_eq!(
+            TypingError::ShiftAmountOutOfRange { amount: "8".to_string(), int_type: u8_int_type }.to_string(),
+            "shift amount '8' is out of range for type 'u8' (0 to 7)"
+        );
+    }
+
+    #[test]
+    fn narrower_ints_are_widened() {
+        // pub fn main() u8 {
+        //     i32 a = 1
+        //     u8 b = 2
+        //     i64 _c = a + b      // both operands are widened to i64
+        //     bool _d = b < a     // `b` is widened to i32
+        //     i8 _e = b           // not every u8 is an i8
+        //     ret 0
+        // }
+
+        let i8_type = Type::Int(IntType { width: 8, signed: true });
+        let i32_type = Type::Int(IntType { width: 32, signed: true });
+        let i64_type = Type::Int(IntType { width: 64, signed: true });
+        let mut program = main_with_body(vec![
+            var_declaration("a", i32_type.clone(), int("1")),
+            var_declaration("b", u8_type(), int("2")),
+            var_declaration("_c", i64_type.clone(), expr(ExprKind::Binary(Binary {
+                left: Box::new(id("a")),
+                operator: BinaryOperator::Add,
+                right: Box::new(id("b")),
+            }))),
+            var_declaration("_d", Type::Bool, expr(ExprKind::Comparison(Comparison {
+                left: Box::new(id("b")),
+                operator: ComparisonOperator::LessThan,
+                right: Box::new(id("a")),
+            }))),
+            statement(StatementKind::Return(Some(int("0")))),
+        ]);
+
+        let typed_id = |name: &str, id_type: &Type| {
+            typed_expr(TypedExprKind::Identifier(TypedIdentifier { name: name.to_string(), id_type: id_type.clone() }))
+        };
+        let cast = |operand: TypedExpr, cast_type: &Type| {
+            typed_expr(TypedExprKind::Unary(TypedUnary {
+                operator: UnaryOperator::Cast(cast_type.clone()),
+                operand: Box::new(operand),
+                result_type: cast_type.clone(),
+            }))
+        };
+        let expected_sum = typed_expr(TypedExprKind::Binary(TypedBinary {
+            left: Box::new(cast(typed_id("a", &i32_type), &i64_type)),
+            operator: BinaryOperator::Add,
+            right: Box::new(cast(typed_id("b", &u8_type()), &i64_type)),
+            result_type: i64_type.clone(),
+        }));
+        let expected_comparison = typed_expr(TypedExprKind::Comparison(TypedComparison {
+            left: Box::new(cast(typed_id("b", &u8_type()), &i32_type)),
+            operator: ComparisonOperator::LessThan,
+            right: Box::new(typed_id("a", &i32_type)),
+            operand_type: i32_type.clone(),
+        }));
+
+        let typed_program = Typer::new().type_program(&program).unwrap();
+        let [TypedGlobalStatement::FuncDef(main)] = typed_program.global_statements.as_slice() else {
+            panic!("expected only main");
+        };
+        let values: Vec<_> = main
+            .body
+            .iter()
+            .filter_map(|s| match &s.kind {
+                TypedStatementKind::VarDeclaration(declaration) => Some(&declaration.var_value),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(values[2..], [&expected_sum, &expected_comparison]);
+
+        let GlobalStatement::FuncDef(main) = &mut program.global_statements[0] else {
+            unreachable!();
+        };
+        main.body.insert(4, var_declaration("_e", i8_type.clone(), id("b")));
+        assert_eq!(
+            typing_error(&program),
+            TypingError::IdentifierTypeMismatch { name: "b".to_string(), expected: i8_type, found: u8_type() }
+        );
+    }
+
+    #[test]
+    fn arithmetic_builtins_are_checked() {
+        // pub fn main() u8 {
+        //     u8 a = 1
+        //     i16 out = 0
+        //     u16 _b = wrapping_add(a, 2)          // `a` and `2` are u16s
+        //     bool _c = checked_mul(a, 3, &out)    // `a` and `3` are i16s
+        //     f64 _d = saturating_sub(1.5, 2.5)
+        //     u8 _e = checked_add(a, 1, &out)
+        //     ret 0
+        // }
+
+        let u16_type = Type::Int(IntType { width: 16, signed: false });
+        let i16_type = Type::Int(IntType { width: 16, signed: true });
+        let f64_type = Type::Float(FloatType { width: 64 });
+        let out_ptr = || expr(ExprKind::Unary(Unary { operator: UnaryOperator::AddressOf, operand: Box::new(id("out")) }));
+        let call = |function_name: &str, args: Vec<Expr>| {
+            expr(ExprKind::Call(Call { function_name: function_name.to_string(), args }))
+        };
+        let mut program = main_with_body(vec![
+            var_declaration("a", u8_type(), int("1")),
+            var_declaration("out", i16_type.clone(), int("0")),
+            var_declaration("_b", u16_type.clone(), call("wrapping_add", vec![id("a"), int("2")])),
+            var_declaration("_c", Type::Bool, call("checked_mul", vec![id("a"), int("3"), out_ptr()])),
+            statement(StatementKind::Return(Some(int("0")))),
+        ]);
+
+        let typed_program = Typer::new().type_program(&program).unwrap();
+        let [TypedGlobalStatement::FuncDef(main)] = typed_program.global_statements.as_slice() else {
+            panic!("expected only main");
+        };
+        let calls: Vec<_> = main
+            .body
+            .iter()
+            .filter_map(|s| match &s.kind {
+                TypedStatementKind::VarDeclaration(TypedVarDeclaration { var_value, .. }) => match &var_value.kind {
+                    TypedExprKind::ArithmeticCall(call) => Some((call.overflow, call.binary.result_type.clone())),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls, [(Overflow::Wrapping, u16_type), (Overflow::Checked, i16_type)]);
+
+        let GlobalStatement::FuncDef(main) = &mut program.global_statements[0] else {
+            unreachable!();
+        };
+        main.body.insert(4, var_declaration("_d", f64_type.clone(), call("saturating_sub", vec![float("1.5"), float("2.5")])));
+        main.body.insert(5, var_declaration("_e", u8_type(), call("checked_add", vec![id("a"), int("1"), out_ptr()])));
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::NonIntegerArithmetic { function_name: "saturating_sub".to_string(), found: f64_type },
+                TypingError::TypeMismatch { expected: u8_type(), found: Type::Bool },
+            ]
+        );
+    }
+
+    #[test]
+    fn chars_are_checked() {
+        // pub fn main() u8 {
+        //     bool _a = 'a' < 'b'
+        //     u32 _b = 'a'
+        //     i8 _c = (i8) 'a'
+        //     char _d = (char) (u8) 65
+        //     char _e = (char) 65       // the literal is a u8
+        //     char _f = 'a' + 'b'
+        //     char _g = (char) 256
+        //     u16 wide = 65
+        //     char _h = (char) wide     // only a u8 can be cast to a char
+        //     ret 0
+        // }
+
+        let u32_type = Type::Int(IntType { width: 32, signed: false });
+        let u16_type = Type::Int(IntType { width: 16, signed: false });
+        let i8_type = Type::Int(IntType { width: 8, signed: true });
+        let char_literal = |c: char| expr(ExprKind::CharLiteral(c));
+        let cast = |cast_type: Type, operand: Expr| {
+            expr(ExprKind::Unary(Unary { operator: UnaryOperator::Cast(cast_type), operand: Box::new(operand) }))
+        };
+        let comparison = Comparison {
+            left: Box::new(char_literal('a')),
+            operator: ComparisonOperator::LessThan,
+            right: Box::new(char_literal('b')),
+        };
+        let sum = Binary {
+            left: Box::new(char_literal('a')),
+            operator: BinaryOperator::Add,
+            right: Box::new(char_literal('b')),
+        };
+        let sixty_five = || int("65");
+
+        let program = main_with_body(vec![
+            var_declaration("_a", Type::Bool, expr(ExprKind::Comparison(comparison))),
+            var_declaration("_b", u32_type.clone(), char_literal('a')),
+            var_declaration("_c", i8_type.clone(), cast(i8_type, char_literal('a'))),
+            var_declaration("_d", Type::Char, cast(Type::Char, cast(u8_type(), sixty_five()))),
+            var_declaration("_e", Type::Char, cast(Type::Char, sixty_five())),
+            var_declaration("_f", Type::Char, expr(ExprKind::Binary(sum))),
+            var_declaration("_g", Type::Char, cast(Type::Char, int("256"))),
+            var_declaration("wide", u16_type.clone(), int("65")),
+            var_declaration("_h", Type::Char, cast(Type::Char, id("wide"))),
+            statement(StatementKind::Return(Some(int("0")))),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::TypeMismatch { expected: u32_type, found: Type::Char },
+                TypingError::InvalidBinaryOperand { operator: BinaryOperator::Add, operand_type: Type::Char },
+                TypingError::IntLiteralOutOfRange {
+                    literal: "256".to_string(),
+                    int_type: IntType { signed: false, width: 8 },
+                },
+                TypingError::InvalidCast { from: u16_type, to: Type::Char },
+            ]
+        );
+    }
+
+    #[test]
+    fn pointers_are_checked() {
+        // pub fn main() u8 {
+        //     i64 x = 3
+        //     *i64 p = &x
+        //     *p = 4
+        //     i64 _a = *x
+        //     *i64 _b = &3
+        //     u8 _c = null
+        //     *void v = null
+        //     u8 _d = *v
+        //     bool _e = null == null
+        //     bool _f = p != null
+        //     ret 0
+        // }
+
+        let i64_type = Type::Int(IntType { width: 64, signed: true });
+        let pointer_to = |t: &Type| Type::Pointer(Box::new(t.clone()));
+        let null = || expr(ExprKind::Null);
+        let unary = |operator: UnaryOperator, operand: Expr| {
+            expr(ExprKind::Unary(Unary { operator, operand: Box::new(operand) }))
+        };
+        let comparison = |left: Expr, operator: ComparisonOperator, right: Expr| {
+            expr(ExprKind::Comparison(Comparison { left: Box::new(left), operator, right: Box::new(right) }))
+        };
+
+        let program = main_with_body(vec![
+            var_declaration("x", i64_type.clone(), int("3")),
+            var_declaration("p", pointer_to(&i64_type), unary(UnaryOperator::AddressOf, id("x"))),
+            statement(StatementKind::Assignment(Assignment {
+                name: "p".to_string(),
+                accessors: vec![Accessor::Deref],
+                value: Box::new(int("4")),
+            })),
+            var_declaration("_a", i64_type.clone(), unary(UnaryOperator::Deref, id("x"))),
+            var_declaration("_b", pointer_to(&i64_type), unary(UnaryOperator::AddressOf, int("3"))),
+            var_declaration("_c", u8_type(), null()),
+            var_declaration("v", pointer_to(&Type::Void), null()),
+            var_declaration("_d", u8_type(), unary(UnaryOperator::Deref, id("v"))),
+            var_declaration("_e", Type::Bool, comparison(null(), ComparisonOperator::EqualTo, null())),
+            // `null` takes the type of the pointer it's compared with
+            var_declaration("_f", Type::Bool, comparison(id("p"), ComparisonOperator::NotEqualTo, null())),
+            statement(StatementKind::Return(Some(int("0")))),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::InvalidDereference(i64_type),
+                TypingError::InvalidAddressOf,
+                TypingError::NullTypeMismatch(u8_type()),
+                TypingError::InvalidDereference(pointer_to(&Type::Void)),
+                TypingError::UnknownNullType,
+            ]
+        );
+    }
+
+    #[test]
+    fn consts_are_evaluated() {
+        // const u16 WIDE = 200 + 100
+        // const u8 NARROW = (u8) WIDE
+        // static mut u8 counter = NARROW
+        //
+        // pub fn main() u8 {
+        //     ret NARROW + counter
+        // }
+
+        let u16_type = Type::Int(IntType { width: 16, signed: false });
+        let typed_u8 = |i: &str| {
+            typed_expr(TypedExprKind::IntLiteral(TypedIntLiteral { int_value: i.to_string(), int_type: IntType { width: 8, signed: false } }))
+        };
+
+        let mut program = main_with_body(vec![statement(StatementKind::Return(Some(expr(ExprKind::Binary(Binary {
+            left: Box::new(id("NARROW")),
+            operator: BinaryOperator::Add,
+            right: Box::new(id("counter")),
+        })))))]);
+        let sum = expr(ExprKind::Binary(Binary {
+            left: Box::new(int("200")),
+            operator: BinaryOperator::Add,
+            right: Box::new(int("100")),
+        }));
+        let cast = expr(ExprKind::Unary(Unary { operator: UnaryOperator::Cast(u8_type()), operand: Box::new(id("WIDE")) }));
+        program.global_statements.splice(0..0, [
+            global_var(GlobalVarKind::Const, u16_type.clone(), "WIDE", sum),
+            global_var(GlobalVarKind::Const, u8_type(), "NARROW", cast),
+            global_var(GlobalVarKind::StaticMut { is_public: false }, u8_type(), "counter", id("NARROW")),
+        ]);
+
+        let typed_program = Typer::new().type_program(&program).unwrap();
+        let typed_globals: Vec<_> = typed_program
+            .global_statements
+            .iter()
+            .filter_map(|global_statement| match global_statement {
+                TypedGlobalStatement::GlobalVar(global_var) => Some(&global_var.var_value),
+                _ => None,
+            })
+            .collect();
+        let wide = TypedIntLiteral { int_value: "300".to_string(), int_type: IntType { width: 16, signed: false } };
+        assert_eq!(
+            typed_globals,
+            vec![&typed_expr(TypedExprKind::IntLiteral(wide)), &typed_u8("44"), &typed_u8("44")]
+        );
+
+        // Uses of constants are replaced by their values, unlike uses of statics
+        let Some(TypedGlobalStatement::FuncDef(main)) = typed_program.global_statements.last() else {
+            panic!("expected main to come last");
+        };
+        let expected_return = typed_expr(TypedExprKind::Binary(TypedBinary {
+            left: Box::new(typed_u8("44")),
+            operator: BinaryOperator::Add,
+            right: Box::new(typed_expr(TypedExprKind::Identifier(TypedIdentifier {
+                name: "counter".to_string(),
+                id_type: u8_type(),
+            }))),
+            result_type: u8_type(),
+        }));
+        assert_eq!(main.body, vec![typed_statement(TypedStatementKind::Return(Some(expected_return)))]);
+    }
+
+    #[test]
+    fn global_vars_are_checked() {
+        // const u8 OVERFLOW = 255 + 1
+        // const u64 DIVIDED = 1 / 0
+        // static mut u64 counter = 0
+        // const u64 COPY = counter
+        // const u8 main = 0
+        // const u8 LIMIT = 10
+        //
+        // pub fn main() u8 {
+        //     LIMIT = 2
+        //     *u8 _p = &LIMIT
+        //     u8 LIMIT = 3      // shadows the constant, so it can be assigned to
+        //     LIMIT = 4
+        //     ret LIMIT
+        // }
+
+        let u64_type = Type::Int(IntType { width: 64, signed: false });
+        let binary = |left: &str, operator: BinaryOperator, right: &str| {
+            expr(ExprKind::Binary(Binary { left: Box::new(int(left)), operator, right: Box::new(int(right)) }))
+        };
+        let assign = |value: &str| {
+            statement(StatementKind::Assignment(Assignment {
+                name: "LIMIT".to_string(),
+                accessors: vec![],
+                value: Box::new(int(value)),
+            }))
+        };
+        let address_of = expr(ExprKind::Unary(Unary { operator: UnaryOperator::AddressOf, operand: Box::new(id("LIMIT")) }));
+
+        let mut program = main_with_body(vec![
+            assign("2"),
+            var_declaration("_p", Type::Pointer(Box::new(u8_type())), address_of),
+            var_declaration("LIMIT", u8_type(), int("3")),
+            assign("4"),
+            statement(StatementKind::Return(Some(id("LIMIT")))),
+        ]);
+        program.global_statements.splice(0..0, [
+            global_var(GlobalVarKind::Const, u8_type(), "OVERFLOW", binary("255", BinaryOperator::Add, "1")),
+            global_var(GlobalVarKind::Const, u64_type.clone(), "DIVIDED", binary("1", BinaryOperator::Divide, "0")),
+            global_var(GlobalVarKind::StaticMut { is_public: true }, u64_type.clone(), "counter", int("0")),
+            global_var(GlobalVarKind::Const, u64_type, "COPY", id("counter")),
+            global_var(GlobalVarKind::Const, u8_type(), "main", int("0")),
+            global_var(GlobalVarKind::Const, u8_type(), "LIMIT", int("10")),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::ConstOverflow(u8_type()),
+                TypingError::ConstDivisionByZero,
+                TypingError::NonConstantExpr,
+                TypingError::GlobalRedefinition { name: "main".to_string(), previous_definition: Span::default() },
+                TypingError::AssignmentToConst("LIMIT".to_string()),
+                TypingError::InvalidAddressOf,
+            ]
+        );
+    }
+
+    #[test]
+    fn let_types_are_inferred() {
+        // pub fn main() u8 {
+        //     let n = 5
+        //     let mut total = n + 1
+        //     total += 2
+        //     ret total             // decides the type of `total`, and so of `n`
+        // }
+
+        let add = |name: &str, i: &str| {
+            expr(ExprKind::Binary(Binary { left: Box::new(id(name)), operator: BinaryOperator::Add, right: Box::new(int(i)) }))
+        };
+        let n_declaration = let_declaration("n", false, int("5"));
+        let total_declaration = let_declaration("total", true, add("n", "1"));
+        // The typed declarations keep their spans
+        let (n_span, total_span) = (n_declaration.span, total_declaration.span);
+        let program = main_with_body(vec![
+            n_declaration,
+            total_declaration,
+            statement(StatementKind::Assignment(Assignment {
+                name: "total".to_string(),
+                accessors: vec![],
+                value: Box::new(add("total", "2")),
+            })),
+            statement(StatementKind::Return(Some(id("total")))),
+        ]);
+
+        let typed_u8 = |i: &str| {
+            typed_expr(TypedExprKind::IntLiteral(TypedIntLiteral { int_value: i.to_string(), int_type: IntType { width: 8, signed: false } }))
+        };
+        let typed_id = |name: &str| {
+            typed_expr(TypedExprKind::Identifier(TypedIdentifier { name: name.to_string(), id_type: u8_type() }))
+        };
+        let typed_add = |name: &str, i: &str| {
+            typed_expr(TypedExprKind::Binary(TypedBinary {
+                left: Box::new(typed_id(name)),
+                operator: BinaryOperator::Add,
+                right: Box::new(typed_u8(i)),
+                result_type: u8_type(),
+            }))
+        };
+        let typed_var_declaration = |var_name: &str, var_value: TypedExpr, span: Span| TypedStatement {
+            kind: TypedStatementKind::VarDeclaration(TypedVarDeclaration {
+                var_name: var_name.to_string(),
+                var_type: u8_type(),
+                var_value,
+            }),
+            span,
+        };
+        let expected_body = vec![
+            typed_var_declaration("n", typed_u8("5"), n_span),
+            typed_var_declaration("total", typed_add("n", "1"), total_span),
+            typed_statement(TypedStatementKind::Assignment(TypedAssignment {
+                name: "total".to_string(),
+                accessors: vec![],
+                value: Box::new(typed_add("total", "2")),
+            })),
+            typed_statement(TypedStatementKind::Return(Some(typed_id("total")))),
+        ];
+
+        let typed_program = Typer::new().type_program(&program).unwrap();
+        let [TypedGlobalStatement::FuncDef(main)] = typed_program.global_statements.as_slice() else {
+            panic!("expected only main");
+        };
+        assert_eq!(main.body, expected_body);
+    }
+
+    #[test]
+    fn let_types_can_be_needed_before_they_are_decided() {
+        // pub fn main() u8 {
+        //     let a = 1
+        //     let b = 2
+        //     bool _less = a < b      // `a` and `b` have the same type, decided by `ret b`
+        //     let c = 300
+        //     u16 _d = (u16) c        // nothing decides the type of `c`, so it's a u64
+        //     ret b
+        // }
+
+        let u16_type = Type::Int(IntType { width: 16, signed: false });
+        let u64_type = Type::Int(IntType { width: 64, signed: false });
+        let program = main_with_body(vec![
+            let_declaration("a", false, int("1")),
+            let_declaration("b", false, int("2")),
+            var_declaration("_less", Type::Bool, expr(ExprKind::Comparison(Comparison {
+                left: Box::new(id("a")),
+                operator: ComparisonOperator::LessThan,
+                right: Box::new(id("b")),
+            }))),
+            let_declaration("c", false, int("300")),
+            var_declaration("_d", u16_type.clone(), expr(ExprKind::Unary(Unary {
+                operator: UnaryOperator::Cast(u16_type.clone()),
+                operand: Box::new(id("c")),
+            }))),
+            statement(StatementKind::Return(Some(id("b")))),
+        ]);
+
+        let typed_program = Typer::new().type_program(&program).unwrap();
+        let [TypedGlobalStatement::FuncDef(main)] = typed_program.global_statements.as_slice() else {
+            panic!("expected only main");
+        };
+        let var_types: Vec<_> = main
+            .body
+            .iter()
+            .filter_map(|s| match &s.kind {
+                TypedStatementKind::VarDeclaration(declaration) => Some(declaration.var_type.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(var_types, [u8_type(), u8_type(), Type::Bool, u64_type, u16_type]);
+    }
+
+    #[test]
+    fn int_literals_take_the_type_of_their_peers() {
+        // pub fn main() u8 {
+        //     i8 y = 3
+        //     bool _b = -1 + y < 2     // every literal is an i8
+        //     let _big = 5000000000    // nothing decides the type, so it defaults to u64...
+        //     let _small = -1          // ...or to i64, since it's negative
+        //     ret 0
+        // }
+
+        let i8_type = Type::Int(IntType { width: 8, signed: true });
+        let sum = expr(ExprKind::Binary(Binary {
+            left: Box::new(negated("1")),
+            operator: BinaryOperator::Add,
+            right: Box::new(id("y")),
+        }));
+        let program = main_with_body(vec![
+            var_declaration("y", i8_type.clone(), int("3")),
+            statement(StatementKind::VarDeclaration(VarDeclaration {
+                var_name: "_b".to_string(),
+                var_type: Type::Bool,
+                var_value: expr(ExprKind::Comparison(Comparison {
+                    left: Box::new(sum),
+                    operator: ComparisonOperator::LessThan,
+                    right: Box::new(int("2")),
+                })),
+            })),
+            let_declaration("_big", false, int("5000000000")),
+            let_declaration("_small", false, negated("1")),
+            statement(StatementKind::Return(Some(int("0")))),
+        ]);
+
+        let typed_program = Typer::new().type_program(&program).unwrap();
+        let [TypedGlobalStatement::FuncDef(main)] = typed_program.global_statements.as_slice() else {
+            panic!("expected only main");
+        };
+        let declarations: Vec<_> = main
+            .body
+            .iter()
+            .filter_map(|s| match &s.kind {
+                TypedStatementKind::VarDeclaration(declaration) => Some(declaration),
+                _ => None,
+            })
+            .collect();
+        let TypedExprKind::Comparison(comparison) = &declarations[1].var_value.kind else {
+            panic!("expected a comparison");
+        };
+        let TypedExprKind::Binary(sum) = &comparison.left.kind else {
+            panic!("expected a sum");
+        };
+        assert_eq!(comparison.operand_type, i8_type);
+        assert_eq!(sum.left.get_result_type(), i8_type);
+        assert_eq!(comparison.right.get_result_type(), i8_type);
+        assert_eq!(declarations[2].var_type, Type::Int(IntType { width: 64, signed: false }));
+        assert_eq!(declarations[3].var_type, Type::Int(IntType { width: 64, signed: true }));
+    }
+
+    #[test]
+    fn let_declarations_are_checked() {
+        // pub fn main() u8 {
+        //     let mut i = 0
+        //     while i < 10 {      // fine, since `ret i + fixed` decides that `i` is a u8
+        //         i += 1
+        //     }
+        //     let fixed = 2
+        //     fixed = 3
+        //     void _nothing = 4
+        //     ret i + fixed
+        // }
+
+        let add = |left: Expr, right: Expr| {
+            expr(ExprKind::Binary(Binary { left: Box::new(left), operator: BinaryOperator::Add, right: Box::new(right) }))
+        };
+        let program = main_with_body(vec![
+            let_declaration("i", true, int("0")),
+            statement(StatementKind::WhileLoop(WhileLoop {
+                label: None,
+                condition: expr(ExprKind::Comparison(Comparison {
+                    left: Box::new(id("i")),
+                    operator: ComparisonOperator::LessThan,
+                    right: Box::new(int("10")),
+                })),
+                body: vec![statement(StatementKind::Assignment(Assignment {
+                    name: "i".to_string(),
+                    accessors: vec![],
+                    value: Box::new(add(id("i"), int("1"))),
+                }))],
+            })),
+            let_declaration("fixed", false, int("2")),
+            statement(StatementKind::Assignment(Assignment {
+                name: "fixed".to_string(),
+                accessors: vec![],
+                value: Box::new(int("3")),
+            })),
+            var_declaration("_nothing", Type::Void, int("4")),
+            statement(StatementKind::Return(Some(add(id("i"), id("fixed"))))),
+        ]);
+
+        assert_eq!(
+            typing_errors(&program),
+            vec![
+                TypingError::AssignmentToImmutable("fixed".to_string()),
+                TypingError::InvalidVarType(Type::Void),
+            ]
+        );
+    }
+
+    #[test]
+    fn let_variables_are_usable_as_counters_and_indices() {
+        // pub fn main() u8 {
+        //     [u8; 3] digits = [1, 2, 3]
+        //     let mut i = 0
+        //     while i < 3 {       // `i` is a u8, decided by `ret i`
+        //         i += 1
+        //     }
+        //     let x = 2
+        //     if x == 2 {         // nothing decides the type of `x`, so it's a u64
+        //         ret digits[x]
+        //     }
+        //     ret i
+        // }
+
+        let u64_type = Type::Int(IntType { width: 64, signed: false });
+        let digits_type = Type::Array(Box::new(u8_type()), 3);
+        let comparison = |name: &str, operator: ComparisonOperator, i: &str| {
+            expr(ExprKind::Comparison(Comparison { left: Box::new(id(name)), operator, right: Box::new(int(i)) }))
+        };
+        let program = main_with_body(vec![
+            var_declaration("digits", digits_type.clone(), expr(ExprKind::ArrayLiteral(vec![int("1"), int("2"), int("3")]))),
+            let_declaration("i", true, int("0")),
+            statement(StatementKind::WhileLoop(WhileLoop {
+                label: None,
+                condition: comparison("i", ComparisonOperator::LessThan, "3"),
+                body: vec![statement(StatementKind::Assignment(Assignment {
+                    name: "i".to_string(),
+                    accessors: vec![],
+                    value: Box::new(expr(ExprKind::Binary(Binary {
+                        left: Box::new(id("i")),
+                        operator: BinaryOperator::Add,
+                        right: Box::new(int("1")),
+                    }))),
+                }))],
+            })),
+            let_declaration("x", false, int("2")),
+            statement(StatementKind::If(If {
+                condition: comparison("x", ComparisonOperator::EqualTo, "2"),
+                then_body: vec![statement(StatementKind::Return(Some(expr(ExprKind::Index(Index {
+                    expr: Box::new(id("digits")),
+                    index: Box::new(id("x")),
+                })))))],
+                else_body: None,
+            })),
+            statement(StatementKind::Return(Some(id("i")))),
+        ]);
+
+        let typed_program = Typer::new().type_program(&program).unwrap();
+        let [TypedGlobalStatement::FuncDef(main)] = typed_program.global_statements.as_slice() else {
+            panic!("expected only main");
+        };
+        let var_types: Vec<_> = main
+            .body
+            .iter()
+            .filter_map(|s| match &s.kind {
+                TypedStatementKind::VarDeclaration(declaration) => Some(declaration.var_type.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(var_types, [digits_type, u8_type(), u64_type.clone()]);
+
+        let TypedStatementKind::WhileLoop(while_loop) = &main.body[2].kind else {
+            panic!("expected the while loop");
+        };
+        let TypedExprKind::Comparison(condition) = &while_loop.condition.kind else {
+            panic!("expected a comparison");
+        };
+        assert_eq!(condition.operand_type, u8_type());
+
+        let TypedStatementKind::If(if_statement) = &main.body[4].kind else {
+            panic!("expected the if statement");
+        };
+        let TypedExprKind::Comparison(condition) = &if_statement.condition.kind else {
+            panic!("expected a comparison");
+        };
+        assert_eq!(condition.operand_type, u64_type);
+        let TypedStatementKind::Return(Some(TypedExpr { kind: TypedExprKind::Index(index), .. })) = &if_statement.then_body[0].kind else {
+            panic!("expected to return an element of `digits`");
+        };
+        assert_eq!(index.index.get_result_type(), u64_type);
     }
 }